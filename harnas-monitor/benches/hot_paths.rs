@@ -0,0 +1,106 @@
+//! Criterion benchmarks of the paths every reload and frame goes through, on `gen` files of a
+//! few sizes: loading, stats, the diff behind the large-change banner, and a full frame drawn
+//! off-screen. Run with `cargo bench`; `cargo bench -- render` picks one group.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use harnas_monitor::app::App;
+use harnas_monitor::diff::diff_tasks;
+use harnas_monitor::gen::{generate, GenSpec};
+use harnas_monitor::tasks::{compute_stats, load_tasks, reload_tasks};
+use harnas_monitor::ui::{render_plain, UiTheme};
+use harnas_monitor::workspace::Workspace;
+
+const SIZES: [usize; 3] = [100, 1_000, 5_000];
+
+/// A generated tasks file of `tasks` tasks in the temp directory; `variant` tells apart files
+/// of the same size.
+fn tasks_file(tasks: usize, variant: &str, edit: impl Fn(String) -> String) -> PathBuf {
+    let plan = generate(&GenSpec {
+        tasks,
+        ..GenSpec::default()
+    });
+    let dir = std::env::temp_dir().join(format!("harnas-monitor-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("creating the bench directory");
+    let path = dir.join(format!("TASKS.{tasks}{variant}.toml"));
+    std::fs::write(&path, edit(plan)).expect("writing a generated tasks file");
+    path
+}
+
+/// The same plan with a tenth of the open tasks started, as between two reloads.
+fn progressed(plan: String) -> String {
+    let started = plan.matches("status = \"todo\"").count() / 10;
+    plan.replacen("status = \"todo\"", "status = \"in_progress\"", started)
+}
+
+fn load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    // Seconds per load at the top size (validation is quadratic); keep the run bearable.
+    group.sample_size(10);
+    for n in SIZES {
+        let path = tasks_file(n, "", |p| p);
+        let prev = load_tasks(&path).expect("loading a generated file");
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("full", n), &path, |b, path| {
+            b.iter(|| load_tasks(path).expect("loading a generated file"))
+        });
+        group.bench_with_input(BenchmarkId::new("incremental", n), &path, |b, path| {
+            b.iter(|| reload_tasks(path, Some(&prev), Workspace::default()).expect("reloading a generated file"))
+        });
+    }
+    group.finish();
+}
+
+fn stats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stats");
+    for n in SIZES {
+        let loaded = load_tasks(&tasks_file(n, "", |p| p)).expect("loading a generated file");
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &loaded, |b, loaded| {
+            b.iter(|| compute_stats(&loaded.tasks, |_| true))
+        });
+    }
+    group.finish();
+}
+
+fn diff(c: &mut Criterion) {
+    let mut group = c.benchmark_group("diff");
+    for n in SIZES {
+        let old = load_tasks(&tasks_file(n, "", |p| p)).expect("loading a generated file");
+        let new =
+            load_tasks(&tasks_file(n, ".next", progressed)).expect("loading a generated file");
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n),
+            &(old, new),
+            |b, (old, new)| b.iter(|| diff_tasks(&old.tasks, &new.tasks)),
+        );
+    }
+    group.finish();
+}
+
+/// The dashboard as `render` draws it: loaded once, then only the frame is timed.
+fn render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    let theme = UiTheme::default();
+    for n in SIZES {
+        let app = loaded_app(&tasks_file(n, "", |p| p));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &app, |b, app| {
+            b.iter(|| render_plain(&theme, app, 120, 40).expect("rendering a frame"))
+        });
+    }
+    group.finish();
+}
+
+fn loaded_app(path: &Path) -> App {
+    let mut app = App::new(path.to_path_buf());
+    app.set_deterministic();
+    app.maybe_reload(Duration::ZERO, Duration::MAX);
+    assert!(app.loaded.is_some(), "loading {}", path.display());
+    app
+}
+
+criterion_group!(benches, load, stats, diff, render);
+criterion_main!(benches);
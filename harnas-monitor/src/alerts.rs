@@ -0,0 +1,117 @@
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::history::History;
+use crate::tasks::{
+    deferred_until, is_blocked, priority_key, status_map, task_status, unmet_deps, LoadedTasks,
+    TaskFile,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    BlockedSla,
+    /// A deferred task whose `deferred_until` day has come.
+    Resurfaced,
+}
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub task_id: Option<String>,
+    pub message: String,
+    /// Names the alert and the condition behind it but not how long it has lasted, so a
+    /// dismissal (`d`) outlives reloads and ends when the condition changes.
+    pub fingerprint: String,
+}
+
+/// Tasks blocked for longer than the SLA configured for their priority.
+pub fn blocked_sla_alerts(
+    loaded: &LoadedTasks,
+    history: &History,
+    config: &Config,
+    now: SystemTime,
+) -> Vec<Alert> {
+    if config.blocked_sla.is_empty() {
+        return Vec::new();
+    }
+    let status_by_id = status_map(&loaded.tasks);
+    let mut out = Vec::new();
+    for t in &loaded.tasks.tasks {
+        if !is_blocked(t, &status_by_id) {
+            continue;
+        }
+        let prio = priority_key(t);
+        let Some(sla) = config.blocked_sla.get(&prio) else {
+            continue;
+        };
+        let Some(since) = history.blocked_since(&t.id) else {
+            continue;
+        };
+        let blocked_for = now.duration_since(since).unwrap_or_default();
+        if blocked_for <= *sla {
+            continue;
+        }
+
+        let deps = unmet_deps(t, &status_by_id);
+        let by = if deps.is_empty() {
+            format!("status {}", task_status(t))
+        } else {
+            deps.join(", ")
+        };
+        out.push(Alert {
+            kind: AlertKind::BlockedSla,
+            task_id: Some(t.id.clone()),
+            message: format!(
+                "{} ({prio}) blocked {} > SLA {} by {by}",
+                t.id,
+                format_age(blocked_for),
+                format_age(*sla)
+            ),
+            fingerprint: format!(
+                "sla:{}:{}:{by}",
+                t.id,
+                humantime::format_rfc3339_seconds(since)
+            ),
+        });
+    }
+    out
+}
+
+/// Deferred tasks that are due back, so they are noticed when they return to the table.
+pub fn resurfaced_alerts(loaded: &LoadedTasks, now: SystemTime) -> Vec<Alert> {
+    loaded
+        .tasks
+        .tasks
+        .iter()
+        .filter(|t| task_status(t) == "deferred")
+        .filter(|t| deferred_until(t).is_some_and(|day| day <= now))
+        .map(|t| {
+            let until = t.deferred_until.as_deref().unwrap_or_default().trim();
+            Alert {
+                kind: AlertKind::Resurfaced,
+                task_id: Some(t.id.clone()),
+                message: format!("{} is back: deferred until {until}", t.id),
+                fingerprint: format!("back:{}:{until}", t.id),
+            }
+        })
+        .collect()
+}
+
+/// Stands for the file's `generated_utc` problems (stale, in the future, malformed) when
+/// dismissed from the problems panel; a new `generated_utc` brings them back.
+pub fn timestamp_fingerprint(file: &TaskFile) -> Option<String> {
+    let generated = file.meta.as_ref()?.generated_utc.as_deref()?;
+    Some(format!("timestamp:{}", generated.trim()))
+}
+
+/// Coarse, human-sized duration ("3d", "5h", "12m").
+pub fn format_age(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 86_400 {
+        format!("{}d", secs / 86_400)
+    } else if secs >= 3_600 {
+        format!("{}h", secs / 3_600)
+    } else {
+        format!("{}m", secs / 60)
+    }
+}
@@ -0,0 +1,660 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::{Duration, SystemTime};
+
+use crate::alerts::format_age;
+use crate::config::PriorityConfig;
+use crate::tasks::{display_id, id_occurrences, normalize_status, Task, TaskFile};
+
+/// `generated_utc` this far ahead of the clock is reported as clock skew.
+pub const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+/// `generated_utc` this much older than the file's mtime suggests the generator wrote a stale
+/// artifact.
+pub const STALE_GENERATION: Duration = Duration::from_secs(7 * 86_400);
+
+/// Titles at or above this token similarity are reported as likely duplicates.
+const DUPLICATE_TITLE_THRESHOLD: f64 = 0.8;
+/// Lower bar for pairs that also reference exactly the same spec sections; identical spec_refs
+/// on their own are common for tasks that split one feature into steps.
+const DUPLICATE_TITLE_THRESHOLD_SAME_REFS: f64 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemKind {
+    MissingDependency,
+    /// A `related_to`, `duplicates` or `supersedes` entry naming no task.
+    MissingReference,
+    LikelyDuplicate,
+    DuplicateId,
+    /// A status the dashboard understands only after normalizing (`In-Progress`, ` done`).
+    StatusFormat,
+    /// `[meta] generated_utc` is malformed or in the future.
+    Timestamp,
+    /// `[meta] generated_utc` is far older than the file's mtime. Info only: the mtime also
+    /// moves on a checkout or the monitor's own writes, so it must not fail `lint`.
+    StaleGeneration,
+    /// A `[meta.lint] suppress` entry naming no rule.
+    Lint,
+    /// A priority the config's `[priorities] order` (or the built-in names) does not rank.
+    UnknownPriority,
+    /// Estimate bounds on the wrong side of `estimate_days`, bounds without it, a negative
+    /// `[meta] estimate_spread` or a negative `[meta.budgets]` entry.
+    EstimateRange,
+}
+
+/// How much a problem matters. Errors leave the dashboard's numbers wrong, warnings are likely
+/// mistakes, info is housekeeping; `lint` fails only on the first two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Error, Severity::Warning, Severity::Info];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+}
+
+impl ProblemKind {
+    /// Short label used in the problems panel and `lint` output.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MissingDependency => "missing dep",
+            Self::MissingReference => "missing ref",
+            Self::LikelyDuplicate => "duplicate?",
+            Self::DuplicateId => "duplicate id",
+            Self::StatusFormat => "status",
+            Self::Timestamp => "timestamp",
+            Self::StaleGeneration => "stale",
+            Self::Lint => "lint",
+            Self::UnknownPriority => "priority",
+            Self::EstimateRange => "estimate",
+        }
+    }
+
+    pub const ALL: [ProblemKind; 10] = [
+        Self::MissingDependency,
+        Self::MissingReference,
+        Self::LikelyDuplicate,
+        Self::DuplicateId,
+        Self::StatusFormat,
+        Self::Timestamp,
+        Self::StaleGeneration,
+        Self::Lint,
+        Self::UnknownPriority,
+        Self::EstimateRange,
+    ];
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::MissingDependency | Self::DuplicateId => Severity::Error,
+            Self::MissingReference
+            | Self::Timestamp
+            | Self::Lint
+            | Self::UnknownPriority
+            | Self::EstimateRange => Severity::Warning,
+            Self::LikelyDuplicate | Self::StatusFormat | Self::StaleGeneration => Severity::Info,
+        }
+    }
+
+    /// Name used in `[lint] suppress` lists.
+    pub fn rule(self) -> &'static str {
+        match self {
+            Self::MissingDependency => "missing-dep",
+            Self::MissingReference => "missing-ref",
+            Self::LikelyDuplicate => "likely-duplicate",
+            Self::DuplicateId => "duplicate-id",
+            Self::StatusFormat => "status-format",
+            Self::Timestamp => "timestamp",
+            Self::StaleGeneration => "stale-generation",
+            Self::Lint => "lint",
+            Self::UnknownPriority => "unknown-priority",
+            Self::EstimateRange => "estimate-range",
+        }
+    }
+}
+
+/// A `[lint] suppress` entry: `rule` silences a rule for the whole file, `rule:ID` only its
+/// problems involving task `ID`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    pub kind: ProblemKind,
+    pub task: Option<String>,
+}
+
+impl Suppression {
+    pub fn parse(entry: &str) -> Result<Suppression, String> {
+        let (rule, task) = match entry.trim().split_once(':') {
+            Some((rule, task)) => (rule.trim(), Some(task.trim().to_string())),
+            None => (entry.trim(), None),
+        };
+        let kind = ProblemKind::ALL
+            .into_iter()
+            .find(|k| k.rule() == rule.to_lowercase())
+            .ok_or_else(|| {
+                let rules: Vec<&str> = ProblemKind::ALL.iter().map(|k| k.rule()).collect();
+                format!("unknown lint rule {rule:?} (rules: {})", rules.join(", "))
+            })?;
+        if task.as_deref() == Some("") {
+            return Err(format!("{entry:?} names no task after ':'"));
+        }
+        Ok(Suppression { kind, task })
+    }
+
+    fn matches(&self, problem: &Problem) -> bool {
+        problem.kind == self.kind
+            && self
+                .task
+                .as_ref()
+                .is_none_or(|id| problem.task_ids.contains(id))
+    }
+}
+
+/// Drops the problems any of `rules` silences.
+pub fn suppress(problems: &mut Vec<Problem>, rules: &[Suppression]) {
+    if !rules.is_empty() {
+        problems.retain(|p| !rules.iter().any(|r| r.matches(p)));
+    }
+}
+
+/// Problem counts by severity, in `Severity::ALL` order.
+pub fn severity_counts(problems: &[Problem]) -> [usize; 3] {
+    let mut counts = [0; 3];
+    for p in problems {
+        counts[p.severity() as usize] += 1;
+    }
+    counts
+}
+
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    /// Tasks involved, in the order they appear in the file.
+    pub task_ids: Vec<String>,
+    pub message: String,
+    /// A mechanical edit that resolves the problem, when there is an unambiguous one.
+    pub fix: Option<Fix>,
+}
+
+impl Problem {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+/// An edit to `TASKS.toml` offered from the problems panel; applied by `writer::apply_fix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fix {
+    /// Replace `from` with `to` in the id list `field` (`depends_on`, `related_to`, ...) of
+    /// task `task`.
+    RenameReference {
+        task: String,
+        field: &'static str,
+        from: String,
+        to: String,
+    },
+    /// Give the `occurrence`-th task (0-based, in file order) with id `id` the id `to`.
+    RenameDuplicateId {
+        id: String,
+        occurrence: usize,
+        to: String,
+    },
+    /// Rewrite task `task`'s status `from` as `to`.
+    NormalizeStatus {
+        task: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl Fix {
+    pub fn describe(&self) -> String {
+        match self {
+            Fix::RenameReference {
+                task,
+                field,
+                from,
+                to,
+            } => format!("change {from} to {to} in {task}'s {field}"),
+            Fix::RenameDuplicateId { id, to, .. } => {
+                format!("rename the later {id} to {to}")
+            }
+            Fix::NormalizeStatus { task, from, to } => {
+                format!("set {task}'s status {from:?} to {to:?}")
+            }
+        }
+    }
+}
+
+/// `now` and `file_mtime` only enter the `generated_utc` checks. Rules the file suppresses in
+/// `[meta.lint]` are left out; the config's `[lint]` is applied by the caller.
+pub fn find_problems(
+    file: &TaskFile,
+    file_mtime: Option<SystemTime>,
+    now: SystemTime,
+) -> Vec<Problem> {
+    let mut problems = missing_references(file);
+    problems.extend(duplicate_ids(file));
+    problems.extend(status_formats(file));
+    problems.extend(estimate_ranges(file));
+    problems.extend(likely_duplicates(file));
+    problems.extend(timestamp_problems(file, file_mtime, now));
+    let mut rules = Vec::new();
+    for entry in file.meta.iter().flat_map(|m| &m.lint.suppress) {
+        match Suppression::parse(entry) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => problems.push(Problem {
+                kind: ProblemKind::Lint,
+                task_ids: Vec::new(),
+                message: format!("[meta.lint] suppress: {e}"),
+                fix: None,
+            }),
+        }
+    }
+    suppress(&mut problems, &rules);
+    problems
+}
+
+/// Tasks whose priority `priorities` does not rank; tasks without one are left alone. It
+/// depends on the config, so `Config::check` adds these to what `find_problems` found.
+pub fn unknown_priorities(file: &TaskFile, priorities: &PriorityConfig) -> Vec<Problem> {
+    let known = if priorities.order.is_empty() {
+        "a built-in priority; list the plan's own in [priorities] order".to_string()
+    } else {
+        format!("in [priorities] order ({})", priorities.order.join(", "))
+    };
+    file.tasks
+        .iter()
+        .filter_map(|t| {
+            let raw = t.priority.as_deref()?;
+            let key = raw.trim().to_lowercase();
+            (!key.is_empty() && priorities.rank(&key).is_none()).then(|| Problem {
+                kind: ProblemKind::UnknownPriority,
+                task_ids: vec![t.id.clone()],
+                message: format!("{} has priority {raw:?}, which is not {known}", t.id),
+                fix: None,
+            })
+        })
+        .collect()
+}
+
+/// `[meta] generated_utc`, parsed strictly as an RFC 3339 UTC timestamp
+/// (`2026-03-02T09:15:00Z`). `None` when the file has none.
+pub fn generated_at(file: &TaskFile) -> Option<Result<SystemTime, String>> {
+    let raw = file.meta.as_ref()?.generated_utc.as_deref()?;
+    Some(humantime::parse_rfc3339(raw).map_err(|e| {
+        format!(
+            "generated_utc {raw:?} is not an RFC 3339 UTC timestamp like \
+             2026-03-02T09:15:00Z ({e})"
+        )
+    }))
+}
+
+fn timestamp_problems(
+    file: &TaskFile,
+    file_mtime: Option<SystemTime>,
+    now: SystemTime,
+) -> Vec<Problem> {
+    let problem = |kind, message| Problem {
+        kind,
+        task_ids: Vec::new(),
+        message,
+        fix: None,
+    };
+    let generated = match generated_at(file) {
+        None => return Vec::new(),
+        Some(Err(e)) => return vec![problem(ProblemKind::Timestamp, e)],
+        Some(Ok(at)) => at,
+    };
+    let mut out = Vec::new();
+    if let Ok(ahead) = generated.duration_since(now) {
+        if ahead > CLOCK_SKEW_TOLERANCE {
+            let message = format!(
+                "generated_utc is {} in the future; a clock is off here or where the file was \
+                 generated",
+                format_age(ahead)
+            );
+            out.push(problem(ProblemKind::Timestamp, message));
+        }
+    }
+    if let Some(behind) = file_mtime.and_then(|m| m.duration_since(generated).ok()) {
+        if behind > STALE_GENERATION {
+            let message = format!(
+                "generated_utc is {} older than the file's mtime; the generator may have \
+                 written a stale artifact",
+                format_age(behind)
+            );
+            out.push(problem(ProblemKind::StaleGeneration, message));
+        }
+    }
+    out
+}
+
+/// Ids in `depends_on` and the relationship lists that name no task, here or (as `project:ID`)
+/// in another workspace project.
+fn missing_references(file: &TaskFile) -> Vec<Problem> {
+    let all_ids: HashSet<&str> = file.tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut out = Vec::new();
+    for t in &file.tasks {
+        for (field, dep) in t.linked_ids() {
+            if all_ids.contains(dep) || file.workspace.statuses.contains_key(dep) {
+                continue;
+            }
+            let external = t.depends_on.iter().any(|d| d.id == dep && d.is_external());
+            if field == "depends_on" && external {
+                continue;
+            }
+            if let Some(what) = file.workspace.explain_missing(dep) {
+                let (kind, message) = match field {
+                    "depends_on" => (
+                        ProblemKind::MissingDependency,
+                        format!("{} depends on {what}", t.id),
+                    ),
+                    _ => (
+                        ProblemKind::MissingReference,
+                        format!("{}'s {field} names {what}", t.id),
+                    ),
+                };
+                out.push(Problem {
+                    kind,
+                    task_ids: vec![t.id.clone()],
+                    message,
+                    fix: None,
+                });
+                continue;
+            }
+            // A typo fix is only offered when exactly one id is a single edit away.
+            let mut near = all_ids.iter().filter(|id| one_edit_apart(id, dep));
+            let fix = match (near.next(), near.next()) {
+                (Some(to), None) => Some(Fix::RenameReference {
+                    task: t.id.clone(),
+                    field,
+                    from: dep.to_string(),
+                    to: to.to_string(),
+                }),
+                _ => None,
+            };
+            let hint = match &fix {
+                Some(Fix::RenameReference { to, .. }) => format!(" (did you mean {to}?)"),
+                _ => String::new(),
+            };
+            let (kind, message) = match field {
+                "depends_on" => (
+                    ProblemKind::MissingDependency,
+                    format!("{} depends on unknown task {dep}{hint}", t.id),
+                ),
+                _ => (
+                    ProblemKind::MissingReference,
+                    format!("{}'s {field} names unknown task {dep}{hint}", t.id),
+                ),
+            };
+            out.push(Problem {
+                kind,
+                task_ids: vec![t.id.clone()],
+                message,
+                fix,
+            });
+        }
+    }
+    out
+}
+
+/// Every repeat of an id after its first use; the fix renames the repeat to the next free id
+/// in the same `PREFIX-NNN` sequence.
+fn duplicate_ids(file: &TaskFile) -> Vec<Problem> {
+    let mut taken: HashSet<String> = file.tasks.iter().map(|t| t.id.clone()).collect();
+    let occurrences = id_occurrences(&file.tasks);
+    let mut uses: HashMap<&str, usize> = HashMap::new();
+    for t in &file.tasks {
+        *uses.entry(t.id.as_str()).or_insert(0) += 1;
+    }
+    // In file order, so the fixes number the repeats top to bottom.
+    let mut repeats: Vec<(usize, &Task)> = file
+        .tasks
+        .iter()
+        .zip(&occurrences)
+        .filter(|(_, &n)| n > 0)
+        .map(|(t, &n)| (n, t))
+        .collect();
+    repeats.sort_by_key(|(_, t)| t.line);
+    let mut out = Vec::new();
+    for (occurrence, t) in repeats {
+        let fix = next_free_id(&t.id, &taken).map(|to| {
+            taken.insert(to.clone());
+            Fix::RenameDuplicateId {
+                id: t.id.clone(),
+                occurrence,
+                to,
+            }
+        });
+        out.push(Problem {
+            kind: ProblemKind::DuplicateId,
+            task_ids: vec![t.id.clone()],
+            message: format!(
+                "{} is used by {} tasks; this one (line {}) shows as {}",
+                t.id,
+                uses[t.id.as_str()],
+                t.line.map_or_else(|| "?".to_string(), |l| l.to_string()),
+                display_id(&t.id, occurrence)
+            ),
+            fix,
+        });
+    }
+    out
+}
+
+fn status_formats(file: &TaskFile) -> Vec<Problem> {
+    file.tasks
+        .iter()
+        .filter_map(|t| {
+            let raw = t.status.as_deref()?;
+            let normalized = normalize_status(raw);
+            (normalized != raw && !normalized.is_empty()).then(|| Problem {
+                kind: ProblemKind::StatusFormat,
+                task_ids: vec![t.id.clone()],
+                message: format!(
+                    "{} has status {raw:?}; the canonical form is {normalized:?}",
+                    t.id
+                ),
+                fix: Some(Fix::NormalizeStatus {
+                    task: t.id.clone(),
+                    from: raw.to_string(),
+                    to: normalized,
+                }),
+            })
+        })
+        .collect()
+}
+
+fn estimate_ranges(file: &TaskFile) -> Vec<Problem> {
+    let problem = |task_ids, message| Problem {
+        kind: ProblemKind::EstimateRange,
+        task_ids,
+        message,
+        fix: None,
+    };
+    let mut out = Vec::new();
+    if let Some(spread) = file.meta.as_ref().and_then(|m| m.estimate_spread) {
+        if spread < 0.0 || !spread.is_finite() {
+            out.push(problem(
+                Vec::new(),
+                format!("[meta] estimate_spread is {spread}; it must be a percentage of 0 or more"),
+            ));
+        }
+    }
+    let budgets = file.meta.iter().flat_map(|m| &m.budgets);
+    for (comp, days) in budgets.filter(|(_, d)| !d.is_finite() || **d < 0.0) {
+        out.push(problem(
+            Vec::new(),
+            format!("[meta.budgets] {comp} is {days}; it must be 0 or more days, so it is ignored"),
+        ));
+    }
+    for t in &file.tasks {
+        let bounds = [("min", t.estimate_days_min), ("max", t.estimate_days_max)];
+        let Some(days) = t.estimate_days else {
+            if let Some((which, _)) = bounds.iter().find(|(_, b)| b.is_some()) {
+                out.push(problem(
+                    vec![t.id.clone()],
+                    format!(
+                        "{} has estimate_days_{which} but no estimate_days; the range is ignored",
+                        t.id
+                    ),
+                ));
+            }
+            continue;
+        };
+        if let Some(min) = t.estimate_days_min.filter(|min| *min > days) {
+            out.push(problem(
+                vec![t.id.clone()],
+                format!("{}'s estimate_days_min {min} is above its estimate_days {days}", t.id),
+            ));
+        }
+        if let Some(max) = t.estimate_days_max.filter(|max| *max < days) {
+            out.push(problem(
+                vec![t.id.clone()],
+                format!("{}'s estimate_days_max {max} is below its estimate_days {days}", t.id),
+            ));
+        }
+    }
+    out
+}
+
+/// `T-012` with `T-013` taken becomes `T-014`: the highest number among ids with the same
+/// prefix, plus one, padded like `id`. `None` when `id` does not end in digits.
+fn next_free_id(id: &str, taken: &HashSet<String>) -> Option<String> {
+    let split = |s: &str| {
+        let digits = s.len() - s.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        let (prefix, num) = s.split_at(s.len() - digits);
+        (!num.is_empty()).then_some((prefix.to_string(), num.to_string()))
+    };
+    let (prefix, num) = split(id)?;
+    let max = taken
+        .iter()
+        .filter_map(|t| split(t))
+        .filter(|(p, _)| *p == prefix)
+        .filter_map(|(_, n)| n.parse::<u64>().ok())
+        .max()?;
+    let next = format!("{prefix}{:0width$}", max + 1, width = num.len());
+    (!taken.contains(&next)).then_some(next)
+}
+
+/// Levenshtein distance of exactly one: one character substituted, inserted or removed.
+pub fn one_edit_apart(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short.iter().zip(&long).take_while(|(x, y)| x == y).count();
+    if short.len() == long.len() {
+        prefix < short.len() && short[prefix + 1..] == long[prefix + 1..]
+    } else {
+        short[prefix..] == long[prefix + 1..]
+    }
+}
+
+/// Flags pairs with near-identical titles (token Jaccard similarity), with a lower bar when
+/// their non-empty spec_refs sets are identical. The generator occasionally re-emits the same
+/// work under a new id.
+///
+/// Only candidate pairs are scored: tasks with identical spec_refs, and tasks sharing one of
+/// the rarest tokens of their titles. Two titles at `DUPLICATE_TITLE_THRESHOLD` or above share
+/// at least `⌈threshold · n⌉` of each one's `n` tokens, so they meet among the
+/// `n - ⌊threshold · n⌋ + 1` rarest.
+fn likely_duplicates(file: &TaskFile) -> Vec<Problem> {
+    let prepared: Vec<(BTreeSet<String>, BTreeSet<&str>)> = file
+        .tasks
+        .iter()
+        .map(|t| {
+            (
+                title_tokens(&t.title),
+                t.spec_refs.iter().map(|s| s.trim()).collect(),
+            )
+        })
+        .collect();
+
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for token in prepared.iter().flat_map(|(tokens, _)| tokens) {
+        *frequency.entry(token).or_default() += 1;
+    }
+    let mut by_token: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_refs: HashMap<&BTreeSet<&str>, Vec<usize>> = HashMap::new();
+    for (i, (tokens, refs)) in prepared.iter().enumerate() {
+        let mut rarest: Vec<&str> = tokens.iter().map(String::as_str).collect();
+        rarest.sort_by_key(|t| (frequency[*t], *t));
+        let shared = (DUPLICATE_TITLE_THRESHOLD * tokens.len() as f64).floor() as usize;
+        for token in rarest.into_iter().take(tokens.len() - shared + 1) {
+            by_token.entry(token).or_default().push(i);
+        }
+        if !refs.is_empty() {
+            by_refs.entry(refs).or_default().push(i);
+        }
+    }
+    let mut candidates = BTreeSet::new();
+    for group in by_token.values().chain(by_refs.values()) {
+        for (n, &i) in group.iter().enumerate() {
+            candidates.extend(group[n + 1..].iter().map(|&j| (i, j)));
+        }
+    }
+
+    let mut out = Vec::new();
+    for (i, j) in candidates {
+        let (ti, si) = &prepared[i];
+        let (tj, sj) = &prepared[j];
+        let title_score = jaccard(ti, tj);
+        let same_refs = !si.is_empty() && si == sj;
+        let threshold = if same_refs {
+            DUPLICATE_TITLE_THRESHOLD_SAME_REFS
+        } else {
+            DUPLICATE_TITLE_THRESHOLD
+        };
+        if title_score < threshold {
+            continue;
+        }
+
+        let (a, b) = (&file.tasks[i], &file.tasks[j]);
+        // Declared with `duplicates` (either way round): already known.
+        if a.duplicates.contains(&b.id) || b.duplicates.contains(&a.id) {
+            continue;
+        }
+        let mut reasons = vec![format!("title similarity {:.2}", title_score)];
+        if same_refs {
+            reasons.push("identical spec_refs".to_string());
+        }
+        out.push(Problem {
+            kind: ProblemKind::LikelyDuplicate,
+            task_ids: vec![a.id.clone(), b.id.clone()],
+            message: format!(
+                "{} and {} look like duplicates ({})",
+                a.id,
+                b.id,
+                reasons.join(", ")
+            ),
+            fix: None,
+        });
+    }
+    out
+}
+
+pub fn title_tokens(title: &str) -> BTreeSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+pub fn jaccard<T: Ord>(a: &BTreeSet<T>, b: &BTreeSet<T>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let inter = a.intersection(b).count();
+    let union = a.len() + b.len() - inter;
+    inter as f64 / union as f64
+}
@@ -0,0 +1,3800 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::alerts::{
+    blocked_sla_alerts, resurfaced_alerts, timestamp_fingerprint, Alert, AlertKind,
+};
+use crate::analysis::{Fix, Problem, ProblemKind, Severity};
+use crate::beacon::write_status_file;
+use crate::browser;
+use crate::clock::{self, Instant};
+use crate::config::{Config, PriorityConfig, Profile, ProfileSort};
+use crate::contributors::{by_author, completions, Contributor};
+use crate::cost::{budget_use, cost_rollup, BudgetUse, CostRollup};
+use crate::demo::{load_demo, Tour, DEMO_PATH};
+use crate::diff::{classify, diff_tasks, ChangeClass, TaskDiff};
+use crate::discover::repo_root;
+use crate::drift::{spec_drift, Drift};
+use crate::export::{select_tasks, write_export, VIEW_FORMATS};
+use crate::feed::{feed_entries, FeedEntry};
+use crate::filter::{custom_field, display_value, Filter};
+use crate::history::{history_path, History};
+use crate::i18n::{tr, trf, Lang};
+use crate::keys::{KeyCode, KeyEvent, KeyModifiers};
+use crate::loader::{load, Loader};
+use crate::matrix::dependency_matrix;
+use crate::notes::{
+    append_comment, append_note, append_session, load_notes, notes_path, prune_dismissed,
+    toggle_dismissed, toggle_verified, Annotation, Comment, FocusSession, NotesFile,
+};
+use crate::palette::{search, Action, Command, KEY_COMMANDS};
+use crate::prune::{redundant_deps, Redundant};
+use crate::review::{review_queue, review_rows};
+use crate::risk::{risk_scores, risk_weight, RiskScore};
+use crate::safe::{reset, Failure, StateFile};
+use crate::schedule::{makespan, replan, Makespan, Replan};
+use crate::sound::{next_volume, Cue};
+use crate::staging::{apply_edits, line_diff, stage, DiffLine, Edit, TaskField};
+use crate::suggest::{suggested_dependencies, Suggestion};
+use crate::targets::{headline, parse_target, track_targets, TargetProgress};
+use crate::tasks::{
+    component_key, display_id, estimate_spread, id_occurrences, is_deferred, link_workspace,
+    parse_tasks, priority_key, scope_to_component, status_map, task_status, unmet_deps, LoadedTasks, Task,
+    TaskFile,
+};
+use crate::termcaps::Caps;
+use crate::triage::{new_missing_deps, BrokenEdge, Resolution};
+use crate::timer::{format_countdown, FocusTimer, FOCUS_SESSION};
+use crate::ui::{Modal, ViewState};
+use crate::usage::{self, Session};
+use crate::watch::WatchLog;
+use crate::workspace::ProjectSummary;
+use crate::writer::{edit_tasks_file, preview_fix, write_atomic};
+
+/// An entry of the navigation sidebar (`b`, `Tab`), with its task count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SidebarEntry {
+    All(usize),
+    Component(String, usize),
+    /// Tasks whose component has no owner in `[owners]`; listed only when owners are configured.
+    Unowned(usize),
+    Status(String, usize),
+}
+
+/// The filter behind `SidebarEntry::Unowned`.
+const UNOWNED_QUERY: &str = "-has:owner";
+
+/// A row of the workspace overview (`0`).
+pub struct OverviewRow {
+    pub name: String,
+    pub path: PathBuf,
+    /// The file this dashboard shows.
+    pub current: bool,
+    pub summary: Result<ProjectSummary, String>,
+}
+
+pub struct PanelEntry {
+    pub label: &'static str,
+    pub alert: bool,
+    /// SLA alerts count as errors, returning deferred tasks as warnings.
+    pub severity: Severity,
+    pub task_id: Option<String>,
+    pub message: String,
+    pub fix: Option<Fix>,
+    /// Set on entries `d` can dismiss: alerts, and `generated_utc` problems.
+    pub fingerprint: Option<String>,
+}
+
+/// An error shown in the header, with when it happened.
+#[derive(Debug, Clone)]
+pub struct TimedError {
+    pub at: SystemTime,
+    pub message: String,
+}
+
+/// The clock in deterministic mode: 2000-01-01T00:00:00Z.
+const DETERMINISTIC_NOW: Duration = Duration::from_secs(946_684_800);
+
+/// Replan deadline, in days from now, when no target gives one.
+const REPLAN_DEFAULT_DAYS: u32 = 14;
+
+/// Statuses `e` steps through; any other status steps to the first.
+const STATUS_CYCLE: [&str; 4] = ["todo", "in_progress", "blocked", "done"];
+
+/// How often the header's memory figure is measured; it walks every retained snapshot.
+const RETAINED_EVERY: Duration = Duration::from_secs(5);
+
+/// How long `L` defers a task when no day is given.
+const DEFAULT_DEFER: Duration = Duration::from_secs(7 * 86_400);
+
+/// Share of tasks (in percent) a reload may change before the large-change banner asks for
+/// confirmation, unless `large_diff_percent` is configured.
+const DEFAULT_LARGE_DIFF_PERCENT: f64 = 50.0;
+/// Smaller plans are left alone: any edit would be a large share of them.
+const LARGE_DIFF_MIN_TASKS: usize = 5;
+
+/// A reload that rewrote much of the file, waiting for the user to accept it (`a`).
+pub struct LargeDiff {
+    /// The last accepted load; later reloads are compared against it until accepted.
+    baseline: TaskFile,
+    pub diff: TaskDiff,
+    /// Selected task when the change arrived, reselected on accept if it still exists.
+    selected: Option<String>,
+}
+
+/// Files with at least this many tasks open with the sidebar shown, unless `b` toggled it.
+const SIDEBAR_AUTO_TASKS: usize = 40;
+
+/// Ids kept in the breadcrumb trail; the oldest fall off the front.
+const TRAIL_LEN: usize = 20;
+
+/// Changes the footer ticker cycles through.
+const TICKER_EVENTS: usize = 10;
+
+/// Table columns the header (`H`) sorts by, in display order. The risk score column shown by
+/// `o` is not among them. Profiles pick from all of them; the default table shows the first
+/// seven.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TableColumn {
+    Id,
+    Status,
+    Wait,
+    Priority,
+    Risk,
+    Component,
+    Title,
+    Estimate,
+    SpecRefs,
+    DependsOn,
+    Dod,
+    /// A field outside the schema, from the task's `extra` table (e.g. `owner`, `due`).
+    Field(String),
+}
+
+impl TableColumn {
+    /// The column a profile names; names outside the schema are custom fields.
+    pub fn parse(name: &str) -> TableColumn {
+        match name.trim().to_lowercase().as_str() {
+            "id" => TableColumn::Id,
+            "status" => TableColumn::Status,
+            "wait" => TableColumn::Wait,
+            "priority" | "prio" => TableColumn::Priority,
+            "risk" => TableColumn::Risk,
+            "component" | "comp" => TableColumn::Component,
+            "title" => TableColumn::Title,
+            "estimate_days" | "estimate" => TableColumn::Estimate,
+            "spec_refs" => TableColumn::SpecRefs,
+            "depends_on" => TableColumn::DependsOn,
+            "dod" => TableColumn::Dod,
+            _ => TableColumn::Field(name.trim().to_string()),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            TableColumn::Field(name) => name,
+            _ => self.builtin_label().unwrap_or_default(),
+        }
+    }
+
+    /// The English header of a built-in column, the key for its translation.
+    pub fn builtin_label(&self) -> Option<&'static str> {
+        Some(match self {
+            TableColumn::Id => "ID",
+            TableColumn::Status => "Status",
+            TableColumn::Wait => "Wait",
+            TableColumn::Priority => "Prio",
+            TableColumn::Risk => "Risk",
+            TableColumn::Component => "Comp",
+            TableColumn::Title => "Title",
+            TableColumn::Estimate => "Est",
+            TableColumn::SpecRefs => "Spec",
+            TableColumn::DependsOn => "Deps",
+            TableColumn::Dod => "DoD",
+            TableColumn::Field(_) => return None,
+        })
+    }
+}
+
+/// A sort picked in the table header: ascending is ids and titles A to Z, statuses in workflow
+/// order, priorities from most urgent down (`[priorities]`), risk from low up and fewest unmet dependencies first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColumnSort {
+    pub column: TableColumn,
+    pub descending: bool,
+}
+
+/// Recently viewed tasks (`[`/`]`), like a browser history: jumping from the middle of the trail
+/// drops the entries ahead of it.
+#[derive(Debug, Default)]
+pub struct Trail {
+    ids: VecDeque<String>,
+    /// Index of the current entry in `ids`.
+    pos: usize,
+}
+
+impl Trail {
+    /// Records `id` as the current entry unless it already is.
+    fn visit(&mut self, id: &str) {
+        if self.ids.get(self.pos).is_some_and(|cur| cur == id) {
+            return;
+        }
+        self.ids.truncate(self.pos + 1);
+        self.ids.push_back(id.to_string());
+        if self.ids.len() > TRAIL_LEN {
+            self.ids.pop_front();
+        }
+        self.pos = self.ids.len() - 1;
+    }
+
+    fn back(&mut self) -> Option<String> {
+        self.pos = self.pos.checked_sub(1)?;
+        self.ids.get(self.pos).cloned()
+    }
+
+    fn forward(&mut self) -> Option<String> {
+        let next = self.ids.get(self.pos + 1)?.clone();
+        self.pos += 1;
+        Some(next)
+    }
+
+    /// The trail, oldest first, with the index of the current entry.
+    pub fn entries(&self) -> (impl Iterator<Item = &str> + '_, usize) {
+        (self.ids.iter().map(String::as_str), self.pos)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+pub struct App {
+    pub path: PathBuf,
+    pub notes_path: PathBuf,
+    pub view: ViewState,
+    /// The current load as the dashboard shows it: scoped to `focus` when one is set.
+    pub loaded: Option<LoadedTasks>,
+    /// Component the dashboard is scoped to (`--component`, `f`), as a `component_key`.
+    pub focus: Option<String>,
+    /// The whole file while `focus` is set; reloads, history and edits work on this.
+    unscoped: Option<LoadedTasks>,
+    /// Active table filter (`/`), if any.
+    pub filter: Option<Filter>,
+    /// The table is ordered by risk score (`o`) instead of file order.
+    pub sort_by_risk: bool,
+    /// The table is ordered by a column picked in its header (`H`); excludes `sort_by_risk`.
+    pub column_sort: Option<ColumnSort>,
+    /// UI language (`language` in the config, or the locale).
+    pub lang: Lang,
+    /// Name of the active view profile (`--profile`, `V`), a key of `config.profiles`.
+    pub profile: Option<String>,
+    /// Indices into `loaded.tasks.tasks` that pass the filter, in display order. The table and
+    /// `view.selected_idx` work on this list.
+    pub visible: Vec<usize>,
+    /// Tasks the filter lets through that are deferred (`tasks::is_deferred`). They sort to the
+    /// end of `visible`, and are left out of it while `view.show_deferred` is off.
+    pub deferred: usize,
+    pub notes: NotesFile,
+    /// Background loads; `None` loads inline.
+    loader: Option<Loader>,
+    /// When the worker was handed the load it is running, for the header spinner.
+    pub loading: Option<Instant>,
+    /// Debounce wait of the load on the worker, for the watch log.
+    load_waited: Duration,
+    /// Running focus session (`t`).
+    pub timer: Option<FocusTimer>,
+    /// Why the last load failed; cleared by the next good one.
+    pub load_error: Option<TimedError>,
+    /// The last error from the file watcher; cleared once its events come in again.
+    pub watch_error: Option<TimedError>,
+    pub notes_error: Option<String>,
+    /// Why the last write of the config's `status_file` failed.
+    pub status_file_error: Option<String>,
+    /// This session's counts for the usage statistics, when `[usage]` is enabled.
+    pub usage: Option<Session>,
+    pub config: Config,
+    pub history: History,
+    pub history_error: Option<String>,
+    /// State files that failed to load at startup (`!`); see `safe`.
+    pub safe_mode: Vec<Failure>,
+    /// Every current alert, dismissed ones included; the header shows `active_alerts`.
+    pub alerts: Vec<Alert>,
+    /// Done tasks whose spec sections changed after they were marked done (`D`).
+    pub drift: Vec<Drift>,
+    /// Newest changes from the history, for the footer ticker (`[ticker]`); newest first.
+    pub ticker: Vec<FeedEntry>,
+    /// Set while a large change waits for confirmation; cues stay silent meanwhile.
+    pub large_diff: Option<LargeDiff>,
+    /// Progress targets from `[[meta.targets]]` and the config, as of the last load.
+    pub targets: Vec<TargetProgress>,
+    /// The remaining work scheduled over `parallelism` workers, when the config sets it.
+    pub makespan: Option<Makespan>,
+    /// Tasks finished per person according to git, read when the stats panel opens; `None`
+    /// outside a repository.
+    pub contributors: Option<Result<Vec<Contributor>, String>>,
+    /// Why a `[[meta.targets]]` entry was skipped.
+    pub target_error: Option<String>,
+    /// Edits not yet written to the file (`w` reviews them, `W` writes them). `loaded` shows them
+    /// layered over the file.
+    pub staged: Vec<Edit>,
+    /// The file as loaded, while `staged` is not empty. The staged view keeps its content hash,
+    /// so writes are still checked against the file.
+    disk: Option<LoadedTasks>,
+    /// Set by the first `q` with edits staged; the second one quits.
+    quit_warned: bool,
+    /// Typeahead (`'`): the prefix typed so far. While it is on, characters extend it instead
+    /// of acting as keys.
+    typeahead: Option<String>,
+    /// `retained_bytes` as last measured by `measure_retained`, for the header.
+    retained: Option<(Instant, usize)>,
+    /// Set when the overview (`0`) drills into another project; the dashboard then reopens on
+    /// that file.
+    pub switch_to: Option<PathBuf>,
+    /// The config file the palette's "edit the config" opens; `None` in the demo.
+    pub config_path: Option<PathBuf>,
+    /// Set by that command: the dashboard hands the terminal to the editor, then reopens.
+    pub edit_config: bool,
+    /// Alert task ids already announced with a chime, so each breach chimes once.
+    alerted: HashSet<String>,
+    pub reload_requested_at: Option<Instant>,
+    pub last_load_attempt: Instant,
+    /// The pending reload skips the rest of its debounce window (`F5`).
+    reload_now: bool,
+    /// The periodic reload is off (`A`); file events still reload.
+    pub refresh_paused: bool,
+    /// Tasks reached by jumps (`:`, `g`, the problems panel, the control socket).
+    pub trail: Trail,
+    /// Task id to select once it shows up in a load (from `--select` or `:`).
+    pub pending_select: Option<String>,
+    /// One-line feedback for the last user action, shown in the footer.
+    pub status_msg: Option<String>,
+    /// What the file watcher is tracking, shown in the header.
+    pub watch_desc: Option<String>,
+    /// Set in `--demo`: data comes from the embedded sample and nothing touches the disk.
+    pub demo: Option<Tour>,
+    /// A generated plan shown instead of the bundled one (`--demo --tasks`).
+    demo_plan: Option<String>,
+    /// `--deterministic`: a fixed clock, load timestamps pinned to it and no history file, so
+    /// the same tasks file always renders the same.
+    deterministic: bool,
+    /// What the terminal can show; screens are brought down to it after drawing.
+    pub caps: Caps,
+    /// Watcher events, debounce windows and load attempts, for the debug view (`F12`).
+    pub watch_log: WatchLog,
+    /// Cue volume in percent (`v` cycles it); 0 is muted.
+    pub volume: u8,
+    last_content_hash: Option<u64>,
+    /// What the last reload that changed the content changed.
+    pub last_change: Option<ChangeClass>,
+}
+
+impl App {
+    pub fn new(path: PathBuf) -> Self {
+        let notes_path = notes_path(&path);
+        let history_file = history_path(&path);
+        let (history, history_error) = match History::load(&history_file) {
+            Ok(h) => (h, None),
+            Err(e) => (History::new(&history_file), Some(e.to_string())),
+        };
+        Self::with_history(path, notes_path, history, history_error)
+    }
+
+    /// The `--demo` app: embedded sample data, in-memory history and annotations.
+    /// `plan` replaces the bundled sample; the tour, which is about the sample, then starts
+    /// hidden.
+    pub fn demo(plan: Option<String>) -> Self {
+        let path = PathBuf::from(DEMO_PATH);
+        let history = History::new(&history_path(&path));
+        let mut app = Self::with_history(path.clone(), notes_path(&path), history, None);
+        app.demo = Some(Tour {
+            visible: plan.is_none(),
+            ..Tour::default()
+        });
+        app.demo_plan = plan;
+        app
+    }
+
+    /// Swaps the demo's plan for `plan` (the browser view's latest copy of the served file)
+    /// and reloads it at the next `maybe_reload`.
+    pub fn replace_demo_plan(&mut self, plan: String) {
+        self.demo_plan = Some(plan);
+        self.reload_requested_at = Some(Instant::now());
+        self.reload_now = true;
+    }
+
+    fn with_history(
+        path: PathBuf,
+        notes_path: PathBuf,
+        history: History,
+        history_error: Option<String>,
+    ) -> Self {
+        Self {
+            path,
+            notes_path,
+            view: ViewState {
+                selected_idx: 0,
+                details_scroll: 0,
+                compact_details: false,
+                compact_header: false,
+                weighted_progress: false,
+                sidebar: None,
+                show_deferred: false,
+                sidebar_focus: false,
+                sidebar_idx: 0,
+                modal: None,
+                header_focus: None,
+                pane_cache: Default::default(),
+                chart_slots: Default::default(),
+                watch_log_rows: Default::default(),
+            },
+            loaded: None,
+            focus: None,
+            unscoped: None,
+            filter: None,
+            sort_by_risk: false,
+            column_sort: None,
+            lang: Lang::default(),
+            profile: None,
+            visible: Vec::new(),
+            deferred: 0,
+            notes: NotesFile::default(),
+            timer: None,
+            loader: None,
+            loading: None,
+            load_waited: Duration::ZERO,
+            load_error: None,
+            watch_error: None,
+            notes_error: None,
+            status_file_error: None,
+            usage: None,
+            config: Config::default(),
+            history,
+            history_error,
+            safe_mode: Vec::new(),
+            alerts: Vec::new(),
+            drift: Vec::new(),
+            ticker: Vec::new(),
+            targets: Vec::new(),
+            makespan: None,
+            contributors: None,
+            target_error: None,
+            large_diff: None,
+            staged: Vec::new(),
+            disk: None,
+            quit_warned: false,
+            typeahead: None,
+            retained: None,
+            switch_to: None,
+            config_path: None,
+            edit_config: false,
+            alerted: HashSet::new(),
+            reload_requested_at: Some(Instant::now()),
+            last_load_attempt: Instant::now(),
+            reload_now: false,
+            refresh_paused: false,
+            trail: Trail::default(),
+            pending_select: None,
+            status_msg: None,
+            watch_desc: None,
+            demo: None,
+            demo_plan: None,
+            deterministic: false,
+            caps: Caps::default(),
+            watch_log: WatchLog::default(),
+            volume: 100,
+            last_content_hash: None,
+            last_change: None,
+        }
+    }
+
+    /// Records a file watcher error without touching the load error.
+    pub fn set_watch_error(&mut self, message: String) {
+        self.watch_log.push("error", message.clone());
+        self.watch_error = Some(TimedError {
+            at: self.now(),
+            message,
+        });
+    }
+
+    /// A watcher event came in, so the watcher works again after an error.
+    pub fn watcher_resumed(&mut self) {
+        if self.watch_error.take().is_some() {
+            self.watch_log.push("error", "cleared: events resumed");
+        }
+    }
+
+    /// Runs a pending reload once the debounce window has passed. Returns a cue when something
+    /// audible happened: a new alert was raised, or else the content changed.
+    pub fn maybe_reload(&mut self, debounce: Duration, auto_refresh: Duration) -> Option<Cue> {
+        if self.reload_requested_at.is_none()
+            && !self.refresh_paused
+            && self.last_load_attempt.elapsed() >= auto_refresh
+        {
+            self.reload_requested_at = Some(Instant::now());
+            self.watch_log
+                .push("debounce", "started by periodic refresh");
+        }
+
+        if let Some(result) = self.loader.as_mut().and_then(Loader::poll) {
+            if let Some(since) = self.loading.take() {
+                let took = since.elapsed().as_millis();
+                self.watch_log
+                    .push("load", format!("worker finished after {took}ms"));
+            }
+            return self.apply_load(result, self.load_waited);
+        }
+
+        let t0 = self.reload_requested_at?;
+        if t0.elapsed() < debounce && !self.reload_now {
+            return None;
+        }
+        self.reload_requested_at = None;
+        self.reload_now = false;
+        self.last_load_attempt = Instant::now();
+        let waited = t0.elapsed();
+
+        if self.demo.is_some() {
+            return self.apply_load(load_demo(self.demo_plan.as_deref()), waited);
+        }
+        self.reload_notes();
+        if let Some(loader) = &mut self.loader {
+            if loader.request(self.path.clone(), self.config.projects.clone()) {
+                let superseded = self.loading.replace(Instant::now()).is_some();
+                self.load_waited = waited;
+                self.watch_log.push(
+                    "load",
+                    if superseded {
+                        "requested on the worker, superseding the running one"
+                    } else {
+                        "requested on the worker"
+                    },
+                );
+                return None;
+            }
+            // The worker is gone; load here from now on.
+            self.loader = None;
+            self.loading = None;
+        }
+        let result = load(&self.path, self.on_disk(), &self.config.projects);
+        self.apply_load(result, waited)
+    }
+
+    /// Puts a worker thread in charge of loading, so a slow parse never blocks input. Without
+    /// it (render, demo, deterministic mode) loads happen inline in `maybe_reload`.
+    pub fn start_loader(&mut self) {
+        if !self.deterministic {
+            self.loader = Some(Loader::spawn());
+        }
+    }
+
+    /// Applies a finished load: history, alerts, the large-change guard and the cue to play.
+    fn apply_load(&mut self, result: Result<LoadedTasks>, waited: Duration) -> Option<Cue> {
+        match result {
+            Ok(mut next) => {
+                if self.deterministic {
+                    next.loaded_at = self.now();
+                    next.file_mtime = next.file_mtime.map(|_| self.now());
+                    // Checked against the real clock and mtime, so they would vary between runs.
+                    next.problems.retain(|p| {
+                        !matches!(p.kind, ProblemKind::Timestamp | ProblemKind::StaleGeneration)
+                    });
+                }
+                self.config.check(&self.path, &mut next);
+                let changed = matches!(self.last_content_hash, Some(prev) if prev != next.content_hash);
+                let class = changed.then(|| {
+                    self.on_disk()
+                        .map_or(ChangeClass::Structural, |prev| classify(prev, &next))
+                });
+                self.watch_log.push(
+                    "load",
+                    format!(
+                        "ok after {}ms debounce: {} ({:?})",
+                        waited.as_millis(),
+                        match (self.last_content_hash, class) {
+                            (None, _) => "first load".to_string(),
+                            (Some(_), Some(class)) => format!("content changed, {}", class.key()),
+                            (Some(_), None) => "content unchanged".to_string(),
+                        },
+                        next.parse_mode
+                    ),
+                );
+                if class.is_some() {
+                    self.last_change = class;
+                }
+                // Reformatting cannot rewrite the plan, so it skips the large-change check.
+                let upheaval =
+                    class > Some(ChangeClass::Cosmetic) && self.check_large_diff(&next.tasks);
+                let broken = match (class, self.on_disk(), &self.demo) {
+                    (Some(_), Some(prev), None) => new_missing_deps(&prev.tasks, &next.tasks),
+                    _ => Vec::new(),
+                };
+                self.last_content_hash = Some(next.content_hash);
+                let next = self.overlay_staged(next);
+                self.unscoped = None;
+                self.loaded = Some(next);
+                self.apply_focus();
+                self.load_error = None;
+                self.refresh_visible();
+                self.clamp_selection();
+                self.refresh_replan();
+                self.refresh_staged_diff();
+                if let Some(id) = self.pending_select.take() {
+                    self.select_id(&id);
+                }
+                self.open_triage(broken);
+                let new_alerts = self.refresh_history_and_alerts();
+                self.write_status_file();
+                let chime = class.is_some_and(|c| self.config.sound.chimes_on(c));
+                match (upheaval, self.large_diff.is_some(), new_alerts, chime) {
+                    (true, ..) => Some(Cue::Alert),
+                    // Rate limit: one cue for the upheaval, none until it is accepted.
+                    (false, true, ..) => None,
+                    (false, false, true, _) => Some(Cue::Alert),
+                    (false, false, false, true) => Some(Cue::Changed),
+                    _ => None,
+                }
+            }
+            Err(e) => {
+                self.watch_log.push(
+                    "load",
+                    format!("failed after {}ms debounce: {e}", waited.as_millis()),
+                );
+                self.load_error = Some(TimedError {
+                    at: self.now(),
+                    message: e.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Rewrites the config's `status_file` for the file as it is on disk, whatever the focus
+    /// and staged edits. Demo and deterministic runs leave it alone.
+    fn write_status_file(&mut self) {
+        let (Some(path), None, false) = (&self.config.status_file, &self.demo, self.deterministic)
+        else {
+            return;
+        };
+        let Some(disk) = self.on_disk() else {
+            return;
+        };
+        self.status_file_error = write_status_file(path, disk, self.now())
+            .err()
+            .map(|e| format!("{e:#}"));
+    }
+
+    /// Starts counting this session for the usage statistics when the config enables them.
+    /// Demo and deterministic runs are not counted.
+    pub fn start_usage(&mut self) {
+        if self.config.usage.enabled && self.demo.is_none() && !self.deterministic {
+            let mut session = Session::new(self.now());
+            session.profile(self.profile.as_deref());
+            self.usage = Some(session);
+        }
+    }
+
+    /// Enters safe mode when a state file failed to load: `failures` (the config's, found by
+    /// the caller) plus a history file that could not be read or has broken lines, and a
+    /// usage file that cannot be read. The panel listing them opens.
+    pub fn check_state_files(&mut self, mut failures: Vec<Failure>) {
+        if self.demo.is_none() && !self.deterministic {
+            let history = history_path(&self.path);
+            if let Some(e) = &self.history_error {
+                failures.push(Failure::new(StateFile::History, &history, e.clone()));
+            } else if self.history.unreadable > 0 {
+                let error = match self.history.unreadable {
+                    1 => "1 line is not a snapshot".to_string(),
+                    n => format!("{n} lines are not snapshots"),
+                };
+                failures.push(Failure::new(StateFile::History, &history, error));
+            }
+            if let (true, Some(path)) = (self.config.usage.enabled, self.config.usage.path()) {
+                if let Err(e) = usage::load(&path) {
+                    failures.push(Failure::new(StateFile::Usage, &path, format!("{e:#}")));
+                }
+            }
+        }
+        if !failures.is_empty() {
+            self.view.modal = Some(Modal::SafeMode { selected: 0 });
+        }
+        self.safe_mode = failures;
+    }
+
+    /// True while a failed state file has not been moved aside.
+    pub fn in_safe_mode(&self) -> bool {
+        self.safe_mode.iter().any(|f| f.moved_to.is_none())
+    }
+
+    /// `r` in the safe mode panel: moves the failed file aside; a history file is read again
+    /// from what it kept.
+    fn reset_state_file(&mut self, i: usize) {
+        let Some(failure) = self.safe_mode.get(i).filter(|f| f.moved_to.is_none()) else {
+            return;
+        };
+        let aside = match reset(failure) {
+            Ok(aside) => aside,
+            Err(e) => {
+                self.say_with("reset failed: {}", &[&format!("{e:#}")]);
+                return;
+            }
+        };
+        if failure.file == StateFile::History {
+            match History::load_limited(&failure.path, self.config.retention.history()) {
+                Ok(history) => {
+                    self.history = history;
+                    self.history_error = None;
+                }
+                Err(e) => self.history_error = Some(e.to_string()),
+            }
+        }
+        self.status_msg = Some(trf(
+            self.lang,
+            "moved {} to {}",
+            &[&failure.path.display(), &aside.display()],
+        ));
+        self.safe_mode[i].moved_to = Some(aside);
+    }
+
+    /// Counts the panel or modal the last key opened, for the usage statistics.
+    pub fn record_view(&mut self) {
+        let kind = self.view.modal.as_ref().map(Modal::kind);
+        if let Some(usage) = &mut self.usage {
+            usage.view(kind);
+        }
+    }
+
+    /// Compares `next` with the last accepted load and raises, updates or (when the file is
+    /// close to the baseline again) clears the large-change banner. Returns true when raised.
+    fn check_large_diff(&mut self, next: &TaskFile) -> bool {
+        let threshold = self
+            .config
+            .large_diff_percent
+            .unwrap_or(DEFAULT_LARGE_DIFF_PERCENT);
+        let baseline = match (&self.large_diff, self.on_disk()) {
+            (Some(pending), _) => &pending.baseline,
+            (None, Some(l)) => &l.tasks,
+            (None, None) => return false,
+        };
+        let diff = diff_tasks(baseline, next);
+        let large = baseline.tasks.len() >= LARGE_DIFF_MIN_TASKS && diff.percent() > threshold;
+        let fresh = (large && self.large_diff.is_none()).then(|| baseline.clone());
+        match (large, fresh) {
+            (true, Some(baseline)) => {
+                self.large_diff = Some(LargeDiff {
+                    baseline,
+                    diff,
+                    selected: self.selected_id(),
+                });
+                true
+            }
+            (true, None) => {
+                if let Some(pending) = &mut self.large_diff {
+                    pending.diff = diff;
+                }
+                false
+            }
+            (false, _) => {
+                self.large_diff = None;
+                false
+            }
+        }
+    }
+
+    /// Accepts a large change as the new baseline, reselecting the task that was selected
+    /// before it when it survived.
+    fn accept_large_diff(&mut self) {
+        let Some(pending) = self.large_diff.take() else {
+            return;
+        };
+        self.view.details_scroll = 0;
+        let kept = pending.selected.is_some_and(|id| self.select_id(&id));
+        if !kept {
+            self.view.selected_idx = 0;
+        }
+        self.say("accepted the change as the new baseline");
+    }
+
+    /// Records a history snapshot for the current load and recomputes alerts. Returns true when
+    /// an alert appeared that was not announced before.
+    fn refresh_history_and_alerts(&mut self) -> bool {
+        let Some(loaded) = &self.loaded else {
+            return false;
+        };
+        let on_disk = self
+            .disk
+            .as_ref()
+            .or(self.unscoped.as_ref())
+            .or(Some(loaded));
+        if let (None, false, Some(full)) = (&self.demo, self.deterministic, on_disk) {
+            match self.history.record(full) {
+                Ok(()) => self.history_error = None,
+                Err(e) => self.history_error = Some(e.to_string()),
+            }
+        }
+        self.alerts = blocked_sla_alerts(loaded, &self.history, &self.config, self.now());
+        self.alerts.extend(resurfaced_alerts(loaded, self.now()));
+        self.drift = spec_drift(loaded, &self.history);
+        if self.config.ticker.enabled {
+            self.ticker = feed_entries(&self.history);
+            self.ticker.truncate(TICKER_EVENTS);
+        }
+        self.refresh_targets();
+        self.forget_stale_dismissals();
+
+        // A dismissed alert never chimes, nor does it when brought back.
+        let fresh = self
+            .active_alerts()
+            .into_iter()
+            .filter_map(|a| a.task_id.as_ref())
+            .any(|id| !self.alerted.contains(id));
+        self.alerted = self
+            .alerts
+            .iter()
+            .filter_map(|a| a.task_id.clone())
+            .collect();
+        fresh
+    }
+
+    /// Alerts not dismissed from the problems panel.
+    pub fn active_alerts(&self) -> Vec<&Alert> {
+        self.alerts
+            .iter()
+            .filter(|a| !self.notes.is_dismissed(&a.fingerprint))
+            .collect()
+    }
+
+    /// Spec drift not dismissed from the drift panel.
+    pub fn active_drift(&self) -> Vec<&Drift> {
+        self.drift
+            .iter()
+            .filter(|d| !self.notes.is_dismissed(&d.fingerprint))
+            .collect()
+    }
+
+    /// Whether the file's `generated_utc` problems were dismissed, which stops the header
+    /// warning about them.
+    pub fn timestamp_dismissed(&self) -> bool {
+        self.loaded
+            .as_ref()
+            .and_then(|l| timestamp_fingerprint(&l.tasks))
+            .is_some_and(|fp| self.notes.is_dismissed(&fp))
+    }
+
+    /// Drops the dismissals whose alert, drift or `generated_utc` problem the file on disk no
+    /// longer has, staged edits and focus aside. Skipped while the history failed to record,
+    /// since the alerts and drift depend on it.
+    fn forget_stale_dismissals(&mut self) {
+        if self.notes.dismissed.is_empty() || self.history_error.is_some() {
+            return;
+        }
+        let full = self.disk.as_ref().or(self.unscoped.as_ref());
+        let Some(full) = full.or(self.loaded.as_ref()) else {
+            return;
+        };
+        let now = self.now();
+        let mut current: HashSet<String> =
+            blocked_sla_alerts(full, &self.history, &self.config, now)
+                .into_iter()
+                .chain(resurfaced_alerts(full, now))
+                .map(|a| a.fingerprint)
+                .collect();
+        current.extend(spec_drift(full, &self.history).into_iter().map(|d| d.fingerprint));
+        current.extend(timestamp_fingerprint(&full.tasks));
+        if self.notes.dismissed.iter().all(|d| current.contains(&d.fingerprint)) {
+            return;
+        }
+        if self.demo.is_some() {
+            self.notes.dismissed.retain(|d| current.contains(&d.fingerprint));
+            return;
+        }
+        match prune_dismissed(&self.notes_path, &current) {
+            Ok(notes) => {
+                self.notes = notes;
+                self.notes_error = None;
+            }
+            Err(e) => self.notes_error = Some(e.to_string()),
+        }
+    }
+
+    /// `d` in the problems and drift panels: dismisses an alert until its condition changes,
+    /// or brings a dismissed one back. Kept in the annotations file.
+    fn toggle_dismissed(&mut self, fingerprint: Option<String>) {
+        let Some(fp) = fingerprint else {
+            self.say("only alerts can be dismissed");
+            return;
+        };
+        let back = self.notes.is_dismissed(&fp);
+        if self.demo.is_some() {
+            self.notes.toggle_dismissed(&fp);
+        } else {
+            match toggle_dismissed(&self.notes_path, &fp) {
+                Ok(notes) => {
+                    self.notes = notes;
+                    self.notes_error = None;
+                }
+                Err(e) => {
+                    self.notes_error = Some(e.to_string());
+                    return;
+                }
+            }
+        }
+        self.say(if back {
+            "alert shown again"
+        } else {
+            "alert dismissed until its condition changes (d again brings it back)"
+        });
+    }
+
+    /// Tracks the plan's targets followed by the config's. Invalid plan targets are skipped and
+    /// reported in `target_error`.
+    fn refresh_targets(&mut self) {
+        let Some(loaded) = &self.loaded else {
+            return;
+        };
+        let mut targets = Vec::new();
+        self.target_error = None;
+        let specs = loaded.tasks.meta.iter().flat_map(|m| m.targets.iter());
+        for (i, spec) in specs.enumerate() {
+            match parse_target(spec) {
+                Ok(t) => targets.push(t),
+                Err(e) => self.target_error = Some(format!("meta.targets[{i}]: {e}")),
+            }
+        }
+        targets.extend(self.config.targets.iter().cloned());
+        let in_view: Option<HashSet<&str>> = self
+            .focus
+            .as_ref()
+            .map(|_| loaded.tasks.tasks.iter().map(|t| t.id.as_str()).collect());
+        self.targets = track_targets(
+            &targets,
+            loaded,
+            &self.history,
+            |id| in_view.as_ref().is_none_or(|ids| ids.contains(id)),
+            self.now(),
+        );
+    }
+
+    /// Alerts followed by validation problems, most severe first, as listed in the Problems
+    /// panel.
+    pub fn panel_entries(&self) -> Vec<PanelEntry> {
+        let mut out: Vec<PanelEntry> = self
+            .alerts
+            .iter()
+            .map(|a| PanelEntry {
+                label: match a.kind {
+                    AlertKind::BlockedSla => "SLA",
+                    AlertKind::Resurfaced => "back",
+                },
+                alert: true,
+                severity: match a.kind {
+                    AlertKind::BlockedSla => Severity::Error,
+                    AlertKind::Resurfaced => Severity::Warning,
+                },
+                task_id: a.task_id.clone(),
+                message: a.message.clone(),
+                fix: None,
+                fingerprint: Some(a.fingerprint.clone()),
+            })
+            .collect();
+        if let Some(l) = &self.loaded {
+            let mut problems: Vec<&Problem> = l.problems.iter().collect();
+            problems.sort_by_key(|p| p.severity());
+            out.extend(problems.into_iter().map(|p| PanelEntry {
+                label: p.kind.label(),
+                alert: false,
+                severity: p.severity(),
+                // A repeated id jumps to the repeat, not to the first task using it.
+                task_id: match &p.fix {
+                    Some(Fix::RenameDuplicateId { id, occurrence, .. }) => {
+                        Some(display_id(id, *occurrence))
+                    }
+                    _ => p.task_ids.first().cloned(),
+                },
+                message: p.message.clone(),
+                fix: p.fix.clone(),
+                fingerprint: match p.kind {
+                    ProblemKind::Timestamp | ProblemKind::StaleGeneration => {
+                        timestamp_fingerprint(&l.tasks)
+                    }
+                    _ => None,
+                },
+            }));
+        }
+        out
+    }
+
+    /// The whole file, regardless of `focus`.
+    pub fn full(&self) -> Option<&LoadedTasks> {
+        self.unscoped.as_ref().or(self.loaded.as_ref())
+    }
+
+    /// Who signs comments: the config's `author`, else the login name.
+    pub fn comment_author(&self) -> String {
+        self.config
+            .author
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "anonymous".to_string())
+    }
+
+    fn handle_comment_key(&mut self, code: KeyCode) {
+        let count = self
+            .selected_id()
+            .map_or(0, |id| self.notes.comment_threads(&id).len());
+        let Some(Modal::Comment { reply, input }) = &mut self.view.modal else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.view.modal = None,
+            KeyCode::Up => *reply = reply.saturating_sub(1),
+            KeyCode::Down => *reply = (*reply + 1).min(count),
+            KeyCode::Enter => {
+                let (reply, body) = (*reply, input.trim().to_string());
+                // An empty comment is not posted; the modal stays open.
+                if !body.is_empty() {
+                    self.view.modal = None;
+                    self.post_comment(reply, body);
+                }
+            }
+            _ => edit_input(input, code),
+        }
+    }
+
+    /// Writes the tasks the table shows, in its order, next to the tasks file as
+    /// `<stem>.view.<ext>` (`x`). Focus and filter narrow it exactly as they narrow the table.
+    fn export_view(&mut self, selected: usize) {
+        let Some(&(format, name, ext)) = VIEW_FORMATS.get(selected) else {
+            return;
+        };
+        if self.demo.is_some() {
+            self.say("demo: exporting is disabled");
+            return;
+        }
+        let Some(loaded) = &self.loaded else {
+            return;
+        };
+        let view = select_tasks(loaded, &self.visible);
+        let cost = self
+            .config
+            .day_rate
+            .map(|rate| cost_rollup(&view.tasks, rate));
+        let path = self.path.with_extension(format!("view.{ext}"));
+        let mut out = Vec::new();
+        let written = write_export(&mut out, &view, format, cost.as_ref())
+            .and_then(|()| write_atomic(&path, &String::from_utf8_lossy(&out)));
+        self.status_msg = Some(match written {
+            Ok(()) => trf(
+                self.lang,
+                "exported {} tasks as {} to {}",
+                &[&view.tasks.tasks.len(), &name, &path.display()],
+            ),
+            Err(e) => trf(self.lang, "export failed: {}", &[&format!("{e:#}")]),
+        });
+    }
+
+    /// Adds a comment to the selected task, answering the `reply`-th comment in thread order
+    /// (0 starts a thread).
+    fn post_comment(&mut self, reply: usize, body: String) {
+        let Some(id) = self.selected_id() else {
+            return;
+        };
+        let reply_to = reply
+            .checked_sub(1)
+            .and_then(|i| self.notes.comment_threads(&id).get(i).map(|(_, c)| c.n));
+        let author = self.comment_author();
+        if self.demo.is_some() {
+            let at = humantime::format_rfc3339_seconds(self.now()).to_string();
+            let n = self.notes.next_comment_n(&id);
+            self.notes.comments.push(Comment {
+                id,
+                n,
+                reply_to,
+                author,
+                at,
+                body,
+            });
+            return;
+        }
+        match append_comment(&self.notes_path, &id, reply_to, &author, &body) {
+            Ok(notes) => {
+                self.notes = notes;
+                self.notes_error = None;
+            }
+            Err(e) => self.notes_error = Some(e.to_string()),
+        }
+    }
+
+    /// Which ticker change is showing: whole rotation periods since the epoch. `None` while the
+    /// ticker is off or has nothing to show; the main loop redraws when it moves on.
+    pub fn ticker_slot(&self) -> Option<u64> {
+        if !self.config.ticker.enabled || self.ticker.is_empty() {
+            return None;
+        }
+        let elapsed = self.now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Some(elapsed.as_secs() / self.config.ticker.rotate().as_secs().max(1))
+    }
+
+    /// The change the ticker shows now, with its position in the list.
+    pub fn ticker_entry(&self) -> Option<(usize, &FeedEntry)> {
+        let slot = self.ticker_slot()?;
+        let i = (slot % self.ticker.len() as u64) as usize;
+        Some((i, &self.ticker[i]))
+    }
+
+    /// Wall-clock time, or the fixed `DETERMINISTIC_NOW` in deterministic mode.
+    pub fn now(&self) -> SystemTime {
+        if self.deterministic {
+            UNIX_EPOCH + DETERMINISTIC_NOW
+        } else {
+            clock::now()
+        }
+    }
+
+    /// The config's `language`, else the locale. Deterministic runs skip the locale so renders
+    /// match across machines.
+    pub fn apply_language(&mut self) {
+        self.lang = match (self.config.language, self.deterministic) {
+            (Some(lang), _) => lang,
+            (None, true) => Lang::En,
+            (None, false) => Lang::from_env(),
+        };
+    }
+
+    /// Bounds the history and the watch log by `[retention]`.
+    pub fn apply_retention(&mut self) {
+        let retention = &self.config.retention;
+        self.watch_log.set_limit(retention.watch_log());
+        if let Err(e) = self.history.set_limit(retention.history()) {
+            self.history_error = Some(e.to_string());
+        }
+    }
+
+    /// Rough size of what the dashboard keeps between loads: history snapshots, the watch log,
+    /// the ticker and the trail. Loads themselves are not counted.
+    pub fn retained_bytes(&self) -> usize {
+        let ticker: usize = self
+            .ticker
+            .iter()
+            .map(|e| std::mem::size_of::<FeedEntry>() + e.task.len() + e.detail.len() + e.at.len())
+            .sum();
+        let trail: usize = self.trail.ids.iter().map(|id| 24 + id.len()).sum();
+        self.history.retained_bytes() + self.watch_log.retained_bytes() + ticker + trail
+    }
+
+    /// Refreshes the header's memory figure once it is `RETAINED_EVERY` old. Not done in
+    /// `retained_bytes` itself: that is too slow for every frame with a long history.
+    pub fn measure_retained(&mut self) {
+        if self
+            .retained
+            .is_none_or(|(at, _)| at.elapsed() >= RETAINED_EVERY)
+        {
+            self.retained = Some((Instant::now(), self.retained_bytes()));
+        }
+    }
+
+    /// The last `measure_retained` figure; `None` before the first, and with `--deterministic`,
+    /// where it would differ between machines.
+    pub fn retained(&self) -> Option<usize> {
+        self.retained
+            .filter(|_| !self.deterministic)
+            .map(|(_, bytes)| bytes)
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Sets deterministic mode. The history already read from disk is set aside.
+    pub fn set_deterministic(&mut self) {
+        self.deterministic = true;
+        self.history = History::new(&history_path(&self.path));
+        self.history_error = None;
+    }
+
+    /// In ASCII mode `[cells]` glyphs give way to the plain values, which read better than
+    /// their `?` stand-ins.
+    pub fn set_caps(&mut self, caps: Caps) {
+        self.caps = caps;
+        if caps.ascii {
+            self.config.cells.icons = false;
+        }
+    }
+
+    /// The whole file as it is on disk, without the staged edits.
+    pub fn on_disk(&self) -> Option<&LoadedTasks> {
+        self.disk.as_ref().or(self.full())
+    }
+
+    /// Layers the staged edits over a fresh load of the file. Edits that no longer apply are
+    /// dropped and reported.
+    fn overlay_staged(&mut self, disk: LoadedTasks) -> LoadedTasks {
+        self.disk = None;
+        if self.staged.is_empty() {
+            return disk;
+        }
+        let staged = apply_edits(&disk.source, &self.staged).and_then(|(source, skipped)| {
+            let view = parse_tasks(&source, disk.path.clone(), disk.file_mtime, |_| {
+                disk.coverage.clone()
+            })?;
+            Ok((view, skipped))
+        });
+        let (mut view, skipped) = match staged {
+            Ok(staged) => staged,
+            Err(e) => {
+                self.staged.clear();
+                self.say_with("staged edits dropped: {}", &[&e]);
+                return disk;
+            }
+        };
+        if let Some((i, reason)) = skipped.first() {
+            let what = self.staged[*i].describe();
+            self.status_msg = Some(match skipped.len() {
+                1 => trf(self.lang, "staged edit dropped ({}): {}", &[&what, reason]),
+                n => trf(
+                    self.lang,
+                    "{} staged edits dropped, e.g. {}: {}",
+                    &[&n, &what, reason],
+                ),
+            });
+            let skipped: HashSet<usize> = skipped.iter().map(|(i, _)| *i).collect();
+            let mut i = 0;
+            self.staged.retain(|_| {
+                i += 1;
+                !skipped.contains(&(i - 1))
+            });
+        }
+        if self.staged.is_empty() {
+            return disk;
+        }
+        view.content_hash = disk.content_hash;
+        view.loaded_at = disk.loaded_at;
+        link_workspace(&mut view, disk.tasks.workspace.clone());
+        self.config.check(&self.path, &mut view);
+        self.disk = Some(disk);
+        view
+    }
+
+    /// `1`–`9` and `O`: opens the selected task's `n`-th link (from 1) in the browser.
+    fn open_link(&mut self, n: usize) {
+        let Some(t) = self.selected_task() else {
+            return;
+        };
+        let Some(link) = t.links.get(n - 1) else {
+            self.status_msg = Some(match t.links.len() {
+                0 => trf(self.lang, "{} has no links", &[&t.id]),
+                len => trf(self.lang, "{} has {} link(s), not {}", &[&t.id, &len, &n]),
+            });
+            return;
+        };
+        let (name, target) = (link.name().to_string(), browser::target(link, &self.path));
+        self.status_msg = Some(match browser::open(&target) {
+            Ok(()) => trf(self.lang, "opening {}", &[&name]),
+            Err(e) => trf(self.lang, "opening {}: {}", &[&name, &format!("{e:#}")]),
+        });
+    }
+
+    /// `s`: the stats panel, with who finished what read from git again.
+    fn open_stats(&mut self) {
+        self.contributors = self.read_contributors();
+        self.view.modal = Some(Modal::Stats { scroll: 0 });
+    }
+
+    /// `None` in the demo, with `--deterministic` and for a file outside a repository.
+    fn read_contributors(&self) -> Option<Result<Vec<Contributor>, String>> {
+        if self.demo.is_some() || self.deterministic {
+            return None;
+        }
+        let repo = match &self.config.git_repo {
+            Some(repo) => repo.clone(),
+            None => repo_root(self.path.parent()?)?,
+        };
+        let done = completions(&repo, &self.path).map_err(|e| format!("{e:#}"));
+        Some(done.map(|c| by_author(&c)))
+    }
+
+    /// `Q`: the tasks finished within `[review] days`, from the history.
+    fn open_review(&mut self) {
+        let Some(file) = self.full().map(|l| &l.tasks) else {
+            return;
+        };
+        let days = self.config.review.days();
+        let queue = review_queue(&self.history, file, clock::now(), days);
+        if queue.is_empty() {
+            self.say_with("no task was finished in the last {} days", &[&days]);
+            return;
+        }
+        self.view.modal = Some(Modal::Review { queue, selected: 0 });
+    }
+
+    /// Ticks or unticks a DoD item in the review queue, in the annotations file.
+    fn toggle_verified(&mut self, id: &str, item: &str) {
+        let by = self.comment_author();
+        if self.demo.is_some() {
+            self.notes.toggle_verified(id, item, &by);
+            return;
+        }
+        match toggle_verified(&self.notes_path, id, item, &by) {
+            Ok(notes) => {
+                self.notes = notes;
+                self.notes_error = None;
+            }
+            Err(e) => self.notes_error = Some(e.to_string()),
+        }
+    }
+
+    /// `t`: starts a focus session on the selected task, or stops the running one early.
+    fn toggle_timer(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            let session = timer.session();
+            self.status_msg = Some(if session.minutes == 0 {
+                trf(
+                    self.lang,
+                    "focus session on {} stopped (under a minute, not logged)",
+                    &[&timer.task],
+                )
+            } else {
+                trf(
+                    self.lang,
+                    "focus session on {} stopped ({} min logged)",
+                    &[&timer.task, &session.minutes],
+                )
+            });
+            if session.minutes > 0 {
+                self.log_session(session);
+            }
+            return;
+        }
+        let Some(id) = self.selected_id() else {
+            return;
+        };
+        self.say_with(
+            "focus session on {}: {}",
+            &[&id, &format_countdown(FOCUS_SESSION)],
+        );
+        self.timer = Some(FocusTimer::start(id, clock::now(), FOCUS_SESSION));
+    }
+
+    /// Ends the running focus session once its time is up; called every tick.
+    pub fn tick_timer(&mut self) -> Option<Cue> {
+        if !self.timer.as_ref().is_some_and(FocusTimer::is_done) {
+            return None;
+        }
+        let timer = self.timer.take()?;
+        let session = timer.session();
+        self.say_with(
+            "focus session on {} done ({} min logged)",
+            &[&timer.task, &session.minutes],
+        );
+        self.log_session(session);
+        Some(Cue::FocusDone)
+    }
+
+    /// Logs a session stopped by quitting.
+    pub fn stop_timer(&mut self) {
+        if let Some(session) = self.timer.take().map(|t| t.session()) {
+            if session.minutes > 0 {
+                self.log_session(session);
+            }
+        }
+    }
+
+    fn log_session(&mut self, session: FocusSession) {
+        if self.demo.is_some() {
+            self.notes.sessions.push(session);
+            return;
+        }
+        match append_session(&self.notes_path, session) {
+            Ok(notes) => {
+                self.notes = notes;
+                self.notes_error = None;
+            }
+            Err(e) => self.notes_error = Some(e.to_string()),
+        }
+    }
+
+    /// Stages `edit` and shows it right away, keeping the selected task selected. Edits address
+    /// a task by id and land on the first task with it, so a selected repeat (`T-3#2`) is
+    /// refused until its id is fixed.
+    fn stage_edit(&mut self, edit: Edit) {
+        let on_repeat = self.selected_occurrence() > 0
+            && edit.task().is_some_and(|id| self.selected_id().as_deref() == Some(id));
+        if on_repeat {
+            self.say("an earlier task has the same id; rename this one first (p)");
+            return;
+        }
+        let Some(disk) = self.disk.take().or_else(|| self.full().cloned()) else {
+            return;
+        };
+        let selected = self.selected_id();
+        let what = edit.describe();
+        // A task deferred into the folded section leaves the selection on the row after it.
+        let folds = matches!(edit, Edit::Defer { until: Some(_), .. }) && !self.view.show_deferred;
+        stage(&mut self.staged, edit);
+        self.quit_warned = false;
+        let view = self.overlay_staged(disk);
+        self.unscoped = None;
+        self.loaded = Some(view);
+        self.apply_focus();
+        self.refresh_visible();
+        self.clamp_selection();
+        if let Some(id) = selected.filter(|_| !folds) {
+            self.select_id(&id);
+        }
+        if !self.staged.is_empty() {
+            self.say_with("staged {} (w review, W write)", &[&what]);
+        }
+    }
+
+    /// Drops every staged edit (or only the last one) and shows the file as it is.
+    fn unstage(&mut self, all: bool) {
+        if all {
+            self.staged.clear();
+        } else {
+            self.staged.pop();
+        }
+        let Some(disk) = self.disk.take() else {
+            return;
+        };
+        let selected = self.selected_id();
+        let view = self.overlay_staged(disk);
+        self.unscoped = None;
+        self.loaded = Some(view);
+        self.apply_focus();
+        self.refresh_visible();
+        self.clamp_selection();
+        self.refresh_staged_diff();
+        if let Some(id) = selected {
+            self.select_id(&id);
+        }
+    }
+
+    /// The change the staged edits make to the file, line by line.
+    fn staged_diff(&self) -> Vec<Option<DiffLine>> {
+        match (self.on_disk(), self.full()) {
+            (Some(disk), Some(staged)) => line_diff(&disk.source, &staged.source),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Recomputes an open staged-edits modal's diff after the edits or the file changed.
+    fn refresh_staged_diff(&mut self) {
+        if !matches!(self.view.modal, Some(Modal::Staged { .. })) {
+            return;
+        }
+        let next = self.staged_diff();
+        if let Some(Modal::Staged { diff, .. }) = &mut self.view.modal {
+            *diff = next;
+        }
+    }
+
+    /// `W`: writes the staged edits to the file in one atomic replace.
+    fn write_staged(&mut self) {
+        if self.staged.is_empty() {
+            self.say("nothing staged");
+            return;
+        }
+        if self.demo.is_some() {
+            self.say("demo: writing edits to TASKS.toml is disabled");
+            return;
+        }
+        let Some(disk) = self.on_disk() else {
+            return;
+        };
+        let edits = &self.staged;
+        match edit_tasks_file(
+            &self.path,
+            disk.content_hash,
+            self.config.backups(),
+            |doc| edits.iter().try_for_each(|e| e.apply(doc)),
+        ) {
+            Ok(()) => {
+                self.say_with("wrote {} staged edits", &[&self.staged.len()]);
+                self.staged.clear();
+                self.quit_warned = false;
+                self.reload_requested_at = Some(Instant::now());
+            }
+            Err(e) => self.say_with("write failed: {}", &[&e]),
+        }
+    }
+
+    /// Lists dependencies a reload left on missing tasks, adding to an open triage. Another
+    /// modal is not interrupted; the problems panel lists them too.
+    fn open_triage(&mut self, broken: Vec<BrokenEdge>) {
+        if broken.is_empty() {
+            return;
+        }
+        match &mut self.view.modal {
+            Some(Modal::Triage { edges, choices, .. }) => {
+                for edge in broken {
+                    if !edges.iter().any(|e| e.task == edge.task && e.dep == edge.dep) {
+                        edges.push(edge);
+                        choices.push(Resolution::Leave);
+                    }
+                }
+            }
+            None => {
+                self.view.modal = Some(Modal::Triage {
+                    choices: vec![Resolution::Leave; broken.len()],
+                    edges: broken,
+                    selected: 0,
+                })
+            }
+            Some(_) => self.say_with(
+                "the reload left {} dependencies on missing tasks (p lists them)",
+                &[&broken.len()],
+            ),
+        }
+    }
+
+    /// Writes the triage's choices in one atomic replace; nothing is written when one fails.
+    fn write_triage(&mut self, edges: &[BrokenEdge], choices: &[Resolution]) {
+        let edits: Vec<Edit> = edges
+            .iter()
+            .zip(choices)
+            .filter_map(|(e, choice)| e.edit(*choice))
+            .collect();
+        if edits.is_empty() {
+            return;
+        }
+        let Some(disk) = self.on_disk() else {
+            return;
+        };
+        match edit_tasks_file(
+            &self.path,
+            disk.content_hash,
+            self.config.backups(),
+            |doc| edits.iter().try_for_each(|e| e.apply(doc)),
+        ) {
+            Ok(()) => {
+                self.say_with("wrote {} dependency fixes", &[&edits.len()]);
+                self.reload_requested_at = Some(Instant::now());
+            }
+            Err(e) => self.say_with("write failed: {}", &[&e]),
+        }
+    }
+
+    /// `X`: lists the redundant dependencies of the file as shown, staged edits included.
+    fn open_prune(&mut self) {
+        let edges = self
+            .full()
+            .map(|l| redundant_deps(&l.tasks))
+            .unwrap_or_default();
+        if edges.is_empty() {
+            self.say("no redundant dependencies");
+            return;
+        }
+        self.view.modal = Some(Modal::Prune {
+            picked: vec![false; edges.len()],
+            edges,
+            selected: 0,
+        });
+    }
+
+    /// Stages the removal of each of `edges`.
+    fn stage_pruned(&mut self, edges: Vec<Redundant>) {
+        let count = edges.len();
+        for e in edges {
+            self.stage_edit(Edit::DropDependency {
+                task: e.task,
+                dep: e.dep,
+            });
+        }
+        if count > 1 {
+            self.say_with(
+                "staged {} dependency removals (w review, W write)",
+                &[&count],
+            );
+        }
+    }
+
+    /// Dependencies the selected task may be missing, judged on the whole file as shown.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        match (self.full(), self.selected_task()) {
+            (Some(l), Some(t)) => suggested_dependencies(&l.tasks, t),
+            _ => Vec::new(),
+        }
+    }
+
+    /// `+`: stages the first suggested dependency of the selected task.
+    fn accept_suggestion(&mut self) {
+        match self.suggestions().into_iter().next() {
+            Some(s) => self.stage_edit(Edit::AddDependency {
+                task: s.task,
+                dep: s.dep,
+            }),
+            None => self.say("no suggested dependencies for this task"),
+        }
+    }
+
+    /// `R`: opens the replan modal, due at the headline target's deadline when there is one.
+    fn open_replan(&mut self) {
+        let now = self.now();
+        let deadline = headline(&self.targets)
+            .and_then(|t| t.target.by.duration_since(now).ok())
+            .map_or(REPLAN_DEFAULT_DAYS, |d| d.as_secs().div_ceil(86_400) as u32);
+        let workers = self.config.parallelism.unwrap_or(1);
+        self.view.modal = Some(Modal::Replan {
+            deadline,
+            workers,
+            scroll: 0,
+            plan: Box::new(self.replan(deadline, workers)),
+        });
+    }
+
+    /// The replan for `deadline` days out with `workers` in parallel, over the tasks shown.
+    fn replan(&self, deadline: u32, workers: usize) -> Replan {
+        let tasks = self.loaded.as_ref().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+        let spread = self.loaded.as_ref().and_then(|l| estimate_spread(&l.tasks));
+        replan(tasks, workers, f64::from(deadline), spread)
+    }
+
+    /// Recomputes an open replan modal's plan after its inputs or the tasks changed.
+    fn refresh_replan(&mut self) {
+        let Some(Modal::Replan {
+            deadline, workers, ..
+        }) = self.view.modal
+        else {
+            return;
+        };
+        let next = self.replan(deadline, workers);
+        if let Some(Modal::Replan { plan, .. }) = &mut self.view.modal {
+            **plan = next;
+        }
+    }
+
+    /// `e`: stages the selected task's next status in `STATUS_CYCLE`.
+    fn cycle_status(&mut self) {
+        let Some(t) = self.selected_task() else {
+            return;
+        };
+        let current = task_status(t);
+        let next = STATUS_CYCLE
+            .iter()
+            .position(|s| *s == current)
+            .map_or(STATUS_CYCLE[0], |i| {
+                STATUS_CYCLE[(i + 1) % STATUS_CYCLE.len()]
+            });
+        let task = t.id.clone();
+        self.stage_edit(Edit::Status {
+            task,
+            to: next.to_string(),
+        });
+    }
+
+    /// `P`: stages the selected task's next priority among those used in the file.
+    fn cycle_priority(&mut self) {
+        let used = self.field_choices(TaskField::Priority);
+        let Some(t) = self.selected_task() else {
+            return;
+        };
+        if used.len() < 2 {
+            self.say("the file uses fewer than two priorities");
+            return;
+        }
+        let next = t
+            .priority
+            .as_deref()
+            .and_then(|p| used.iter().position(|u| u.eq_ignore_ascii_case(p.trim())))
+            .map_or(0, |i| (i + 1) % used.len());
+        let task = t.id.clone();
+        self.stage_edit(Edit::Priority {
+            task,
+            to: used[next].clone(),
+        });
+    }
+
+    /// The values the file uses for a choice field (priority, component), in file order; a
+    /// value differing only in case counts once.
+    pub fn field_choices(&self, field: TaskField) -> Vec<String> {
+        let mut used: Vec<String> = Vec::new();
+        let tasks = self.full().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+        for t in tasks {
+            let value = match field {
+                TaskField::Priority => t.priority.as_deref(),
+                TaskField::Component => t.component.as_deref(),
+                TaskField::Title | TaskField::Estimate => None,
+            };
+            let Some(v) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            if !used.iter().any(|u| u.eq_ignore_ascii_case(v)) {
+                used.push(v.to_string());
+            }
+        }
+        used
+    }
+
+    /// `E`: opens the inline field editor on the selected task.
+    fn open_field_editor(&mut self) {
+        let Some(task) = self.selected_id() else {
+            return;
+        };
+        self.view.modal = Some(Modal::EditField {
+            task,
+            field: None,
+            selected: 0,
+            input: String::new(),
+            error: None,
+        });
+    }
+
+    fn handle_field_editor_key(&mut self, code: KeyCode) {
+        let Some(Modal::EditField {
+            task,
+            field,
+            mut selected,
+            mut input,
+            mut error,
+        }) = self.view.modal.take()
+        else {
+            return;
+        };
+        // Gone after a reload: the editor closes.
+        let Some(t) = self.task(&task) else {
+            return;
+        };
+        let field = match (field, code) {
+            (None, KeyCode::Esc) => return,
+            (Some(f), KeyCode::Esc) => {
+                // Back to the field picker.
+                selected = TaskField::ALL.iter().position(|a| *a == f).unwrap_or(0);
+                error = None;
+                None
+            }
+            (None, KeyCode::Up) => {
+                selected = selected.saturating_sub(1);
+                None
+            }
+            (None, KeyCode::Down) => {
+                selected = (selected + 1).min(TaskField::ALL.len() - 1);
+                None
+            }
+            (None, KeyCode::Enter) => {
+                let f = TaskField::ALL[selected];
+                let current = match f {
+                    TaskField::Title => Some(t.title.clone()),
+                    TaskField::Priority => t.priority.clone(),
+                    TaskField::Component => t.component.clone(),
+                    TaskField::Estimate => t.estimate_days.map(|d| d.to_string()),
+                };
+                let current = current.unwrap_or_default();
+                if f.is_choice() {
+                    let choices = self.field_choices(f);
+                    if choices.is_empty() {
+                        error = Some(trf(
+                            self.lang,
+                            "the file uses no {} values yet",
+                            &[&f.key()],
+                        ));
+                        None
+                    } else {
+                        selected = choices
+                            .iter()
+                            .position(|c| c.eq_ignore_ascii_case(current.trim()))
+                            .unwrap_or(0);
+                        error = None;
+                        Some(f)
+                    }
+                } else {
+                    input = current;
+                    error = None;
+                    Some(f)
+                }
+            }
+            (Some(f), KeyCode::Up) if f.is_choice() => {
+                selected = selected.saturating_sub(1);
+                Some(f)
+            }
+            (Some(f), KeyCode::Down) if f.is_choice() => {
+                let last = self.field_choices(f).len().saturating_sub(1);
+                selected = (selected + 1).min(last);
+                Some(f)
+            }
+            (Some(f), KeyCode::Enter) => match self.field_edit(&task, f, selected, &input) {
+                Ok(edit) => {
+                    self.stage_edit(edit);
+                    return;
+                }
+                Err(e) => {
+                    error = Some(e);
+                    Some(f)
+                }
+            },
+            (Some(f), code) if !f.is_choice() => {
+                edit_input(&mut input, code);
+                Some(f)
+            }
+            (field, _) => field,
+        };
+        self.view.modal = Some(Modal::EditField {
+            task,
+            field,
+            selected,
+            input,
+            error,
+        });
+    }
+
+    /// The staged edit for the field editor's input, or why the input is not allowed.
+    fn field_edit(
+        &self,
+        task: &str,
+        field: TaskField,
+        selected: usize,
+        input: &str,
+    ) -> std::result::Result<Edit, String> {
+        let task = task.to_string();
+        match field {
+            TaskField::Title => {
+                let to = input.trim();
+                if to.is_empty() {
+                    return Err(tr(self.lang, "the title cannot be empty").into_owned());
+                }
+                Ok(Edit::Title {
+                    task,
+                    to: to.to_string(),
+                })
+            }
+            TaskField::Estimate => match input.trim().parse::<f64>() {
+                Ok(days) if days.is_finite() && days >= 0.0 => Ok(Edit::Estimate { task, days }),
+                _ => Err(trf(
+                    self.lang,
+                    "{} is not a number of days (e.g. 2 or 0.5)",
+                    &[&format!("{:?}", input.trim())],
+                )),
+            },
+            TaskField::Priority | TaskField::Component => {
+                let to = self
+                    .field_choices(field)
+                    .into_iter()
+                    .nth(selected)
+                    .ok_or_else(|| trf(self.lang, "no {} to choose", &[&field.key()]))?;
+                Ok(if field == TaskField::Priority {
+                    Edit::Priority { task, to }
+                } else {
+                    Edit::Component { task, to }
+                })
+            }
+        }
+    }
+
+    /// The whole file when a focus hides part of it.
+    pub fn unscoped(&self) -> Option<&LoadedTasks> {
+        self.unscoped.as_ref()
+    }
+
+    /// Re-derives `loaded` from the whole file for the current `focus`.
+    fn apply_focus(&mut self) {
+        let Some(full) = self.unscoped.take().or_else(|| self.loaded.take()) else {
+            return;
+        };
+        match &self.focus {
+            Some(component) => {
+                self.loaded = Some(scope_to_component(&full, component));
+                self.unscoped = Some(full);
+            }
+            None => self.loaded = Some(full),
+        }
+        self.makespan = self
+            .config
+            .parallelism
+            .zip(self.loaded.as_ref())
+            .and_then(|(workers, l)| {
+                makespan(&l.tasks.tasks, workers, estimate_spread(&l.tasks))
+            });
+    }
+
+    /// Scopes the dashboard to `component` (or back to the whole file), keeping the selected
+    /// task selected when it stays in view.
+    pub fn set_focus(&mut self, component: Option<String>) {
+        let selected = self.selected_id();
+        self.focus = component.map(|c| c.trim().to_lowercase());
+        self.apply_focus();
+        self.refresh_visible();
+        self.view.details_scroll = 0;
+        self.view.selected_idx = 0;
+        if let Some(id) = selected {
+            let tasks = self.loaded.as_ref().map(|l| l.tasks.tasks.as_slice());
+            if let Some(pos) = self
+                .visible
+                .iter()
+                .position(|&i| tasks.and_then(|t| t.get(i)).is_some_and(|t| t.id == id))
+            {
+                self.view.selected_idx = pos;
+            }
+        }
+        self.clamp_selection();
+        self.refresh_history_and_alerts();
+    }
+
+    /// `f`: focus on the selected task's component, or leave focus mode.
+    pub fn sidebar_shown(&self) -> bool {
+        self.view.sidebar.unwrap_or_else(|| {
+            self.full()
+                .is_some_and(|l| l.tasks.tasks.len() >= SIDEBAR_AUTO_TASKS)
+        })
+    }
+
+    /// Everything, then components of the whole file, then statuses within the current focus.
+    pub fn sidebar_entries(&self) -> Vec<SidebarEntry> {
+        let Some(full) = self.full() else {
+            return Vec::new();
+        };
+        let mut components: BTreeMap<String, usize> = BTreeMap::new();
+        for t in &full.tasks.tasks {
+            *components.entry(component_key(t)).or_default() += 1;
+        }
+        let by_status = self
+            .loaded
+            .as_ref()
+            .map_or(&full.stats.by_status, |l| &l.stats.by_status);
+        // Known statuses in workflow order, then any others alphabetically.
+        let mut statuses: Vec<(&String, &usize)> = by_status.iter().collect();
+        statuses.sort_by_key(|(s, _)| {
+            STATUS_CYCLE
+                .iter()
+                .position(|c| c == s)
+                .unwrap_or(STATUS_CYCLE.len())
+        });
+        let mut entries = vec![SidebarEntry::All(full.tasks.tasks.len())];
+        let unowned = components
+            .iter()
+            .filter(|(c, _)| !self.config.owners.contains_key(*c))
+            .map(|(_, n)| n)
+            .sum();
+        entries.extend(
+            components
+                .into_iter()
+                .map(|(c, n)| SidebarEntry::Component(c, n)),
+        );
+        if !self.config.owners.is_empty() {
+            entries.push(SidebarEntry::Unowned(unowned));
+        }
+        entries.extend(
+            statuses
+                .into_iter()
+                .map(|(s, n)| SidebarEntry::Status(s.clone(), *n)),
+        );
+        entries
+    }
+
+    /// Whether the entry is what the table currently shows.
+    pub fn sidebar_active(&self, entry: &SidebarEntry) -> bool {
+        match entry {
+            SidebarEntry::All(_) => self.focus.is_none() && self.filter.is_none(),
+            SidebarEntry::Component(c, _) => self.focus.as_ref() == Some(c),
+            SidebarEntry::Unowned(_) => self
+                .filter
+                .as_ref()
+                .is_some_and(|f| f.query() == UNOWNED_QUERY),
+            SidebarEntry::Status(s, _) => self
+                .filter
+                .as_ref()
+                .is_some_and(|f| f.query() == format!("status:{s}")),
+        }
+    }
+
+    /// `Tab`: moves the keyboard between the table and the sidebar, showing it if needed.
+    fn toggle_sidebar_focus(&mut self) {
+        if !self.sidebar_shown() {
+            self.view.sidebar = Some(true);
+            self.view.sidebar_focus = true;
+        } else {
+            self.view.sidebar_focus = !self.view.sidebar_focus;
+        }
+    }
+
+    fn handle_sidebar_key(&mut self, code: KeyCode) {
+        let entries = self.sidebar_entries();
+        let last = entries.len().saturating_sub(1);
+        let idx = self.view.sidebar_idx.min(last);
+        match code {
+            KeyCode::Up => self.view.sidebar_idx = idx.saturating_sub(1),
+            KeyCode::Down => self.view.sidebar_idx = (idx + 1).min(last),
+            KeyCode::Home => self.view.sidebar_idx = 0,
+            KeyCode::End => self.view.sidebar_idx = last,
+            KeyCode::Esc => self.view.sidebar_focus = false,
+            KeyCode::Enter => {
+                let Some(entry) = entries.into_iter().nth(idx) else {
+                    return;
+                };
+                // Picking what is already shown undoes it.
+                let active = self.sidebar_active(&entry);
+                match entry {
+                    SidebarEntry::All(_) => {
+                        self.set_focus(None);
+                        self.set_filter(None);
+                    }
+                    SidebarEntry::Component(_, _) if active => self.set_focus(None),
+                    SidebarEntry::Component(c, _) => self.set_focus(Some(c)),
+                    SidebarEntry::Unowned(_) | SidebarEntry::Status(_, _) if active => {
+                        self.set_filter(None)
+                    }
+                    SidebarEntry::Unowned(_) => {
+                        self.status_msg = self
+                            .apply_filter_query(UNOWNED_QUERY)
+                            .err()
+                            .map(|e| trf(self.lang, "filter: {}", &[&e]));
+                    }
+                    SidebarEntry::Status(s, _) => {
+                        self.status_msg = self
+                            .apply_filter_query(&format!("status:{s}"))
+                            .err()
+                            .map(|e| trf(self.lang, "filter: {}", &[&e]));
+                    }
+                }
+                // Statuses are counted within the focus, so the list may have changed.
+                let entries = self.sidebar_entries();
+                if let Some(pos) = entries.iter().position(|e| self.sidebar_active(e)) {
+                    self.view.sidebar_idx = pos;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_focus(&mut self) {
+        if self.focus.is_some() {
+            self.set_focus(None);
+            self.say("focus off");
+            return;
+        }
+        let Some(component) = self.selected_task().map(component_key) else {
+            return;
+        };
+        self.say_with("focus: {}", &[&component]);
+        self.set_focus(Some(component));
+    }
+
+    /// The workspace overview (`0`): this file first, then the other configured projects.
+    pub fn overview(&self) -> Vec<OverviewRow> {
+        let not_loaded = || Err("not loaded yet".to_string());
+        let this_name = self
+            .config
+            .projects
+            .iter()
+            .find(|(_, p)| **p == self.path)
+            .map(|(name, _)| name.clone())
+            .or_else(|| {
+                let dir = self.path.parent()?.file_name()?;
+                Some(dir.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "this file".to_string());
+        let full = self.full();
+        let mut rows = vec![OverviewRow {
+            name: this_name,
+            path: self.path.clone(),
+            current: true,
+            summary: full.map_or_else(not_loaded, |l| Ok(ProjectSummary::of(l))),
+        }];
+        for (name, path) in &self.config.projects {
+            if *path == self.path {
+                continue;
+            }
+            let summary = full
+                .and_then(|l| l.tasks.workspace.projects.get(name).cloned())
+                .unwrap_or_else(not_loaded);
+            rows.push(OverviewRow {
+                name: name.clone(),
+                path: path.clone(),
+                current: false,
+                summary,
+            });
+        }
+        rows
+    }
+
+    /// Estimated effort per component against its budget, for the whole file.
+    pub fn budgets(&self) -> Vec<BudgetUse> {
+        self.full()
+            .map(|l| budget_use(&l.tasks, &self.config.budgets))
+            .unwrap_or_default()
+    }
+
+    /// Cost roll-up for the current load, when a `day_rate` is configured.
+    pub fn cost(&self) -> Option<CostRollup> {
+        let rate = self.config.day_rate?;
+        let l = self.loaded.as_ref()?;
+        Some(cost_rollup(&l.tasks, rate))
+    }
+
+    fn reload_notes(&mut self) {
+        match load_notes(&self.notes_path) {
+            Ok(notes) => {
+                self.notes = notes;
+                self.notes_error = None;
+            }
+            Err(e) => self.notes_error = Some(e.to_string()),
+        }
+    }
+
+    /// Parses and applies a filter query; an empty query clears the filter. On a parse error
+    /// the current filter stays in place.
+    pub fn apply_filter_query(&mut self, query: &str) -> Result<()> {
+        let filter = match query.trim() {
+            "" => None,
+            q => Some(Filter::parse(q)?),
+        };
+        self.set_filter(filter);
+        Ok(())
+    }
+
+    fn refresh_visible(&mut self) {
+        let tasks: &[Task] = self
+            .loaded
+            .as_ref()
+            .map(|l| l.tasks.tasks.as_slice())
+            .unwrap_or(&[]);
+        self.visible = tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| {
+                self.filter
+                    .as_ref()
+                    .is_none_or(|f| f.matches(t, &self.config.owners))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if self.sort_by_risk {
+            let scores = risk_scores(tasks);
+            // Done tasks last; ties keep file order.
+            self.visible.sort_by_key(|&i| {
+                let t = &tasks[i];
+                let score = scores.get(t.id.as_str()).map_or(0, RiskScore::value);
+                (task_status(t) == "done", std::cmp::Reverse(score))
+            });
+        }
+        if let Some(sort) = &self.column_sort {
+            let status_by_id = self.loaded.as_ref().map(|l| status_map(&l.tasks));
+            let status_by_id = status_by_id.unwrap_or_default();
+            // Stable, so ties keep file order either way.
+            self.visible.sort_by(|&a, &b| {
+                let order = column_order(
+                    &sort.column,
+                    &tasks[a],
+                    &tasks[b],
+                    &status_by_id,
+                    &self.config.priorities,
+                );
+                if sort.descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+        // Deferred tasks make a section of their own at the bottom, collapsed until `l`.
+        let now = self.now();
+        let (deferred, mut visible): (Vec<usize>, Vec<usize>) = self
+            .visible
+            .iter()
+            .partition(|&&i| is_deferred(&tasks[i], now));
+        self.deferred = deferred.len();
+        if self.view.show_deferred {
+            visible.extend(deferred);
+        }
+        self.visible = visible;
+    }
+
+    /// `l`: expands or collapses the deferred section, keeping the selected task selected
+    /// unless it is folded away.
+    fn toggle_deferred(&mut self) {
+        if self.deferred == 0 {
+            self.say("no deferred tasks");
+            return;
+        }
+        let selected = self.selected_id();
+        self.view.show_deferred = !self.view.show_deferred;
+        self.refresh_visible();
+        if self.view.show_deferred {
+            // Straight to the section.
+            self.view.selected_idx = self.visible.len() - self.deferred;
+        } else {
+            self.clamp_selection();
+            if let Some(id) = selected {
+                self.select_id(&id);
+            }
+        }
+    }
+
+    /// A key while typeahead is on. Characters extend the prefix and select the first task
+    /// from the selected one on whose id or title starts with it, `'` the next such task;
+    /// `Esc` and `Enter` end typeahead. Returns false for any other key, which ends typeahead
+    /// and then acts as usual.
+    fn handle_typeahead_key(&mut self, key: KeyEvent) -> bool {
+        let Some(prefix) = &mut self.typeahead else {
+            return false;
+        };
+        let from_next = match key.code {
+            KeyCode::Char('\'') => true,
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                prefix.push(c);
+                false
+            }
+            KeyCode::Backspace => {
+                prefix.pop();
+                false
+            }
+            code => {
+                self.typeahead = None;
+                self.status_msg = None;
+                return matches!(code, KeyCode::Esc | KeyCode::Enter);
+            }
+        };
+        let typed = prefix.clone();
+        let prefix = typed.to_lowercase();
+        let start = self.view.selected_idx + usize::from(from_next);
+        let tasks = self.loaded.as_ref().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+        let len = self.visible.len();
+        // From the selected row down, then around from the top.
+        let found = (0..len).map(|k| (start + k) % len.max(1)).find(|&row| {
+            tasks.get(self.visible[row]).is_some_and(|t| {
+                t.id.to_lowercase().starts_with(&prefix)
+                    || t.title.to_lowercase().starts_with(&prefix)
+            })
+        });
+        match found {
+            Some(row) if !prefix.is_empty() => {
+                self.view.selected_idx = row;
+                self.view.details_scroll = 0;
+                self.say_with("find: {}  (' next, Esc done)", &[&typed]);
+            }
+            Some(_) => self.say_with("find: {}  (' next, Esc done)", &[&""]),
+            None => self.say_with("find: {}  (no task starts with it)", &[&typed]),
+        }
+        true
+    }
+
+    /// `L`: asks until when to defer the selected task; a deferred one comes back right away.
+    fn defer_selected(&mut self) {
+        let Some(t) = self.selected_task() else {
+            return;
+        };
+        if task_status(t) == "deferred" {
+            let task = t.id.clone();
+            self.stage_edit(Edit::Defer { task, until: None });
+            return;
+        }
+        self.view.modal = Some(Modal::Defer {
+            input: String::new(),
+        });
+    }
+
+    /// Stages renaming component `from` to `to` across the file. A focus on `from` follows it,
+    /// so the renamed tasks stay in view.
+    fn rename_component(&mut self, from: String, to: String) {
+        let key = from.trim().to_lowercase();
+        if self.focus.as_deref() == Some(key.as_str()) {
+            self.focus = Some(to.trim().to_lowercase());
+        }
+        self.stage_edit(Edit::RenameComponent { from, to });
+    }
+
+    /// The columns the task table shows, in order, but for the risk score column.
+    pub fn table_columns(&self) -> Vec<TableColumn> {
+        if let Some(columns) = self.profile().and_then(|p| p.columns.clone()) {
+            return columns;
+        }
+        let risk_column = self
+            .loaded
+            .as_ref()
+            .is_some_and(|l| l.tasks.tasks.iter().any(|t| t.risk.is_some()));
+        let mut columns = vec![
+            TableColumn::Id,
+            TableColumn::Status,
+            TableColumn::Wait,
+            TableColumn::Priority,
+        ];
+        if risk_column {
+            columns.push(TableColumn::Risk);
+        }
+        columns.extend([TableColumn::Component, TableColumn::Title]);
+        columns
+    }
+
+    /// Shows a fixed message in the footer, translated.
+    fn say(&mut self, en: &'static str) {
+        self.status_msg = Some(tr(self.lang, en).into_owned());
+    }
+
+    /// Like `say`, with each `{}` in `en` filled by the next of `args`.
+    fn say_with(&mut self, en: &'static str, args: &[&dyn Display]) {
+        self.status_msg = Some(trf(self.lang, en, args));
+    }
+
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile
+            .as_ref()
+            .and_then(|p| self.config.profiles.get(p))
+    }
+
+    /// Switches to a profile, or back to no profile. Its filter and sort replace the current
+    /// ones; what it leaves unset stays as it is.
+    pub fn apply_profile(&mut self, name: Option<String>) {
+        self.profile = name;
+        if let Some(usage) = &mut self.usage {
+            usage.profile(self.profile.as_deref());
+        }
+        self.view.header_focus = None;
+        let Some(profile) = self.profile().cloned() else {
+            self.say("no profile");
+            return;
+        };
+        if let Some(filter) = profile.filter {
+            self.set_filter(Some(filter));
+        }
+        match profile.sort {
+            Some(ProfileSort::File) => self.set_column_sort(None),
+            Some(ProfileSort::Risk) => {
+                self.set_column_sort(None);
+                self.toggle_risk_sort();
+            }
+            Some(ProfileSort::Column(sort)) => self.set_column_sort(Some(sort)),
+            None => {}
+        }
+        self.status_msg = self
+            .profile
+            .as_ref()
+            .map(|p| trf(self.lang, "profile {}", &[p]));
+    }
+
+    /// `V`: the next profile in name order, then none.
+    fn cycle_profile(&mut self) {
+        if self.config.profiles.is_empty() {
+            self.say("no profiles configured (see [profiles] in the config)");
+            return;
+        }
+        let next = match &self.profile {
+            None => self.config.profiles.keys().next(),
+            Some(current) => self
+                .config
+                .profiles
+                .range::<String, _>((
+                    std::ops::Bound::Excluded(current),
+                    std::ops::Bound::Unbounded,
+                ))
+                .next()
+                .map(|(k, _)| k),
+        };
+        self.apply_profile(next.cloned());
+    }
+
+    /// The key a profile's `keys` table maps `code` to.
+    fn remap_key(&self, code: KeyCode) -> KeyCode {
+        self.profile()
+            .and_then(|p| p.keys.get(&code))
+            .copied()
+            .unwrap_or(code)
+    }
+
+    /// `H`: moves the keyboard to the table header, starting on the sorted column.
+    fn focus_header(&mut self) {
+        let columns = self.table_columns();
+        let sorted = self
+            .column_sort
+            .as_ref()
+            .and_then(|s| columns.iter().position(|c| *c == s.column));
+        self.view.header_focus = Some(sorted.unwrap_or(0));
+        self.view.sidebar_focus = false;
+    }
+
+    fn handle_header_key(&mut self, code: KeyCode) {
+        let columns = self.table_columns();
+        let last = columns.len().saturating_sub(1);
+        let idx = self.view.header_focus.unwrap_or(0).min(last);
+        match code {
+            KeyCode::Left => self.view.header_focus = Some(idx.saturating_sub(1)),
+            KeyCode::Right => self.view.header_focus = Some((idx + 1).min(last)),
+            KeyCode::Esc | KeyCode::Char('H') => self.view.header_focus = None,
+            KeyCode::Enter => {
+                if let Some(column) = columns.get(idx).cloned() {
+                    self.sort_by_column(column);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Sorts by `column` ascending; the same column again reverses it.
+    fn sort_by_column(&mut self, column: TableColumn) {
+        let descending = self
+            .column_sort
+            .as_ref()
+            .is_some_and(|s| s.column == column && !s.descending);
+        self.set_column_sort(Some(ColumnSort { column, descending }));
+    }
+
+    /// The key that does what `code` does under the current profile: `code` itself unless the
+    /// profile remaps it away, else a key remapped onto it.
+    fn key_for(&self, code: KeyCode) -> Option<KeyCode> {
+        let Some(profile) = self.profile() else {
+            return Some(code);
+        };
+        if !matches!(profile.keys.get(&code), Some(to) if *to != code) {
+            return Some(code);
+        }
+        profile
+            .keys
+            .iter()
+            .find(|(_, to)| **to == code)
+            .map(|(from, _)| *from)
+    }
+
+    /// The command palette's commands (`Ctrl-P`): the dashboard keys, then what has no key of
+    /// its own.
+    pub fn palette_commands(&self) -> Vec<Command> {
+        let lang = self.lang;
+        let command = |name: String, action: Action| Command {
+            name,
+            key: None,
+            action,
+        };
+        let mut commands: Vec<Command> = KEY_COMMANDS
+            .iter()
+            // `d` and `a` only act while the large-change banner is shown.
+            .filter(|(_, code)| {
+                self.large_diff.is_some() || !matches!(code, KeyCode::Char('d' | 'a'))
+            })
+            .map(|(name, code)| Command {
+                name: tr(lang, name).into_owned(),
+                key: self.key_for(*code),
+                action: Action::Key(*code),
+            })
+            .collect();
+        let sort_by = tr(lang, "sort by");
+        for column in self.table_columns() {
+            let label = match column.builtin_label() {
+                Some(label) => tr(lang, label).into_owned(),
+                None => column.label().to_string(),
+            };
+            commands.push(command(format!("{sort_by} {label}"), Action::Sort(column)));
+        }
+        if self.column_sort.is_some() || self.sort_by_risk {
+            commands.push(command(
+                tr(lang, "sort in file order").into_owned(),
+                Action::FileOrder,
+            ));
+        }
+        for name in self.config.profiles.keys() {
+            let label = format!("{} {name}", tr(lang, "view profile"));
+            commands.push(command(label, Action::Profile(Some(name.clone()))));
+        }
+        if self.profile.is_some() {
+            commands.push(command(
+                tr(lang, "no view profile").into_owned(),
+                Action::Profile(None),
+            ));
+        }
+        if self.filter.is_some() {
+            commands.push(command(
+                tr(lang, "clear the filter").into_owned(),
+                Action::ClearFilter,
+            ));
+        }
+        if self.selected_task().is_some_and(|t| t.component.is_some()) {
+            commands.push(command(
+                tr(lang, "rename the selected task's component").into_owned(),
+                Action::RenameComponent,
+            ));
+        }
+        if self.config_path.is_some() {
+            commands.push(command(
+                tr(lang, "edit the config ($EDITOR)").into_owned(),
+                Action::EditConfig,
+            ));
+        }
+        commands
+    }
+
+    /// Runs a palette command. Returns true when the app should quit.
+    fn run_command(&mut self, action: Action) -> bool {
+        match action {
+            Action::Key(code) => return self.dashboard_key(code).unwrap_or(false),
+            Action::Sort(column) => self.sort_by_column(column),
+            Action::FileOrder => self.set_column_sort(None),
+            Action::Profile(name) => self.apply_profile(name),
+            Action::ClearFilter => self.set_filter(None),
+            // Editing restarts the dashboard, which would drop them.
+            Action::EditConfig if !self.staged.is_empty() => {
+                self.say("write (W) or discard (w, x) the staged edits before editing the config");
+            }
+            Action::EditConfig => self.edit_config = true,
+            Action::RenameComponent => {
+                if let Some(from) = self.selected_task().and_then(|t| t.component.clone()) {
+                    let from = from.trim().to_string();
+                    self.view.modal = Some(Modal::RenameComponent {
+                        input: from.clone(),
+                        from,
+                    });
+                }
+            }
+        }
+        false
+    }
+
+    /// Reorders the table, keeping the selection.
+    fn set_column_sort(&mut self, sort: Option<ColumnSort>) {
+        let selected = self.selected_id();
+        self.column_sort = sort.clone();
+        self.sort_by_risk = false;
+        self.refresh_visible();
+        if let Some(id) = selected {
+            self.select_id(&id);
+        }
+        self.status_msg = Some(match sort {
+            Some(s) => trf(
+                self.lang,
+                "sorted by {} {}",
+                &[
+                    &s.column
+                        .builtin_label()
+                        .map_or(s.column.label().into(), |en| tr(self.lang, en)),
+                    &if s.descending { "▼" } else { "▲" },
+                ],
+            ),
+            None => tr(self.lang, "sorted in file order").into_owned(),
+        });
+    }
+
+    /// `o`: switches the table between file order and risk score, keeping the selection. A
+    /// header sort goes back to file order.
+    fn toggle_risk_sort(&mut self) {
+        if self.column_sort.is_some() {
+            self.set_column_sort(None);
+            return;
+        }
+        let selected = self.selected_id();
+        self.sort_by_risk = !self.sort_by_risk;
+        self.refresh_visible();
+        if let Some(id) = selected {
+            self.select_id(&id);
+        }
+        self.say(if self.sort_by_risk {
+            "sorted by risk score (? shows the formula)"
+        } else {
+            "sorted in file order"
+        });
+    }
+
+    /// Replaces the table filter, keeping the selected task selected when it is still visible.
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        let selected = self.selected_id();
+        self.filter = filter;
+        self.refresh_visible();
+        self.view.details_scroll = 0;
+        let Some(l) = &self.loaded else {
+            return;
+        };
+        let keep = selected.and_then(|id| {
+            self.visible
+                .iter()
+                .position(|&i| l.tasks.tasks.get(i).is_some_and(|t| t.id == id))
+        });
+        self.view.selected_idx = keep.unwrap_or(0);
+        self.clamp_selection();
+    }
+
+    fn clamp_selection(&mut self) {
+        let len = self.task_count();
+        if len > 0 {
+            self.view.selected_idx = self.view.selected_idx.min(len - 1);
+        } else {
+            self.view.selected_idx = 0;
+        }
+    }
+
+    /// Selects the task with the given id (case-insensitive). Falls back to the first id that
+    /// starts with the query so `:42` style shorthands still land somewhere useful.
+    pub fn select_id(&mut self, query: &str) -> bool {
+        let Some(l) = &self.loaded else {
+            return false;
+        };
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return false;
+        }
+        let tasks = &l.tasks.tasks;
+        // `T-3#2` names the second task using `T-3`, as the table shows it.
+        let occurrences = id_occurrences(tasks);
+        let idx = tasks
+            .iter()
+            .zip(&occurrences)
+            .position(|(t, &n)| n > 0 && display_id(&t.id, n).to_lowercase() == q)
+            .or_else(|| tasks.iter().position(|t| t.id.to_lowercase() == q))
+            .or_else(|| tasks.iter().position(|t| t.id.to_lowercase().starts_with(&q)))
+            .or_else(|| tasks.iter().position(|t| t.id.to_lowercase().ends_with(&q)));
+        let Some(idx) = idx else {
+            if self.focus.is_some() && self.unscoped.as_ref().is_some_and(|f| has_id(f, &q)) {
+                self.set_focus(None);
+                let found = self.select_id(query);
+                self.say("focus cleared to show the task");
+                return found;
+            }
+            self.say_with("no task with id {}", &[&query.trim()]);
+            return false;
+        };
+        let mut cleared = false;
+        if !self.visible.contains(&idx) && is_deferred(&tasks[idx], self.now()) {
+            self.view.show_deferred = true;
+            self.refresh_visible();
+        }
+        if !self.visible.contains(&idx) {
+            // The deep link wins over the filter: show the task rather than refuse.
+            self.filter = None;
+            self.refresh_visible();
+            cleared = true;
+        }
+        match self.visible.iter().position(|&i| i == idx) {
+            Some(pos) => {
+                self.view.selected_idx = pos;
+                self.view.details_scroll = 0;
+                self.status_msg =
+                    cleared.then(|| tr(self.lang, "filter cleared to show the task").into_owned());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// `select_id` for a jump: records both the task left behind and the one landed on in the
+    /// breadcrumb trail.
+    pub fn jump_to(&mut self, query: &str) -> bool {
+        let origin = self.selected_id();
+        if !self.select_id(query) {
+            return false;
+        }
+        if let Some(id) = origin {
+            self.trail.visit(&id);
+        }
+        if let Some(id) = self.selected_id() {
+            self.trail.visit(&id);
+        }
+        true
+    }
+
+    /// Steps back (`[`) or forward (`]`) through the breadcrumb trail. A task reached with the
+    /// arrow keys since the last jump is recorded first, so `]` returns to it.
+    fn step_trail(&mut self, back: bool) {
+        if let Some(id) = self.selected_id() {
+            self.trail.visit(&id);
+        }
+        let next = if back {
+            self.trail.back()
+        } else {
+            self.trail.forward()
+        };
+        match next {
+            Some(id) => {
+                self.select_id(&id);
+            }
+            None => {
+                self.say(if back {
+                    "at the start of the trail"
+                } else {
+                    "at the end of the trail"
+                });
+            }
+        }
+    }
+
+    /// Number of rows in the (filtered) table.
+    fn task_count(&self) -> usize {
+        self.visible.len()
+    }
+
+    /// Tasks in table order, after the filter.
+    pub fn visible_tasks(&self) -> impl Iterator<Item = &Task> + '_ {
+        let tasks: &[Task] = self
+            .loaded
+            .as_ref()
+            .map(|l| l.tasks.tasks.as_slice())
+            .unwrap_or(&[]);
+        self.visible.iter().filter_map(move |&i| tasks.get(i))
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        let l = self.loaded.as_ref()?;
+        let idx = *self.visible.get(self.view.selected_idx)?;
+        l.tasks.tasks.get(idx)
+    }
+
+    /// The first task with `id`, focus or not.
+    pub fn task(&self, id: &str) -> Option<&Task> {
+        self.full()?.tasks.tasks.iter().find(|t| t.id == id)
+    }
+
+    pub fn selected_id(&self) -> Option<String> {
+        self.selected_task().map(|t| t.id.clone())
+    }
+
+    /// Earlier tasks sharing the selected task's id (see `tasks::id_occurrences`).
+    pub fn selected_occurrence(&self) -> usize {
+        let (Some(l), Some(&idx)) = (&self.loaded, self.visible.get(self.view.selected_idx)) else {
+            return 0;
+        };
+        id_occurrences(&l.tasks.tasks).get(idx).copied().unwrap_or(0)
+    }
+
+    /// Moves the selected task one step up (`delta < 0`) or down within its priority group and
+    /// stages the resulting positions as explicit `order` fields.
+    fn move_within_priority(&mut self, delta: isize) {
+        let (Some(l), Some(current)) = (self.full(), self.selected_task()) else {
+            return;
+        };
+        let group = priority_key(current);
+        // Orders are written for the whole group; in focus mode the task swaps places with the
+        // nearest group member that is in view.
+        let mut members: Vec<&str> = l
+            .tasks
+            .tasks
+            .iter()
+            .filter(|t| priority_key(t) == group)
+            .map(|t| t.id.as_str())
+            .collect();
+        let in_view: HashSet<&str> = self
+            .loaded
+            .iter()
+            .flat_map(|v| v.tasks.tasks.iter().map(|t| t.id.as_str()))
+            .collect();
+        let Some(pos) = members.iter().position(|id| *id == current.id) else {
+            return;
+        };
+        let mut target = pos.checked_add_signed(delta).filter(|&p| p < members.len());
+        while let Some(p) = target.filter(|&p| !in_view.contains(members[p])) {
+            target = p.checked_add_signed(delta).filter(|&p| p < members.len());
+        }
+        let Some(target) = target else {
+            self.status_msg = Some(trf(
+                self.lang,
+                "{} is already at the edge of its priority group",
+                &[&current.id],
+            ));
+            return;
+        };
+        members.swap(pos, target);
+
+        let orders: Vec<(String, i64)> = members
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.to_string(), i as i64 + 1))
+            .collect();
+        let task = current.id.clone();
+        self.stage_edit(Edit::Orders {
+            task,
+            group,
+            orders,
+        });
+    }
+
+    /// Opens the fix-it modal with a preview of the lines `fix` changes. `back` is the problems
+    /// panel row to return to.
+    fn open_fix(&mut self, fix: Fix, back: usize) {
+        let Some(l) = self.full() else {
+            return;
+        };
+        let preview = preview_fix(&l.source, &fix).map_err(|e| e.to_string());
+        self.view.modal = Some(Modal::Fix { fix, preview, back });
+    }
+
+    /// Returns true when the app should quit.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let code = key.code;
+        if matches!(self.view.modal, Some(Modal::QuickLook)) {
+            // A hover card: any key dismisses it, and all but Esc and Tab then act as usual.
+            self.view.modal = None;
+            match code {
+                KeyCode::Esc => return Ok(false),
+                KeyCode::Tab => {
+                    self.toggle_sidebar_focus();
+                    return Ok(false);
+                }
+                _ => {}
+            }
+        }
+        if let Some(Modal::Help { query, scroll }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc if !query.is_empty() => {
+                    query.clear();
+                    *scroll = 0;
+                }
+                // `?` closes the help it opened, unless it is part of a search.
+                KeyCode::Esc | KeyCode::Enter => self.view.modal = None,
+                KeyCode::Char('?') if query.is_empty() => self.view.modal = None,
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+                _ => {
+                    edit_input(query, code);
+                    *scroll = 0;
+                }
+            }
+            return Ok(false);
+        }
+        if matches!(self.view.modal, Some(Modal::Palette { .. })) {
+            let commands = self.palette_commands();
+            let Some(Modal::Palette { query, selected }) = &mut self.view.modal else {
+                return Ok(false);
+            };
+            let found = search(&commands, query);
+            match code {
+                KeyCode::Esc => self.view.modal = None,
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.view.modal = None;
+                }
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(found.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    let action = found.get(*selected).map(|c| c.action.clone());
+                    self.view.modal = None;
+                    if let Some(action) = action {
+                        return Ok(self.run_command(action));
+                    }
+                }
+                _ => {
+                    edit_input(query, code);
+                    *selected = 0;
+                }
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Jump { input }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc => self.view.modal = None,
+                KeyCode::Enter => {
+                    let query = input.clone();
+                    self.view.modal = None;
+                    self.jump_to(&query);
+                }
+                _ => edit_input(input, code),
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Defer { input }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc => self.view.modal = None,
+                KeyCode::Enter => {
+                    let input = input.clone();
+                    self.view.modal = None;
+                    match (defer_day(&input, self.now()), self.selected_id()) {
+                        (Ok(day), Some(task)) => self.stage_edit(Edit::Defer {
+                            task,
+                            until: Some(day),
+                        }),
+                        (Err(e), _) => self.say_with("defer: {}", &[&e]),
+                        (Ok(_), None) => {}
+                    }
+                }
+                _ => edit_input(input, code),
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::RenameComponent { from, input }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc => self.view.modal = None,
+                KeyCode::Enter => {
+                    let (from, to) = (from.clone(), input.trim().to_string());
+                    self.view.modal = None;
+                    if !to.is_empty() && to != from {
+                        self.rename_component(from, to);
+                    }
+                }
+                _ => edit_input(input, code),
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Filter { input }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc => self.view.modal = None,
+                KeyCode::Enter => {
+                    let query = input.trim().to_string();
+                    self.view.modal = None;
+                    self.status_msg = self
+                        .apply_filter_query(&query)
+                        .err()
+                        .map(|e| trf(self.lang, "filter: {}", &[&e]));
+                }
+                _ => edit_input(input, code),
+            }
+            return Ok(false);
+        }
+        if matches!(self.view.modal, Some(Modal::Problems { .. })) {
+            let entries = self.panel_entries();
+            let count = entries.len();
+            let Some(Modal::Problems { selected }) = &mut self.view.modal else {
+                return Ok(false);
+            };
+            match code {
+                KeyCode::Esc | KeyCode::Char('p') | KeyCode::Char('q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down if count > 0 => *selected = (*selected + 1).min(count - 1),
+                KeyCode::Enter => {
+                    let target = entries.get(*selected).and_then(|e| e.task_id.clone());
+                    self.view.modal = None;
+                    if let Some(id) = target {
+                        self.jump_to(&id);
+                    }
+                }
+                KeyCode::Char('d') => {
+                    let fingerprint = entries.get(*selected).and_then(|e| e.fingerprint.clone());
+                    self.toggle_dismissed(fingerprint);
+                }
+                KeyCode::Char('f') => {
+                    let back = *selected;
+                    match entries.get(back).and_then(|e| e.fix.clone()) {
+                        Some(fix) => self.open_fix(fix, back),
+                        None => self.say("no automatic fix for this entry"),
+                    }
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Staged { scroll, .. }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc | KeyCode::Char('w' | 'q') => self.view.modal = None,
+                KeyCode::Char('W') | KeyCode::Enter => {
+                    self.view.modal = None;
+                    self.write_staged();
+                }
+                KeyCode::Char('u') => {
+                    *scroll = 0;
+                    self.unstage(false);
+                }
+                KeyCode::Char('x') => {
+                    self.view.modal = None;
+                    self.unstage(true);
+                    self.say("staged edits discarded");
+                }
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+                _ => {}
+            }
+            if self.staged.is_empty() && matches!(self.view.modal, Some(Modal::Staged { .. })) {
+                self.view.modal = None;
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Overview { selected }) = self.view.modal {
+            let rows = self.overview();
+            let last = rows.len().saturating_sub(1);
+            let selected = match code {
+                KeyCode::Up => selected.saturating_sub(1),
+                KeyCode::Down => (selected + 1).min(last),
+                _ => selected,
+            };
+            self.view.modal = Some(Modal::Overview { selected });
+            match code {
+                KeyCode::Esc | KeyCode::Char('0') | KeyCode::Char('q') => self.view.modal = None,
+                KeyCode::Enter => {
+                    let Some(row) = rows.into_iter().nth(selected) else {
+                        return Ok(false);
+                    };
+                    if row.current {
+                        self.view.modal = None;
+                    } else if !self.staged.is_empty() {
+                        self.say_with(
+                            "{} staged edits: write (W) or discard them before switching projects",
+                            &[&self.staged.len()],
+                        );
+                    } else {
+                        self.switch_to = Some(row.path);
+                        return Ok(true);
+                    }
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::WatchLog { scroll }) = &mut self.view.modal {
+            // Up to the oldest entry, on the top row.
+            let hidden = self
+                .watch_log
+                .entries()
+                .len()
+                .saturating_sub(self.view.watch_log_rows.get());
+            let top = u16::try_from(hidden).unwrap_or(u16::MAX);
+            match code {
+                KeyCode::Esc | KeyCode::F(12) | KeyCode::Char('q') => self.view.modal = None,
+                KeyCode::Up => *scroll = scroll.saturating_add(1).min(top),
+                KeyCode::Down => *scroll = scroll.saturating_sub(1),
+                KeyCode::PageUp => *scroll = scroll.saturating_add(10).min(top),
+                KeyCode::PageDown => *scroll = scroll.saturating_sub(10),
+                KeyCode::End => *scroll = 0,
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Replan {
+            deadline,
+            workers,
+            scroll,
+            ..
+        }) = &mut self.view.modal
+        {
+            let before = (*deadline, *workers);
+            match code {
+                KeyCode::Esc | KeyCode::Char('R' | 'q') => {
+                    self.view.modal = None;
+                    return Ok(false);
+                }
+                KeyCode::Left => *deadline = deadline.saturating_sub(1),
+                KeyCode::Right => *deadline = deadline.saturating_add(1),
+                KeyCode::PageUp => *deadline = deadline.saturating_sub(7),
+                KeyCode::PageDown => *deadline = deadline.saturating_add(7),
+                KeyCode::Char('+') => *workers = (*workers + 1).min(99),
+                KeyCode::Char('-') => *workers = workers.saturating_sub(1).max(1),
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                _ => {}
+            }
+            if (*deadline, *workers) != before {
+                self.refresh_replan();
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Diff { scroll }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc | KeyCode::Char('d' | 'q') => self.view.modal = None,
+                KeyCode::Char('a') => {
+                    self.view.modal = None;
+                    self.accept_large_diff();
+                }
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::SafeMode { selected }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc | KeyCode::Char('!' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down if !self.safe_mode.is_empty() => {
+                    *selected = (*selected + 1).min(self.safe_mode.len() - 1)
+                }
+                KeyCode::Char('r') => {
+                    let i = *selected;
+                    self.reset_state_file(i);
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Review { queue, selected }) = &mut self.view.modal {
+            let rows = review_rows(queue);
+            match code {
+                KeyCode::Esc | KeyCode::Char('Q' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(rows.len().saturating_sub(1)),
+                KeyCode::Char(' ') => {
+                    let item = rows.get(*selected).and_then(|row| {
+                        let t = &queue[row.task];
+                        Some((t.id.clone(), t.dod.get(row.item?)?.clone()))
+                    });
+                    if let Some((id, item)) = item {
+                        self.toggle_verified(&id, &item);
+                    }
+                }
+                KeyCode::Enter => {
+                    let target = rows.get(*selected).map(|row| queue[row.task].id.clone());
+                    self.view.modal = None;
+                    if let Some(id) = target {
+                        self.jump_to(&id);
+                    }
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Drift { selected }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc | KeyCode::Char('D' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down if !self.drift.is_empty() => {
+                    *selected = (*selected + 1).min(self.drift.len() - 1)
+                }
+                KeyCode::Enter => {
+                    let target = self.drift.get(*selected).map(|d| d.task_id.clone());
+                    self.view.modal = None;
+                    if let Some(id) = target {
+                        self.jump_to(&id);
+                    }
+                }
+                KeyCode::Char('d') => {
+                    let fingerprint = self.drift.get(*selected).map(|d| d.fingerprint.clone());
+                    self.toggle_dismissed(fingerprint);
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if matches!(self.view.modal, Some(Modal::Links { .. })) {
+            let links: Vec<String> = self
+                .selected_task()
+                .map(|t| t.linked_ids().into_iter().map(|(_, id)| id.to_string()).collect())
+                .unwrap_or_default();
+            let Some(Modal::Links { selected }) = &mut self.view.modal else {
+                return Ok(false);
+            };
+            match code {
+                KeyCode::Esc | KeyCode::Char('g' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down if !links.is_empty() => {
+                    *selected = (*selected + 1).min(links.len() - 1)
+                }
+                KeyCode::Enter => {
+                    let target = links.get(*selected).cloned();
+                    self.view.modal = None;
+                    if let Some(id) = target {
+                        self.jump_to(&id);
+                    }
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Prune {
+            edges,
+            selected,
+            picked,
+        }) = &mut self.view.modal
+        {
+            match code {
+                KeyCode::Esc | KeyCode::Char('X' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(edges.len() - 1),
+                KeyCode::Char(' ') => picked[*selected] = !picked[*selected],
+                KeyCode::Char('a') => {
+                    let all = picked.iter().all(|p| *p);
+                    picked.fill(!all);
+                }
+                KeyCode::Enter => {
+                    let chosen: Vec<Redundant> = if picked.contains(&true) {
+                        edges
+                            .iter()
+                            .zip(picked.iter())
+                            .filter(|(_, p)| **p)
+                            .map(|(e, _)| e.clone())
+                            .collect()
+                    } else {
+                        vec![edges[*selected].clone()]
+                    };
+                    self.view.modal = None;
+                    self.stage_pruned(chosen);
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Triage {
+            edges,
+            choices,
+            selected,
+        }) = &mut self.view.modal
+        {
+            let choice = match code {
+                KeyCode::Char('d') => Some(Resolution::Remove),
+                KeyCode::Char('r') if edges[*selected].suggestion.is_some() => {
+                    Some(Resolution::Remap)
+                }
+                KeyCode::Char('e') => Some(Resolution::External),
+                KeyCode::Char('l') => Some(Resolution::Leave),
+                _ => None,
+            };
+            if let Some(choice) = choice {
+                choices[*selected] = choice;
+                *selected = (*selected + 1).min(edges.len() - 1);
+                return Ok(false);
+            }
+            match code {
+                KeyCode::Char('r') => self.say("no similar id to point it at"),
+                KeyCode::Esc | KeyCode::Char('q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(edges.len() - 1),
+                KeyCode::Enter => {
+                    let (edges, choices) = (std::mem::take(edges), std::mem::take(choices));
+                    self.view.modal = None;
+                    self.write_triage(&edges, &choices);
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Fix { fix, back, .. }) = &self.view.modal {
+            let (fix, back) = (fix.clone(), *back);
+            match code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.view.modal = Some(Modal::Problems { selected: back })
+                }
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    self.view.modal = None;
+                    self.stage_edit(Edit::Fix(fix));
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Matrix { selected }) = &mut self.view.modal {
+            let rows = self
+                .loaded
+                .as_ref()
+                .map(|l| dependency_matrix(&l.tasks).components)
+                .unwrap_or_default();
+            match code {
+                KeyCode::Esc | KeyCode::Char('m' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down if !rows.is_empty() => {
+                    *selected = (*selected + 1).min(rows.len() - 1)
+                }
+                KeyCode::Enter => {
+                    if let Some(component) = rows.get(*selected) {
+                        let query = format!("component:{component}");
+                        self.view.modal = None;
+                        self.status_msg = self
+                            .apply_filter_query(&query)
+                            .err()
+                            .map(|e| trf(self.lang, "filter: {}", &[&e]));
+                    }
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Coverage { scroll } | Modal::Stats { scroll }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc | KeyCode::Char('c' | 's' | 'q') => self.view.modal = None,
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if matches!(self.view.modal, Some(Modal::EditField { .. })) {
+            self.handle_field_editor_key(code);
+            return Ok(false);
+        }
+        if matches!(self.view.modal, Some(Modal::Comment { .. })) {
+            self.handle_comment_key(code);
+            return Ok(false);
+        }
+        if let Some(Modal::Export { selected }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc | KeyCode::Char('x' | 'q') => self.view.modal = None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(VIEW_FORMATS.len() - 1),
+                KeyCode::Enter => {
+                    let selected = *selected;
+                    self.view.modal = None;
+                    self.export_view(selected);
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+        if let Some(Modal::Note { input }) = &mut self.view.modal {
+            match code {
+                KeyCode::Esc => self.view.modal = None,
+                KeyCode::Enter => {
+                    let text = input.trim().to_string();
+                    self.view.modal = None;
+                    if let (false, Some(id)) = (text.is_empty(), self.selected_id()) {
+                        if self.demo.is_some() {
+                            self.notes
+                                .annotations
+                                .push(Annotation { id, at: None, text });
+                            return Ok(false);
+                        }
+                        match append_note(&self.notes_path, &id, &text) {
+                            Ok(notes) => {
+                                self.notes = notes;
+                                self.notes_error = None;
+                            }
+                            Err(e) => self.notes_error = Some(e.to_string()),
+                        }
+                    }
+                }
+                _ => edit_input(input, code),
+            }
+            return Ok(false);
+        }
+
+        if self.typeahead.is_some() && self.handle_typeahead_key(key) {
+            return Ok(false);
+        }
+
+        if code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.view.modal = Some(Modal::Palette {
+                query: String::new(),
+                selected: 0,
+            });
+            return Ok(false);
+        }
+
+        // Modals keep their keys; a profile's remapping applies to the rest.
+        let code = self.remap_key(code);
+        if let Some(usage) = &mut self.usage {
+            usage.key(code, key.modifiers);
+        }
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            let sorted = self.sort_by_risk || self.column_sort.is_some();
+            if sorted && matches!(code, KeyCode::Up | KeyCode::Down) {
+                self.say("reordering works in file order (o)");
+                return Ok(false);
+            }
+            match code {
+                KeyCode::Up => self.move_within_priority(-1),
+                KeyCode::Down => self.move_within_priority(1),
+                _ => {}
+            }
+            return Ok(false);
+        }
+
+        if let (Some(tour), None) = (&mut self.demo, &self.view.modal) {
+            match code {
+                KeyCode::Char('t') => tour.next(),
+                KeyCode::Char('T') => tour.prev(),
+                KeyCode::Esc if tour.visible => tour.visible = false,
+                _ => {}
+            }
+            if matches!(code, KeyCode::Char('t' | 'T') | KeyCode::Esc) {
+                return Ok(false);
+            }
+        }
+
+        if self.view.header_focus.is_some() {
+            if matches!(
+                code,
+                KeyCode::Left | KeyCode::Right | KeyCode::Enter | KeyCode::Esc | KeyCode::Char('H')
+            ) {
+                self.handle_header_key(code);
+                return Ok(false);
+            }
+            // Any other key goes back to the rows.
+            self.view.header_focus = None;
+        }
+
+        if self.view.sidebar_focus
+            && self.sidebar_shown()
+            && matches!(
+                code,
+                KeyCode::Up
+                    | KeyCode::Down
+                    | KeyCode::Home
+                    | KeyCode::End
+                    | KeyCode::Enter
+                    | KeyCode::Esc
+            )
+        {
+            self.handle_sidebar_key(code);
+            return Ok(false);
+        }
+
+        match self.dashboard_key(code) {
+            Some(quit) => Ok(quit),
+            None => {
+                // A printable key the dashboard does not bind starts typeahead with itself.
+                if let KeyCode::Char(c) = code {
+                    let plain = !key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+                    if plain
+                        && !c.is_whitespace()
+                        && !self.view.sidebar_focus
+                        && !self.visible.is_empty()
+                    {
+                        self.typeahead = Some(String::new());
+                        self.handle_typeahead_key(KeyEvent::new(code, key.modifiers));
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// A key on the dashboard itself, after modals, focus and remapping had their turn; the
+    /// palette's key commands land here too. `None` for a key the dashboard does not bind,
+    /// `Some(true)` when the app should quit.
+    fn dashboard_key(&mut self, code: KeyCode) -> Option<bool> {
+        let max = self.task_count();
+        let quick_look = !self.view.sidebar_focus
+            && self
+                .selected_task()
+                .is_some_and(|t| !t.depends_on.is_empty());
+        let view = &mut self.view;
+        match code {
+            KeyCode::Char('q') if !self.staged.is_empty() && !self.quit_warned => {
+                self.quit_warned = true;
+                self.status_msg = Some(trf(
+                    self.lang,
+                    "{} staged edits are not written: W writes them, q again quits",
+                    &[&self.staged.len()],
+                ));
+            }
+            KeyCode::Char('q') => return Some(true),
+            KeyCode::Char('e') if max > 0 => self.cycle_status(),
+            KeyCode::Char('P') if max > 0 => self.cycle_priority(),
+            KeyCode::Char('E') if max > 0 => self.open_field_editor(),
+            KeyCode::Char('L') if max > 0 => self.defer_selected(),
+            KeyCode::Char('l') => self.toggle_deferred(),
+            KeyCode::Char('w') if !self.staged.is_empty() => {
+                self.view.modal = Some(Modal::Staged {
+                    scroll: 0,
+                    diff: self.staged_diff(),
+                });
+            }
+            KeyCode::Char('w') => self.say("nothing staged"),
+            KeyCode::Char('W') => self.write_staged(),
+            KeyCode::Char('R') => self.open_replan(),
+            KeyCode::Char('X') => self.open_prune(),
+            KeyCode::Char('+') if max > 0 => self.accept_suggestion(),
+            KeyCode::F(12) => self.view.modal = Some(Modal::WatchLog { scroll: 0 }),
+            KeyCode::Char('0') if self.config.projects.is_empty() => {
+                self.say("no other projects configured (see [projects] in the config)");
+            }
+            KeyCode::Char('0') => self.view.modal = Some(Modal::Overview { selected: 0 }),
+            KeyCode::Char('?') => {
+                view.modal = Some(Modal::Help {
+                    query: String::new(),
+                    scroll: 0,
+                });
+            }
+            KeyCode::Char('r') => {
+                self.reload_requested_at = Some(Instant::now());
+            }
+            KeyCode::F(5) => {
+                self.reload_requested_at.get_or_insert_with(Instant::now);
+                self.reload_now = true;
+                self.watch_log.push("debounce", "skipped by F5");
+            }
+            KeyCode::Char('A') => {
+                self.refresh_paused = !self.refresh_paused;
+                self.say(if self.refresh_paused {
+                    "auto-refresh paused; file changes still reload"
+                } else {
+                    "auto-refresh resumed"
+                });
+            }
+            KeyCode::Char(':') => {
+                view.modal = Some(Modal::Jump {
+                    input: String::new(),
+                });
+            }
+            KeyCode::Char('/') => {
+                view.modal = Some(Modal::Filter {
+                    input: self
+                        .filter
+                        .as_ref()
+                        .map(|f| f.query().to_string())
+                        .unwrap_or_default(),
+                });
+            }
+            KeyCode::Char('p') => {
+                view.modal = Some(Modal::Problems { selected: 0 });
+            }
+            KeyCode::Char('c') => {
+                view.modal = Some(Modal::Coverage { scroll: 0 });
+            }
+            KeyCode::Char('s') => self.open_stats(),
+            KeyCode::Char('m') => {
+                view.modal = Some(Modal::Matrix { selected: 0 });
+            }
+            KeyCode::Char('f') => self.toggle_focus(),
+            KeyCode::Char('o') => self.toggle_risk_sort(),
+            KeyCode::Char('H') => self.focus_header(),
+            KeyCode::Char('V') => self.cycle_profile(),
+            KeyCode::Char('D') if self.drift.is_empty() => {
+                self.say("no spec drift: done tasks match the current spec");
+            }
+            KeyCode::Char('D') => self.view.modal = Some(Modal::Drift { selected: 0 }),
+            KeyCode::Char('Q') => self.open_review(),
+            KeyCode::Char('!') if self.safe_mode.is_empty() => {
+                self.say("not in safe mode: every state file loaded");
+            }
+            KeyCode::Char('!') => self.view.modal = Some(Modal::SafeMode { selected: 0 }),
+            KeyCode::Char('b') => {
+                let shown = !self.sidebar_shown();
+                self.view.sidebar = Some(shown);
+                self.view.sidebar_focus = shown && self.view.sidebar_focus;
+            }
+            KeyCode::Tab if quick_look => self.view.modal = Some(Modal::QuickLook),
+            KeyCode::Tab => self.toggle_sidebar_focus(),
+            KeyCode::Char('d') if self.large_diff.is_some() => {
+                self.view.modal = Some(Modal::Diff { scroll: 0 });
+            }
+            KeyCode::Char('a') if self.large_diff.is_some() => self.accept_large_diff(),
+            KeyCode::Char('[') => self.step_trail(true),
+            KeyCode::Char(']') => self.step_trail(false),
+            KeyCode::Char('g') => match self.selected_task() {
+                Some(t) if t.linked_ids().is_empty() => {
+                    self.status_msg = Some(trf(self.lang, "{} links to no other task", &[&t.id]));
+                }
+                Some(_) => self.view.modal = Some(Modal::Links { selected: 0 }),
+                None => {}
+            },
+            KeyCode::Char('t') => self.toggle_timer(),
+            KeyCode::Char(c @ '1'..='9') => self.open_link(c as usize - '0' as usize),
+            KeyCode::Char('O') => self.open_link(1),
+            KeyCode::Char('z') => {
+                view.compact_details = !view.compact_details;
+                view.details_scroll = 0;
+            }
+            KeyCode::Char('Z') => view.compact_header = !view.compact_header,
+            KeyCode::Char('%') => view.weighted_progress = !view.weighted_progress,
+            KeyCode::Char('v') => {
+                self.volume = next_volume(self.volume);
+                self.status_msg = Some(match self.volume {
+                    0 => tr(self.lang, "sound muted").into_owned(),
+                    v => trf(self.lang, "volume {}%", &[&v]),
+                });
+            }
+            KeyCode::Char('x') => view.modal = Some(Modal::Export { selected: 0 }),
+            KeyCode::Char('C') if max > 0 => {
+                view.modal = Some(Modal::Comment {
+                    reply: 0,
+                    input: String::new(),
+                });
+            }
+            KeyCode::Char('n') if max > 0 => {
+                view.modal = Some(Modal::Note {
+                    input: String::new(),
+                });
+            }
+            KeyCode::Up => {
+                view.details_scroll = 0;
+                view.selected_idx = match view.selected_idx {
+                    0 if self.config.table.wrap => max.saturating_sub(1),
+                    i => i.saturating_sub(1),
+                };
+            }
+            KeyCode::Down => {
+                view.details_scroll = 0;
+                if max > 0 {
+                    view.selected_idx = match view.selected_idx + 1 {
+                        next if next == max && self.config.table.wrap => 0,
+                        next => next.min(max - 1),
+                    };
+                }
+            }
+            KeyCode::Char('\'') if max > 0 => {
+                self.typeahead = Some(String::new());
+                self.say("find: type the start of an id or title (' next, Esc done)");
+            }
+            KeyCode::PageUp => {
+                view.details_scroll = view.details_scroll.saturating_sub(4);
+            }
+            KeyCode::PageDown => {
+                view.details_scroll = view.details_scroll.saturating_add(4);
+            }
+            KeyCode::Home => {
+                view.details_scroll = 0;
+                view.selected_idx = 0;
+            }
+            KeyCode::End => {
+                view.details_scroll = 0;
+                if max > 0 {
+                    view.selected_idx = max - 1;
+                }
+            }
+            // Bound, with nothing to act on: no task, or no large change.
+            KeyCode::Char('e' | 'P' | 'E' | 'L' | '+' | 'C' | 'n' | '\'' | 'd' | 'a') => {}
+            _ => return None,
+        }
+
+        Some(false)
+    }
+}
+
+fn has_id(l: &LoadedTasks, lowercase_id: &str) -> bool {
+    l.tasks
+        .tasks
+        .iter()
+        .any(|t| {
+            let id = t.id.to_lowercase();
+            id.starts_with(lowercase_id) || id.ends_with(lowercase_id)
+        })
+}
+
+/// The `deferred_until` day for the defer prompt: a `YYYY-MM-DD` date, a duration from `now`
+/// (`3d`, `2w`), or `DEFAULT_DEFER` from now when empty. It has to lie ahead.
+fn defer_day(input: &str, now: SystemTime) -> Result<String> {
+    let input = input.trim();
+    let day = match humantime::parse_rfc3339(&format!("{input}T00:00:00Z")) {
+        Ok(day) if input.len() == 10 => day,
+        _ => {
+            let after = match input {
+                "" => DEFAULT_DEFER,
+                _ => humantime::parse_duration(input).map_err(|e| {
+                    anyhow!("{input:?} is neither a date (YYYY-MM-DD) nor a duration (2w): {e}")
+                })?,
+            };
+            now + after
+        }
+    };
+    if day <= now {
+        bail!("{input} has already begun; pick a later day");
+    }
+    Ok(humantime::format_rfc3339_seconds(day).to_string()[..10].to_string())
+}
+
+fn edit_input(input: &mut String, code: KeyCode) {
+    match code {
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        KeyCode::Char(c) => input.push(c),
+        _ => {}
+    }
+}
+
+/// Ascending order of two tasks by a header column (see `ColumnSort`).
+fn column_order(
+    column: &TableColumn,
+    a: &Task,
+    b: &Task,
+    status_by_id: &std::collections::HashMap<&str, String>,
+    priorities: &PriorityConfig,
+) -> std::cmp::Ordering {
+    let status_rank = |t: &Task| {
+        let status = task_status(t);
+        let rank = STATUS_CYCLE.iter().position(|s| *s == status);
+        (rank.unwrap_or(STATUS_CYCLE.len()), status)
+    };
+    // Tasks without dependencies ("-") sort before those with none unmet ("0").
+    let waiting = |t: &Task| (!t.depends_on.is_empty()).then(|| unmet_deps(t, status_by_id).len());
+    // Ranked names first, then unknown ones by name, then tasks without a priority.
+    let priority = |t: &Task| {
+        let key = priority_key(t);
+        (priorities.rank(&key).unwrap_or(usize::MAX), t.priority.is_none(), key)
+    };
+    let risk = |t: &Task| (t.risk.is_some(), risk_weight(t));
+    match column {
+        TableColumn::Id => natural_key(&a.id).cmp(&natural_key(&b.id)),
+        TableColumn::Status => status_rank(a).cmp(&status_rank(b)),
+        TableColumn::Wait => waiting(a).cmp(&waiting(b)),
+        TableColumn::Priority => priority(a).cmp(&priority(b)),
+        TableColumn::Risk => risk(a).cmp(&risk(b)),
+        TableColumn::Component => component_key(a).cmp(&component_key(b)),
+        TableColumn::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        TableColumn::Estimate => number_order(a.estimate_days, b.estimate_days),
+        TableColumn::SpecRefs => a.spec_refs.cmp(&b.spec_refs),
+        TableColumn::DependsOn => a.depends_on.len().cmp(&b.depends_on.len()),
+        TableColumn::Dod => a.dod.len().cmp(&b.dod.len()),
+        TableColumn::Field(name) => {
+            let (a, b) = (custom_field(a, name), custom_field(b, name));
+            let number = |v: Option<&toml::Value>| match v {
+                Some(toml::Value::Integer(n)) => Some(*n as f64),
+                Some(toml::Value::Float(n)) => Some(*n),
+                _ => None,
+            };
+            match (number(a), number(b)) {
+                (Some(x), Some(y)) => x.total_cmp(&y),
+                _ => {
+                    let text = |v: Option<&toml::Value>| v.map(|v| display_value(v).to_lowercase());
+                    text(a).cmp(&text(b))
+                }
+            }
+        }
+    }
+}
+
+/// Unset values first, then ascending.
+fn number_order(a: Option<f64>, b: Option<f64>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        _ => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// `T-9` before `T-10`: the text before the first run of digits, then its value, then the rest.
+fn natural_key(id: &str) -> (&str, u64, &str) {
+    let start = id.find(|c: char| c.is_ascii_digit()).unwrap_or(id.len());
+    let digits = id[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(id.len(), |n| start + n);
+    let number = id[start..digits].parse().unwrap_or(0);
+    (&id[..start], number, &id[digits..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::{key_name, BINDINGS};
+
+    /// The help is generated from `BINDINGS` and the dispatching is `dashboard_key`: every
+    /// key the table lists for the dashboard has to be bound there, and no other.
+    #[test]
+    fn dashboard_keys_match_the_binding_table() {
+        let named = [
+            KeyCode::Up,
+            KeyCode::Down,
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Home,
+            KeyCode::End,
+            KeyCode::PageUp,
+            KeyCode::PageDown,
+            KeyCode::Tab,
+            KeyCode::Enter,
+            KeyCode::Esc,
+            KeyCode::Backspace,
+        ];
+        let keys = (' '..='~')
+            .map(KeyCode::Char)
+            .chain((1..=12).map(KeyCode::F))
+            .chain(named);
+        for code in keys {
+            let listed = BINDINGS
+                .iter()
+                .any(|b| b.group.is_dashboard() && b.codes.contains(&code));
+            let bound = App::demo(None).dashboard_key(code).is_some();
+            assert_eq!(listed, bound, "key {}", key_name(code));
+        }
+    }
+
+    const PLAN: &str = r#"
+[[tasks]]
+id = "A-1"
+title = "Parse the file"
+
+[[tasks]]
+id = "S-1"
+title = "Render the table"
+
+[[tasks]]
+id = "S-2"
+title = "Watch for changes"
+"#;
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+                .unwrap();
+        }
+    }
+
+    fn selected(app: &App) -> Option<&str> {
+        app.selected_task().map(|t| t.id.as_str())
+    }
+
+    #[test]
+    fn typing_an_unbound_key_finds_as_you_type() {
+        let mut app = App::demo(Some(PLAN.to_string()));
+        app.maybe_reload(Duration::ZERO, Duration::MAX);
+        assert_eq!(selected(&app), Some("A-1"));
+        assert!(App::demo(None).dashboard_key(KeyCode::Char('S')).is_none());
+        press(&mut app, "S");
+        assert_eq!(selected(&app), Some("S-1"));
+        // Once typeahead is on, bound keys extend the prefix too.
+        press(&mut app, "-2");
+        assert_eq!(selected(&app), Some("S-2"));
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+            .unwrap();
+        assert!(app.typeahead.is_none());
+    }
+
+    #[test]
+    fn a_bound_key_keeps_its_action() {
+        let mut app = App::demo(Some(PLAN.to_string()));
+        app.maybe_reload(Duration::ZERO, Duration::MAX);
+        assert!(App::demo(None).dashboard_key(KeyCode::Char('w')).is_some());
+        press(&mut app, "w");
+        assert!(app.typeahead.is_none());
+        assert_eq!(selected(&app), Some("A-1"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Rotated copies of the tasks file, taken right before the monitor overwrites it (`W`, `fmt`,
+//! `restore`), in a `.harnas-backups/` directory next to it. `restore` lists and reinstates them.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+
+use crate::tasks::task_header_lines;
+use crate::writer::write_atomic;
+
+pub const BACKUP_DIR: &str = ".harnas-backups";
+/// Backups kept per tasks file when the config does not set `backups`.
+pub const DEFAULT_BACKUPS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When it was taken, as RFC 3339 (from the file name).
+    pub taken: String,
+    pub size: u64,
+}
+
+/// Backups are kept next to the file a symlink points at, like the writes they guard.
+fn resolve(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(Path::new(".")).join(BACKUP_DIR)
+}
+
+/// Copies the file as it is now to `.harnas-backups/<name>.<UTC time>` and drops the oldest
+/// backups beyond `keep`. Nothing happens when `keep` is 0 or the file does not exist yet.
+pub fn take(path: &Path, keep: usize) -> Result<Option<PathBuf>> {
+    if keep == 0 {
+        return Ok(None);
+    }
+    let path = resolve(path);
+    let contents = match std::fs::read(&path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let dir = backup_dir(&path);
+    std::fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    // Colons are not allowed in Windows file names, and sort the same without them.
+    let stamp = humantime::format_rfc3339_seconds(SystemTime::now())
+        .to_string()
+        .replace(':', "");
+    let base = format!("{}.{stamp}", file_name(&path));
+    let mut target = dir.join(&base);
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{base}-{n}"));
+        n += 1;
+    }
+    std::fs::write(&target, contents)
+        .with_context(|| format!("writing backup {}", target.display()))?;
+    for old in list(&path)?.iter().skip(keep) {
+        std::fs::remove_file(&old.path)
+            .with_context(|| format!("removing old backup {}", old.path.display()))?;
+    }
+    Ok(Some(target))
+}
+
+/// The file's backups, newest first.
+pub fn list(path: &Path) -> Result<Vec<Backup>> {
+    let path = resolve(path);
+    let dir = backup_dir(&path);
+    let prefix = format!("{}.", file_name(&path));
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", dir.display())),
+    };
+    let mut out = Vec::new();
+    for entry in entries {
+        let entry = entry.with_context(|| format!("reading {}", dir.display()))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some((taken, seq)) = name.strip_prefix(&prefix).and_then(parse_stamp) else {
+            continue;
+        };
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        let backup = Backup {
+            path: entry.path(),
+            taken,
+            size,
+        };
+        out.push((seq, backup));
+    }
+    out.sort_by(|a, b| (&b.1.taken, b.0).cmp(&(&a.1.taken, a.0)));
+    Ok(out.into_iter().map(|(_, b)| b).collect())
+}
+
+/// `2026-03-02T091500Z` back to `2026-03-02T09:15:00Z`, with the `-n` suffix of a later backup
+/// within the same second (0 without one).
+fn parse_stamp(stamp: &str) -> Option<(String, u32)> {
+    let (stamp, n) = stamp.split_once('Z')?;
+    let seq = match n {
+        "" => 0,
+        n => n.strip_prefix('-')?.parse().ok()?,
+    };
+    let (date, time) = stamp.split_once('T')?;
+    if time.len() != 6 || !time.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let formatted = format!("{date}T{}:{}:{}Z", &time[..2], &time[2..4], &time[4..]);
+    humantime::parse_rfc3339(&formatted).ok()?;
+    Some((formatted, seq))
+}
+
+/// Task count of a backup, for the `restore` listing; `None` when it cannot be read.
+pub fn task_count(backup: &Backup) -> Option<usize> {
+    let contents = std::fs::read_to_string(&backup.path).ok()?;
+    Some(task_header_lines(&contents).len())
+}
+
+/// Puts `backup` back in place of the file, after backing up the current contents so the
+/// restore itself can be undone. Returns that new backup.
+pub fn restore(path: &Path, backup: &Backup, keep: usize) -> Result<Option<PathBuf>> {
+    // Read first: taking the new backup may rotate this one out.
+    let contents = std::fs::read_to_string(&backup.path)
+        .with_context(|| format!("reading {}", backup.path.display()))?;
+    let saved = take(path, keep)?;
+    write_atomic(path, &contents)?;
+    Ok(saved)
+}
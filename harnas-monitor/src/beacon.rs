@@ -0,0 +1,54 @@
+//! The status file (`status_file` in the config): a few numbers about the last good load as
+//! JSON, rewritten atomically after every one, for shell prompts and status-bar widgets that
+//! should not parse `TASKS.toml` themselves.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::analysis::severity_counts;
+use crate::tasks::LoadedTasks;
+use crate::workspace::ProjectSummary;
+use crate::writer::write_atomic;
+
+/// Bumped when a field changes meaning or goes away; new fields do not bump it.
+const VERSION: u32 = 1;
+
+/// The document written to the status file. `loaded` is the whole file as it is on disk.
+pub fn status_document(loaded: &LoadedTasks, now: SystemTime) -> Value {
+    let s = &loaded.stats;
+    let percent = if s.total == 0 {
+        0.0
+    } else {
+        (s.done as f64 * 1000.0 / s.total as f64).round() / 10.0
+    };
+    let [errors, warnings, info] = severity_counts(&loaded.problems);
+    json!({
+        "version": VERSION,
+        "file": loaded.path,
+        "updated": humantime::format_rfc3339_seconds(now).to_string(),
+        "file_mtime": loaded
+            .file_mtime
+            .map(|m| humantime::format_rfc3339_seconds(m).to_string()),
+        "total": s.total,
+        "done": s.done,
+        "percent": percent,
+        "in_progress": s.segments.in_progress,
+        "blocked": s.segments.blocked,
+        "todo": s.segments.todo,
+        "missing_deps": s.missing_deps,
+        "problems": { "error": errors, "warning": warnings, "info": info },
+        "health": ProjectSummary::of(loaded).health(),
+    })
+}
+
+/// Writes `status_document` to `path`, creating its directory if needed.
+pub fn write_status_file(path: &Path, loaded: &LoadedTasks, now: SystemTime) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    let doc = status_document(loaded, now);
+    write_atomic(path, &format!("{doc}\n")).context("writing the status file")
+}
@@ -0,0 +1,51 @@
+//! Opens a task's `links` (`1`–`9`, `O`) with `$BROWSER` when it is set, otherwise with the
+//! platform's opener. Runs in the background with its output discarded, so the dashboard keeps
+//! the terminal.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use crate::tasks::Link;
+
+#[cfg(target_os = "macos")]
+const DEFAULT_OPENER: &[&str] = &["open"];
+#[cfg(windows)]
+const DEFAULT_OPENER: &[&str] = &["cmd", "/C", "start", ""];
+#[cfg(not(any(target_os = "macos", windows)))]
+const DEFAULT_OPENER: &[&str] = &["xdg-open"];
+
+/// What to open for `link`: its URL, or for a URL without a scheme the path it names relative
+/// to the directory of `tasks_file`.
+pub fn target(link: &Link, tasks_file: &Path) -> String {
+    let url = link.url.trim();
+    if url.contains("://") || url.starts_with("mailto:") {
+        return url.to_string();
+    }
+    let dir = tasks_file.parent().unwrap_or(Path::new("."));
+    dir.join(url).display().to_string()
+}
+
+pub fn open(target: &str) -> Result<()> {
+    let browser = std::env::var("BROWSER").ok().filter(|b| !b.trim().is_empty());
+    let mut command = match &browser {
+        Some(b) => Command::new(b.trim()),
+        None => {
+            let mut c = Command::new(DEFAULT_OPENER[0]);
+            c.args(&DEFAULT_OPENER[1..]);
+            c
+        }
+    };
+    let program = browser.as_deref().unwrap_or(DEFAULT_OPENER[0]).to_string();
+    let mut child = command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("running {program}"))?;
+    // Reaped in the background; openers like xdg-open return as soon as the browser has it.
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
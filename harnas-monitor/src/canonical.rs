@@ -0,0 +1,422 @@
+//! The canonical `TASKS.toml` layout written by `harnas-monitor fmt`, so that the generator and
+//! hand edits produce minimal diffs. Comments are kept; everything else is normalized:
+//!
+//! - keys in schema order (custom fields last, alphabetically), one `key = value` per line
+//! - `status` lowercased with `_` separators, `depends_on` and the relationship lists sorted
+//! - one blank line before each table header
+//! - strings and arrays that do not fit on a line are wrapped
+
+use std::cmp::Ordering;
+
+use anyhow::{anyhow, Result};
+use toml_edit::{Array, DocumentMut, Item, RawString, Table, Value};
+
+use crate::tasks::normalize_status;
+use crate::writer::listed_id;
+
+/// Longest `key = value` line left on one line.
+const MAX_WIDTH: usize = 100;
+/// Content width of the lines of a wrapped string.
+const WRAP_WIDTH: usize = 88;
+
+const META_KEYS: &[&str] = &["title", "spec_file", "spec_version", "output_file", "generated_utc"];
+const TARGET_KEYS: &[&str] = &["status", "percent", "by"];
+const TASK_KEYS: &[&str] = &[
+    "id",
+    "title",
+    "component",
+    "priority",
+    "risk",
+    "status",
+    "deferred_until",
+    "spec_refs",
+    "depends_on",
+    "related_to",
+    "duplicates",
+    "supersedes",
+    "summary",
+    "deliverables",
+    "estimate_days",
+    "estimate_days_min",
+    "estimate_days_max",
+    "dod",
+    "notes",
+    "links",
+    "order",
+];
+
+/// Task fields listing other task ids; sorted so reordering them is not a change.
+const ID_LISTS: &[&str] = &["depends_on", "related_to", "duplicates", "supersedes"];
+
+/// Returns `contents` in the canonical layout. Formatting the result again is a no-op.
+pub fn canonical_format(contents: &str) -> Result<String> {
+    let mut doc: DocumentMut = contents.parse().map_err(|e| anyhow!("parsing TOML: {e}"))?;
+    if let Some(meta) = doc.get_mut("meta").and_then(Item::as_table_mut) {
+        tidy_table(meta, META_KEYS);
+        if let Some(targets) = meta
+            .get_mut("targets")
+            .and_then(Item::as_array_of_tables_mut)
+        {
+            for target in targets.iter_mut() {
+                tidy_table(target, TARGET_KEYS);
+            }
+        }
+    }
+    if let Some(tasks) = doc.get_mut("tasks").and_then(Item::as_array_of_tables_mut) {
+        for task in tasks.iter_mut() {
+            if let Some(status) = task.get_mut("status").and_then(Item::as_value_mut) {
+                if let Some(s) = status.as_str() {
+                    let decor = status.decor().clone();
+                    *status = Value::from(normalize_status(s));
+                    *status.decor_mut() = decor;
+                }
+            }
+            for key in ID_LISTS {
+                if let Some(ids) = task.get_mut(key).and_then(Item::as_array_mut) {
+                    sort_ids(ids);
+                }
+            }
+            tidy_table(task, TASK_KEYS);
+        }
+    }
+    let out = doc.to_string();
+    Ok(format!("{}\n", out.trim_matches('\n')))
+}
+
+fn tidy_table(table: &mut Table, key_order: &[&str]) {
+    let rank = |k: &str| {
+        key_order
+            .iter()
+            .position(|o| *o == k)
+            .unwrap_or(key_order.len())
+    };
+    table.sort_values_by(|k1, _, k2, _| match rank(k1).cmp(&rank(k2)) {
+        Ordering::Equal => k1.get().cmp(k2.get()),
+        other => other,
+    });
+    let header_comments = comment_lines(table.decor().prefix(), true);
+    table.decor_mut().set_prefix(format!("\n{header_comments}"));
+    table.decor_mut().set_suffix("");
+
+    for (mut key, item) in table.iter_mut() {
+        // Sub-tables keep their headers as they are.
+        let Some(value) = item.as_value_mut() else {
+            continue;
+        };
+        let comments = comment_lines(key.leaf_decor().prefix(), false);
+        key.leaf_decor_mut().set_prefix(comments);
+        key.leaf_decor_mut().set_suffix(" ");
+        tidy_value(key.get().len(), value);
+    }
+}
+
+fn tidy_value(key_len: usize, value: &mut Value) {
+    let trailing_comment = value
+        .decor()
+        .suffix()
+        .and_then(RawString::as_str)
+        .map(str::trim)
+        .filter(|s| s.starts_with('#'))
+        .map(|s| format!(" {s}"))
+        .unwrap_or_default();
+    match value {
+        Value::String(s) => {
+            let s = s.value().clone();
+            let single = Value::from(s.as_str());
+            *value = if fits(key_len, &single) {
+                single
+            } else {
+                wrap_string(&s).unwrap_or(single)
+            };
+        }
+        Value::Array(items) => tidy_array(key_len, items),
+        _ => {}
+    }
+    value.decor_mut().set_prefix(" ");
+    value.decor_mut().set_suffix(trailing_comment);
+}
+
+/// `["a", "b"]` when it fits, otherwise one element per line. An array with comments is always
+/// one element per line, so that each comment keeps its place.
+fn tidy_array(key_len: usize, items: &mut Array) {
+    let comments = ArrayComments::of(items);
+    for item in items.iter_mut() {
+        if let Some(s) = item.as_str() {
+            *item = Value::from(s);
+        }
+    }
+    items.fmt();
+    items.set_trailing_comma(false);
+    items.set_trailing("");
+    if comments.is_empty() && key_len + 3 + items.to_string().trim().len() <= MAX_WIDTH {
+        return;
+    }
+    comments.write(items);
+}
+
+/// Sorts a list of task ids, moving each element's comments along with it.
+fn sort_ids(ids: &mut Array) {
+    let comments = ArrayComments::of(ids);
+    let mut pairs: Vec<_> = ids.iter().cloned().zip(comments.elements).collect();
+    pairs.sort_by(|(a, _), (b, _)| listed_id(a).cmp(&listed_id(b)));
+    let (values, elements): (Vec<_>, Vec<_>) = pairs.into_iter().unzip();
+    ids.clear();
+    for value in values {
+        ids.push_formatted(value);
+    }
+    let comments = ArrayComments {
+        elements,
+        ..comments
+    };
+    if !comments.is_empty() {
+        comments.write(ids);
+    }
+}
+
+/// The comments inside an array, attached to what they are about.
+struct ArrayComments {
+    /// The comment on the line of the `[`.
+    head: Option<String>,
+    /// Per element: the comment lines above it, and the comment ending its line.
+    elements: Vec<(Vec<String>, Option<String>)>,
+    /// Comment lines before the `]`.
+    tail: Vec<String>,
+}
+
+impl ArrayComments {
+    fn of(items: &Array) -> Self {
+        let mut comments = Self {
+            head: None,
+            elements: vec![(Vec::new(), None); items.len()],
+            tail: Vec::new(),
+        };
+        // Comments on lines of their own, waiting for the element (or `]`) below them.
+        let mut above = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            for (same_line, comment) in comment_parts(item.decor().prefix()) {
+                let line_end = match i {
+                    0 => &mut comments.head,
+                    _ => &mut comments.elements[i - 1].1,
+                };
+                if same_line && line_end.is_none() {
+                    *line_end = Some(comment);
+                } else {
+                    above.push(comment);
+                }
+            }
+            comments.elements[i].0 = std::mem::take(&mut above);
+            for (same_line, comment) in comment_parts(item.decor().suffix()) {
+                if same_line && comments.elements[i].1.is_none() {
+                    comments.elements[i].1 = Some(comment);
+                } else {
+                    above.push(comment);
+                }
+            }
+        }
+        for (same_line, comment) in comment_parts(Some(items.trailing())) {
+            let line_end = match comments.elements.last_mut() {
+                Some((_, after)) => after,
+                None => &mut comments.head,
+            };
+            if same_line && line_end.is_none() {
+                *line_end = Some(comment);
+            } else {
+                above.push(comment);
+            }
+        }
+        comments.tail = above;
+        comments
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.is_none()
+            && self.tail.is_empty()
+            && self
+                .elements
+                .iter()
+                .all(|(above, after)| above.is_empty() && after.is_none())
+    }
+
+    /// Lays `items` out one element per line with the comments in place.
+    fn write(&self, items: &mut Array) {
+        let mut line_end = self.head.clone();
+        for (item, (above, after)) in items.iter_mut().zip(&self.elements) {
+            item.decor_mut()
+                .set_prefix(format!("{}\n    ", comment_block(line_end.take(), above)));
+            item.decor_mut().set_suffix("");
+            line_end.clone_from(after);
+        }
+        items.set_trailing_comma(!items.is_empty());
+        items.set_trailing(format!("{}\n", comment_block(line_end, &self.tail)));
+    }
+}
+
+/// The comments of a decor string: whether each is on the line the decor starts on, and its text.
+fn comment_parts(raw: Option<&RawString>) -> Vec<(bool, String)> {
+    let raw = raw.and_then(RawString::as_str).unwrap_or("");
+    raw.split('\n')
+        .enumerate()
+        .map(|(i, line)| (i == 0, line.trim()))
+        .filter(|(_, line)| line.starts_with('#'))
+        .map(|(same_line, line)| (same_line, line.to_string()))
+        .collect()
+}
+
+/// ` # end of line` followed by indented comment lines.
+fn comment_block(line_end: Option<String>, lines: &[String]) -> String {
+    let mut out = line_end.map(|c| format!(" {c}")).unwrap_or_default();
+    for line in lines {
+        out.push_str("\n    ");
+        out.push_str(line);
+    }
+    out
+}
+
+fn fits(key_len: usize, value: &Value) -> bool {
+    key_len + 3 + value.to_string().trim().len() <= MAX_WIDTH
+}
+
+/// A multi-line basic string whose lines end in a line-ending backslash, so the wrapped text
+/// reads back exactly as `s`. Lines only break at a single space before a word; `None` when the
+/// string holds characters this form cannot show literally.
+fn wrap_string(s: &str) -> Option<Value> {
+    let mut raw = String::from("\"\"\"\n");
+    for (i, line) in s.split('\n').enumerate() {
+        if i > 0 {
+            raw.push('\n');
+        }
+        let mut width = 0;
+        for (j, word) in line.split(' ').enumerate() {
+            let escaped = escape(word)?;
+            if j > 0 {
+                if !word.is_empty() && width > 0 && width + 1 + escaped.len() > WRAP_WIDTH {
+                    raw.push_str(" \\\n");
+                    width = 0;
+                } else {
+                    raw.push(' ');
+                    width += 1;
+                }
+            }
+            width += escaped.len();
+            raw.push_str(&escaped);
+        }
+    }
+    raw.push_str("\"\"\"");
+    let value: Value = raw.parse().ok()?;
+    (value.as_str() == Some(s)).then_some(value)
+}
+
+fn escape(word: &str) -> Option<String> {
+    let mut out = String::with_capacity(word.len());
+    for c in word.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\t' => out.push(c),
+            c if c.is_control() => return None,
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+/// The comment lines of a decor prefix, each ending in a newline. With `keep_blank`, a blank
+/// line after a comment is kept (collapsed to one), e.g. between a file header and `[meta]`.
+fn comment_lines(prefix: Option<&RawString>, keep_blank: bool) -> String {
+    let raw = prefix.and_then(RawString::as_str).unwrap_or("");
+    let mut out = String::new();
+    let mut blank = false;
+    for line in raw.lines().map(str::trim) {
+        if line.starts_with('#') {
+            if blank {
+                out.push('\n');
+                blank = false;
+            }
+            out.push_str(line);
+            out.push('\n');
+        } else if line.is_empty() && keep_blank && !out.is_empty() {
+            blank = true;
+        }
+    }
+    if blank {
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = r#"# Plan for the monitor
+
+[meta]
+spec_version = "1"
+title = "monitor"
+
+[[tasks]]
+status = "In Progress"
+title = "Render the table"
+id = "T-2"
+depends_on = ["T-1", # the parser first
+  # and the watcher, for reloads
+  "T-0",
+  "T-9" # not written yet
+  ,
+  # more to come
+]
+deliverables = [ # in review order
+"src/ui.rs", "src/app.rs"]
+dod = ["fast", "small"]
+reviewer = "ann" # custom, sorted last
+
+[[tasks]]
+id = "T-1"
+title = "Parse the file"
+spec_refs = ["§1", "§2"]
+"#;
+
+    #[test]
+    fn formatting_twice_changes_nothing() {
+        let once = canonical_format(MESSY).unwrap();
+        assert_eq!(canonical_format(&once).unwrap(), once);
+    }
+
+    #[test]
+    fn comments_in_arrays_survive() {
+        let out = canonical_format(MESSY).unwrap();
+        for comment in [
+            "# Plan for the monitor",
+            "# the parser first",
+            "# and the watcher, for reloads",
+            "# not written yet",
+            "# more to come",
+            "# in review order",
+            "# custom, sorted last",
+        ] {
+            assert!(out.contains(comment), "{comment:?} lost:\n{out}");
+        }
+        let tasks: crate::tasks::TaskFile = toml::from_str(&out).unwrap();
+        let deps: Vec<_> = tasks.tasks[0]
+            .depends_on
+            .iter()
+            .map(|d| d.id.as_str())
+            .collect();
+        assert_eq!(deps, ["T-0", "T-1", "T-9"]);
+    }
+
+    #[test]
+    fn a_comment_stays_on_its_element_line() {
+        let out = canonical_format(MESSY).unwrap();
+        assert!(out.contains("\"T-1\", # the parser first\n"), "{out}");
+        assert!(out.contains("\"T-9\", # not written yet\n"), "{out}");
+        assert!(out.contains("deliverables = [ # in review order\n"), "{out}");
+    }
+
+    #[test]
+    fn arrays_without_comments_stay_on_one_line() {
+        let out = canonical_format(MESSY).unwrap();
+        assert!(out.contains("dod = [\"fast\", \"small\"]\n"), "{out}");
+        assert!(out.contains("spec_refs = [\"§1\", \"§2\"]\n"), "{out}");
+    }
+}
@@ -0,0 +1,142 @@
+//! The charts under the stats panel (`s`): open tasks per load (burndown), tasks per estimate
+//! (histogram) and the dependency graph laid out in layers. The panel draws them with
+//! characters; builds with the `graphics` feature draw them as images on terminals that show
+//! kitty or sixel graphics (see `graphics`), which is why each chart is kept as plain data.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use ratatui::layout::Rect;
+
+use crate::graph::dependency_graph;
+use crate::history::History;
+use crate::tasks::{is_deferred, TaskFile};
+
+/// Loads the burndown goes back.
+pub const BURNDOWN_SAMPLES: usize = 60;
+
+/// Upper bounds of the estimate buckets in days; the last bucket takes the rest.
+pub const ESTIMATE_LIMITS: [f64; 5] = [0.5, 1.0, 2.0, 3.0, 5.0];
+/// One per bucket: without an estimate, then up to each limit, then above the last.
+pub const ESTIMATE_LABELS: [&str; 7] = ["none", "0.5", "1", "2", "3", "5", "5+"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Chart {
+    /// Open tasks in each of the last loads, oldest first.
+    Burndown(Vec<u64>),
+    /// Tasks per estimate bucket, in `ESTIMATE_LABELS` order.
+    Estimates(Vec<u64>),
+    Graph(GraphLayout),
+}
+
+/// A chart drawn as an image, at the cells it covers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSlot {
+    pub area: Rect,
+    pub chart: Chart,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphLayout {
+    pub nodes: Vec<PlacedNode>,
+    /// Node indices, dependency first, and whether the dependency is unmet.
+    pub edges: Vec<(usize, usize, bool)>,
+}
+
+/// A node at (`x`, `y`), both from 0 to 1: `x` by how many dependencies deep it sits, `y` by
+/// its place within that layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedNode {
+    pub x: f64,
+    pub y: f64,
+    /// As in `graph::Node`: "blocked" for waiting tasks, "missing" for unknown ids.
+    pub status: String,
+}
+
+/// Open tasks (neither done nor deferred) in each of the last `BURNDOWN_SAMPLES` snapshots.
+pub fn burndown(history: &History) -> Vec<u64> {
+    let skip = history.snapshots.len().saturating_sub(BURNDOWN_SAMPLES);
+    history
+        .snapshots
+        .range(skip..)
+        .map(|s| {
+            s.tasks
+                .values()
+                .filter(|t| t.status != "done" && t.status != "deferred")
+                .count() as u64
+        })
+        .collect()
+}
+
+/// Tasks per estimate bucket; deferred ones are left out, as in the burndown.
+pub fn estimates(file: &TaskFile, now: SystemTime) -> Vec<u64> {
+    let mut counts = vec![0; ESTIMATE_LABELS.len()];
+    for t in file.tasks.iter().filter(|t| !is_deferred(t, now)) {
+        let bucket = match t.estimate_days {
+            None => 0,
+            Some(days) => {
+                1 + ESTIMATE_LIMITS
+                    .iter()
+                    .position(|limit| days <= *limit)
+                    .unwrap_or(ESTIMATE_LIMITS.len())
+            }
+        };
+        counts[bucket] += 1;
+    }
+    counts
+}
+
+/// Lays the dependency graph out left to right: tasks without dependencies first, every other
+/// one a layer right of its deepest dependency. Cycles stop deepening after a pass per node.
+pub fn graph_layout(file: &TaskFile) -> GraphLayout {
+    let graph = dependency_graph(file);
+    let index: HashMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+    let edges: Vec<(usize, usize, bool)> = graph
+        .edges
+        .iter()
+        .filter_map(|e| {
+            Some((
+                *index.get(e.from.as_str())?,
+                *index.get(e.to.as_str())?,
+                e.unmet,
+            ))
+        })
+        .collect();
+    let mut depth = vec![0usize; graph.nodes.len()];
+    for _ in 0..graph.nodes.len() {
+        let mut deeper = false;
+        for &(from, to, _) in &edges {
+            if depth[to] <= depth[from] {
+                depth[to] = depth[from] + 1;
+                deeper = true;
+            }
+        }
+        if !deeper {
+            break;
+        }
+    }
+    let layers = depth.iter().max().map_or(1, |d| d + 1);
+    let mut sizes = vec![0usize; layers];
+    let mut place = vec![0usize; graph.nodes.len()];
+    for (i, d) in depth.iter().enumerate() {
+        place[i] = sizes[*d];
+        sizes[*d] += 1;
+    }
+    let spread = |at: usize, of: usize| (at as f64 + 0.5) / of as f64;
+    let nodes = graph
+        .nodes
+        .into_iter()
+        .enumerate()
+        .map(|(i, n)| PlacedNode {
+            x: spread(depth[i], layers),
+            y: spread(place[i], sizes[depth[i]]),
+            status: n.status,
+        })
+        .collect();
+    GraphLayout { nodes, edges }
+}
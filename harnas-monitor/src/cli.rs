@@ -0,0 +1,334 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::filter::Filter;
+
+pub const USAGE: &str = "\
+usage: harnas-monitor [--config <path>] [--select <id>] [--socket <path>] [--component <name>]
+                      [--profile <name>] [--serve <addr>] [--deterministic] [--debug-watch]
+                      [--ascii] [--discover | TASKS.toml]
+       harnas-monitor --demo [--tasks <n> [--max-deps <n>] [--seed <n>]] [--component <name>]
+                             [--ascii]
+       harnas-monitor export [--format json|quickfix|dot|mermaid|jira] [--filter <query>] [--quiet]
+                             [TASKS.toml]
+       harnas-monitor export --split --dir <path> [--format markdown|json] [--filter <query>]
+                             [--quiet] [TASKS.toml]
+       harnas-monitor lint [--config <path>] [--quiet] [TASKS.toml]
+       harnas-monitor stats [--watch] [--config <path>] [--quiet] [TASKS.toml]
+       harnas-monitor render [--width <cols>] [--height <rows>] [--component <name>]
+                             [--profile <name>] [--deterministic] [--ascii] [TASKS.toml]
+       harnas-monitor fmt [--check] [--config <path>] [--quiet] [TASKS.toml]
+       harnas-monitor prune-deps [--write] [--config <path>] [--quiet] [TASKS.toml]
+       harnas-monitor rename-component <old> <new> [--write] [--config <path>] [--quiet]
+                                       [TASKS.toml]
+       harnas-monitor restore [--backup <n>] [--config <path>] [--quiet] [TASKS.toml]
+       harnas-monitor usage [--config <path>]
+       harnas-monitor history query \"<sql or name>\" [TASKS.toml]
+       harnas-monitor gen [--tasks <n>] [--max-deps <n>] [--seed <n>]
+       harnas-monitor schema
+       harnas-monitor merge-driver <base> <ours> <theirs>";
+
+/// Exit codes of the headless commands, so CI can gate on `TASKS.toml` health.
+pub const EXIT_PROBLEMS: u8 = 1;
+/// Parse, config and usage errors.
+pub const EXIT_PARSE: u8 = 2;
+pub const EXIT_IO: u8 = 3;
+
+/// Maps an error to `EXIT_IO` when an I/O failure is anywhere in its chain, `EXIT_PARSE`
+/// otherwise.
+pub fn exit_code_for(e: &anyhow::Error) -> u8 {
+    if e.chain().any(|c| c.is::<std::io::Error>()) {
+        EXIT_IO
+    } else {
+        EXIT_PARSE
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// The interactive TUI.
+    #[default]
+    Dashboard,
+    /// Print the tasks in a machine-readable format and exit.
+    Export,
+    /// Print validation problems; exits with `EXIT_PROBLEMS` on any error or warning.
+    Lint,
+    /// Print a short summary; exits with `EXIT_PROBLEMS` on any error or warning.
+    Stats,
+    /// Rewrite the file in the canonical layout.
+    Fmt,
+    /// Draw the dashboard once as plain text and exit.
+    Render,
+    /// Three-way merge of task files for git; writes the result over `ours`.
+    MergeDriver,
+    /// List the rotated backups of the tasks file, or put one back (`--backup`).
+    Restore,
+    /// List redundant dependencies; exits with `EXIT_PROBLEMS` when there are any, unless
+    /// `--write` removed them.
+    PruneDeps,
+    /// Show the diff of giving every task of one component another name, or make it with
+    /// `--write`.
+    RenameComponent,
+    /// Print the local usage statistics (`[usage]` in the config).
+    Usage,
+    /// Print a synthetic tasks file (`gen::generate`).
+    Gen,
+    /// Run a query against the SQLite history (`store::query`).
+    History,
+    /// Print the JSON Schema of the tasks file (`schema::schema`).
+    Schema,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Args {
+    pub help: bool,
+    pub command: Command,
+    pub path: Option<PathBuf>,
+    pub select: Option<String>,
+    /// Unix-domain socket for the JSON-RPC control interface.
+    pub socket: Option<PathBuf>,
+    /// Address for the HTTP server with the Atom feed of task changes.
+    pub serve: Option<String>,
+    pub format: Option<String>,
+    pub config: Option<PathBuf>,
+    /// Explore the UI with embedded sample data instead of a tasks file.
+    pub demo: bool,
+    /// Headless commands print nothing but errors; the exit code carries the result.
+    pub quiet: bool,
+    /// Scope the dashboard to one component from the start.
+    pub component: Option<String>,
+    /// Start with this view profile from the config's `[profiles]`.
+    pub profile: Option<String>,
+    /// `fmt` only reports whether the file is formatted; exits with `EXIT_PROBLEMS` if not.
+    pub check: bool,
+    /// `prune-deps` removes what it lists instead of only listing it; `rename-component` writes
+    /// the diff it shows.
+    pub write: bool,
+    /// `rename-component` inputs: the old name, then the new one.
+    pub rename: Vec<String>,
+    /// `merge-driver` inputs: base, ours, theirs.
+    pub merge_inputs: Vec<PathBuf>,
+    /// `export` writes one file per task into `dir` instead of printing.
+    pub split: bool,
+    pub dir: Option<PathBuf>,
+    /// `export` only the tasks matching this query, in the TUI's filter syntax.
+    pub filter: Option<Filter>,
+    /// `stats` keeps running and prints a new summary whenever the file changes.
+    pub watch: bool,
+    /// Open the watch log (`F12`) at startup.
+    pub debug_watch: bool,
+    /// Pick among every tasks file in the repository, or open them all as a workspace.
+    pub discover: bool,
+    /// Fixed clock and no history file, so the same tasks file renders byte-identically.
+    pub deterministic: bool,
+    /// Draw with ASCII symbols only, whatever the terminal seems to support.
+    pub ascii: bool,
+    /// `render` size; defaults to `RENDER_WIDTH` x `RENDER_HEIGHT`.
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    /// `restore` this backup, counting from 1 for the newest.
+    pub backup: Option<usize>,
+    /// `gen`, or `--demo` on a generated plan instead of the bundled one.
+    pub tasks: Option<usize>,
+    pub max_deps: Option<usize>,
+    pub seed: Option<u64>,
+    /// `history query`: SQL, or the name of one of `store::QUERIES`.
+    pub query: Option<String>,
+}
+
+pub const RENDER_WIDTH: u16 = 120;
+pub const RENDER_HEIGHT: u16 = 40;
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args> {
+    let mut out = Args::default();
+    let mut it = args.into_iter();
+    // `history` takes the `query` verb before its query.
+    let mut history_verb = false;
+    while let Some(arg) = it.next() {
+        // Accept both `--flag value` and `--flag=value`.
+        let (flag, inline) = match arg.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |what: &str| -> Result<String> {
+            inline
+                .clone()
+                .or_else(|| it.next())
+                .ok_or_else(|| anyhow!("{flag} needs {what}\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => out.help = true,
+            "--demo" => out.demo = true,
+            "-q" | "--quiet" => out.quiet = true,
+            "--check" => out.check = true,
+            "--write" => out.write = true,
+            "--deterministic" => out.deterministic = true,
+            "--debug-watch" => out.debug_watch = true,
+            "--watch" => out.watch = true,
+            "--discover" => out.discover = true,
+            "--ascii" => out.ascii = true,
+            "--split" => out.split = true,
+            "--dir" => out.dir = Some(PathBuf::from(value("a directory")?)),
+            "--select" => out.select = Some(value("a task id")?),
+            "--socket" => out.socket = Some(PathBuf::from(value("a socket path")?)),
+            "--serve" => out.serve = Some(value("an address like 127.0.0.1:8080")?),
+            "--format" => out.format = Some(value("a format")?),
+            "--filter" => out.filter = Some(Filter::parse(&value("a filter query")?)?),
+            "--config" => out.config = Some(PathBuf::from(value("a config path")?)),
+            "--component" => out.component = Some(value("a component name")?),
+            "--profile" => out.profile = Some(value("a profile name")?),
+            "--width" => out.width = Some(size(&flag, &value("a column count")?)?),
+            "--height" => out.height = Some(size(&flag, &value("a row count")?)?),
+            "--tasks" => out.tasks = Some(count(&flag, &value("a task count")?, 1)?),
+            "--max-deps" => out.max_deps = Some(count(&flag, &value("a dependency count")?, 0)?),
+            "--seed" => {
+                let v = value("a number")?;
+                match v.parse::<u64>() {
+                    Ok(n) => out.seed = Some(n),
+                    Err(_) => bail!("--seed needs a whole number, got {v:?}\n{USAGE}"),
+                }
+            }
+            "--backup" => {
+                let v = value("a backup number")?;
+                match v.parse::<usize>() {
+                    Ok(n) if n >= 1 => out.backup = Some(n),
+                    _ => bail!(
+                        "--backup needs a number from the restore listing, got {v:?}\n{USAGE}"
+                    ),
+                }
+            }
+            s if s.starts_with('-') => bail!("unknown option {s}\n{USAGE}"),
+            "export" | "lint" | "stats" | "fmt" | "render" | "merge-driver" | "restore"
+            | "prune-deps" | "rename-component" | "usage" | "gen" | "history" | "schema"
+                if out.command == Command::Dashboard && out.path.is_none() =>
+            {
+                out.command = match flag.as_str() {
+                    "export" => Command::Export,
+                    "lint" => Command::Lint,
+                    "stats" => Command::Stats,
+                    "fmt" => Command::Fmt,
+                    "render" => Command::Render,
+                    "restore" => Command::Restore,
+                    "prune-deps" => Command::PruneDeps,
+                    "rename-component" => Command::RenameComponent,
+                    "usage" => Command::Usage,
+                    "gen" => Command::Gen,
+                    "history" => Command::History,
+                    "schema" => Command::Schema,
+                    _ => Command::MergeDriver,
+                };
+            }
+            _ if out.command == Command::MergeDriver => out.merge_inputs.push(PathBuf::from(arg)),
+            _ if out.command == Command::RenameComponent && out.rename.len() < 2 => {
+                out.rename.push(arg)
+            }
+            _ if out.command == Command::History && !history_verb => {
+                if arg != "query" {
+                    bail!("unknown history command {arg:?}; try history query\n{USAGE}");
+                }
+                history_verb = true;
+            }
+            _ if out.command == Command::History && out.query.is_none() => out.query = Some(arg),
+            _ => {
+                if out.path.is_some() {
+                    bail!("only one tasks file may be given\n{USAGE}");
+                }
+                out.path = Some(PathBuf::from(arg));
+            }
+        }
+    }
+    if out.demo && (out.command != Command::Dashboard || out.path.is_some()) {
+        bail!("--demo takes no tasks file and only works with the dashboard\n{USAGE}");
+    }
+    if out.command == Command::MergeDriver && out.merge_inputs.len() != 3 {
+        bail!("merge-driver needs exactly three files: <base> <ours> <theirs>\n{USAGE}");
+    }
+    if out.command == Command::RenameComponent
+        && (out.rename.len() != 2 || out.rename.iter().any(|n| n.trim().is_empty()))
+    {
+        bail!("rename-component needs the old and the new component name\n{USAGE}");
+    }
+    if out.check && out.command != Command::Fmt {
+        bail!("--check only works with fmt\n{USAGE}");
+    }
+    if out.component.is_some() && !matches!(out.command, Command::Dashboard | Command::Render) {
+        bail!("--component only works with the dashboard and render\n{USAGE}");
+    }
+    if out.profile.is_some()
+        && (!matches!(out.command, Command::Dashboard | Command::Render) || out.demo)
+    {
+        bail!("--profile only works with the dashboard and render\n{USAGE}");
+    }
+    if (out.split || out.dir.is_some()) && out.command != Command::Export {
+        bail!("--split and --dir only work with export\n{USAGE}");
+    }
+    if out.filter.is_some() && out.command != Command::Export {
+        bail!("--filter only works with export\n{USAGE}");
+    }
+    if out.split != out.dir.is_some() {
+        bail!("--split and --dir go together: export --split --dir <path>\n{USAGE}");
+    }
+    if out.discover && (out.command != Command::Dashboard || out.demo || out.path.is_some()) {
+        bail!("--discover only works with the dashboard, without a tasks file\n{USAGE}");
+    }
+    if out.watch && out.command != Command::Stats {
+        bail!("--watch only works with stats\n{USAGE}");
+    }
+    if out.debug_watch && (out.command != Command::Dashboard || out.demo) {
+        bail!("--debug-watch only works with the dashboard\n{USAGE}");
+    }
+    if out.serve.is_some() && (out.command != Command::Dashboard || out.demo) {
+        bail!("--serve only works with the dashboard\n{USAGE}");
+    }
+    if out.deterministic && !matches!(out.command, Command::Dashboard | Command::Render) {
+        bail!("--deterministic only works with the dashboard and render\n{USAGE}");
+    }
+    if out.ascii && !matches!(out.command, Command::Dashboard | Command::Render) {
+        bail!("--ascii only works with the dashboard and render\n{USAGE}");
+    }
+    if out.write && !matches!(out.command, Command::PruneDeps | Command::RenameComponent) {
+        bail!("--write only works with prune-deps and rename-component\n{USAGE}");
+    }
+    if out.backup.is_some() && out.command != Command::Restore {
+        bail!("--backup only works with restore\n{USAGE}");
+    }
+    if out.command == Command::Usage && out.path.is_some() {
+        bail!("usage takes no tasks file\n{USAGE}");
+    }
+    if out.command == Command::Gen && out.path.is_some() {
+        bail!("gen takes no tasks file; it prints one\n{USAGE}");
+    }
+    if out.command == Command::Schema && out.path.is_some() {
+        bail!("schema takes no tasks file; validate one with lint\n{USAGE}");
+    }
+    let generated = out.tasks.is_some() || out.max_deps.is_some() || out.seed.is_some();
+    if generated && out.command != Command::Gen && !out.demo {
+        bail!("--tasks, --max-deps and --seed only work with gen and --demo\n{USAGE}");
+    }
+    if out.demo && (out.max_deps.is_some() || out.seed.is_some()) && out.tasks.is_none() {
+        bail!("--demo needs --tasks for a generated plan\n{USAGE}");
+    }
+    if out.command == Command::History && out.query.is_none() {
+        bail!("history query needs SQL or a query name\n{USAGE}");
+    }
+    if (out.width.is_some() || out.height.is_some()) && out.command != Command::Render {
+        bail!("--width and --height only work with render\n{USAGE}");
+    }
+    Ok(out)
+}
+
+/// A count of at least `min`, for `--tasks` and `--max-deps`.
+fn count(flag: &str, v: &str, min: usize) -> Result<usize> {
+    match v.parse::<usize>() {
+        Ok(n) if n >= min && n <= 1_000_000 => Ok(n),
+        _ => bail!("{flag} needs a number from {min} to 1000000, got {v:?}\n{USAGE}"),
+    }
+}
+
+fn size(flag: &str, v: &str) -> Result<u16> {
+    match v.parse::<u16>() {
+        Ok(n) if n >= 20 => Ok(n),
+        _ => bail!("{flag} needs a number of at least 20, got {v:?}\n{USAGE}"),
+    }
+}
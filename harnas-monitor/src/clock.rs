@@ -0,0 +1,93 @@
+//! The clocks the core reads. std's `Instant::now` and `SystemTime::now` panic on
+//! `wasm32-unknown-unknown`, so there the page hosting the module supplies the time through the
+//! imported `harnas_now_ms` (milliseconds since the Unix epoch, `Date.now()`); everywhere else
+//! these are std's clocks.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+/// Wall-clock time.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now() -> std::time::SystemTime {
+    std::time::SystemTime::now()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use wasm::{now, Instant};
+
+#[cfg(target_arch = "wasm32")]
+mod wasm {
+    use std::ops::{Add, Sub};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[link(wasm_import_module = "env")]
+    extern "C" {
+        fn harnas_now_ms() -> f64;
+    }
+
+    fn since_epoch() -> Duration {
+        // SAFETY: an imported host function without arguments or memory access.
+        let ms = unsafe { harnas_now_ms() };
+        Duration::from_secs_f64(ms.max(0.0) / 1000.0)
+    }
+
+    /// Wall-clock time.
+    pub fn now() -> SystemTime {
+        UNIX_EPOCH + since_epoch()
+    }
+
+    /// A point in time as the host's clock sees it. Not monotonic; a clock set back makes
+    /// `elapsed` zero rather than negative.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Instant(since_epoch())
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Self::now().saturating_duration_since(*self)
+        }
+
+        pub fn duration_since(&self, earlier: Self) -> Duration {
+            self.saturating_duration_since(earlier)
+        }
+
+        pub fn saturating_duration_since(&self, earlier: Self) -> Duration {
+            self.0.saturating_sub(earlier.0)
+        }
+
+        pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+            self.0.checked_add(duration).map(Instant)
+        }
+
+        pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+            self.0.checked_sub(duration).map(Instant)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Self;
+
+        fn add(self, duration: Duration) -> Self {
+            Instant(self.0 + duration)
+        }
+    }
+
+    impl Sub<Duration> for Instant {
+        type Output = Self;
+
+        fn sub(self, duration: Duration) -> Self {
+            Instant(self.0.saturating_sub(duration))
+        }
+    }
+
+    impl Sub for Instant {
+        type Output = Duration;
+
+        fn sub(self, earlier: Self) -> Duration {
+            self.saturating_duration_since(earlier)
+        }
+    }
+}
@@ -0,0 +1,881 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::analysis::{suppress, unknown_priorities, Problem, Suppression};
+use crate::app::{ColumnSort, TableColumn};
+use crate::backup::DEFAULT_BACKUPS;
+use crate::diff::ChangeClass;
+use crate::filter::Filter;
+use crate::history::HISTORY_SNAPSHOTS;
+use crate::i18n::{Lang, LANGUAGES};
+use crate::keys::KeyCode;
+use crate::schedule::{known_priority_rank, UNESTIMATED_DAYS};
+use crate::sound::{Cue, SoundConfig};
+use crate::review::REVIEW_DAYS;
+use crate::targets::{parse_target, Target, TargetSpec};
+use crate::tasks::{normalize_status, LoadedTasks};
+use crate::ui::THEMES;
+use crate::usage::default_usage_path;
+use crate::watch::WATCH_LOG_LEN;
+
+/// User configuration, read from `--config`, `$HARNAS_MONITOR_CONFIG` or
+/// `$XDG_CONFIG_HOME/harnas-monitor/config.toml` (falling back to `~/.config`). Everything is
+/// optional; a missing file means defaults.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Maximum time a task may stay blocked, keyed by lowercased priority.
+    pub blocked_sla: BTreeMap<String, Duration>,
+    /// Cost of one estimated day; enables the cost roll-up in the stats panel and exports.
+    pub day_rate: Option<f64>,
+    pub sound: SoundConfig,
+    /// Personal progress targets, tracked next to the ones in `[[meta.targets]]`.
+    pub targets: Vec<Target>,
+    /// A reload changing more than this share of tasks (in percent) waits for confirmation.
+    pub large_diff_percent: Option<f64>,
+    /// Tasks worked on at the same time, for the replan modal's schedule.
+    pub parallelism: Option<usize>,
+    /// Rotated copies of the tasks file kept before each write (`.harnas-backups/`); 0 keeps
+    /// none.
+    pub backups: Option<usize>,
+    /// JSON summary rewritten after every good load, for prompts and status bars (`beacon`).
+    pub status_file: Option<PathBuf>,
+    /// Repository whose history the stats panel reads for who finished which tasks; defaults
+    /// to the one the tasks file is in.
+    pub git_repo: Option<PathBuf>,
+    pub usage: UsageConfig,
+    pub details: DetailsConfig,
+    pub table: TableConfig,
+    pub header: HeaderConfig,
+    /// Other projects' tasks files by name, for `project:ID` dependencies (workspace mode).
+    pub projects: BTreeMap<String, PathBuf>,
+    pub cells: CellConfig,
+    /// Effort budgets in estimated days by component, overriding the plan's `[meta.budgets]`.
+    pub budgets: BTreeMap<String, f64>,
+    /// Owner or team by lowercased component (`[owners]`), shown in the details and stats and
+    /// matched by the `owner:` and `has:owner` filter terms.
+    pub owners: BTreeMap<String, String>,
+    pub progress: ProgressConfig,
+    pub ticker: TickerConfig,
+    pub retention: RetentionConfig,
+    pub refresh: RefreshConfig,
+    pub review: ReviewConfig,
+    /// Name signed under comments (`C`); defaults to `$USER`.
+    pub author: Option<String>,
+    /// UI language; defaults to the locale (`$LC_ALL`, `$LC_MESSAGES`, `$LANG`).
+    pub language: Option<Lang>,
+    /// Named views, picked with `--profile` or cycled with `V`.
+    pub profiles: BTreeMap<String, Profile>,
+    pub lint: LintConfig,
+    pub priorities: PriorityConfig,
+}
+
+impl Config {
+    pub fn backups(&self) -> usize {
+        self.backups.unwrap_or(DEFAULT_BACKUPS)
+    }
+
+    /// Adds the problems only the config can find (unknown priorities) to those of `loaded`,
+    /// the tasks file at `path`, then drops the ones `[lint]` silences.
+    pub fn check(&self, path: &Path, loaded: &mut LoadedTasks) {
+        let unknown = unknown_priorities(&loaded.tasks, &self.priorities);
+        loaded.problems.extend(unknown);
+        self.lint.apply(path, &mut loaded.problems);
+    }
+}
+
+/// How priorities rank (`[priorities]`): the colours and sort order of the Prio column, and
+/// which names the unknown-priority warning accepts.
+#[derive(Debug, Clone, Default)]
+pub struct PriorityConfig {
+    /// Lowercased names from most to least urgent; empty for the built-in MoSCoW, high/low and
+    /// P0..P4 names (`schedule::priority_rank`).
+    pub order: Vec<String>,
+}
+
+impl PriorityConfig {
+    /// Where the lowercased priority `key` ranks, from 0 (most urgent) up to `levels() - 1`;
+    /// `None` for a name the order does not know.
+    pub fn rank(&self, key: &str) -> Option<usize> {
+        if self.order.is_empty() {
+            return known_priority_rank(key).map(usize::from);
+        }
+        self.order.iter().position(|p| p == key)
+    }
+
+    /// How many ranks there are: the configured names, or the five built-in tiers.
+    pub fn levels(&self) -> usize {
+        if self.order.is_empty() {
+            5
+        } else {
+            self.order.len()
+        }
+    }
+}
+
+/// Local usage statistics (`[usage]`, see `usage`); off unless `enabled`.
+#[derive(Debug, Clone, Default)]
+pub struct UsageConfig {
+    pub enabled: bool,
+    /// Defaults to `usage::default_usage_path`.
+    pub file: Option<PathBuf>,
+}
+
+impl UsageConfig {
+    pub fn path(&self) -> Option<PathBuf> {
+        self.file.clone().or_else(default_usage_path)
+    }
+}
+
+/// `[lint]`: validation rules silenced on top of each file's own `[meta.lint]`.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    /// For every tasks file.
+    pub suppress: Vec<Suppression>,
+    /// By tasks file (`[lint.files]`), resolved like `[projects]` paths.
+    pub files: BTreeMap<PathBuf, Vec<Suppression>>,
+}
+
+impl LintConfig {
+    /// Drops the problems of the tasks file at `path` that the config silences.
+    pub fn apply(&self, path: &Path, problems: &mut Vec<Problem>) {
+        suppress(problems, &self.suppress);
+        let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        for (file, rules) in &self.files {
+            if std::fs::canonicalize(file).unwrap_or_else(|_| file.clone()) == key {
+                suppress(problems, rules);
+            }
+        }
+    }
+}
+
+/// A named view (`[profiles.NAME]`): what the table shows and how, so a PM's and an
+/// engineer's dashboard can share one config. Anything unset leaves the current view alone.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    /// Table columns in order; the default set when unset.
+    pub columns: Option<Vec<TableColumn>>,
+    /// One of `ui::THEMES`.
+    pub theme: Option<String>,
+    pub filter: Option<Filter>,
+    pub sort: Option<ProfileSort>,
+    /// Key pressed to the key it acts as, outside modals.
+    pub keys: HashMap<KeyCode, KeyCode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileSort {
+    File,
+    Risk,
+    Column(ColumnSort),
+}
+
+/// The footer ticker of recent changes (`[ticker]`), for dashboards nobody interacts with.
+#[derive(Debug, Clone, Default)]
+pub struct TickerConfig {
+    pub enabled: bool,
+    /// Time each change stays on screen; defaults to five seconds.
+    pub rotate: Option<Duration>,
+}
+
+impl TickerConfig {
+    pub fn rotate(&self) -> Duration {
+        self.rotate.unwrap_or(Duration::from_secs(5))
+    }
+}
+
+/// When the dashboard reloads on its own (`[refresh]`).
+#[derive(Debug, Clone, Default)]
+pub struct RefreshConfig {
+    /// Reload this long after the last load attempt even without a file event; defaults to a
+    /// minute.
+    pub auto: Option<Duration>,
+    /// Quiet time after a file event before loading, so a burst of writes loads once; defaults
+    /// to 200ms.
+    pub debounce: Option<Duration>,
+    /// Start with the periodic reload paused (`A` resumes it).
+    pub paused: bool,
+}
+
+impl RefreshConfig {
+    pub fn auto(&self) -> Duration {
+        self.auto.unwrap_or(Duration::from_secs(60))
+    }
+
+    pub fn debounce(&self) -> Duration {
+        self.debounce.unwrap_or(Duration::from_millis(200))
+    }
+}
+
+/// The review queue (`[review]`, `Q`).
+#[derive(Debug, Clone, Default)]
+pub struct ReviewConfig {
+    /// How many days back a finished task stays in the queue.
+    pub days: Option<u64>,
+}
+
+impl ReviewConfig {
+    pub fn days(&self) -> u64 {
+        self.days.unwrap_or(REVIEW_DAYS)
+    }
+}
+
+/// How much state a long-running dashboard keeps in memory (`[retention]`).
+#[derive(Debug, Clone, Default)]
+pub struct RetentionConfig {
+    /// History snapshots kept in memory; older ones stay in the history file.
+    pub history: Option<usize>,
+    /// Watch log entries (`F12`).
+    pub watch_log: Option<usize>,
+}
+
+impl RetentionConfig {
+    pub fn history(&self) -> usize {
+        self.history.unwrap_or(HISTORY_SNAPSHOTS)
+    }
+
+    pub fn watch_log(&self) -> usize {
+        self.watch_log.unwrap_or(WATCH_LOG_LEN)
+    }
+}
+
+/// Moving around the task table (`[table]`).
+#[derive(Debug, Clone, Default)]
+pub struct TableConfig {
+    /// `↑` on the first row goes to the last one, and `↓` on the last to the first.
+    pub wrap: bool,
+}
+
+/// The header at the top of the dashboard (`[header]`).
+#[derive(Debug, Clone, Default)]
+pub struct HeaderConfig {
+    /// Start with the header collapsed to one summary line (`Z`).
+    pub compact: bool,
+}
+
+/// How the progress gauge counts (`[progress]`).
+#[derive(Debug, Clone, Default)]
+pub struct ProgressConfig {
+    /// Start with the gauge weighted by `estimate_days` instead of task count (`%`).
+    pub weighted: bool,
+    /// Weight of a task without `estimate_days`; defaults to the scheduler's `UNESTIMATED_DAYS`.
+    pub unestimated_days: Option<f64>,
+}
+
+impl ProgressConfig {
+    pub fn unestimated_days(&self) -> f64 {
+        self.unestimated_days.unwrap_or(UNESTIMATED_DAYS)
+    }
+}
+
+/// What the task table shows for a value (`[cells]`), e.g. priority "high" as "🔥".
+#[derive(Debug, Clone)]
+pub struct CellConfig {
+    /// `false` ignores the mappings, for terminals without emoji or nerd-font glyphs.
+    pub icons: bool,
+    /// Column ("status", "priority", "component") to value to display text. Values are keyed as
+    /// the table shows them: lowercased, statuses normalized.
+    pub columns: BTreeMap<&'static str, BTreeMap<String, String>>,
+}
+
+impl Default for CellConfig {
+    fn default() -> Self {
+        CellConfig {
+            icons: true,
+            columns: BTreeMap::new(),
+        }
+    }
+}
+
+impl CellConfig {
+    pub fn display<'a>(&'a self, column: &str, value: &'a str) -> &'a str {
+        if !self.icons {
+            return value;
+        }
+        self.columns
+            .get(column)
+            .and_then(|m| m.get(value))
+            .map_or(value, String::as_str)
+    }
+}
+
+/// How much of a task the details pane shows.
+#[derive(Debug, Clone, Default)]
+pub struct DetailsConfig {
+    /// Deliverables and definition-of-done lines listed before "… (+n more)".
+    pub max_deliverables: Option<usize>,
+    pub max_dod: Option<usize>,
+    /// Start in compact mode (`z`): long fields show only their first paragraph.
+    pub compact: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawConfig {
+    #[serde(default)]
+    sla: RawSla,
+    day_rate: Option<f64>,
+    #[serde(default)]
+    sound: RawSound,
+    #[serde(default)]
+    targets: Vec<TargetSpec>,
+    large_diff_percent: Option<f64>,
+    parallelism: Option<usize>,
+    backups: Option<usize>,
+    status_file: Option<String>,
+    git_repo: Option<String>,
+    #[serde(default)]
+    usage: RawUsage,
+    #[serde(default)]
+    details: RawDetails,
+    #[serde(default)]
+    table: RawTable,
+    #[serde(default)]
+    header: RawHeader,
+    #[serde(default)]
+    projects: BTreeMap<String, String>,
+    #[serde(default)]
+    cells: RawCells,
+    #[serde(default)]
+    budgets: BTreeMap<String, f64>,
+    #[serde(default)]
+    owners: BTreeMap<String, String>,
+    #[serde(default)]
+    progress: RawProgress,
+    #[serde(default)]
+    ticker: RawTicker,
+    #[serde(default)]
+    retention: RawRetention,
+    #[serde(default)]
+    refresh: RawRefresh,
+    #[serde(default)]
+    review: RawReview,
+    author: Option<String>,
+    language: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, RawProfile>,
+    #[serde(default)]
+    lint: RawLint,
+    #[serde(default)]
+    priorities: RawPriorities,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPriorities {
+    #[serde(default)]
+    order: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProfile {
+    columns: Option<Vec<String>>,
+    theme: Option<String>,
+    /// A query in the filter syntax (`/`).
+    filter: Option<String>,
+    /// "file", "risk" or a column name; a leading "-" sorts descending.
+    sort: Option<String>,
+    /// Key names, e.g. `j = "down"`.
+    #[serde(default)]
+    keys: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLint {
+    #[serde(default)]
+    suppress: Vec<String>,
+    #[serde(default)]
+    files: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawUsage {
+    #[serde(default)]
+    enabled: bool,
+    file: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRetention {
+    history: Option<usize>,
+    watch_log: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawReview {
+    days: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRefresh {
+    /// Humantime durations, e.g. `"5m"` and `"1s"`.
+    auto: Option<String>,
+    debounce: Option<String>,
+    #[serde(default)]
+    paused: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTicker {
+    #[serde(default)]
+    enabled: bool,
+    /// A humantime duration, e.g. `"8s"`.
+    rotate: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProgress {
+    #[serde(default)]
+    weighted: bool,
+    unestimated_days: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCells {
+    icons: Option<bool>,
+    #[serde(default)]
+    status: BTreeMap<String, String>,
+    #[serde(default)]
+    priority: BTreeMap<String, String>,
+    #[serde(default)]
+    component: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTable {
+    #[serde(default)]
+    wrap: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHeader {
+    #[serde(default)]
+    compact: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawDetails {
+    max_deliverables: Option<usize>,
+    max_dod: Option<usize>,
+    #[serde(default)]
+    compact: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawSound {
+    command: Option<String>,
+    volume: Option<u8>,
+    /// Cue name to audio file; relative paths are relative to the config file.
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+    /// Reload kinds that chime: "cosmetic", "status", "structural".
+    chime_on: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawSla {
+    /// Durations in humantime syntax, e.g. `must = "2days"`.
+    #[serde(default)]
+    blocked: BTreeMap<String, String>,
+}
+
+pub fn default_config_path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os("HARNAS_MONITOR_CONFIG") {
+        return Some(PathBuf::from(p));
+    }
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("harnas-monitor").join("config.toml"))
+}
+
+pub fn load_config(path: Option<&Path>) -> Result<Config> {
+    let Some(path) = path.map(Path::to_path_buf).or_else(default_config_path) else {
+        return Ok(Config::default());
+    };
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let raw: RawConfig =
+        toml::from_str(&contents).map_err(|e| anyhow!("parsing {}: {e}", path.display()))?;
+
+    if let Some(rate) = raw.day_rate.filter(|r| !r.is_finite() || *r < 0.0) {
+        bail!(
+            "{}: day_rate must be a non-negative number, got {rate}",
+            path.display()
+        );
+    }
+    if let Some(p) = raw
+        .large_diff_percent
+        .filter(|p| !(0.0..=100.0).contains(p))
+    {
+        bail!(
+            "{}: large_diff_percent must be 0-100, got {p}",
+            path.display()
+        );
+    }
+    if let Some((comp, days)) = raw
+        .budgets
+        .iter()
+        .find(|(_, d)| !d.is_finite() || **d < 0.0)
+    {
+        bail!(
+            "{}: budgets.{comp} must be a non-negative number of days, got {days}",
+            path.display()
+        );
+    }
+    if let Some(days) = raw
+        .progress
+        .unestimated_days
+        .filter(|d| !d.is_finite() || *d < 0.0)
+    {
+        bail!(
+            "{}: progress.unestimated_days must be a non-negative number, got {days}",
+            path.display()
+        );
+    }
+    if let Some(comp) = raw.owners.iter().find(|(_, o)| o.trim().is_empty()).map(|(c, _)| c) {
+        bail!("{}: owners.{comp} must name an owner", path.display());
+    }
+    let mut order: Vec<String> = Vec::new();
+    for name in &raw.priorities.order {
+        let key = lowercase(name);
+        if key.is_empty() {
+            bail!("{}: priorities.order has an empty name", path.display());
+        }
+        if order.contains(&key) {
+            bail!("{}: priorities.order lists {name:?} twice", path.display());
+        }
+        order.push(key);
+    }
+    if raw.parallelism == Some(0) {
+        bail!("{}: parallelism must be at least 1", path.display());
+    }
+    if let Some(volume) = raw.sound.volume.filter(|v| *v > 100) {
+        bail!(
+            "{}: sound.volume must be 0-100, got {volume}",
+            path.display()
+        );
+    }
+    let mut projects = BTreeMap::new();
+    for (name, file) in raw.projects {
+        if name.trim().is_empty() || name.contains(':') {
+            bail!(
+                "{}: project name {name:?} must be non-empty and without ':'",
+                path.display()
+            );
+        }
+        projects.insert(name, resolve_path(&path, &file));
+    }
+    let mut files = BTreeMap::new();
+    for (cue, file) in raw.sound.files {
+        if !Cue::ALL.iter().any(|c| c.key() == cue) {
+            let known: Vec<&str> = Cue::ALL.iter().map(|c| c.key()).collect();
+            bail!(
+                "{}: unknown sound cue {cue:?} (expected one of: {})",
+                path.display(),
+                known.join(", ")
+            );
+        }
+        files.insert(cue, resolve_path(&path, &file));
+    }
+    let chime_on = raw
+        .sound
+        .chime_on
+        .map(|kinds| {
+            kinds
+                .iter()
+                .map(|k| {
+                    ChangeClass::ALL
+                        .into_iter()
+                        .find(|c| c.key() == k.trim().to_lowercase())
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "{}: unknown sound.chime_on kind {k:?} (expected cosmetic, \
+                                 status or structural)",
+                                path.display()
+                            )
+                        })
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+    let rotate = raw
+        .ticker
+        .rotate
+        .as_deref()
+        .map(|d| {
+            let rotate = humantime::parse_duration(d)
+                .with_context(|| format!("{}: ticker.rotate = {d:?}", path.display()))?;
+            if rotate < Duration::from_secs(1) {
+                bail!(
+                    "{}: ticker.rotate must be at least 1s, got {d:?}",
+                    path.display()
+                );
+            }
+            Ok(rotate)
+        })
+        .transpose()?;
+    let refresh_auto = raw
+        .refresh
+        .auto
+        .as_deref()
+        .map(|d| {
+            let auto = humantime::parse_duration(d)
+                .with_context(|| format!("{}: refresh.auto = {d:?}", path.display()))?;
+            if auto < Duration::from_secs(1) {
+                bail!(
+                    "{}: refresh.auto must be at least 1s, got {d:?}",
+                    path.display()
+                );
+            }
+            Ok(auto)
+        })
+        .transpose()?;
+    let debounce = raw
+        .refresh
+        .debounce
+        .as_deref()
+        .map(|d| {
+            humantime::parse_duration(d)
+                .with_context(|| format!("{}: refresh.debounce = {d:?}", path.display()))
+        })
+        .transpose()?;
+    if raw.review.days == Some(0) {
+        bail!("{}: review.days must be at least 1", path.display());
+    }
+    // Two snapshots are the least that still show a change.
+    for (key, value, min) in [
+        ("history", raw.retention.history, 2),
+        ("watch_log", raw.retention.watch_log, 1),
+    ] {
+        if let Some(n) = value.filter(|n| *n < min) {
+            bail!(
+                "{}: retention.{key} must be at least {min}, got {n}",
+                path.display()
+            );
+        }
+    }
+    let language = raw
+        .language
+        .as_deref()
+        .map(|l| {
+            Lang::parse(l).ok_or_else(|| {
+                anyhow!(
+                    "{}: language must be one of {}, got {l:?}",
+                    path.display(),
+                    LANGUAGES.join(", ")
+                )
+            })
+        })
+        .transpose()?;
+    let mut config = Config {
+        language,
+        day_rate: raw.day_rate,
+        large_diff_percent: raw.large_diff_percent,
+        parallelism: raw.parallelism,
+        backups: raw.backups,
+        status_file: raw.status_file.map(|f| resolve_path(&path, &f)),
+        git_repo: raw.git_repo.map(|r| resolve_path(&path, &r)),
+        usage: UsageConfig {
+            enabled: raw.usage.enabled,
+            file: raw.usage.file.map(|f| resolve_path(&path, &f)),
+        },
+        details: DetailsConfig {
+            max_deliverables: raw.details.max_deliverables,
+            max_dod: raw.details.max_dod,
+            compact: raw.details.compact,
+        },
+        table: TableConfig {
+            wrap: raw.table.wrap,
+        },
+        header: HeaderConfig {
+            compact: raw.header.compact,
+        },
+        priorities: PriorityConfig { order },
+        projects,
+        budgets: raw.budgets,
+        owners: raw
+            .owners
+            .into_iter()
+            .map(|(c, o)| (lowercase(&c), o.trim().to_string()))
+            .collect(),
+        author: raw
+            .author
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty()),
+        ticker: TickerConfig {
+            enabled: raw.ticker.enabled,
+            rotate,
+        },
+        retention: RetentionConfig {
+            history: raw.retention.history,
+            watch_log: raw.retention.watch_log,
+        },
+        review: ReviewConfig {
+            days: raw.review.days,
+        },
+        refresh: RefreshConfig {
+            auto: refresh_auto,
+            debounce,
+            paused: raw.refresh.paused,
+        },
+        progress: ProgressConfig {
+            weighted: raw.progress.weighted,
+            unestimated_days: raw.progress.unestimated_days,
+        },
+        cells: CellConfig {
+            icons: raw.cells.icons.unwrap_or(true),
+            columns: BTreeMap::from([
+                ("status", normalize_keys(raw.cells.status, normalize_status)),
+                ("priority", normalize_keys(raw.cells.priority, lowercase)),
+                ("component", normalize_keys(raw.cells.component, lowercase)),
+            ]),
+        },
+        sound: SoundConfig {
+            command: raw.sound.command,
+            files,
+            volume: raw.sound.volume,
+            chime_on,
+        },
+        ..Config::default()
+    };
+    for (i, spec) in raw.targets.iter().enumerate() {
+        let target =
+            parse_target(spec).with_context(|| format!("{}: targets[{i}]", path.display()))?;
+        config.targets.push(target);
+    }
+    for (name, raw) in raw.profiles {
+        let profile =
+            parse_profile(raw).with_context(|| format!("{}: profiles.{name}", path.display()))?;
+        config.profiles.insert(name, profile);
+    }
+    let parse_rules = |key: &str, entries: &[String]| {
+        entries
+            .iter()
+            .map(|e| Suppression::parse(e).map_err(|e| anyhow!("{}: {key}: {e}", path.display())))
+            .collect::<Result<Vec<_>>>()
+    };
+    config.lint.suppress = parse_rules("lint.suppress", &raw.lint.suppress)?;
+    for (file, entries) in &raw.lint.files {
+        let rules = parse_rules(&format!("lint.files.{file:?}"), entries)?;
+        config.lint.files.insert(resolve_path(&path, file), rules);
+    }
+    for (prio, d) in raw.sla.blocked {
+        let duration = humantime::parse_duration(&d)
+            .with_context(|| format!("{}: sla.blocked.{prio} = {d:?}", path.display()))?;
+        config
+            .blocked_sla
+            .insert(prio.trim().to_lowercase(), duration);
+    }
+    Ok(config)
+}
+
+fn parse_profile(raw: RawProfile) -> Result<Profile> {
+    let columns = raw
+        .columns
+        .map(|names| {
+            if names.is_empty() {
+                bail!("columns must name at least one column");
+            }
+            Ok(names.iter().map(|n| TableColumn::parse(n)).collect())
+        })
+        .transpose()?;
+    if let Some(theme) = raw.theme.as_deref().filter(|t| !THEMES.contains(t)) {
+        bail!("theme must be one of {}, got {theme:?}", THEMES.join(", "));
+    }
+    let filter = raw
+        .filter
+        .as_deref()
+        .map(|q| Filter::parse(q).with_context(|| format!("filter = {q:?}")))
+        .transpose()?;
+    let sort = raw.sort.as_deref().map(|s| match s.trim() {
+        "file" => ProfileSort::File,
+        "risk" => ProfileSort::Risk,
+        s => {
+            let (descending, name) = match s.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, s),
+            };
+            ProfileSort::Column(ColumnSort {
+                column: TableColumn::parse(name),
+                descending,
+            })
+        }
+    });
+    let mut keys = HashMap::new();
+    for (from, to) in &raw.keys {
+        let parse = |k: &str| parse_key(k).ok_or_else(|| anyhow!("keys: unknown key {k:?}"));
+        keys.insert(parse(from)?, parse(to)?);
+    }
+    Ok(Profile {
+        columns,
+        theme: raw.theme,
+        filter,
+        sort,
+        keys,
+    })
+}
+
+/// A single character, or a named key like "down" or "pgdn".
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    Some(match name.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pgup" => KeyCode::PageUp,
+        "pgdn" => KeyCode::PageDown,
+        "space" => KeyCode::Char(' '),
+        "f12" => KeyCode::F(12),
+        _ => return None,
+    })
+}
+
+fn normalize_keys(
+    map: BTreeMap<String, String>,
+    key: impl Fn(&str) -> String,
+) -> BTreeMap<String, String> {
+    map.into_iter().map(|(k, v)| (key(&k), v)).collect()
+}
+
+fn lowercase(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+/// `~/` expands to the home directory; other relative paths are taken relative to the config
+/// file's directory.
+fn resolve_path(config_path: &Path, file: &str) -> PathBuf {
+    if let (Some(rest), Some(home)) = (file.strip_prefix("~/"), std::env::var_os("HOME")) {
+        return PathBuf::from(home).join(rest);
+    }
+    let file = PathBuf::from(file);
+    match config_path.parent() {
+        Some(dir) if file.is_relative() => dir.join(file),
+        _ => file,
+    }
+}
@@ -0,0 +1,199 @@
+//! Who finished which tasks, from the git history of the tasks file: every commit that changed
+//! it is compared with its first parent, and a task whose status became `done` is credited to
+//! the commit's author at the author date. The stats panel (`s`) shows the result per person.
+//! Old versions are read as plain TOML, so files from before a schema change still count.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+
+use crate::tasks::normalize_status;
+
+/// Commits looked at, newest first; older ones are left out.
+pub const MAX_COMMITS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub id: String,
+    pub author: String,
+    pub at: SystemTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contributor {
+    pub name: String,
+    /// Task ids in the order they were finished; one that was reopened and finished again
+    /// shows up again.
+    pub tasks: Vec<String>,
+    pub last: SystemTime,
+}
+
+struct Commit {
+    hash: String,
+    author: String,
+    at: SystemTime,
+    /// The tasks file's path in this commit, which `--follow` tracks across renames.
+    path: String,
+}
+
+/// Completions recorded in `repo`'s history of `file`, oldest first. A commit that adds the
+/// file, or renames it, credits nobody: there is no earlier version to compare with.
+pub fn completions(repo: &Path, file: &Path) -> Result<Vec<Completion>> {
+    let commits = file_commits(repo, file)?;
+    let mut names = Vec::new();
+    for c in &commits {
+        names.push(format!("{}:{}", c.hash, c.path));
+        names.push(format!("{}^:{}", c.hash, c.path));
+    }
+    let blobs = read_blobs(repo, &names)?;
+    let mut out = Vec::new();
+    for (c, pair) in commits.iter().zip(blobs.chunks(2)).rev() {
+        let (Some(after), Some(Some(before))) = (&pair[0], pair.get(1)) else {
+            continue;
+        };
+        let before = done_by_id(before);
+        for (id, done) in statuses(after) {
+            if done && before.get(&id) != Some(&true) {
+                out.push(Completion {
+                    id,
+                    author: c.author.clone(),
+                    at: c.at,
+                });
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// One entry per author, most tasks first, then by name.
+pub fn by_author(completions: &[Completion]) -> Vec<Contributor> {
+    let mut by_name: BTreeMap<&str, Contributor> = BTreeMap::new();
+    for c in completions {
+        let entry = by_name.entry(&c.author).or_insert_with(|| Contributor {
+            name: c.author.clone(),
+            tasks: Vec::new(),
+            last: c.at,
+        });
+        entry.tasks.push(c.id.clone());
+        entry.last = entry.last.max(c.at);
+    }
+    let mut out: Vec<Contributor> = by_name.into_values().collect();
+    out.sort_by(|a, b| b.tasks.len().cmp(&a.tasks.len()).then(a.name.cmp(&b.name)));
+    out
+}
+
+/// `git log --follow` for `file`, newest first.
+fn file_commits(repo: &Path, file: &Path) -> Result<Vec<Commit>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args([
+            "log",
+            "--follow",
+            "--name-only",
+            "--format=%x1e%H%x1f%an%x1f%at",
+        ])
+        .arg(format!("--max-count={MAX_COMMITS}"))
+        .arg("--")
+        .arg(file)
+        .output()
+        .context("running git log")?;
+    if !out.status.success() {
+        bail!("git log: {}", String::from_utf8_lossy(&out.stderr).trim());
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let commits = text
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut fields = lines.next()?.split('\x1f');
+            let hash = fields.next()?.to_string();
+            let author = fields.next()?.trim().to_string();
+            let secs = fields.next()?.trim().parse().ok()?;
+            let path = lines.map(str::trim).find(|l| !l.is_empty())?.to_string();
+            Some(Commit {
+                hash,
+                author,
+                at: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+                path,
+            })
+        })
+        .collect();
+    Ok(commits)
+}
+
+/// The contents of each object in `names` (`rev:path`), `None` for the ones that do not exist,
+/// from a single `git cat-file --batch`.
+fn read_blobs(repo: &Path, names: &[String]) -> Result<Vec<Option<String>>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("running git cat-file")?;
+    let mut stdin = child.stdin.take().context("git cat-file stdin")?;
+    let input = names.join("\n") + "\n";
+    // Written from a thread: git answers while it reads, and both pipes can fill up.
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let stdout = child.stdout.take().context("git cat-file stdout")?;
+    let mut reader = BufReader::new(stdout);
+    let mut out = Vec::with_capacity(names.len());
+    for _ in names {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            bail!("git cat-file stopped early");
+        }
+        // `<oid> <type> <size>`, or `<name> missing` (`ambiguous` for a bad name).
+        let size = match header.trim_end().rsplit_once(' ') {
+            Some((_, size)) => size.parse::<usize>().ok(),
+            None => None,
+        };
+        let Some(size) = size else {
+            out.push(None);
+            continue;
+        };
+        let mut blob = vec![0; size + 1];
+        reader.read_exact(&mut blob)?;
+        blob.truncate(size);
+        out.push(Some(String::from_utf8_lossy(&blob).into_owned()));
+    }
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("git cat-file writer panicked"))?
+        .context("writing to git cat-file")?;
+    child.wait().context("waiting for git cat-file")?;
+    Ok(out)
+}
+
+/// Each task's id and whether it is done, in file order. A version that is not valid TOML has
+/// none.
+fn statuses(contents: &str) -> Vec<(String, bool)> {
+    let Ok(doc) = contents.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(tasks) = doc.get("tasks").and_then(|t| t.as_array()) else {
+        return Vec::new();
+    };
+    tasks
+        .iter()
+        .filter_map(|t| {
+            let id = t.get("id")?.as_str()?.to_string();
+            let status = t.get("status").and_then(|s| s.as_str()).unwrap_or("");
+            Some((id, normalize_status(status) == "done"))
+        })
+        .collect()
+}
+
+fn done_by_id(contents: &str) -> HashMap<String, bool> {
+    statuses(contents).into_iter().collect()
+}
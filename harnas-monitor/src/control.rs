@@ -0,0 +1,198 @@
+//! JSON-RPC 2.0 over a Unix-domain socket, one request per line, so scripts and editor plugins
+//! can drive a running monitor. Requests are handed to the UI thread and answered from there.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::app::App;
+use crate::clock::Instant;
+use crate::export::task_json;
+use crate::tasks::LoadedTasks;
+
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+const PARSE_ERROR: i64 = -32700;
+
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+pub struct ControlRequest {
+    pub method: String,
+    pub params: Value,
+    reply: mpsc::Sender<Result<Value, RpcError>>,
+}
+
+impl ControlRequest {
+    pub fn respond(self, result: Result<Value, RpcError>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+#[derive(Deserialize)]
+struct RawRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Removes the socket file when the monitor exits.
+pub struct ControlSocket {
+    path: PathBuf,
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Binds `path` (replacing a stale socket file) and accepts connections on a background thread.
+pub fn spawn(path: &Path) -> Result<(ControlSocket, mpsc::Receiver<ControlRequest>)> {
+    if path.exists() && UnixStream::connect(path).is_err() {
+        std::fs::remove_file(path).with_context(|| format!("removing stale {}", path.display()))?;
+    }
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("binding {}", path.display()))?;
+    let (tx, rx) = mpsc::channel::<ControlRequest>();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            std::thread::spawn(move || serve_connection(stream, tx));
+        }
+    });
+    Ok((
+        ControlSocket {
+            path: path.to_path_buf(),
+        },
+        rx,
+    ))
+}
+
+fn serve_connection(stream: UnixStream, tx: mpsc::Sender<ControlRequest>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<RawRequest>(&line) {
+            Ok(req) => {
+                let (reply_tx, reply_rx) = mpsc::channel();
+                let request = ControlRequest {
+                    method: req.method,
+                    params: req.params,
+                    reply: reply_tx,
+                };
+                if tx.send(request).is_err() {
+                    return;
+                }
+                match reply_rx.recv() {
+                    Ok(Ok(result)) => json!({"jsonrpc": "2.0", "id": req.id, "result": result}),
+                    Ok(Err(e)) => error_response(req.id, e),
+                    Err(_) => return,
+                }
+            }
+            Err(e) => error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string())),
+        };
+        if writeln!(writer, "{response}").is_err() {
+            return;
+        }
+    }
+}
+
+fn error_response(id: Value, e: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": e.code, "message": e.message},
+    })
+}
+
+/// Answers one request against the current app state.
+pub fn dispatch(app: &mut App, req: ControlRequest) {
+    let result = handle(app, &req.method, &req.params);
+    req.respond(result);
+}
+
+fn handle(app: &mut App, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "select" => {
+            let id = params
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected {\"id\": \"T-…\"}"))?;
+            if app.loaded.is_none() {
+                app.pending_select = Some(id.to_string());
+                return Ok(json!({"selected": null, "pending": id}));
+            }
+            if app.jump_to(id) {
+                Ok(json!({"selected": app.selected_id()}))
+            } else {
+                Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("no task with id {id}"),
+                ))
+            }
+        }
+        "filter" => {
+            let query = params
+                .get("query")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected {\"query\": \"…\"}"))?;
+            app.apply_filter_query(query)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, format!("{e:#}")))?;
+            Ok(json!({ "visible": app.visible.len() }))
+        }
+        "reload" => {
+            app.reload_requested_at = Some(Instant::now());
+            Ok(json!(true))
+        }
+        "stats" => {
+            let l = loaded(app)?;
+            Ok(json!({
+                "path": l.path,
+                "stats": l.stats,
+                "problems": l.problems.len(),
+                "cost": app.cost(),
+            }))
+        }
+        "export" => {
+            let l = loaded(app)?;
+            let tasks: Vec<Value> = l.tasks.tasks.iter().map(task_json).collect();
+            Ok(json!({ "tasks": tasks }))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method {method}"),
+        )),
+    }
+}
+
+fn loaded(app: &App) -> Result<&LoadedTasks, RpcError> {
+    app.loaded
+        .as_ref()
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "no data loaded yet"))
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::tasks::{
+    component_key, estimate_range, estimate_spread, status_map, task_status, unmet_deps, TaskFile,
+};
+
+/// Estimated effort and cost for a set of tasks. Done tasks count as spent, everything else as
+/// remaining.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CostLine {
+    pub spent_days: f64,
+    pub remaining_days: f64,
+    /// `remaining_days` with every ranged estimate at its optimistic and its pessimistic bound
+    /// (see `tasks::estimate_range`); both equal it when no estimate has a range.
+    pub remaining_low_days: f64,
+    pub remaining_high_days: f64,
+    pub spent: f64,
+    pub remaining: f64,
+    /// Tasks without `estimate_days`; they add nothing to the totals.
+    pub unestimated: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CostRollup {
+    pub day_rate: f64,
+    pub total: CostLine,
+    pub by_component: BTreeMap<String, CostLine>,
+}
+
+/// `estimate_days × day_rate`, summed per component and overall.
+pub fn cost_rollup(file: &TaskFile, day_rate: f64) -> CostRollup {
+    let mut total = CostLine::default();
+    let mut by_component: BTreeMap<String, CostLine> = BTreeMap::new();
+    let spread = estimate_spread(file);
+    for t in &file.tasks {
+        let done = task_status(t) == "done";
+        let range = estimate_range(t, spread);
+        let line = by_component.entry(component_key(t)).or_default();
+        for l in [&mut total, line] {
+            match t.estimate_days {
+                Some(days) if done => {
+                    l.spent_days += days;
+                    l.spent += days * day_rate;
+                }
+                Some(days) => {
+                    let (low, high) = range.unwrap_or((days, days));
+                    l.remaining_days += days;
+                    l.remaining_low_days += low;
+                    l.remaining_high_days += high;
+                    l.remaining += days * day_rate;
+                }
+                None => l.unestimated += 1,
+            }
+        }
+    }
+    CostRollup {
+        day_rate,
+        total,
+        by_component,
+    }
+}
+
+/// Estimated days per progress-bar segment, for the estimate-weighted gauge (`%`). Tasks land in
+/// the same segment as in `Stats::segments`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EffortSegments {
+    pub done: f64,
+    pub in_progress: f64,
+    pub blocked: f64,
+    pub todo: f64,
+}
+
+impl EffortSegments {
+    pub fn total(&self) -> f64 {
+        self.done + self.in_progress + self.blocked + self.todo
+    }
+}
+
+/// Tasks without `estimate_days` weigh `unestimated_days`.
+pub fn effort_segments(file: &TaskFile, unestimated_days: f64) -> EffortSegments {
+    let status_by_id = status_map(file);
+    let mut out = EffortSegments::default();
+    for t in &file.tasks {
+        let days = t.estimate_days.unwrap_or(unestimated_days);
+        let segment = match task_status(t).as_str() {
+            "done" => &mut out.done,
+            "in_progress" => &mut out.in_progress,
+            "blocked" => &mut out.blocked,
+            _ if !unmet_deps(t, &status_by_id).is_empty() => &mut out.blocked,
+            _ => &mut out.todo,
+        };
+        *segment += days;
+    }
+    out
+}
+
+/// A component's estimates against its effort budget.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUse {
+    pub component: String,
+    pub budget_days: f64,
+    /// `estimate_days` of all the component's tasks, done ones included.
+    pub estimated_days: f64,
+}
+
+impl BudgetUse {
+    pub fn over(&self) -> bool {
+        self.estimated_days > self.budget_days
+    }
+}
+
+/// Every budgeted component's estimates. Budgets come from the plan's `[meta.budgets]`, with the
+/// config's `[budgets]` overriding them per component; keys are matched like `component_key`.
+/// A negative plan budget is left out (the `estimate-range` rule reports it); the config's are
+/// checked when it loads.
+pub fn budget_use(file: &TaskFile, config_budgets: &BTreeMap<String, f64>) -> Vec<BudgetUse> {
+    let mut budgets: BTreeMap<String, f64> = BTreeMap::new();
+    let meta = file
+        .meta
+        .iter()
+        .flat_map(|m| &m.budgets)
+        .filter(|(_, d)| d.is_finite() && **d >= 0.0);
+    for (comp, days) in meta.chain(config_budgets) {
+        budgets.insert(comp.trim().to_lowercase(), *days);
+    }
+    budgets
+        .into_iter()
+        .map(|(component, budget_days)| {
+            let estimated_days = file
+                .tasks
+                .iter()
+                .filter(|t| component_key(t) == component)
+                .filter_map(|t| t.estimate_days)
+                .sum();
+            BudgetUse {
+                component,
+                budget_days,
+                estimated_days,
+            }
+        })
+        .collect()
+}
+
+/// Whole units with thousands separators (`12,500`); estimates are too rough for cents.
+pub fn format_amount(amount: f64) -> String {
+    let digits = format!("{:.0}", amount.abs());
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    if amount < -0.5 {
+        out.insert(0, '-');
+    }
+    out
+}
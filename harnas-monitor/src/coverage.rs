@@ -0,0 +1,138 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::tasks::{fnv1a_64, TaskFile};
+
+/// How well the plan covers the spec referenced by `meta.spec_file`.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    pub spec_path: Option<PathBuf>,
+    /// Why the spec could not be read, if it could not.
+    pub spec_error: Option<String>,
+    /// Requirement anchors found in the spec (e.g. `FR-012`, `NFR-3.1`).
+    pub anchors: BTreeSet<String>,
+    /// Anchors no task references.
+    pub uncovered: Vec<String>,
+    /// Tasks with an empty `spec_refs`.
+    pub tasks_without_refs: Vec<String>,
+    /// Hash of the spec text, when it could be read.
+    pub spec_hash: Option<u64>,
+    /// Hash of each anchor's section (see `section_hashes`), for spec drift.
+    pub sections: BTreeMap<String, u64>,
+}
+
+impl Coverage {
+    pub fn covered(&self) -> usize {
+        self.anchors.len() - self.uncovered.len()
+    }
+}
+
+pub fn compute_coverage(tasks_path: &Path, file: &TaskFile) -> Coverage {
+    let Some(spec_file) = file.meta.as_ref().and_then(|m| m.spec_file.as_deref()) else {
+        return coverage_from_spec(None, Err("meta.spec_file is not set".to_string()), file);
+    };
+    let spec_path = tasks_path
+        .parent()
+        .map(|d| d.join(spec_file))
+        .unwrap_or_else(|| PathBuf::from(spec_file));
+    let spec = std::fs::read_to_string(&spec_path)
+        .map_err(|e| format!("reading {}: {e}", spec_path.display()));
+    coverage_from_spec(Some(spec_path), spec, file)
+}
+
+/// Coverage against spec text that was already read (or the reason it could not be).
+pub fn coverage_from_spec(
+    spec_path: Option<PathBuf>,
+    spec: Result<String, String>,
+    file: &TaskFile,
+) -> Coverage {
+    let mut coverage = Coverage {
+        spec_path,
+        tasks_without_refs: file
+            .tasks
+            .iter()
+            .filter(|t| t.spec_refs.iter().all(|r| r.trim().is_empty()))
+            .map(|t| t.id.clone())
+            .collect(),
+        ..Coverage::default()
+    };
+    match spec {
+        Ok(spec) => {
+            coverage.anchors = find_anchors(&spec);
+            coverage.spec_hash = Some(fnv1a_64(spec.as_bytes()));
+            coverage.sections = section_hashes(&spec);
+            let referenced: HashSet<&str> = file
+                .tasks
+                .iter()
+                .flat_map(|t| t.spec_refs.iter().map(|r| r.trim()))
+                .collect();
+            coverage.uncovered = coverage
+                .anchors
+                .iter()
+                .filter(|a| !referenced.contains(a.as_str()))
+                .cloned()
+                .collect();
+        }
+        Err(e) => coverage.spec_error = Some(e),
+    }
+    coverage
+}
+
+/// An anchor's section runs from the line that first mentions it up to the next heading or the
+/// next line that introduces another anchor. Blank lines and indentation do not count, so
+/// reflowing the spec leaves the hashes alone.
+fn section_hashes(spec: &str) -> BTreeMap<String, u64> {
+    let lines: Vec<&str> = spec.lines().collect();
+    let mut seen = HashSet::new();
+    let mut starts: Vec<(usize, Vec<String>)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let fresh: Vec<String> = find_anchors(line)
+            .into_iter()
+            .filter(|a| seen.insert(a.clone()))
+            .collect();
+        if !fresh.is_empty() {
+            starts.push((i, fresh));
+        }
+    }
+    let mut out = BTreeMap::new();
+    for (n, (start, anchors)) in starts.iter().enumerate() {
+        let next = starts.get(n + 1).map_or(lines.len(), |(i, _)| *i);
+        let end = lines[start + 1..next]
+            .iter()
+            .position(|l| l.trim_start().starts_with('#'))
+            .map_or(next, |p| start + 1 + p);
+        let text: Vec<&str> = lines[*start..end]
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        let hash = fnv1a_64(text.join("\n").as_bytes());
+        out.extend(anchors.iter().map(|a| (a.clone(), hash)));
+    }
+    out
+}
+
+/// Requirement-style identifiers: an uppercase prefix, a dash and a dotted number
+/// (`FR-1`, `NFR-012`, `UI-3.2`).
+fn find_anchors(spec: &str) -> BTreeSet<String> {
+    let mut out = BTreeSet::new();
+    for word in spec.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '.')) {
+        let word = word.trim_end_matches(['.', '-']);
+        let Some((prefix, number)) = word.split_once('-') else {
+            continue;
+        };
+        let prefix_ok = !prefix.is_empty()
+            && prefix.starts_with(|c: char| c.is_ascii_uppercase())
+            && prefix
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        let number_ok = !number.is_empty()
+            && number
+                .split('.')
+                .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+        if prefix_ok && number_ok {
+            out.insert(word.to_string());
+        }
+    }
+    out
+}
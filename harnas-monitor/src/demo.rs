@@ -0,0 +1,109 @@
+//! `--demo`: a bundled sample plan plus a short guided tour, so the UI can be explored without a
+//! project checkout. Nothing is read from or written to disk in this mode.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::coverage::coverage_from_spec;
+use crate::tasks::{parse_tasks, LoadedTasks};
+
+/// Shown wherever a path would be; never opened.
+pub const DEMO_PATH: &str = "demo:TASKS.toml";
+
+const TASKS: &str = include_str!("../demo/TASKS.toml");
+const SPEC: &str = include_str!("../demo/SPEC.md");
+
+/// The bundled plan, or `generated` (`--demo --tasks`), which has no spec to cover.
+pub fn load_demo(generated: Option<&str>) -> Result<LoadedTasks> {
+    if let Some(plan) = generated {
+        return parse_tasks(plan, DEMO_PATH.to_string(), None, |file| {
+            coverage_from_spec(None, Err("meta.spec_file is not set".to_string()), file)
+        });
+    }
+    parse_tasks(TASKS, DEMO_PATH.to_string(), None, |file| {
+        coverage_from_spec(
+            Some(PathBuf::from("demo:SPEC.md")),
+            Ok(SPEC.to_string()),
+            file,
+        )
+    })
+}
+
+pub struct TourStep {
+    pub title: &'static str,
+    pub text: &'static str,
+}
+
+pub const TOUR: &[TourStep] = &[
+    TourStep {
+        title: "Welcome",
+        text: "This is a sample plan for a small invoicing tool. Nothing here touches your disk, \
+               so feel free to press anything.",
+    },
+    TourStep {
+        title: "Header",
+        text: "The header shows where the plan came from, when it was loaded and a progress bar \
+               split into done, in progress, blocked and todo.",
+    },
+    TourStep {
+        title: "Task table",
+        text: "Use ↑/↓ to select a task and Home/End to jump. The Wait column counts unmet \
+               dependencies.",
+    },
+    TourStep {
+        title: "Details",
+        text: "The right pane shows the selected task in full. PgUp/PgDn scroll it. Press n to \
+               add an annotation (kept in memory in the demo).",
+    },
+    TourStep {
+        title: "Problems",
+        text: "Press p for the Problems panel: T-012 depends on a task that does not exist and \
+               T-009 looks like a duplicate of T-007. Enter jumps to the task.",
+    },
+    TourStep {
+        title: "Spec coverage",
+        text: "Press c to see which requirements no task references (FR-10 here) and which \
+               tasks have no spec_refs.",
+    },
+    TourStep {
+        title: "Jump and help",
+        text: "Press : and type an id such as 7 to jump to T-007. ? lists every key. On a real \
+               TASKS.toml the monitor reloads whenever the file changes.",
+    },
+];
+
+/// Position in the tour; the tooltip can be hidden and brought back with `t`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tour {
+    pub step: usize,
+    pub visible: bool,
+}
+
+impl Default for Tour {
+    fn default() -> Self {
+        Self {
+            step: 0,
+            visible: true,
+        }
+    }
+}
+
+impl Tour {
+    /// Shows the tooltip, or advances to the next one; hides after the last step.
+    pub fn next(&mut self) {
+        if !self.visible {
+            self.visible = true;
+        } else if self.step + 1 < TOUR.len() {
+            self.step += 1;
+        } else {
+            self.step = 0;
+            self.visible = false;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        self.visible = true;
+        self.step = self.step.saturating_sub(1);
+    }
+}
@@ -0,0 +1,227 @@
+//! Task-level differences between two loads of the tasks file, used by the large-change guard
+//! that holds back a wholesale rewrite by the generator until the user has looked at it, and the
+//! classification of each reload that decides whether it chimes.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::filter::display_value;
+use crate::tasks::{task_status, Dependency, LoadedTasks, Link, Task, TaskFile};
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskDiff {
+    /// Ids only in the new load, in its order, with their titles.
+    pub added: Vec<(String, String)>,
+    /// Ids only in the old load, in its order, with their titles.
+    pub removed: Vec<(String, String)>,
+    pub changed: Vec<TaskChange>,
+    /// Distinct ids across both loads.
+    pub total: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskChange {
+    pub id: String,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl TaskDiff {
+    pub fn affected(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+
+    /// Share of all ids that were added, removed or changed.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.affected() as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// What a reload changed, from least to most significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeClass {
+    /// Bytes differ but no parsed value does: whitespace, comments, quoting.
+    Cosmetic,
+    /// Only task statuses changed.
+    Status,
+    /// Tasks added, removed or reordered, or any other field or `[meta]` changed.
+    Structural,
+}
+
+impl ChangeClass {
+    pub const ALL: [ChangeClass; 3] = [
+        ChangeClass::Cosmetic,
+        ChangeClass::Status,
+        ChangeClass::Structural,
+    ];
+
+    /// Name used in the config's `[sound] chime_on`.
+    pub fn key(self) -> &'static str {
+        match self {
+            ChangeClass::Cosmetic => "cosmetic",
+            ChangeClass::Status => "status",
+            ChangeClass::Structural => "structural",
+        }
+    }
+}
+
+/// Classifies the change from `prev` to `next` (whole files, not focus-scoped views) by their
+/// per-task hashes; only tasks whose hash differs are compared field by field.
+pub fn classify(prev: &LoadedTasks, next: &LoadedTasks) -> ChangeClass {
+    let same_ids = prev.task_hashes.len() == next.task_hashes.len()
+        && prev.tasks.tasks.len() == prev.task_hashes.len()
+        && next.tasks.tasks.len() == next.task_hashes.len()
+        && prev
+            .task_hashes
+            .iter()
+            .zip(&next.task_hashes)
+            .all(|((a, _), (b, _))| a == b);
+    if prev.meta_hash != next.meta_hash || !same_ids {
+        return ChangeClass::Structural;
+    }
+    let mut class = ChangeClass::Cosmetic;
+    for (i, ((_, a), (_, b))) in prev.task_hashes.iter().zip(&next.task_hashes).enumerate() {
+        if a == b {
+            continue;
+        }
+        let changes = field_changes(&prev.tasks.tasks[i], &next.tasks.tasks[i]);
+        // Empty when only the spelling of a value changed, e.g. `Done` for `done`.
+        if changes.is_empty() {
+            continue;
+        }
+        if !changes.iter().all(|c| c.field == "status") {
+            return ChangeClass::Structural;
+        }
+        class = ChangeClass::Status;
+    }
+    class
+}
+
+/// Compares tasks by id. A repeated id is compared by its first occurrence.
+pub fn diff_tasks(old: &TaskFile, new: &TaskFile) -> TaskDiff {
+    let first = |file: &TaskFile| -> BTreeMap<String, Task> {
+        let mut out = BTreeMap::new();
+        for t in &file.tasks {
+            out.entry(t.id.clone()).or_insert_with(|| t.clone());
+        }
+        out
+    };
+    let (before, after) = (first(old), first(new));
+    let mut diff = TaskDiff {
+        total: before
+            .keys()
+            .chain(after.keys())
+            .collect::<HashSet<_>>()
+            .len(),
+        ..TaskDiff::default()
+    };
+    let mut seen = HashSet::new();
+    for t in &new.tasks {
+        if !seen.insert(t.id.as_str()) {
+            continue;
+        }
+        match before.get(&t.id) {
+            None => diff.added.push((t.id.clone(), t.title.clone())),
+            Some(prev) => {
+                let fields = field_changes(prev, t);
+                if !fields.is_empty() {
+                    diff.changed.push(TaskChange {
+                        id: t.id.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    for t in &old.tasks {
+        if seen.insert(t.id.as_str()) && !after.contains_key(&t.id) {
+            diff.removed.push((t.id.clone(), t.title.clone()));
+        }
+    }
+    diff
+}
+
+fn field_changes(before: &Task, after: &Task) -> Vec<FieldChange> {
+    let (a, b) = (fields(before), fields(after));
+    let names: Vec<&String> = a
+        .keys()
+        .chain(b.keys().filter(|k| !a.contains_key(*k)))
+        .collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (x, y) = (a.get(name), b.get(name));
+            (x != y).then(|| FieldChange {
+                field: name.clone(),
+                before: x.cloned().unwrap_or_default(),
+                after: y.cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Dependencies as display text, each with its reason, so a changed note shows as a change.
+fn dependency_list(deps: &[Dependency]) -> Option<String> {
+    let shown: Vec<String> = deps
+        .iter()
+        .map(|d| match d.reason() {
+            Some(reason) => format!("{} ({reason})", d.id),
+            None => d.id.clone(),
+        })
+        .collect();
+    (!shown.is_empty()).then(|| shown.join(", "))
+}
+
+/// Links as `label <url>`, so a repointed link shows as a change.
+fn link_list(links: &[Link]) -> Option<String> {
+    let shown: Vec<String> = links
+        .iter()
+        .map(|l| format!("{} <{}>", l.name(), l.url))
+        .collect();
+    (!shown.is_empty()).then(|| shown.join(", "))
+}
+
+/// Every field of a task as display text; unset fields are left out.
+fn fields(t: &Task) -> BTreeMap<String, String> {
+    let list = |v: &[String]| (!v.is_empty()).then(|| v.join(", "));
+    let known = [
+        ("title", Some(t.title.clone())),
+        ("status", Some(task_status(t))),
+        ("priority", t.priority.clone()),
+        ("risk", t.risk.clone()),
+        ("component", t.component.clone()),
+        ("spec_refs", list(&t.spec_refs)),
+        ("depends_on", dependency_list(&t.depends_on)),
+        ("related_to", list(&t.related_to)),
+        ("duplicates", list(&t.duplicates)),
+        ("supersedes", list(&t.supersedes)),
+        ("summary", t.summary.clone()),
+        ("deliverables", list(&t.deliverables)),
+        ("estimate_days", t.estimate_days.map(|d| d.to_string())),
+        ("estimate_days_min", t.estimate_days_min.map(|d| d.to_string())),
+        ("estimate_days_max", t.estimate_days_max.map(|d| d.to_string())),
+        ("dod", list(&t.dod)),
+        ("notes", t.notes.clone()),
+        ("links", link_list(&t.links)),
+        ("order", t.order.map(|o| o.to_string())),
+        ("deferred_until", t.deferred_until.clone()),
+    ];
+    let mut out: BTreeMap<String, String> = known
+        .into_iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v?)))
+        .collect();
+    for (k, v) in &t.extra {
+        out.insert(k.clone(), display_value(v));
+    }
+    out
+}
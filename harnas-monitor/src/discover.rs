@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Deserialize;
+
+pub const TASKS_FILE_NAME: &str = "TASKS.toml";
+
+/// How deep `--discover` walks without git.
+const MAX_WALK_DEPTH: usize = 8;
+
+/// A tasks file as the picker lists it.
+#[derive(Debug, Clone)]
+pub struct Found {
+    pub path: PathBuf,
+    /// Its directory relative to the repository root (`.` for the root); the project name when
+    /// the files open as a workspace.
+    pub name: String,
+    /// `[meta] title`.
+    pub title: Option<String>,
+    /// `None` when the file does not parse.
+    pub tasks: Option<usize>,
+}
+
+/// Just enough of a tasks file for the picker.
+#[derive(Deserialize)]
+struct Head {
+    meta: Option<HeadMeta>,
+    #[serde(default)]
+    tasks: Vec<toml::Table>,
+}
+
+#[derive(Deserialize)]
+struct HeadMeta {
+    title: Option<String>,
+}
+
+impl Found {
+    pub fn read(path: &Path, root: &Path) -> Found {
+        let dir = path.parent().unwrap_or(path);
+        let name = match dir.strip_prefix(root) {
+            Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+            Ok(rel) => rel.to_string_lossy().replace('\\', "/"),
+            Err(_) => dir.display().to_string(),
+        };
+        let head = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| toml::from_str::<Head>(&text).ok());
+        Found {
+            path: path.to_path_buf(),
+            name,
+            title: head.as_ref().and_then(|h| h.meta.as_ref()?.title.clone()),
+            tasks: head.map(|h| h.tasks.len()),
+        }
+    }
+}
+
+/// The repository `start` is in: the nearest ancestor with a `.git` directory, or a `.git`
+/// file as in a worktree or submodule.
+pub fn repo_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Every `TASKS.toml` under `root`, sorted by path. In a git checkout that is what git lists,
+/// tracked or not, minus what `.gitignore` excludes; elsewhere a walk that skips hidden
+/// directories.
+pub fn scan_repo(root: &Path) -> Vec<PathBuf> {
+    let mut found = git_files(root).unwrap_or_else(|| {
+        let mut out = Vec::new();
+        walk(root, 0, &mut out);
+        out
+    });
+    found.sort();
+    found.dedup();
+    found
+}
+
+/// `git ls-files` for tasks files; `None` when git is missing or `root` is no checkout.
+fn git_files(root: &Path) -> Option<Vec<PathBuf>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["ls-files", "-z", "--cached", "--others", "--exclude-standard", "--"])
+        // Pathspec wildcards cross directories; the name is checked exactly below.
+        .arg(format!("*{TASKS_FILE_NAME}"))
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let files = out
+        .stdout
+        .split(|b| *b == 0)
+        .map(|rel| root.join(String::from_utf8_lossy(rel).as_ref()))
+        .filter(|p| p.file_name().is_some_and(|n| n == TASKS_FILE_NAME) && p.is_file())
+        .collect();
+    Some(files)
+}
+
+fn walk(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    let file = dir.join(TASKS_FILE_NAME);
+    if file.is_file() {
+        out.push(file);
+    }
+    if depth < MAX_WALK_DEPTH {
+        for sub in sorted_subdirs(dir) {
+            walk(&sub, depth + 1, out);
+        }
+    }
+}
+
+/// Looks for `TASKS.toml` in `start`, its immediate subdirectories, its ancestors and the
+/// immediate sibling directories of `start`. Closest matches come first; duplicates (e.g. via
+/// symlinks) are dropped.
+pub fn find_tasks_files(start: &Path) -> Vec<PathBuf> {
+    let mut out: Vec<PathBuf> = Vec::new();
+    let mut push = |p: PathBuf| {
+        if !p.is_file() {
+            return;
+        }
+        let key = std::fs::canonicalize(&p).unwrap_or_else(|_| p.clone());
+        if !out
+            .iter()
+            .any(|o| std::fs::canonicalize(o).unwrap_or_else(|_| o.clone()) == key)
+        {
+            out.push(p);
+        }
+    };
+
+    push(start.join(TASKS_FILE_NAME));
+    for dir in sorted_subdirs(start) {
+        push(dir.join(TASKS_FILE_NAME));
+    }
+    for ancestor in start.ancestors().skip(1) {
+        push(ancestor.join(TASKS_FILE_NAME));
+    }
+    if let Some(parent) = start.parent() {
+        for dir in sorted_subdirs(parent) {
+            if dir != start {
+                push(dir.join(TASKS_FILE_NAME));
+            }
+        }
+    }
+
+    out
+}
+
+fn sorted_subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            !p.file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(false)
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
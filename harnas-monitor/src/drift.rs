@@ -0,0 +1,57 @@
+use crate::history::{History, SpecState};
+use crate::tasks::{fnv1a_64, task_status, LoadedTasks};
+
+/// A done task whose spec sections changed after it was marked done, so it needs verifying
+/// again.
+#[derive(Debug, Clone)]
+pub struct Drift {
+    pub task_id: String,
+    /// Referenced anchors whose section changed, appeared or disappeared.
+    pub changed: Vec<String>,
+    /// `spec_version` then and now.
+    pub versions: (Option<String>, Option<String>),
+    /// The task and its changed sections as they read now, so a dismissal (`d`) lasts until
+    /// one of them changes again.
+    pub fingerprint: String,
+}
+
+/// Done tasks measured against an older spec than the current one. Tasks done before the
+/// history recorded a spec have nothing to compare against and are left out.
+pub fn spec_drift(loaded: &LoadedTasks, history: &History) -> Vec<Drift> {
+    let Some(current) = SpecState::of(loaded) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for t in &loaded.tasks.tasks {
+        if task_status(t) != "done" {
+            continue;
+        }
+        let Some(then) = history.spec_since(&t.id) else {
+            continue;
+        };
+        if then.hash == current.hash {
+            continue;
+        }
+        let changed: Vec<String> = t
+            .spec_refs
+            .iter()
+            .map(|r| r.trim())
+            .filter(|r| then.sections.get(*r) != current.sections.get(*r))
+            .map(str::to_string)
+            .collect();
+        if !changed.is_empty() {
+            let sections: Vec<String> = changed
+                .iter()
+                .map(|r| format!("{r}={:?}", current.sections.get(r)))
+                .collect();
+            let hash = fnv1a_64(sections.join("\n").as_bytes());
+            out.push(Drift {
+                task_id: t.id.clone(),
+                changed,
+                versions: (then.version.clone(), current.version.clone()),
+                fingerprint: format!("drift:{}:{hash:016x}", t.id),
+            });
+        }
+    }
+    out
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::cost::CostRollup;
+use crate::graph::{dependency_graph, write_dot, write_mermaid};
+use crate::jira::write_csv;
+use crate::tasks::{normalize_status, task_status, LoadedTasks, Task};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    /// `file:line: message` lines for vim's quickfix list (`:cfile`, `:cexpr`).
+    Quickfix,
+    /// Front matter plus the task's text; only per task (`--split`).
+    Markdown,
+    /// The dependency graph for Graphviz, clustered by component and coloured by status.
+    Dot,
+    /// The same graph as a Mermaid flowchart, for docs that render Mermaid inline.
+    Mermaid,
+    /// CSV for Jira's importer (`jira`).
+    Jira,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "quickfix" | "qf" => Ok(Self::Quickfix),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "mermaid" | "mmd" => Ok(Self::Mermaid),
+            "jira" => Ok(Self::Jira),
+            _ => bail!(
+                "unknown export format {s} (expected json, quickfix, markdown, dot, mermaid or jira)"
+            ),
+        }
+    }
+}
+
+/// What the dashboard's export (`x`) offers: the format, its name, and the extension of the file
+/// it writes next to the tasks file.
+pub const VIEW_FORMATS: [(ExportFormat, &str, &str); 5] = [
+    (ExportFormat::Json, "json", "json"),
+    (ExportFormat::Quickfix, "quickfix", "qf"),
+    (ExportFormat::Dot, "dot", "dot"),
+    (ExportFormat::Mermaid, "mermaid", "mmd"),
+    (ExportFormat::Jira, "jira", "csv"),
+];
+
+pub fn write_export(
+    out: &mut impl Write,
+    loaded: &LoadedTasks,
+    format: ExportFormat,
+    cost: Option<&CostRollup>,
+) -> Result<()> {
+    match format {
+        ExportFormat::Json => {
+            let tasks: Vec<Value> = loaded.tasks.tasks.iter().map(task_json).collect();
+            let mut doc = json!({ "tasks": tasks });
+            if let Some(cost) = cost {
+                doc["cost"] = serde_json::to_value(cost)?;
+            }
+            serde_json::to_writer_pretty(&mut *out, &doc)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Quickfix => {
+            for t in &loaded.tasks.tasks {
+                let status = t
+                    .status
+                    .as_deref()
+                    .map(normalize_status)
+                    .unwrap_or_else(|| "unknown".to_string());
+                writeln!(
+                    out,
+                    "{}:{}:1: {} [{status}] {}",
+                    loaded.path,
+                    t.line.unwrap_or(1),
+                    t.id,
+                    t.title
+                )?;
+            }
+        }
+        ExportFormat::Markdown => {
+            bail!("the markdown export writes one file per task; add --split")
+        }
+        ExportFormat::Dot => write_dot(out, &dependency_graph(&loaded.tasks))?,
+        ExportFormat::Mermaid => write_mermaid(out, &dependency_graph(&loaded.tasks))?,
+        ExportFormat::Jira => write_csv(out, &loaded.tasks)?,
+    }
+    Ok(())
+}
+
+/// The load narrowed to the tasks at `indices`, in that order: what `--filter` or the dashboard's
+/// current view exports. Tasks left out still lend their status to dependencies on them, as
+/// another project's tasks do, so graphs show them as context rather than as missing.
+pub fn select_tasks(loaded: &LoadedTasks, indices: &[usize]) -> LoadedTasks {
+    let mut view = loaded.clone();
+    let all = &loaded.tasks.tasks;
+    view.tasks.tasks = indices
+        .iter()
+        .filter_map(|&i| all.get(i).cloned())
+        .collect();
+    for (i, t) in all.iter().enumerate() {
+        if !indices.contains(&i) {
+            view.tasks
+                .workspace
+                .statuses
+                .entry(t.id.clone())
+                .or_insert_with(|| task_status(t));
+        }
+    }
+    view
+}
+
+/// Writes one file per task into `dir` (created if missing), named by id. A repeated id gets a
+/// `-2`, `-3`… suffix rather than overwriting the first. Returns the written paths.
+pub fn write_split(dir: &Path, loaded: &LoadedTasks, format: ExportFormat) -> Result<Vec<PathBuf>> {
+    let ext = match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Markdown => "md",
+        ExportFormat::Quickfix | ExportFormat::Dot | ExportFormat::Mermaid | ExportFormat::Jira => {
+            bail!("this format covers every task in one file; drop --split")
+        }
+    };
+    std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut written = Vec::new();
+    for t in &loaded.tasks.tasks {
+        let stem = file_stem(&t.id);
+        let n = seen.entry(stem.clone()).or_default();
+        *n += 1;
+        let name = match *n {
+            1 => format!("{stem}.{ext}"),
+            n => format!("{stem}-{n}.{ext}"),
+        };
+        let contents = match format {
+            ExportFormat::Json => format!("{}\n", serde_json::to_string_pretty(&task_document(t))?),
+            _ => task_markdown(t),
+        };
+        let path = dir.join(name);
+        std::fs::write(&path, contents).with_context(|| format!("writing {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// A task id as a file name: anything but letters, digits, `-`, `_` and `.` becomes `_`.
+fn file_stem(id: &str) -> String {
+    let stem: String = id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match stem.trim_start_matches('.') {
+        "" => "task".to_string(),
+        s => s.to_string(),
+    }
+}
+
+/// `task_json` plus the task's text, for a file that stands on its own.
+fn task_document(t: &Task) -> Value {
+    let mut doc = task_json(t);
+    doc["spec_refs"] = json!(t.spec_refs);
+    doc["summary"] = json!(t.summary);
+    doc["deliverables"] = json!(t.deliverables);
+    doc["dod"] = json!(t.dod);
+    doc["notes"] = json!(t.notes);
+    doc
+}
+
+/// YAML front matter (scalars and lists written as JSON, which YAML reads as-is) followed by the
+/// title, summary, deliverables, definition of done and notes.
+fn task_markdown(t: &Task) -> String {
+    let mut out = String::from("---\n");
+    let mut field = |key: &str, value: Value| {
+        if !value.is_null() && value != json!([]) {
+            out.push_str(&format!("{}: {value}\n", yaml_key(key)));
+        }
+    };
+    field("id", json!(t.id));
+    field("title", json!(t.title));
+    field("status", json!(t.status.as_deref().map(normalize_status)));
+    field("deferred_until", json!(t.deferred_until));
+    field("priority", json!(t.priority));
+    field("risk", json!(t.risk));
+    field("component", json!(t.component));
+    field("spec_refs", json!(t.spec_refs));
+    field("depends_on", json!(t.depends_on));
+    for (kind, ids) in t.relations() {
+        field(kind, json!(ids));
+    }
+    field("estimate_days", json!(t.estimate_days));
+    field("estimate_days_min", json!(t.estimate_days_min));
+    field("estimate_days_max", json!(t.estimate_days_max));
+    for (k, v) in &t.extra {
+        field(k, toml_to_json(v));
+    }
+    out.push_str("---\n\n");
+    out.push_str(&format!("# {}: {}\n", t.id, t.title));
+    if let Some(summary) = t.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        out.push_str(&format!("\n{}\n", summary.trim()));
+    }
+    for (heading, items) in [
+        ("Deliverables", &t.deliverables),
+        ("Definition of done", &t.dod),
+    ] {
+        if !items.is_empty() {
+            out.push_str(&format!("\n## {heading}\n\n"));
+            for item in items {
+                out.push_str(&format!("- {item}\n"));
+            }
+        }
+    }
+    if let Some(notes) = t.notes.as_deref().filter(|s| !s.trim().is_empty()) {
+        out.push_str(&format!("\n## Notes\n\n{}\n", notes.trim()));
+    }
+    if !t.links.is_empty() {
+        out.push_str("\n## Links\n\n");
+        for link in &t.links {
+            out.push_str(&format!("- [{}]({})\n", link.name(), link.url));
+        }
+    }
+    out
+}
+
+/// Custom field names are quoted unless YAML reads them as plain keys.
+fn yaml_key(key: &str) -> String {
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+    if plain {
+        key.to_string()
+    } else {
+        json!(key).to_string()
+    }
+}
+
+pub fn task_json(t: &Task) -> Value {
+    json!({
+        "id": t.id,
+        "title": t.title,
+        "status": t.status.as_deref().map(normalize_status),
+        "deferred_until": t.deferred_until,
+        "priority": t.priority,
+        "risk": t.risk,
+        "component": t.component,
+        "depends_on": t.depends_on,
+        "related_to": t.related_to,
+        "duplicates": t.duplicates,
+        "supersedes": t.supersedes,
+        "estimate_days": t.estimate_days,
+        "estimate_days_min": t.estimate_days_min,
+        "estimate_days_max": t.estimate_days_max,
+        "links": t.links,
+        "line": t.line,
+        "custom": t
+            .extra
+            .iter()
+            .map(|(k, v)| (k.clone(), toml_to_json(v)))
+            .collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+fn toml_to_json(v: &toml::Value) -> Value {
+    match v {
+        toml::Value::String(s) => json!(s),
+        toml::Value::Integer(i) => json!(i),
+        toml::Value::Float(x) => json!(x),
+        toml::Value::Boolean(b) => json!(b),
+        toml::Value::Datetime(d) => json!(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.iter().map(toml_to_json).collect()),
+        toml::Value::Table(t) => Value::Object(
+            t.iter()
+                .map(|(k, v)| (k.clone(), toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,322 @@
+//! Atom feed of task changes (`--serve`): one entry per task that was finished, added or newly
+//! blocked between two snapshots of the history file, served as `/feed.atom` over plain HTTP so
+//! feed readers can follow the project without watching the terminal.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+
+use crate::history::{history_path, History, Snapshot};
+use crate::tasks::{fnv1a_64, load_tasks};
+
+/// Newest entries kept in the feed.
+const FEED_ENTRIES: usize = 100;
+/// Bytes of a request read, request line and headers together; the rest is ignored.
+const REQUEST_LIMIT: u64 = 8 * 1024;
+/// Connections served at once; further ones are closed unanswered until one finishes.
+const MAX_CONNECTIONS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Done,
+    Added,
+    Blocked,
+}
+
+impl ChangeKind {
+    fn key(self) -> &'static str {
+        match self {
+            ChangeKind::Done => "done",
+            ChangeKind::Added => "added",
+            ChangeKind::Blocked => "blocked",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    /// RFC 3339 timestamp of the snapshot that showed the change.
+    pub at: String,
+    /// Content hash of that snapshot, so each entry keeps a stable id.
+    pub snapshot: u64,
+    pub task: String,
+    pub kind: ChangeKind,
+    pub detail: String,
+}
+
+/// Changes between consecutive snapshots, newest first. The first snapshot has nothing to
+/// compare with and gives no entries.
+pub fn feed_entries(history: &History) -> Vec<FeedEntry> {
+    let mut out = Vec::new();
+    let snapshots = &history.snapshots;
+    for (prev, next) in snapshots.iter().zip(snapshots.iter().skip(1)).rev() {
+        changes(prev, next, &mut out);
+        if out.len() >= FEED_ENTRIES {
+            break;
+        }
+    }
+    out.truncate(FEED_ENTRIES);
+    out
+}
+
+fn changes(prev: &Snapshot, next: &Snapshot, out: &mut Vec<FeedEntry>) {
+    let mut push = |task: &str, kind, detail: String| {
+        out.push(FeedEntry {
+            at: next.at.clone(),
+            snapshot: next.hash,
+            task: task.to_string(),
+            kind,
+            detail,
+        })
+    };
+    for (id, state) in &next.tasks {
+        let blocked = state.blocked || state.status == "blocked";
+        let why_blocked = || {
+            if state.status == "blocked" {
+                "status blocked".to_string()
+            } else {
+                "waiting on unmet dependencies".to_string()
+            }
+        };
+        let Some(before) = prev.tasks.get(id) else {
+            push(
+                id,
+                ChangeKind::Added,
+                format!("new task, status {}", state.status),
+            );
+            if blocked {
+                push(id, ChangeKind::Blocked, why_blocked());
+            }
+            continue;
+        };
+        if state.status == "done" && before.status != "done" {
+            push(
+                id,
+                ChangeKind::Done,
+                format!("status {} → done", before.status),
+            );
+        }
+        if blocked && !(before.blocked || before.status == "blocked") {
+            push(id, ChangeKind::Blocked, why_blocked());
+        }
+    }
+}
+
+/// Writes the feed. `titles` names tasks by id as of now; ids no longer in the file show
+/// without a title.
+pub fn write_atom(
+    out: &mut impl Write,
+    tasks_path: &Path,
+    entries: &[FeedEntry],
+    titles: &HashMap<String, String>,
+    now: SystemTime,
+) -> Result<()> {
+    let name = tasks_path
+        .file_name()
+        .map_or_else(|| "TASKS.toml".into(), |n| n.to_string_lossy());
+    let feed_id = format!(
+        "urn:harnas-monitor:{:016x}",
+        fnv1a_64(tasks_path.to_string_lossy().as_bytes())
+    );
+    let updated = entries.first().map_or_else(
+        || humantime::format_rfc3339_seconds(now).to_string(),
+        |e| e.at.clone(),
+    );
+    writeln!(out, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    writeln!(out, r#"<feed xmlns="http://www.w3.org/2005/Atom">"#)?;
+    writeln!(out, "  <title>{} changes</title>", xml_escape(&name))?;
+    writeln!(out, "  <id>{feed_id}</id>")?;
+    writeln!(out, "  <updated>{updated}</updated>")?;
+    writeln!(out, "  <author><name>harnas-monitor</name></author>")?;
+    for e in entries {
+        let title = match titles.get(&e.task) {
+            Some(t) => format!("{} {}: {t}", e.task, e.kind.key()),
+            None => format!("{} {}", e.task, e.kind.key()),
+        };
+        writeln!(out, "  <entry>")?;
+        writeln!(out, "    <title>{}</title>", xml_escape(&title))?;
+        writeln!(
+            out,
+            "    <id>{feed_id}:{:016x}:{}:{}</id>",
+            e.snapshot,
+            xml_escape(&e.task),
+            e.kind.key()
+        )?;
+        writeln!(out, "    <updated>{}</updated>", e.at)?;
+        writeln!(
+            out,
+            "    <content type=\"text\">{}</content>",
+            xml_escape(&e.detail)
+        )?;
+        writeln!(out, "  </entry>")?;
+    }
+    writeln!(out, "</feed>")?;
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Stops the server when the dashboard closes, so a reopened one can bind the address again.
+pub struct FeedServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+}
+
+impl FeedServer {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for FeedServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wakes the accept loop so it sees the flag.
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+/// Binds `addr` and answers `GET /feed.atom`, and the browser view at `/`, on a background
+/// thread. Every request reads the files afresh, so the feed is as current as the last load that
+/// changed the content.
+pub fn serve(addr: &str, tasks_path: &Path) -> Result<FeedServer> {
+    let listener = TcpListener::bind(addr).with_context(|| format!("binding {addr}"))?;
+    let bound = listener.local_addr().context("reading the bound address")?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let tasks_path = tasks_path.to_path_buf();
+    let stopped = Arc::clone(&stop);
+    let open = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            let Ok(stream) = stream else {
+                continue;
+            };
+            if open.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+                open.fetch_sub(1, Ordering::Relaxed);
+                continue;
+            }
+            let tasks_path = tasks_path.clone();
+            let open = Arc::clone(&open);
+            std::thread::spawn(move || {
+                serve_connection(stream, &tasks_path);
+                open.fetch_sub(1, Ordering::Relaxed);
+            });
+        }
+    });
+    Ok(FeedServer { addr: bound, stop })
+}
+
+fn serve_connection(stream: TcpStream, tasks_path: &Path) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream.take(REQUEST_LIMIT));
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // The headers are not needed, but are read so the client sees an orderly close.
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|n| n > 0) && header.trim() != "" {
+        header.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    let (status, content_type, body) = match (method, path) {
+        ("GET" | "HEAD", "/feed.atom") => match render_feed(tasks_path) {
+            Ok(body) => ("200 OK", "application/atom+xml; charset=utf-8", body),
+            Err(e) => (
+                "500 Internal Server Error",
+                "text/plain; charset=utf-8",
+                format!("{e:#}\n").into_bytes(),
+            ),
+        },
+        // The browser view (`src/web.rs`): the page, the module it runs and the plan it shows.
+        ("GET" | "HEAD", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            WEB_PAGE.as_bytes().to_vec(),
+        ),
+        ("GET" | "HEAD", "/harnas_monitor.wasm") => match web_module() {
+            Ok(body) => ("200 OK", "application/wasm", body),
+            Err(e) => (
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                format!("{e:#}\n").into_bytes(),
+            ),
+        },
+        ("GET" | "HEAD", "/tasks.toml") => match std::fs::read(tasks_path) {
+            Ok(body) => ("200 OK", "application/toml; charset=utf-8", body),
+            Err(e) => (
+                "500 Internal Server Error",
+                "text/plain; charset=utf-8",
+                format!("reading {}: {e}\n", tasks_path.display()).into_bytes(),
+            ),
+        },
+        ("GET" | "HEAD", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            b"not found; the feed is at /feed.atom and the dashboard at /\n".to_vec(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            b"only GET is supported\n".to_vec(),
+        ),
+    };
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    );
+    let _ = writer.write_all(head.as_bytes());
+    if method != "HEAD" {
+        let _ = writer.write_all(&body);
+    }
+}
+
+/// The browser view's page, served at `/`.
+const WEB_PAGE: &str = include_str!("../web/index.html");
+
+/// The browser view's module: `harnas_monitor.wasm` next to the running executable, where
+/// `web/build.sh` puts it.
+fn web_module() -> Result<Vec<u8>> {
+    let exe = std::env::current_exe().context("locating the executable")?;
+    let path = exe.with_file_name("harnas_monitor.wasm");
+    std::fs::read(&path).with_context(|| {
+        format!("no browser view: {} is missing (see web/build.sh)", path.display())
+    })
+}
+
+fn render_feed(tasks_path: &Path) -> Result<Vec<u8>> {
+    let history = History::load(&history_path(tasks_path))?;
+    // Titles are a nicety: a file that does not parse right now still gets its feed.
+    let titles = load_tasks(tasks_path)
+        .map(|l| l.tasks.tasks.into_iter().map(|t| (t.id, t.title)).collect())
+        .unwrap_or_default();
+    let mut body = Vec::new();
+    write_atom(
+        &mut body,
+        tasks_path,
+        &feed_entries(&history),
+        &titles,
+        SystemTime::now(),
+    )?;
+    Ok(body)
+}
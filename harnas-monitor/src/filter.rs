@@ -0,0 +1,429 @@
+//! The task filter query language shared by the TUI (`/`), the control socket and exports.
+//!
+//! A query is a list of terms; terms are ANDed (an explicit `AND` is accepted), and `OR`
+//! separates alternatives with lower precedence:
+//!
+//! ```text
+//! status:blocked component:api          both must hold
+//! status:blocked AND component:api      same
+//! priority:must OR risk:high            either
+//! -status:done reviewer:ann             negation with a leading `-`
+//! has:ticket has:risk                   the task sets the field (custom or from the schema)
+//! -has:owner                            nobody owns the task's component
+//! estimate>=3 estimate<5                numeric comparisons (`<`, `<=`, `>`, `>=`)
+//! title:"render table"                  double quotes keep spaces in a value
+//! login                                 bare words match id or title
+//! ```
+//!
+//! `status`, `priority` and `component` compare normalized values exactly; every other field
+//! (including custom fields from the generator) matches case-insensitive substrings. `owner`
+//! is the task's own `owner` field or its component's owner in the config's `[owners]`.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use crate::tasks::{component_key, priority_key, risk_key, task_status, Task};
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    query: String,
+    /// Alternatives (OR) of conjunctions (AND).
+    groups: Vec<Vec<Term>>,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    negate: bool,
+    kind: TermKind,
+}
+
+#[derive(Debug, Clone)]
+enum TermKind {
+    Text(String),
+    Field { name: String, value: String },
+    Has(String),
+    /// `name<value` and friends; `accept` lists the orderings of field against value that match.
+    Compare {
+        name: String,
+        accept: &'static [Ordering],
+        value: f64,
+    },
+}
+
+impl Filter {
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut groups: Vec<Vec<Term>> = vec![Vec::new()];
+        for (word, quoted) in words(query)? {
+            match word.as_str() {
+                "AND" if !quoted => continue,
+                "OR" if !quoted => {
+                    groups.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+            let (negate, word) = match word.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, word.as_str()),
+            };
+            if let Some(kind) = comparison(word)? {
+                if let Some(group) = groups.last_mut() {
+                    group.push(Term { negate, kind });
+                }
+                continue;
+            }
+            let kind = match word.split_once(':') {
+                Some(("has", name)) if !name.is_empty() => {
+                    TermKind::Has(canonical_field(&name.to_lowercase()).to_string())
+                }
+                Some((name, value)) if !name.is_empty() && !value.is_empty() => TermKind::Field {
+                    name: canonical_field(&name.to_lowercase()).to_string(),
+                    value: value.to_lowercase(),
+                },
+                Some(_) => {
+                    bail!("filter term {word:?} needs a field and a value (e.g. status:todo)")
+                }
+                None => TermKind::Text(word.to_lowercase()),
+            };
+            if let Some(group) = groups.last_mut() {
+                group.push(Term { negate, kind });
+            }
+        }
+        if groups.iter().any(Vec::is_empty) {
+            bail!("filter {query:?} has an empty side of OR");
+        }
+        Ok(Self {
+            query: query.trim().to_string(),
+            groups,
+        })
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// `owners` is the config's `[owners]`, by component key.
+    pub fn matches(&self, t: &Task, owners: &BTreeMap<String, String>) -> bool {
+        let owner = owners.get(&component_key(t)).map(String::as_str);
+        self.groups
+            .iter()
+            .any(|group| group.iter().all(|term| term.matches(t, owner) != term.negate))
+    }
+}
+
+impl Term {
+    fn matches(&self, t: &Task, owner: Option<&str>) -> bool {
+        match &self.kind {
+            TermKind::Text(s) => {
+                t.id.to_lowercase().contains(s) || t.title.to_lowercase().contains(s)
+            }
+            TermKind::Has(name) => match has_field(t, name) {
+                Some(set) => set,
+                None => {
+                    (name == "owner" && owner.is_some())
+                        || t.extra.keys().any(|k| k.to_lowercase() == *name)
+                        || t.relations()
+                            .iter()
+                            .any(|(k, ids)| k == name && !ids.is_empty())
+                }
+            },
+            TermKind::Compare {
+                name,
+                accept,
+                value,
+            } => number_field(t, name)
+                .and_then(|n| n.partial_cmp(value))
+                .is_some_and(|o| accept.contains(&o)),
+            TermKind::Field { name, value } => match name.as_str() {
+                "status" => task_status(t) == value.replace('-', "_"),
+                "priority" => priority_key(t) == *value,
+                "risk" => risk_key(t) == *value,
+                "component" => component_key(t) == *value,
+                "id" => t.id.to_lowercase().contains(value),
+                "title" => t.title.to_lowercase().contains(value),
+                "spec" => t.spec_refs.iter().any(|r| r.to_lowercase().contains(value)),
+                "dep" => t
+                    .depends_on
+                    .iter()
+                    .any(|d| d.id.to_lowercase().contains(value)),
+                "rel" => t
+                    .relations()
+                    .iter()
+                    .flat_map(|(_, ids)| ids.iter())
+                    .any(|d| d.to_lowercase().contains(value)),
+                "related_to" | "duplicates" | "supersedes" => t
+                    .relations()
+                    .iter()
+                    .filter(|(k, _)| k == name)
+                    .flat_map(|(_, ids)| ids.iter())
+                    .any(|d| d.to_lowercase().contains(value)),
+                "owner" if owner.is_some_and(|o| o.to_lowercase().contains(value)) => true,
+                _ => t
+                    .extra
+                    .iter()
+                    .find(|(k, _)| k.to_lowercase() == *name)
+                    .is_some_and(|(_, v)| display_value(v).to_lowercase().contains(value)),
+            },
+        }
+    }
+}
+
+/// Splits a query at whitespace outside double quotes; the quotes themselves are dropped. The
+/// flag tells whether the word had quotes, so a quoted `"OR"` is a word and not an operator.
+fn words(query: &str) -> Result<Vec<(String, bool)>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let (mut quoted, mut in_quotes) = (false, false);
+    for c in query.chars() {
+        match c {
+            '"' => {
+                quoted = true;
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if quoted || !word.is_empty() {
+                    words.push((std::mem::take(&mut word), quoted));
+                }
+                quoted = false;
+            }
+            c => word.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("filter {query:?} has an unclosed quote");
+    }
+    if quoted || !word.is_empty() {
+        words.push((word, quoted));
+    }
+    Ok(words)
+}
+
+/// `estimate>=3` and the like; `None` when the word has no comparison operator.
+fn comparison(word: &str) -> Result<Option<TermKind>> {
+    let Some(at) = word.find(['<', '>']) else {
+        return Ok(None);
+    };
+    let (name, rest) = word.split_at(at);
+    let (accept, value): (&'static [Ordering], &str) = if let Some(v) = rest.strip_prefix(">=") {
+        (&[Ordering::Greater, Ordering::Equal], v)
+    } else if let Some(v) = rest.strip_prefix("<=") {
+        (&[Ordering::Less, Ordering::Equal], v)
+    } else if let Some(v) = rest.strip_prefix('>') {
+        (&[Ordering::Greater], v)
+    } else {
+        (&[Ordering::Less], &rest[1..])
+    };
+    let value = value.parse::<f64>().ok().filter(|v| v.is_finite());
+    let (Some(value), false) = (value, name.is_empty()) else {
+        bail!("filter term {word:?} compares a field with a number (e.g. estimate>2)")
+    };
+    let name = match name.to_lowercase().as_str() {
+        "estimate" => "estimate_days".to_string(),
+        other => other.to_string(),
+    };
+    Ok(Some(TermKind::Compare {
+        name,
+        accept,
+        value,
+    }))
+}
+
+/// Whether a task sets one of its optional schema fields (an empty list counts as unset);
+/// `None` for any other name.
+fn has_field(t: &Task, name: &str) -> Option<bool> {
+    let set = match name {
+        "component" => t.component.is_some(),
+        "priority" => t.priority.is_some(),
+        "risk" => t.risk.is_some(),
+        "status" => t.status.is_some(),
+        "summary" => t.summary.is_some(),
+        "notes" => t.notes.is_some(),
+        "estimate" | "estimate_days" => t.estimate_days.is_some(),
+        "estimate_days_min" => t.estimate_days_min.is_some(),
+        "estimate_days_max" => t.estimate_days_max.is_some(),
+        "order" => t.order.is_some(),
+        "deferred_until" => t.deferred_until.is_some(),
+        "spec" => !t.spec_refs.is_empty(),
+        "dep" => !t.depends_on.is_empty(),
+        "deliverables" => !t.deliverables.is_empty(),
+        "dod" => !t.dod.is_empty(),
+        "links" => !t.links.is_empty(),
+        _ => return None,
+    };
+    Some(set)
+}
+
+/// A task's numeric field by filter name: the estimates, `order`, or a numeric custom field.
+fn number_field(t: &Task, name: &str) -> Option<f64> {
+    match name {
+        "estimate_days" => t.estimate_days,
+        "estimate_days_min" => t.estimate_days_min,
+        "estimate_days_max" => t.estimate_days_max,
+        // Orders are small positions; the cast is exact for any realistic value.
+        "order" => t.order.map(|o| o as f64),
+        _ => match custom_field(t, name)? {
+            toml::Value::Integer(i) => Some(*i as f64),
+            toml::Value::Float(f) => Some(*f),
+            _ => None,
+        },
+    }
+}
+
+/// Short aliases for built-in fields.
+fn canonical_field(name: &str) -> &str {
+    match name {
+        "prio" => "priority",
+        "comp" => "component",
+        "spec_refs" => "spec",
+        "depends_on" | "deps" => "dep",
+        other => other,
+    }
+}
+
+/// A custom field of a task by name, ignoring case.
+pub fn custom_field<'a>(t: &'a Task, name: &str) -> Option<&'a toml::Value> {
+    t.extra
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+/// Human-readable rendering of a custom field value: strings without quotes, arrays joined.
+pub fn display_value(v: &toml::Value) -> String {
+    match v {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskFile;
+
+    const PLAN: &str = r#"
+[[tasks]]
+id = "T-1"
+title = "Render the table"
+status = "done"
+component = "ui"
+estimate_days = 2.0
+reviewer = "ann"
+links = [{ label = "PR", url = "https://example.com/pr/1" }]
+
+[[tasks]]
+id = "T-2"
+title = "Watch the file"
+status = "blocked"
+component = "api"
+estimate_days = 5.0
+risk = "high"
+depends_on = ["T-1"]
+points = 8
+
+[[tasks]]
+id = "T-3"
+title = "Login page"
+status = "deferred"
+deferred_until = "2026-11-02"
+"#;
+
+    /// Ids of the plan's tasks that `query` matches, with `ui` owned by ann.
+    fn matching(query: &str) -> Vec<String> {
+        let filter = Filter::parse(query).unwrap();
+        let owners = BTreeMap::from([("ui".to_string(), "ann".to_string())]);
+        let file: TaskFile = toml::from_str(PLAN).unwrap();
+        file.tasks
+            .iter()
+            .filter(|t| filter.matches(t, &owners))
+            .map(|t| t.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn terms_are_anded_and_or_separates_alternatives() {
+        assert_eq!(matching("status:blocked component:api"), ["T-2"]);
+        assert_eq!(matching("status:blocked AND component:ui"), [] as [&str; 0]);
+        assert_eq!(matching("status:done OR status:deferred"), ["T-1", "T-3"]);
+        assert_eq!(matching("login"), ["T-3"]);
+    }
+
+    #[test]
+    fn a_leading_dash_negates_a_term() {
+        assert_eq!(matching("-status:done"), ["T-2", "T-3"]);
+        assert_eq!(matching("-table -watch"), ["T-3"]);
+        assert_eq!(matching("-has:owner"), ["T-2", "T-3"]);
+    }
+
+    #[test]
+    fn has_looks_at_custom_fields_and_owners() {
+        assert_eq!(matching("has:reviewer"), ["T-1"]);
+        assert_eq!(matching("has:REVIEWER"), ["T-1"]);
+        assert_eq!(matching("has:owner"), ["T-1"]);
+        assert_eq!(matching("has:ticket"), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn has_looks_at_optional_schema_fields() {
+        assert_eq!(matching("has:risk"), ["T-2"]);
+        assert_eq!(matching("has:estimate_days"), ["T-1", "T-2"]);
+        assert_eq!(matching("-has:estimate"), ["T-3"]);
+        assert_eq!(matching("has:links"), ["T-1"]);
+        assert_eq!(matching("has:deferred_until"), ["T-3"]);
+        assert_eq!(matching("has:deps"), ["T-2"]);
+        assert_eq!(matching("has:dod"), [] as [&str; 0]);
+        assert_eq!(matching("has:component"), ["T-1", "T-2"]);
+    }
+
+    #[test]
+    fn comparisons_are_numeric() {
+        assert_eq!(matching("estimate>2"), ["T-2"]);
+        assert_eq!(matching("estimate>=2"), ["T-1", "T-2"]);
+        assert_eq!(matching("estimate_days<5"), ["T-1"]);
+        assert_eq!(matching("estimate<=5 -status:done"), ["T-2"]);
+        assert_eq!(matching("points>7.5"), ["T-2"]);
+        // A task without the field matches neither side of a comparison.
+        assert_eq!(matching("-estimate>0"), ["T-3"]);
+    }
+
+    #[test]
+    fn quotes_keep_spaces_in_a_value() {
+        assert_eq!(matching("title:\"render the\""), ["T-1"]);
+        assert_eq!(matching("\"login page\""), ["T-3"]);
+        assert_eq!(matching("-title:\"the file\""), ["T-1", "T-3"]);
+        // Quoted, `OR` is a word to look for rather than an operator.
+        assert_eq!(matching("status:deferred \"OR\""), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn malformed_queries_are_errors() {
+        for query in [
+            "status:",
+            ":todo",
+            "has:",
+            "status:todo OR",
+            "OR status:todo",
+            "a OR OR b",
+            "title:\"render",
+            "estimate>",
+            "estimate>lots",
+            ">3",
+            "estimate<inf",
+        ] {
+            assert!(Filter::parse(query).is_err(), "{query:?} parsed");
+        }
+    }
+
+    #[test]
+    fn the_query_is_kept_as_written() {
+        let filter = Filter::parse("  title:\"render the\"  ").unwrap();
+        assert_eq!(filter.query(), "title:\"render the\"");
+    }
+}
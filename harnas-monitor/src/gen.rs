@@ -0,0 +1,240 @@
+//! `gen`: a synthetic but plausible `TASKS.toml` of any size, for benchmarks, `--demo --tasks`
+//! and bug reports that need a big file. The same options and seed always give the same
+//! file: dependencies form a DAG over earlier tasks, mostly within a component, and statuses
+//! follow them (done tasks only wait on done tasks, blocked ones on unfinished ones).
+
+use std::fmt::Write;
+
+/// What to generate; `Default` is what `gen` does without options.
+#[derive(Debug, Clone, Copy)]
+pub struct GenSpec {
+    pub tasks: usize,
+    pub max_deps: usize,
+    pub seed: u64,
+}
+
+impl Default for GenSpec {
+    fn default() -> Self {
+        GenSpec {
+            tasks: 500,
+            max_deps: 3,
+            seed: 1,
+        }
+    }
+}
+
+const COMPONENTS: [&str; 12] = [
+    "core", "storage", "api", "cli", "tui", "engine", "export", "auth", "sync", "search",
+    "release", "qa",
+];
+const VERBS: [&str; 10] = [
+    "Add",
+    "Implement",
+    "Refactor",
+    "Document",
+    "Harden",
+    "Speed up",
+    "Test",
+    "Migrate",
+    "Validate",
+    "Expose",
+];
+const OBJECTS: [&str; 16] = [
+    "invoice numbering",
+    "config loader",
+    "retry policy",
+    "audit log",
+    "CSV export",
+    "session handling",
+    "search index",
+    "rate limiter",
+    "schema migrations",
+    "error reporting",
+    "plugin API",
+    "cache layer",
+    "login flow",
+    "backup job",
+    "metrics endpoint",
+    "undo history",
+];
+/// Who or what a task is for; with the verb and object this keeps titles apart, so the
+/// duplicate check only flags the odd pair, as in real plans.
+const AREAS: [&str; 24] = [
+    "admins",
+    "guests",
+    "teams",
+    "billing",
+    "onboarding",
+    "reports",
+    "mobile",
+    "desktop",
+    "webhooks",
+    "imports",
+    "invites",
+    "payments",
+    "archives",
+    "dashboards",
+    "alerts",
+    "tenants",
+    "uploads",
+    "profiles",
+    "schedules",
+    "approvals",
+    "quotas",
+    "partners",
+    "offline",
+    "exports",
+];
+/// Weighted by how often plans use them.
+const PRIORITIES: [(&str, u64); 4] = [("must", 30), ("should", 40), ("could", 20), ("wont", 10)];
+const RISKS: [(&str, u64); 3] = [("low", 30), ("medium", 50), ("high", 20)];
+const ESTIMATES: [(f64, u64); 6] = [
+    (0.5, 15),
+    (1.0, 30),
+    (1.5, 15),
+    (2.0, 20),
+    (3.0, 15),
+    (5.0, 5),
+];
+/// Earlier tasks a dependency is picked from, so chains stay local like in real plans.
+const DEP_WINDOW: usize = 60;
+
+/// SplitMix64: tiny, fast and the same everywhere, which is all reproducibility needs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    /// True with probability `percent` / 100.
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn weighted<T: Copy>(&mut self, items: &[(T, u64)]) -> T {
+        let total: u64 = items.iter().map(|(_, w)| w).sum();
+        let mut pick = self.next() % total;
+        for (item, w) in items {
+            if pick < *w {
+                return *item;
+            }
+            pick -= w;
+        }
+        items[items.len() - 1].0
+    }
+}
+
+struct Generated {
+    component: &'static str,
+    deps: Vec<usize>,
+    status: &'static str,
+}
+
+/// The tasks file for `spec`, as TOML text.
+pub fn generate(spec: &GenSpec) -> String {
+    let mut rng = Rng(spec.seed);
+    let n = spec.tasks;
+    let components = &COMPONENTS[..(n / 40).clamp(3, COMPONENTS.len())];
+    // How far the plan has come: the share of the file, from the top, that is mostly done.
+    let progress = 25 + rng.below(40);
+    let mut tasks: Vec<Generated> = Vec::with_capacity(n);
+    for i in 0..n {
+        let component = components[rng.below(components.len())];
+        let mut deps = Vec::new();
+        if i > 0 {
+            let wanted = rng.below(spec.max_deps + 1);
+            let lo = i.saturating_sub(DEP_WINDOW);
+            for _ in 0..wanted * 3 {
+                if deps.len() == wanted {
+                    break;
+                }
+                let d = lo + rng.below(i - lo);
+                // Mostly within the component; some cross-component edges for the matrix.
+                if (tasks[d].component == component || rng.chance(25)) && !deps.contains(&d) {
+                    deps.push(d);
+                }
+            }
+            deps.sort_unstable();
+        }
+        let ahead = i * 100 / n.max(1) < progress;
+        if ahead && rng.chance(94) {
+            // Finished work only ever waited on finished work.
+            deps.retain(|&d| tasks[d].status == "done");
+            tasks.push(Generated {
+                component,
+                deps,
+                status: "done",
+            });
+            continue;
+        }
+        let deps_done = deps.iter().all(|&d| tasks[d].status == "done");
+        let status = match (deps_done, ahead) {
+            (true, _) if rng.chance(if ahead { 70 } else { 10 }) => "in_progress",
+            (false, _) if rng.chance(4) => "blocked",
+            _ => "todo",
+        };
+        tasks.push(Generated {
+            component,
+            deps,
+            status,
+        });
+    }
+
+    let width = n.to_string().len().max(3);
+    let id = |i: usize| format!("T-{:0width$}", i + 1);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Generated by `harnas-monitor gen --tasks {n} --max-deps {} --seed {}`.\n",
+        spec.max_deps, spec.seed
+    );
+    let _ = writeln!(out, "[meta]");
+    let _ = writeln!(out, "spec_version = \"gen-{}\"", spec.seed);
+    let _ = writeln!(out, "output_file = \"TASKS.toml\"");
+    for (i, t) in tasks.iter().enumerate() {
+        let title = format!(
+            "{} {} for {} ({})",
+            VERBS[rng.below(VERBS.len())],
+            OBJECTS[rng.below(OBJECTS.len())],
+            AREAS[rng.below(AREAS.len())],
+            t.component
+        );
+        let deps: Vec<String> = t.deps.iter().map(|&d| quote(&id(d))).collect();
+        let _ = writeln!(out, "\n[[tasks]]");
+        let _ = writeln!(out, "id = {}", quote(&id(i)));
+        let _ = writeln!(out, "title = {}", quote(&title));
+        let _ = writeln!(out, "component = {}", quote(t.component));
+        let _ = writeln!(out, "priority = {}", quote(rng.weighted(&PRIORITIES)));
+        if rng.chance(60) {
+            let _ = writeln!(out, "risk = {}", quote(rng.weighted(&RISKS)));
+        }
+        let _ = writeln!(out, "status = {}", quote(t.status));
+        let _ = writeln!(out, "spec_refs = [\"FR-{}\"]", 1 + i);
+        let _ = writeln!(out, "depends_on = [{}]", deps.join(", "));
+        let _ = writeln!(
+            out,
+            "summary = {}",
+            quote(&format!(
+                "Synthetic task {} of {n} in {}.",
+                i + 1,
+                t.component
+            ))
+        );
+        let _ = writeln!(out, "estimate_days = {:.1}", rng.weighted(&ESTIMATES));
+        let _ = writeln!(out, "dod = [\"Covered by tests\"]");
+    }
+    out
+}
+
+fn quote(s: &str) -> String {
+    toml::Value::String(s.to_string()).to_string()
+}
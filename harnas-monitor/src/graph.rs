@@ -0,0 +1,268 @@
+//! The dependency graph as nodes and edges, and its DOT (Graphviz) and Mermaid renderings for
+//! `export --format dot|mermaid`. Edges point from a dependency to the task waiting on it, and
+//! are labelled with the dependency's `kind` and `note` when the file gives them.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::tasks::{component_key, is_blocked, status_map, task_status, TaskFile};
+use crate::workspace::split_ref;
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub id: String,
+    pub title: String,
+    /// Normalized status; "blocked" also for a task waiting on unmet dependencies.
+    pub status: String,
+    pub component: String,
+    /// A dependency that names no task; drawn as a placeholder.
+    pub missing: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edge {
+    /// The dependency.
+    pub from: String,
+    /// The task that depends on it.
+    pub to: String,
+    /// The dependency is not done yet.
+    pub unmet: bool,
+    /// Why the task waits on it (`kind: note`), from an annotated `depends_on` entry.
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DepGraph {
+    /// Tasks in file order (first occurrence of a repeated id), then dependencies on other
+    /// projects' tasks and missing ones.
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl DepGraph {
+    /// Node indices by component, components sorted; missing dependencies are left out.
+    fn clusters(&self) -> BTreeMap<&str, Vec<usize>> {
+        let mut out: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (i, n) in self.nodes.iter().enumerate().filter(|(_, n)| !n.missing) {
+            out.entry(n.component.as_str()).or_default().push(i);
+        }
+        out
+    }
+}
+
+pub fn dependency_graph(file: &TaskFile) -> DepGraph {
+    let status_by_id = status_map(file);
+    let mut graph = DepGraph::default();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for t in &file.tasks {
+        if !seen.insert(&t.id) {
+            continue;
+        }
+        let status = task_status(t);
+        graph.nodes.push(Node {
+            id: t.id.clone(),
+            title: t.title.clone(),
+            status: if status != "done" && is_blocked(t, &status_by_id) {
+                "blocked".to_string()
+            } else {
+                status
+            },
+            component: component_key(t),
+            missing: false,
+        });
+    }
+    // Dependencies outside the file: other workspace projects' tasks, or missing ones.
+    let mut outside: Vec<&str> = Vec::new();
+    for t in &file.tasks {
+        for dep in &t.depends_on {
+            let dep_status = status_by_id.get(dep.id.as_str());
+            if !seen.contains(dep.id.as_str()) && !outside.contains(&dep.id.as_str()) {
+                outside.push(&dep.id);
+            }
+            graph.edges.push(Edge {
+                from: dep.id.clone(),
+                to: t.id.clone(),
+                unmet: dep_status.is_none_or(|s| s != "done"),
+                reason: dep.reason(),
+            });
+        }
+    }
+    graph.nodes.extend(outside.into_iter().map(|id| {
+        match status_by_id.get(id) {
+            Some(status) => Node {
+                id: id.to_string(),
+                title: String::new(),
+                status: status.clone(),
+                // Clustered by project; this file's own ids are tasks a filtered export left out.
+                component: split_ref(id)
+                    .map_or("(filtered out)", |(project, _)| project)
+                    .to_string(),
+                missing: false,
+            },
+            None => Node {
+                id: id.to_string(),
+                title: String::new(),
+                status: "missing".to_string(),
+                component: String::new(),
+                missing: true,
+            },
+        }
+    }));
+    graph
+}
+
+/// Fill and border colours by status, shared by both renderings.
+fn colors(status: &str) -> (&'static str, &'static str) {
+    match status {
+        "done" => ("#c8e6c9", "#2e7d32"),
+        "in_progress" => ("#bbdefb", "#1565c0"),
+        "blocked" => ("#ffcdd2", "#c62828"),
+        "missing" => ("#ffffff", "#9e9e9e"),
+        "todo" => ("#eeeeee", "#616161"),
+        _ => ("#fff9c4", "#f9a825"),
+    }
+}
+
+pub fn write_dot(out: &mut impl Write, graph: &DepGraph) -> Result<()> {
+    writeln!(out, "digraph tasks {{")?;
+    writeln!(out, "  rankdir=LR;")?;
+    writeln!(
+        out,
+        "  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];"
+    )?;
+    for (i, (component, members)) in graph.clusters().into_iter().enumerate() {
+        writeln!(out, "  subgraph cluster_{i} {{")?;
+        writeln!(out, "    label=\"{}\";", dot_escape(component))?;
+        for &n in &members {
+            let node = &graph.nodes[n];
+            let (fill, border) = colors(&node.status);
+            let label = match node.title.as_str() {
+                "" => dot_escape(&node.id),
+                title => format!("{}\\n{}", dot_escape(&node.id), dot_escape(title)),
+            };
+            writeln!(
+                out,
+                "    \"{}\" [label=\"{label}\", fillcolor=\"{fill}\", color=\"{border}\"];",
+                dot_escape(&node.id)
+            )?;
+        }
+        writeln!(out, "  }}")?;
+    }
+    for node in graph.nodes.iter().filter(|n| n.missing) {
+        let (fill, border) = colors(&node.status);
+        writeln!(
+            out,
+            "  \"{}\" [label=\"{}\\n(missing)\", style=\"rounded,dashed\", fillcolor=\"{fill}\", \
+             color=\"{border}\"];",
+            dot_escape(&node.id),
+            dot_escape(&node.id)
+        )?;
+    }
+    for e in &graph.edges {
+        let mut attrs = Vec::new();
+        if !e.unmet {
+            attrs.push("color=\"#9e9e9e\"".to_string());
+        }
+        if let Some(reason) = &e.reason {
+            attrs.push(format!("label=\"{}\"", dot_escape(reason)));
+        }
+        let attrs = match attrs.is_empty() {
+            true => String::new(),
+            false => format!(" [{}]", attrs.join(", ")),
+        };
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\"{attrs};",
+            dot_escape(&e.from),
+            dot_escape(&e.to)
+        )?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+pub fn write_mermaid(out: &mut impl Write, graph: &DepGraph) -> Result<()> {
+    // Mermaid ids must be plain words, so nodes are numbered and the task id goes in the label.
+    let index: BTreeMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+    let label = |n: &Node| {
+        if n.missing {
+            format!(
+                "n{}[\"{} (missing)\"]",
+                index[n.id.as_str()],
+                mermaid_escape(&n.id)
+            )
+        } else if n.title.is_empty() {
+            format!("n{}[\"{}\"]", index[n.id.as_str()], mermaid_escape(&n.id))
+        } else {
+            format!(
+                "n{}[\"{}: {}\"]",
+                index[n.id.as_str()],
+                mermaid_escape(&n.id),
+                mermaid_escape(&n.title)
+            )
+        }
+    };
+    writeln!(out, "flowchart LR")?;
+    for (i, (component, members)) in graph.clusters().into_iter().enumerate() {
+        writeln!(out, "  subgraph c{i} [\"{}\"]", mermaid_escape(component))?;
+        for &n in &members {
+            writeln!(out, "    {}", label(&graph.nodes[n]))?;
+        }
+        writeln!(out, "  end")?;
+    }
+    for node in graph.nodes.iter().filter(|n| n.missing) {
+        writeln!(out, "  {}", label(node))?;
+    }
+    for e in &graph.edges {
+        let arrow = if e.unmet { "-->" } else { "-.->" };
+        let label = e
+            .reason
+            .as_deref()
+            .map_or(String::new(), |r| format!("|\"{}\"|", mermaid_escape(r)));
+        writeln!(
+            out,
+            "  n{} {arrow}{label} n{}",
+            index[e.from.as_str()],
+            index[e.to.as_str()]
+        )?;
+    }
+    let mut by_status: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (i, n) in graph.nodes.iter().enumerate() {
+        by_status
+            .entry(n.status.as_str())
+            .or_default()
+            .push(format!("n{i}"));
+    }
+    for (status, nodes) in by_status {
+        let (fill, border) = colors(status);
+        let class = status.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        let dash = if status == "missing" {
+            ",stroke-dasharray:4"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  classDef s_{class} fill:{fill},stroke:{border}{dash};"
+        )?;
+        writeln!(out, "  class {} s_{class};", nodes.join(","))?;
+    }
+    Ok(())
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;").replace('\n', " ")
+}
@@ -0,0 +1,352 @@
+//! The stats panel's charts as images, for terminals that show them: the kitty graphics
+//! protocol (kitty, WezTerm, Ghostty) or sixel (foot, mlterm, xterm built with it). The
+//! dashboard draws the frame as usual with the chart cells left blank, and `Images::paint`
+//! writes the pictures over them. Built with the `graphics` feature only; everywhere else the
+//! charts are drawn with characters.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::charts::{Chart, ChartSlot};
+use crate::termcaps::ImageProtocol;
+
+/// Pixels per cell when the terminal does not report its size in pixels.
+const DEFAULT_CELL: (u32, u32) = (8, 16);
+/// Base64 bytes per kitty escape, the protocol's limit.
+const KITTY_CHUNK: usize = 4096;
+
+/// Palette indices of the rasters; 0 is transparent, so the terminal's background shows.
+const AXIS: u8 = 1;
+const LINE: u8 = 2;
+const DONE: u8 = 3;
+const IN_PROGRESS: u8 = 4;
+const BLOCKED: u8 = 5;
+const TODO: u8 = 6;
+const OTHER: u8 = 7;
+const PALETTE: [(u8, u8, u8); 8] = [
+    (0, 0, 0),
+    (128, 128, 128),
+    (0, 172, 193),
+    (67, 160, 71),
+    (30, 136, 229),
+    (229, 57, 53),
+    (189, 189, 189),
+    (253, 216, 53),
+];
+
+/// The protocol from the environment. `$HARNAS_MONITOR_IMAGES` (`kitty`, `sixel` or `off`)
+/// overrides the guess; inside tmux or screen, which pass neither through, it is off.
+pub fn detect() -> Option<ImageProtocol> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    match var("HARNAS_MONITOR_IMAGES").as_deref() {
+        Some("kitty") => return Some(ImageProtocol::Kitty),
+        Some("sixel") => return Some(ImageProtocol::Sixel),
+        Some(_) => return None,
+        None => {}
+    }
+    let term = var("TERM").unwrap_or_default().to_lowercase();
+    let program = var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+    if var("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux") {
+        return None;
+    }
+    if var("KITTY_WINDOW_ID").is_some()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || matches!(program.as_str(), "wezterm" | "ghostty")
+    {
+        Some(ImageProtocol::Kitty)
+    } else if term.contains("sixel")
+        || ["foot", "mlterm", "contour"]
+            .iter()
+            .any(|t| term.starts_with(t))
+    {
+        Some(ImageProtocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// What is on screen, so a frame with the same charts writes nothing.
+#[derive(Debug)]
+pub struct Images {
+    protocol: ImageProtocol,
+    shown: Vec<ChartSlot>,
+}
+
+impl Images {
+    pub fn new(protocol: ImageProtocol) -> Self {
+        Images {
+            protocol,
+            shown: Vec::new(),
+        }
+    }
+
+    /// Whether the frame's charts differ from the ones on screen. The caller then redraws the
+    /// whole frame before `paint`: the cells under an old picture did not change, so nothing
+    /// else would clear it.
+    pub fn changed(&self, slots: &[ChartSlot]) -> bool {
+        self.shown != slots
+    }
+
+    /// After a resize the terminal may have dropped the pictures.
+    pub fn forget(&mut self) {
+        self.shown.clear();
+    }
+
+    /// Draws `slots` over the frame, removing the kitty pictures of the last one first.
+    pub fn paint(&mut self, slots: &[ChartSlot], out: &mut impl Write) -> Result<()> {
+        if self.protocol == ImageProtocol::Kitty {
+            write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+        }
+        let (cell_w, cell_h) = cell_size();
+        for slot in slots {
+            let area = slot.area;
+            let width = (u32::from(area.width) * cell_w) as usize;
+            let mut height = (u32::from(area.height) * cell_h) as usize;
+            if self.protocol == ImageProtocol::Sixel {
+                // Sixels come in bands of six rows; a partial band could spill into the next row.
+                height -= height % 6;
+            }
+            if width == 0 || height == 0 {
+                continue;
+            }
+            let raster = rasterize(&slot.chart, width, height);
+            write!(out, "\x1b[{};{}H", area.y + 1, area.x + 1)?;
+            match self.protocol {
+                ImageProtocol::Kitty => write_kitty(out, &raster, area.width, area.height)?,
+                ImageProtocol::Sixel => write_sixel(out, &raster)?,
+            }
+        }
+        out.flush()?;
+        self.shown = slots.to_vec();
+        Ok(())
+    }
+}
+
+fn cell_size() -> (u32, u32) {
+    crossterm::terminal::window_size()
+        .ok()
+        .filter(|s| s.width > 0 && s.height > 0 && s.columns > 0 && s.rows > 0)
+        .map_or(DEFAULT_CELL, |s| {
+            (u32::from(s.width / s.columns), u32::from(s.height / s.rows))
+        })
+}
+
+/// A picture as palette indices, row by row.
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize) -> Self {
+        Raster {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: u8) {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            self.pixels[y as usize * self.width + x as usize] = color;
+        }
+    }
+
+    fn fill(&mut self, (x0, y0): (i64, i64), (x1, y1): (i64, i64), color: u8) {
+        for y in y0.min(y1)..=y0.max(y1) {
+            for x in x0.min(x1)..=x0.max(x1) {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// A line `thickness` pixels wide (Bresenham, with a square pen).
+    fn line(&mut self, (x0, y0): (i64, i64), (x1, y1): (i64, i64), thickness: i64, color: u8) {
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+        let half = (thickness - 1) / 2;
+        loop {
+            self.fill(
+                (x - half, y - half),
+                (x - half + thickness - 1, y - half + thickness - 1),
+                color,
+            );
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn disc(&mut self, (cx, cy): (i64, i64), radius: i64, color: u8) {
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                if x * x + y * y <= radius * radius {
+                    self.set(cx + x, cy + y, color);
+                }
+            }
+        }
+    }
+}
+
+fn status_color(status: &str) -> u8 {
+    match status {
+        "done" => DONE,
+        "in_progress" => IN_PROGRESS,
+        "blocked" => BLOCKED,
+        "todo" => TODO,
+        "missing" => AXIS,
+        _ => OTHER,
+    }
+}
+
+fn rasterize(chart: &Chart, width: usize, height: usize) -> Raster {
+    let mut r = Raster::new(width, height);
+    let pad = (width.min(height) / 16).max(2) as i64;
+    let (left, top) = (pad, pad);
+    let (right, bottom) = (width as i64 - 1 - pad, height as i64 - 1 - pad);
+    let (w, h) = ((right - left).max(1) as f64, (bottom - top).max(1) as f64);
+    match chart {
+        Chart::Burndown(open) => {
+            r.line((left, top), (left, bottom), 1, AXIS);
+            r.line((left, bottom), (right, bottom), 1, AXIS);
+            let max = open.iter().max().copied().unwrap_or(0).max(1) as f64;
+            let steps = open.len().saturating_sub(1).max(1) as f64;
+            let at = |i: usize, n: u64| {
+                (
+                    left + (w * i as f64 / steps).round() as i64,
+                    bottom - (h * n as f64 / max).round() as i64,
+                )
+            };
+            for (i, pair) in open.windows(2).enumerate() {
+                r.line(at(i, pair[0]), at(i + 1, pair[1]), 2, LINE);
+            }
+            if let Some(last) = open.last() {
+                r.disc(at(open.len() - 1, *last), 3, LINE);
+            }
+        }
+        Chart::Estimates(counts) => {
+            r.line((left, bottom), (right, bottom), 1, AXIS);
+            let max = counts.iter().max().copied().unwrap_or(0).max(1) as f64;
+            let slot = w / counts.len().max(1) as f64;
+            let gap = (slot / 5.0).max(1.0);
+            for (i, n) in counts.iter().enumerate().filter(|(_, n)| **n > 0) {
+                let x0 = left + (slot * i as f64 + gap / 2.0).round() as i64;
+                let x1 = left + (slot * (i + 1) as f64 - gap / 2.0).round() as i64;
+                let y0 = bottom - (h * *n as f64 / max).round() as i64;
+                // The first bucket holds the tasks without an estimate.
+                r.fill((x0, y0), (x1, bottom - 1), if i == 0 { AXIS } else { LINE });
+            }
+        }
+        Chart::Graph(layout) => {
+            let at = |x: f64, y: f64| (left + (w * x).round() as i64, top + (h * y).round() as i64);
+            for &(from, to, unmet) in &layout.edges {
+                let (a, b) = (&layout.nodes[from], &layout.nodes[to]);
+                let color = if unmet { OTHER } else { AXIS };
+                r.line(at(a.x, a.y), at(b.x, b.y), 1, color);
+            }
+            let per_node = (w * h) / (layout.nodes.len().max(1) as f64 * 40.0);
+            let radius = per_node.sqrt().clamp(2.0, 6.0) as i64;
+            for n in &layout.nodes {
+                r.disc(at(n.x, n.y), radius, status_color(&n.status));
+            }
+        }
+    }
+    r
+}
+
+/// RGBA, base64-encoded in chunks, scaled by the terminal to `cols` × `rows` cells.
+fn write_kitty(out: &mut impl Write, raster: &Raster, cols: u16, rows: u16) -> Result<()> {
+    let mut rgba = Vec::with_capacity(raster.pixels.len() * 4);
+    for &p in &raster.pixels {
+        let (red, green, blue) = PALETTE[usize::from(p)];
+        rgba.extend_from_slice(&[red, green, blue, if p == 0 { 0 } else { 255 }]);
+    }
+    let data = base64(&rgba);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            write!(
+                out,
+                "\x1b_Ga=T,f=32,s={},v={},c={cols},r={rows},q=2,m={more};",
+                raster.width, raster.height
+            )?;
+        } else {
+            write!(out, "\x1b_Gm={more};")?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+/// Sixel with the palette defined up front; pixels left at 0 stay transparent.
+fn write_sixel(out: &mut impl Write, raster: &Raster) -> Result<()> {
+    write!(out, "\x1bP0;1;0q\"1;1;{};{}", raster.width, raster.height)?;
+    for (i, (red, green, blue)) in PALETTE.iter().enumerate().skip(1) {
+        let pct = |c: u8| u32::from(c) * 100 / 255;
+        write!(out, "#{i};2;{};{};{}", pct(*red), pct(*green), pct(*blue))?;
+    }
+    for band in (0..raster.height).step_by(6) {
+        for color in 1..PALETTE.len() as u8 {
+            let sixels: Vec<u8> = (0..raster.width)
+                .map(|x| {
+                    let bits = (0..6)
+                        .filter(|k| band + k < raster.height)
+                        .filter(|k| raster.pixels[(band + k) * raster.width + x] == color)
+                        .fold(0u8, |acc, k| acc | 1 << k);
+                    63 + bits
+                })
+                .collect();
+            if sixels.iter().all(|s| *s == 63) {
+                continue;
+            }
+            write!(out, "#{color}")?;
+            let mut i = 0;
+            while i < sixels.len() {
+                let run = sixels[i..].iter().take_while(|s| **s == sixels[i]).count();
+                match run {
+                    1..=3 => out.write_all(&sixels[i..i + run])?,
+                    _ => write!(out, "!{run}{}", char::from(sixels[i]))?,
+                }
+                i += run;
+            }
+            write!(out, "$")?;
+        }
+        write!(out, "-")?;
+    }
+    write!(out, "\x1b\\")?;
+    Ok(())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 63) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,362 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::tasks::{id_occurrences, is_blocked, is_waiting, status_map, task_status, LoadedTasks};
+
+/// Snapshots kept in memory unless `[retention] history` says otherwise. Older ones stay in the
+/// history file only.
+pub const HISTORY_SNAPSHOTS: usize = 1000;
+
+/// Per-task state captured at each content change, appended to a monitor-owned
+/// `TASKS.history.jsonl` next to the tasks file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// RFC 3339 timestamp; the file mtime when known, otherwise the load time.
+    pub at: String,
+    pub hash: u64,
+    pub tasks: BTreeMap<String, TaskState>,
+    /// The spec the plan was measured against; absent when it could not be read, and in
+    /// snapshots from before spec drift detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec: Option<SpecState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub hash: u64,
+    /// Section hash by anchor (see `Coverage::sections`).
+    #[serde(default)]
+    pub sections: BTreeMap<String, u64>,
+}
+
+impl SpecState {
+    pub fn of(loaded: &LoadedTasks) -> Option<SpecState> {
+        Some(SpecState {
+            version: loaded
+                .tasks
+                .meta
+                .as_ref()
+                .and_then(|m| m.spec_version.clone()),
+            hash: loaded.coverage.spec_hash?,
+            sections: loaded.coverage.sections.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskState {
+    pub status: String,
+    #[serde(default)]
+    pub blocked: bool,
+    /// Not done and waiting on an unfinished dependency, whatever the status says. `None` in
+    /// snapshots written before this was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    /// The newest snapshots, oldest first; at most `limit` of them.
+    pub snapshots: VecDeque<Snapshot>,
+    limit: usize,
+    /// Snapshots dropped from memory; they are still in the file.
+    spilled: usize,
+    /// Each task's state as of the newest dropped snapshot, so status history and blocked
+    /// streaks that began before the retained ones keep their start.
+    spilled_tasks: BTreeMap<String, SpilledTask>,
+    /// The spec of the newest dropped snapshot that had one.
+    spilled_spec: Option<SpecState>,
+    /// Non-empty lines of the file that were not a snapshot when it was read.
+    pub unreadable: usize,
+}
+
+#[derive(Debug, Clone)]
+struct SpilledTask {
+    state: TaskState,
+    /// When the task entered `state`.
+    entered: String,
+    /// Start of the blocked streak the task was in, if it was blocked.
+    blocked_since: Option<String>,
+}
+
+/// `TASKS.toml` -> `TASKS.history.jsonl`, in the same directory.
+pub fn history_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "TASKS".to_string());
+    tasks_path.with_file_name(format!("{stem}.history.jsonl"))
+}
+
+impl History {
+    pub fn new(path: &Path) -> Self {
+        History {
+            path: path.to_path_buf(),
+            snapshots: VecDeque::new(),
+            limit: HISTORY_SNAPSHOTS,
+            spilled: 0,
+            spilled_tasks: BTreeMap::new(),
+            spilled_spec: None,
+            unreadable: 0,
+        }
+    }
+
+    /// Reads existing snapshots; unreadable lines are skipped so one bad write does not lose
+    /// the rest of the history.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::load_limited(path, HISTORY_SNAPSHOTS)
+    }
+
+    /// Like `load`, keeping only the newest `limit` snapshots in memory.
+    pub fn load_limited(path: &Path, limit: usize) -> Result<Self> {
+        let mut history = History::new(path);
+        history.limit = limit.max(1);
+        let file = match std::fs::File::open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(history),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        for line in BufReader::new(file).split(b'\n') {
+            let line = line.with_context(|| format!("reading {}", path.display()))?;
+            match serde_json::from_slice::<Snapshot>(&line) {
+                Ok(s) => {
+                    history.snapshots.push_back(s);
+                    history.evict();
+                }
+                Err(_) if !line.trim_ascii().is_empty() => history.unreadable += 1,
+                Err(_) => {}
+            }
+        }
+        Ok(history)
+    }
+
+    /// Changes how many snapshots are kept in memory. Raising the limit rereads the file for
+    /// the snapshots already dropped.
+    pub fn set_limit(&mut self, limit: usize) -> Result<()> {
+        let limit = limit.max(1);
+        if limit > self.limit && self.spilled > 0 {
+            *self = Self::load_limited(&self.path, limit)?;
+            return Ok(());
+        }
+        self.limit = limit;
+        self.evict();
+        Ok(())
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Snapshots in the file that are no longer in memory.
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    /// Rough size of the snapshots in memory, in bytes.
+    pub fn retained_bytes(&self) -> usize {
+        // A BTreeMap entry costs about its key and value plus a share of its node.
+        let entry = std::mem::size_of::<String>() + std::mem::size_of::<TaskState>() + 16;
+        let tasks = |m: &BTreeMap<String, TaskState>| {
+            m.iter()
+                .map(|(id, t)| entry + id.capacity() + t.status.capacity())
+                .sum::<usize>()
+        };
+        self.snapshots
+            .iter()
+            .map(|s| std::mem::size_of::<Snapshot>() + s.at.capacity() + tasks(&s.tasks))
+            .sum::<usize>()
+            + self.spilled_tasks.len() * (entry + 64)
+    }
+
+    /// Drops the oldest snapshots beyond `limit`, folding each into `spilled_tasks`.
+    fn evict(&mut self) {
+        while self.snapshots.len() > self.limit {
+            let Some(s) = self.snapshots.pop_front() else {
+                break;
+            };
+            // A task missing from a snapshot keeps its state but ends its blocked streak, as
+            // `task_changes` and `blocked_since` treat gaps.
+            for (id, spilled) in self.spilled_tasks.iter_mut() {
+                if !s.tasks.contains_key(id) {
+                    spilled.blocked_since = None;
+                }
+            }
+            if s.spec.is_some() {
+                self.spilled_spec = s.spec;
+            }
+            for (id, state) in s.tasks {
+                let prev = self.spilled_tasks.remove(&id);
+                let entered = match &prev {
+                    Some(p) if p.state == state => p.entered.clone(),
+                    _ => s.at.clone(),
+                };
+                let blocked_since = state.blocked.then(|| {
+                    prev.and_then(|p| p.blocked_since)
+                        .unwrap_or_else(|| s.at.clone())
+                });
+                self.spilled_tasks.insert(
+                    id,
+                    SpilledTask {
+                        state,
+                        entered,
+                        blocked_since,
+                    },
+                );
+            }
+            self.spilled += 1;
+        }
+    }
+
+    /// Appends a snapshot when the content or the spec differs from the last recorded one, and
+    /// adds it to the SQLite history in builds with the `sqlite` feature.
+    pub fn record(&mut self, loaded: &LoadedTasks) -> Result<()> {
+        let spec = SpecState::of(loaded);
+        if let Some(last) = self.snapshots.back() {
+            if last.hash == loaded.content_hash && last.spec == spec {
+                return Ok(());
+            }
+        }
+        let status_by_id = status_map(&loaded.tasks);
+        // One state per id: a repeated id keeps the first task's, as dependencies do.
+        let occurrences = id_occurrences(&loaded.tasks.tasks);
+        let tasks = loaded
+            .tasks
+            .tasks
+            .iter()
+            .zip(&occurrences)
+            .filter(|(_, &n)| n == 0)
+            .map(|(t, _)| {
+                (
+                    t.id.clone(),
+                    TaskState {
+                        status: task_status(t),
+                        blocked: is_blocked(t, &status_by_id),
+                        waiting: Some(is_waiting(t, &status_by_id)),
+                    },
+                )
+            })
+            .collect();
+        let at = loaded.file_mtime.unwrap_or(loaded.loaded_at);
+        let snapshot = Snapshot {
+            at: humantime::format_rfc3339_seconds(at).to_string(),
+            hash: loaded.content_hash,
+            tasks,
+            spec,
+        };
+
+        // Before the append, so a new database imports the file without this snapshot.
+        let stored = crate::store::record(&self.path, &snapshot, loaded);
+        let line = serde_json::to_string(&snapshot).context("serializing snapshot")?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("opening {}", self.path.display()))?;
+        writeln!(file, "{line}").with_context(|| format!("appending {}", self.path.display()))?;
+        self.snapshots.push_back(snapshot);
+        self.evict();
+        stored
+    }
+
+    /// Number of tasks waiting on unfinished dependencies (the stats' `blocked_by_deps`) in
+    /// each of the last `n` snapshots, oldest first, counting only the task ids `include`
+    /// accepts. Older snapshots without `waiting` count their blocked tasks instead.
+    pub fn waiting_counts(&self, n: usize, include: impl Fn(&str) -> bool) -> Vec<u64> {
+        let skip = self.snapshots.len().saturating_sub(n);
+        self.snapshots
+            .range(skip..)
+            .map(|s| {
+                s.tasks
+                    .iter()
+                    .filter(|(id, t)| t.waiting.unwrap_or(t.blocked) && include(id))
+                    .count() as u64
+            })
+            .collect()
+    }
+
+    /// `id`'s state in each of the last `n` snapshots, oldest first; `None` where the task was
+    /// not in the file.
+    pub fn task_states(&self, id: &str, n: usize) -> Vec<Option<&TaskState>> {
+        let skip = self.snapshots.len().saturating_sub(n);
+        self.snapshots
+            .range(skip..)
+            .map(|s| s.tasks.get(id))
+            .collect()
+    }
+
+    /// Each state `id` entered, oldest first, with the timestamp of the snapshot that first
+    /// recorded it. Snapshots without the task are skipped.
+    pub fn task_changes(&self, id: &str) -> Vec<(&str, &TaskState)> {
+        let mut out: Vec<(&str, &TaskState)> = Vec::new();
+        if let Some(spilled) = self.spilled_tasks.get(id) {
+            out.push((&spilled.entered, &spilled.state));
+        }
+        for s in &self.snapshots {
+            let Some(state) = s.tasks.get(id) else {
+                continue;
+            };
+            if out.last().is_none_or(|(_, prev)| *prev != state) {
+                out.push((&s.at, state));
+            }
+        }
+        out
+    }
+
+    /// The spec `id` was measured against when it entered the status it has in the latest
+    /// snapshot: the first snapshot in that status with a spec. Entered before the snapshots in
+    /// memory, it is the newest dropped one's spec, which may postdate the change.
+    pub fn spec_since(&self, id: &str) -> Option<&SpecState> {
+        let current = &self.snapshots.back()?.tasks.get(id)?.status;
+        let mut spec = None;
+        for s in self.snapshots.iter().rev() {
+            match s.tasks.get(id) {
+                Some(state) if state.status == *current => spec = s.spec.as_ref().or(spec),
+                Some(_) => return spec,
+                None => {}
+            }
+        }
+        let entered_earlier = self
+            .spilled_tasks
+            .get(id)
+            .is_some_and(|t| t.state.status == *current);
+        match (entered_earlier, &self.spilled_spec) {
+            (true, Some(earlier)) => Some(earlier),
+            _ => spec,
+        }
+    }
+
+    /// Start of the current uninterrupted blocked streak of `id`, if it is blocked in the
+    /// latest snapshot.
+    pub fn blocked_since(&self, id: &str) -> Option<SystemTime> {
+        let mut since = None;
+        let mut whole_window = true;
+        for s in self.snapshots.iter().rev() {
+            match s.tasks.get(id) {
+                Some(state) if state.blocked => since = Some(&s.at),
+                _ => {
+                    whole_window = false;
+                    break;
+                }
+            }
+        }
+        // The streak may have begun in snapshots no longer in memory.
+        if whole_window && since.is_some() {
+            if let Some(earlier) = self
+                .spilled_tasks
+                .get(id)
+                .and_then(|t| t.blocked_since.as_ref())
+            {
+                since = Some(earlier);
+            }
+        }
+        since.and_then(|at| humantime::parse_rfc3339(at).ok())
+    }
+}
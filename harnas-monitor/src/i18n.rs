@@ -0,0 +1,804 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+/// UI languages. English text is the message key, gettext-style: a string without an entry in
+/// the selected language's catalog shows in English, so a missing translation never hides a
+/// control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Nl,
+}
+
+pub const LANGUAGES: [&str; 2] = ["en", "nl"];
+
+impl Lang {
+    /// `nl`, `nl_BE` or a full locale like `nl_NL.UTF-8`.
+    pub fn parse(code: &str) -> Option<Lang> {
+        let language = code
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "nl" => Some(Lang::Nl),
+            _ => None,
+        }
+    }
+
+    /// The first of `$LC_ALL`, `$LC_MESSAGES` and `$LANG` that is set; English when none is,
+    /// or its language has no catalog.
+    pub fn from_env() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|v| std::env::var(v).ok())
+            .find(|v| !v.is_empty())
+            .and_then(|v| Lang::parse(&v))
+            .unwrap_or_default()
+    }
+}
+
+/// `en` in `lang`. Surrounding whitespace is kept as is, so footer and help spans keep their
+/// padding while the catalogs stay free of it.
+pub fn tr(lang: Lang, en: &'static str) -> Cow<'static, str> {
+    let text = en.trim();
+    let translated = match lang {
+        Lang::En => None,
+        Lang::Nl => dutch(text),
+    };
+    let Some(translated) = translated else {
+        return Cow::Borrowed(en);
+    };
+    let start = en.len() - en.trim_start().len();
+    let end = en.trim_end().len();
+    Cow::Owned(format!("{}{translated}{}", &en[..start], &en[end..]))
+}
+
+/// `en` in `lang` with each `{}` filled by the next of `args`. Catalog entries keep the
+/// placeholders, so a translation can move the words around the values but not reorder them.
+pub fn trf(lang: Lang, en: &'static str, args: &[&dyn Display]) -> String {
+    let template = tr(lang, en);
+    let mut args = args.iter();
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template.as_ref();
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn dutch(en: &str) -> Option<&'static str> {
+    Some(match en {
+        // Header.
+        "File:" => "Bestand:",
+        "watching:" => "bewaakt:",
+        "Output:" => "Uitvoer:",
+        "Generated:" => "Gegenereerd:",
+        "Last load error:" => "Fout bij laatste lading:",
+        "Last load:" => "Laatste lading:",
+        "Last change:" => "Laatste wijziging:",
+        "reloading" => "herladen",
+        "Refresh:" => "Verversen:",
+        "paused" => "gepauzeerd",
+        "Memory:" => "Geheugen:",
+        "every" => "elke",
+        ", next in" => ", volgende over",
+        "Over budget:" => "Boven budget:",
+        "Problems:" => "Problemen:",
+        "error" => "fout",
+        "errors" => "fouten",
+        "warning" => "waarschuwing",
+        "warnings" => "waarschuwingen",
+        "Drift:" => "Afwijking:",
+        "Remaining:" => "Resterend:",
+        "workday" => "werkdag",
+        "workdays" => "werkdagen",
+        "with" => "met",
+        "dev" => "ontwikkelaar",
+        "devs" => "ontwikkelaars",
+        "of work" => "werk",
+        "unestimated" => "zonder schatting",
+        "in a cycle" => "in een cyclus",
+        "Large change:" => "Grote wijziging:",
+        "view diff" => "verschil bekijken",
+        "Alerts:" => "Meldingen:",
+        "Load error:" => "Laadfout:",
+        "Watcher error:" => "Bewakingsfout:",
+        "Waiting for first load…" => "Wachten op de eerste lading…",
+        "Progress" => "Voortgang",
+        "done" => "klaar",
+        "TASKS.toml dashboard" => "TASKS.toml-dashboard",
+        "blocked" => "geblokkeerd",
+        "(incremental, {}/{} reparsed)" => "(incrementeel, {}/{} opnieuw geparset)",
+        "{}% of tasks ({} added, {} removed, {} changed)" => {
+            "{}% van de taken ({} toegevoegd, {} verwijderd, {} gewijzigd)"
+        }
+        "{}% est" => "{}% schatting",
+        ", {}% count" => ", {}% aantal",
+        "{} missing deps" => "{} ontbrekende afh.",
+        "(generated_utc not RFC 3339)" => "(generated_utc is geen RFC 3339)",
+        "(generated {} earlier)" => "({} eerder gegenereerd)",
+        "(generated {} later)" => "({} later gegenereerd)",
+        "Target:" => "Doel:",
+        "(+{} more, s)" => "(+{} meer, s)",
+        "hidden:" => "verborgen:",
+        "{} tasks ({} done, {} blocked, {} open)" => {
+            "{} taken ({} klaar, {} geblokkeerd, {} open)"
+        }
+        ", {} problems" => ", {} problemen",
+        "(f to leave)" => "(f om te verlaten)",
+        "Waiting" => "Wachtend",
+        // Panes and table headers.
+        "Tasks" => "Taken",
+        "by risk score" => "op risicoscore",
+        "deferred" => "uitgesteld",
+        "deferred, at the bottom" => "uitgesteld, onderaan",
+        "by" => "op",
+        "Wait" => "Wacht",
+        "Risk" => "Risico",
+        "Title" => "Titel",
+        "Est" => "Sch",
+        "Deps" => "Afh",
+        "Navigate" => "Navigeren",
+        "Components" => "Componenten",
+        "Statuses" => "Statussen",
+        "all tasks" => "alle taken",
+        "(unowned)" => "(zonder eigenaar)",
+        "Score" => "Score",
+        "{}–{} of {}" => "{}–{} van {}",
+        // Details pane.
+        "Details" => "Details",
+        "No data loaded yet." => "Nog geen gegevens geladen.",
+        "No tasks." => "Geen taken.",
+        "No tasks match the filter (/ to change it)." => {
+            "Geen taken passen bij de filter (/ om hem te wijzigen)."
+        }
+        "duplicate id: an earlier task is also {} (p to fix)" => {
+            "dubbel id: een eerdere taak is ook {} (p om te herstellen)"
+        }
+        "unknown" => "onbekend",
+        "priority:" => "prioriteit:",
+        "risk:" => "risico:",
+        "owned by:" => "eigenaar:",
+        "nobody" => "niemand",
+        "deferred until:" => "uitgesteld tot:",
+        "(back; L clears)" => "(terug; L wist)",
+        "(L brings it back now)" => "(L haalt haar nu terug)",
+        "risk score:" => "risicoscore:",
+        "{} = {} × {} × (1 + {} downstream)" => "{} = {} × {} × (1 + {} stroomafwaarts)",
+        "depends_on:" => "hangt af van:",
+        "({}: not found)" => "({}: niet gevonden)",
+        "spec_refs:" => "spec-verwijzingen:",
+        "maybe depends on:" => "hangt misschien af van:",
+        "({} → {}, shares {})" => "({} → {}, deelt {})",
+        "(+ stages it)" => "(+ zet het klaar)",
+        "(1-9 to open)" => "(1-9 om te openen)",
+        "estimate_days:" => "schatting (dagen):",
+        "focus time:" => "focustijd:",
+        "{} min in {} session(s)" => "{} min in {} sessie(s)",
+        "summary" => "samenvatting",
+        "({} words, ~{} min read)" => "({} woorden, ~{} min lezen)",
+        "… (+{} words," => "… (+{} woorden,",
+        "to expand)" => "om uit te klappen)",
+        "deliverables" => "opleveringen",
+        "… (+{} more)" => "… (+{} meer)",
+        "DoD" => "DoD",
+        "(g to jump)" => "(g om te springen)",
+        "status history" => "statusgeschiedenis",
+        "… ({} earlier)" => "… ({} eerder)",
+        "(waiting on deps)" => "(wacht op afhankelijkheden)",
+        "notes" => "notities",
+        "custom fields" => "eigen velden",
+        "annotations" => "aantekeningen",
+        "(notes file)" => "(notitiebestand)",
+        "comments" => "opmerkingen",
+        "(C to add or reply)" => "(C om toe te voegen of te antwoorden)",
+        "unknown task" => "onbekende taak",
+        // Panels and modals.
+        "Add annotation" => "Aantekening toevoegen",
+        "Jump to task id" => "Spring naar taak-id",
+        "Defer until (YYYY-MM-DD, or from today: 3d, 2w; empty: 1 week)" => {
+            "Uitstellen tot (JJJJ-MM-DD, of vanaf vandaag: 3d, 2w; leeg: 1 week)"
+        }
+        "Rename component {} in every task to" => "Component {} in elke taak hernoemen naar",
+        "Filter (status:blocked component:api, OR, -negate, has:field)" => {
+            "Filter (status:blocked component:api, OR, -uitsluiten, has:veld)"
+        }
+        "Enter confirm  Esc cancel" => "Enter bevestigen  Esc annuleren",
+        "→ done" => "→ klaar",
+        "→ blocked" => "→ geblokkeerd",
+        "new:" => "nieuw:",
+        "just now" => "zonet",
+        "{} ago" => "{} geleden",
+        "Problems ({})" => "Problemen ({})",
+        "↑/↓ select  Enter jump  f fix  d dismiss  Esc close" => {
+            "↑/↓ selecteren  Enter springen  f herstellen  d negeren  Esc sluiten"
+        }
+        "No problems found." => "Geen problemen gevonden.",
+        "fix" => "herstel",
+        "(dismissed)" => "(genegeerd)",
+        "Changes since the last accepted load" => "Wijzigingen sinds de laatst geaccepteerde lading",
+        "↑/↓ scroll  a accept  Esc close" => "↑/↓ scrollen  a accepteren  Esc sluiten",
+        "No pending change." => "Geen openstaande wijziging.",
+        "Added ({})" => "Toegevoegd ({})",
+        "Removed ({})" => "Verwijderd ({})",
+        "Changed ({})" => "Gewijzigd ({})",
+        "Links" => "Koppelingen",
+        "Links of {}" => "Koppelingen van {}",
+        "↑/↓ select  Enter jump  Esc close" => "↑/↓ selecteren  Enter springen  Esc sluiten",
+        "Redundant dependencies: {} ({} picked)" => "Overbodige afhankelijkheden: {} ({} gekozen)",
+        "↑/↓ select  Space pick  a all  Enter stage removal  Esc close" => {
+            "↑/↓ selecteren  Spatie kiezen  a alles  Enter verwijderen klaarzetten  Esc sluiten"
+        }
+        "via {}" => "via {}",
+        "The reload left {} dependencies on missing tasks" => {
+            "Na het herladen wijzen {} afhankelijkheden naar ontbrekende taken"
+        }
+        "↑/↓ select  d remove  r remap  e external  l leave  Enter write  Esc close" => {
+            "↑/↓ selecteren  d verwijderen  r omleggen  e extern  l laten  Enter schrijven  Esc sluiten"
+        }
+        "remap → {}" => "omleggen → {}",
+        "remap" => "omleggen",
+        "leave" => "laten",
+        "remove" => "verwijderen",
+        "external" => "extern",
+        "was {}" => "was {}",
+        "similar: {}" => "vergelijkbaar: {}",
+        "Review queue: {} tasks done in the last {} days, {}/{} DoD items verified" => {
+            "Reviewwachtrij: {} taken klaar in de afgelopen {} dagen, {}/{} DoD-punten nagekeken"
+        }
+        "↑/↓ select  Space verified  Enter jump  Esc close" => {
+            "↑/↓ selecteren  Spatie nagekeken  Enter springen  Esc sluiten"
+        }
+        "no DoD items" => "geen DoD-punten",
+        "{}/{} verified" => "{}/{} nagekeken",
+        "done {} ago" => "{} geleden klaar",
+        "(other project)" => "(ander project)",
+        "{} depends on" => "{} hangt af van",
+        "Spec drift: {} done tasks to verify again" => {
+            "Spec-afwijking: {} klare taken om opnieuw na te kijken"
+        }
+        "↑/↓ select  Enter jump  d dismiss  Esc close" => {
+            "↑/↓ selecteren  Enter springen  d negeren  Esc sluiten"
+        }
+        "unversioned" => "zonder versie",
+        "Task" => "Taak",
+        "Changed" => "Gewijzigd",
+        "Safe mode: {} state files failed to load, {} not moved aside yet" => {
+            "Veilige modus: {} statusbestanden laadden niet, {} nog niet opzijgezet"
+        }
+        "↑/↓ select  r move the file aside  Esc close" => {
+            "↑/↓ selecteren  r bestand opzijzetten  Esc sluiten"
+        }
+        "moved to {}; the next start is a normal one" => {
+            "verplaatst naar {}; de volgende start is een gewone"
+        }
+        "using the default settings" => "de standaardinstellingen worden gebruikt",
+        "using the readable snapshots only" => "alleen de leesbare momentopnamen worden gebruikt",
+        "this session's usage is not recorded" => "het gebruik van deze sessie wordt niet vastgelegd",
+        "Export {} tasks matching {}" => "{} taken exporteren die passen bij {}",
+        "Export {} tasks" => "{} taken exporteren",
+        "↑/↓ select  Enter write  Esc close" => "↑/↓ selecteren  Enter schrijven  Esc sluiten",
+        "Comment on {}" => "Opmerking bij {}",
+        "↑/↓ reply to  Enter post  Esc cancel" => "↑/↓ antwoorden op  Enter plaatsen  Esc annuleren",
+        "(start a new thread)" => "(nieuwe draad beginnen)",
+        "reply to #{}" => "antwoord op #{}",
+        "new thread" => "nieuwe draad",
+        "Replan" => "Herplannen",
+        "←/→ deadline ±1 day  PgUp/PgDn ±1 week  +/- workers  ↑/↓ scroll  Esc close" => {
+            "←/→ deadline ±1 dag  PgUp/PgDn ±1 week  +/- ontwikkelaars  ↑/↓ scrollen  Esc sluiten"
+        }
+        "All remaining tasks finish by" => "Alle resterende taken zijn klaar op",
+        "({} days)" => "({} dagen)",
+        ", between {} and {}" => ", tussen {} en {}",
+        "Deadline" => "Deadline",
+        "(in {} days)   workers" => "(over {} dagen)   ontwikkelaars",
+        "Fits ({})" => "Past ({})",
+        "Slips ({})" => "Loopt uit ({})",
+        "Cut or defer to fit ({})" => "Schrappen of uitstellen om te passen ({})",
+        "without them the rest finishes by {}" => "zonder deze is de rest klaar op {}",
+        "{} tasks without estimate_days count as {} day: {}" => {
+            "{} taken zonder estimate_days tellen als {} dag: {}"
+        }
+        "In a dependency cycle, not scheduled: {}" => {
+            "In een afhankelijkheidscyclus, niet ingepland: {}"
+        }
+        "Edit {}" => "{} bewerken",
+        "Edit {}: {}" => "{} bewerken: {}",
+        "↑/↓ select  Enter edit  Esc close" => "↑/↓ selecteren  Enter bewerken  Esc sluiten",
+        "↑/↓ select  Enter stage  Esc back" => "↑/↓ selecteren  Enter klaarzetten  Esc terug",
+        "Enter stage  Esc back" => "Enter klaarzetten  Esc terug",
+        "Workspace" => "Werkruimte",
+        "↑/↓ select  Enter open  Esc close" => "↑/↓ selecteren  Enter openen  Esc sluiten",
+        "project" => "project",
+        "progress" => "voortgang",
+        "updated" => "bijgewerkt",
+        "health" => "gezondheid",
+        "Watch log (debug)" => "Bewakingslog (debug)",
+        "↑/↓ PgUp/PgDn scroll  End newest  Esc close" => {
+            "↑/↓ PgUp/PgDn scrollen  End nieuwste  Esc sluiten"
+        }
+        "No watcher activity yet." => "Nog geen bewakingsactiviteit.",
+        "Staged edits" => "Klaargezette wijzigingen",
+        "↑/↓ scroll  W write  u unstage last  x discard all  Esc close" => {
+            "↑/↓ scrollen  W schrijven  u laatste terug  x alles weggooien  Esc sluiten"
+        }
+        "Resulting change to the file" => "Resulterende wijziging aan het bestand",
+        "Fix" => "Herstel",
+        "The file already has this change." => "Het bestand heeft deze wijziging al.",
+        "line {}" => "regel {}",
+        "Spec coverage" => "Spec-dekking",
+        "↑/↓ scroll  Esc close" => "↑/↓ scrollen  Esc sluiten",
+        "Anchors referenced:" => "Verwezen ankers:",
+        "Spec anchors without tasks ({})" => "Spec-ankers zonder taken ({})",
+        "none" => "geen",
+        "Tasks without spec_refs ({})" => "Taken zonder spec_refs ({})",
+        "Open tasks, {} loads" => "Open taken, {} ladingen",
+        "Tasks by estimate (days)" => "Taken per schatting (dagen)",
+        "Dependencies ({} edges)" => "Afhankelijkheden ({} verbindingen)",
+        "Not enough history yet." => "Nog niet genoeg geschiedenis.",
+        "Stats" => "Statistiek",
+        "Tasks:" => "Taken:",
+        "done:" => "klaar:",
+        "blocked by deps:" => "geblokkeerd door afh.:",
+        "By status" => "Per status",
+        "By priority" => "Per prioriteit",
+        "By component" => "Per component",
+        "tasks" => "taken",
+        "est.days" => "sch.dagen",
+        "range" => "bereik",
+        "spent" => "besteed",
+        "remaining" => "resterend",
+        "owner" => "eigenaar",
+        "Budgets (est. days)" => "Budgetten (sch. dagen)",
+        "over by {}" => "{} erover",
+        "Cost (day rate {})" => "Kosten (dagtarief {})",
+        "spent:" => "besteed:",
+        "remaining:" => "resterend:",
+        "total:" => "totaal:",
+        "{} task(s) without estimate_days are not counted" => {
+            "{} taak/taken zonder estimate_days tellen niet mee"
+        }
+        "Set day_rate in the config file to see cost estimates." => {
+            "Stel day_rate in het configbestand in om kostenramingen te zien."
+        }
+        "Targets" => "Doelen",
+        "Completed by (git)" => "Afgerond door (git)",
+        "no commit has set a task done yet" => "nog geen commit heeft een taak op klaar gezet",
+        "author" => "auteur",
+        "last" => "laatst",
+        "Completed by (git): {}" => "Afgerond door (git): {}",
+        "Component dependencies (row waits on column)" => {
+            "Afhankelijkheden tussen componenten (rij wacht op kolom)"
+        }
+        "↑/↓ select  Enter filter to component  Esc close" => {
+            "↑/↓ selecteren  Enter filteren op component  Esc sluiten"
+        }
+        "No unmet dependencies cross component boundaries." => {
+            "Geen onvervulde afhankelijkheden over componentgrenzen heen."
+        }
+        "{} unmet dependencies cross component boundaries." => {
+            "{} onvervulde afhankelijkheden gaan over componentgrenzen heen."
+        }
+        // Footer.
+        "DEMO" => "DEMO",
+        "tour" => "rondleiding",
+        "LARGE CHANGE" => "GROTE WIJZIGING",
+        "diff" => "verschil",
+        "accept" => "accepteren",
+        "STAGED" => "KLAARGEZET",
+        "review" => "bekijken",
+        "write" => "schrijven",
+        "DRIFT" => "AFWIJKING",
+        "stop" => "stoppen",
+        "workspace" => "werkruimte",
+        "quit" => "afsluiten",
+        "help" => "hulp",
+        "select" => "selecteren",
+        "scroll details" => "details scrollen",
+        "reload" => "herladen",
+        "note" => "notitie",
+        "comment" => "opmerking",
+        "export view" => "weergave exporteren",
+        "jump" => "springen",
+        "links" => "koppelingen",
+        "back/fwd" => "terug/vooruit",
+        "status/prio" => "status/prio",
+        "edit" => "bewerken",
+        "replan" => "herplannen",
+        "prune deps" => "afh. opschonen",
+        "problems" => "problemen",
+        "coverage" => "dekking",
+        "stats" => "statistiek",
+        "dep matrix" => "afh.-matrix",
+        "sidebar" => "zijbalk",
+        "deps/sidebar" => "afh./zijbalk",
+        "file order" => "bestandsvolgorde",
+        "risk sort" => "op risico",
+        "sort by column" => "sorteer op kolom",
+        "profile" => "profiel",
+        "expand" => "uitklappen",
+        "compact" => "inklappen",
+        "by count" => "op aantal",
+        "by estimate" => "op schatting",
+        "muted" => "gedempt",
+        "statuses:" => "statussen:",
+        // Help.
+        "Help" => "Hulp",
+        "Dashboard for a sibling" => "Dashboard voor een naastgelegen",
+        "file." => "bestand.",
+        "type to search  ↑/↓ scroll  Esc clear/close" => {
+            "typ om te zoeken  ↑/↓ scrollen  Esc wissen/sluiten"
+        }
+        "Search:" => "Zoeken:",
+        "profile:" => "profiel:",
+        "(remapped by the profile)" => "(door het profiel omgelegd)",
+        "No key matches." => "Geen toets gevonden.",
+        "Moving around" => "Navigeren",
+        "Editing (staged until W)" => "Bewerken (klaargezet tot W)",
+        "Panels" => "Panelen",
+        "Display" => "Weergave",
+        "Sidebar (Tab)" => "Zijbalk (Tab)",
+        "Table header (H)" => "Tabelkop (H)",
+        "Problems panel (p)" => "Problemenpaneel (p)",
+        "Staged edits (w)" => "Klaargezette wijzigingen (w)",
+        "Replan (R)" => "Herplannen (R)",
+        "Redundant dependencies (X)" => "Overbodige afhankelijkheden (X)",
+        "Missing dependencies (after a reload)" => "Ontbrekende afhankelijkheden (na herladen)",
+        "Review queue (Q)" => "Reviewwachtrij (Q)",
+        "This help (?)" => "Deze hulp (?)",
+        "Demo (--demo)" => "Demo (--demo)",
+        "first / last task" => "eerste / laatste taak",
+        "pick an entry" => "regel kiezen",
+        "show that component or status; again shows everything" => {
+            "die component of status tonen; nogmaals toont alles"
+        }
+        "back to the table" => "terug naar de tabel",
+        "pick a column" => "kolom kiezen",
+        "sort by it; again reverses" => "erop sorteren; nogmaals keert om",
+        "back to the rows" => "terug naar de rijen",
+        "jump to its task" => "naar de taak springen",
+        "preview its fix; Enter or y stages it, Esc goes back" => {
+            "herstelvoorstel tonen; Enter of y zet het klaar, Esc gaat terug"
+        }
+        "write the staged edits" => "klaargezette wijzigingen schrijven",
+        "unstage the last edit" => "laatste wijziging terugnemen",
+        "discard every staged edit" => "alle klaargezette wijzigingen weggooien",
+        "deadline a day earlier / later" => "deadline een dag eerder / later",
+        "deadline a week earlier / later" => "deadline een week eerder / later",
+        "one worker more / fewer" => "een ontwikkelaar meer / minder",
+        "pick or unpick a dependency" => "afhankelijkheid kiezen of loslaten",
+        "pick all, or none" => "alles kiezen, of niets",
+        "stage the picked removals (or the selected one)" => {
+            "gekozen verwijderingen klaarzetten (of de geselecteerde)"
+        }
+        "remove the dependency" => "afhankelijkheid verwijderen",
+        "point it at the suggested id" => "naar het voorgestelde id laten wijzen",
+        "keep it as an external dependency" => "als externe afhankelijkheid houden",
+        "leave it as it is" => "laten zoals het is",
+        "write the chosen fixes" => "gekozen herstellingen schrijven",
+        "pick a task or DoD item" => "taak of DoD-punt kiezen",
+        "mark the DoD item verified, or not" => "DoD-punt als nagekeken markeren, of niet",
+        "jump to the task" => "naar de taak springen",
+        "search keys and descriptions" => "zoeken in toetsen en beschrijvingen",
+        "scroll" => "scrollen",
+        "clear the search, then close" => "zoekopdracht wissen, daarna sluiten",
+        "reload now" => "nu herladen",
+        "reload at once, without waiting for the debounce" => {
+            "meteen herladen, zonder de debounce af te wachten"
+        }
+        "pause/resume the periodic reload" => "periodiek herladen pauzeren/hervatten",
+        "annotate selected task (TASKS.notes.toml)" => {
+            "notitie bij de geselecteerde taak (TASKS.notes.toml)"
+        }
+        "comment on the selected task, or reply to one of its comments" => {
+            "opmerking bij de geselecteerde taak, of antwoord op een van haar opmerkingen"
+        }
+        "jump to task id" => "spring naar taak-id",
+        "view / accept a large change (only while its banner is shown)" => {
+            "grote wijziging bekijken / accepteren (alleen zolang de melding staat)"
+        }
+        "open the selected task's numbered link (O: the first) in the browser" => {
+            "genummerde link van de geselecteerde taak openen in de browser (O: de eerste)"
+        }
+        "tasks linked by depends_on, related_to, duplicates, supersedes" => {
+            "taken gekoppeld via depends_on, related_to, duplicates, supersedes"
+        }
+        "find as you type: the next task whose id or title starts with it (' again: next); a key with no binding of its own starts it too" => {
+            "zoeken tijdens het typen: de volgende taak waarvan id of titel zo begint (' nogmaals: volgende); een toets zonder eigen functie begint er ook mee"
+        }
+        "stage the next status / priority of the selected task" => {
+            "volgende status / prioriteit van de geselecteerde taak klaarzetten"
+        }
+        "defer the selected task until a day; on a deferred task, bring it back" => {
+            "geselecteerde taak uitstellen tot een dag; een uitgestelde taak terughalen"
+        }
+        "sort by risk score or file order; the score is" => {
+            "sorteer op risicoscore of bestandsvolgorde; de score is"
+        }
+        "table header: ←/→ pick a column, Enter sorts by it (again reverses)" => {
+            "tabelkop: ←/→ kies een kolom, Enter sorteert erop (nogmaals keert om)"
+        }
+        "safe mode: state files that failed to load (r moves one aside)" => {
+            "veilige modus: statusbestanden die niet laadden (r zet er een opzij)"
+        }
+        "spec drift: done tasks whose spec sections changed since (Enter jumps, d dismisses)" => {
+            "spec-afwijking: klare taken waarvan de spec sindsdien wijzigde (Enter springt, d negeert)"
+        }
+        "review queue: tasks done lately, with their DoD items to verify" => {
+            "reviewwachtrij: recent afgeronde taken, met hun DoD-punten om na te kijken"
+        }
+        "next view profile from the config (columns, theme, filter, sort, keys)" => {
+            "volgend weergaveprofiel uit de config (kolommen, thema, filter, sortering, toetsen)"
+        }
+        "progress gauge by task count or by estimate_days" => {
+            "voortgangsbalk op aantal taken of op estimate_days"
+        }
+        "on a task with dependencies: their status at a glance (Tab again: sidebar)" => {
+            "op een taak met afhankelijkheden: hun status in één oogopslag (nogmaals Tab: zijbalk)"
+        }
+        "show/hide the sidebar / move between sidebar and table" => {
+            "zijbalk tonen/verbergen / wisselen tussen zijbalk en tabel"
+        }
+        "edit a field: title, priority, component or estimate_days" => {
+            "veld bewerken: title, priority, component of estimate_days"
+        }
+        "replan: what fits before a deadline, what slips, what to cut" => {
+            "herplannen: wat past voor een deadline, wat uitloopt, wat kan vervallen"
+        }
+        "redundant dependencies (A→B→C and A→C): Space picks, Enter stages removal" => {
+            "overbodige afhankelijkheden (A→B→C en A→C): Spatie kiest, Enter zet verwijderen klaar"
+        }
+        "add the dependency the details pane suggests from shared spec_refs" => {
+            "de afhankelijkheid toevoegen die het detailpaneel voorstelt op basis van gedeelde spec_refs"
+        }
+        "workspace overview: every configured project; Enter opens one" => {
+            "werkruimte-overzicht: elk geconfigureerd project; Enter opent er een"
+        }
+        "watch log: file events, debounce windows, load attempts (debug)" => {
+            "bewakingslog: bestandsgebeurtenissen, debounce-vensters, laadpogingen (debug)"
+        }
+        "review staged edits (u unstage last, x discard) / write them" => {
+            "klaargezette wijzigingen bekijken (u laatste terug, x weggooien) / schrijven"
+        }
+        "back / forward through recently viewed tasks (breadcrumbs)" => {
+            "terug / vooruit door recent bekeken taken (broodkruimels)"
+        }
+        "filter tasks (status:blocked comp:api, OR, -negate, has:field)" => {
+            "taken filteren (status:blocked comp:api, OR, -uitsluiten, has:veld)"
+        }
+        "problems panel (Enter jumps to the task, f previews a fix, d dismisses an alert)" => {
+            "problemenpaneel (Enter springt naar de taak, f toont een herstelvoorstel, d negeert een melding)"
+        }
+        "spec coverage panel" => "paneel spec-dekking",
+        "stats panel (counts, estimates, cost when day_rate is set)" => {
+            "statistiekpaneel (aantallen, schattingen, kosten als day_rate is ingesteld)"
+        }
+        "export the table as shown: json, quickfix, dot or mermaid" => {
+            "tabel exporteren zoals getoond: json, quickfix, dot of mermaid"
+        }
+        "component dependency matrix (Enter filters to the row's component)" => {
+            "afhankelijkheidsmatrix per component (Enter filtert op die component)"
+        }
+        "focus on the selected task's component / leave focus mode" => {
+            "focus op de component van de geselecteerde taak / focusmodus verlaten"
+        }
+        "start a 25-minute focus session on the selected task, or stop it" => {
+            "focussessie van 25 minuten op de geselecteerde taak starten, of stoppen"
+        }
+        "compact details: only the first paragraph of long summaries and notes" => {
+            "compacte details: alleen de eerste alinea van lange samenvattingen en notities"
+        }
+        "collapse the header to one summary line, or expand it" => {
+            "de kop tot één samenvattende regel inklappen, of uitklappen"
+        }
+        "show/hide the deferred tasks at the bottom of the table" => {
+            "uitgestelde taken onderaan de tabel tonen/verbergen"
+        }
+        "cycle cue volume (100%, 50%, 25%, muted)" => {
+            "volume van signalen wisselen (100%, 50%, 25%, gedempt)"
+        }
+        "select task" => "taak selecteren",
+        "move task within its priority group (stages order)" => {
+            "taak verplaatsen binnen haar prioriteitsgroep (zet volgorde klaar)"
+        }
+        "scroll details pane" => "detailpaneel scrollen",
+        "toggle this help" => "deze hulp tonen/verbergen",
+        "next/previous tour tip (--demo only; Esc hides)" => {
+            "volgende/vorige rondleidingstip (alleen --demo; Esc verbergt)"
+        }
+        "Auto reload" => "Automatisch herladen",
+        ": watches file changes and refreshes." => {
+            ": volgt wijzigingen in het bestand en ververst."
+        }
+        // Status messages.
+        "accepted the change as the new baseline" => {
+            "wijziging geaccepteerd als nieuwe uitgangssituatie"
+        }
+        "demo: exporting is disabled" => "demo: exporteren is uitgeschakeld",
+        "nothing staged" => "niets klaargezet",
+        "no deferred tasks" => "geen uitgestelde taken",
+        "find: type the start of an id or title (' next, Esc done)" => {
+            "zoeken: typ het begin van een id of titel (' volgende, Esc klaar)"
+        }
+        "demo: writing edits to TASKS.toml is disabled" => {
+            "demo: wijzigingen naar TASKS.toml schrijven is uitgeschakeld"
+        }
+        "the file uses fewer than two priorities" => {
+            "het bestand gebruikt minder dan twee prioriteiten"
+        }
+        "focus off" => "focus uit",
+        "no profile" => "geen profiel",
+        "no profiles configured (see [profiles] in the config)" => {
+            "geen profielen ingesteld (zie [profiles] in de config)"
+        }
+        "focus cleared to show the task" => "focus opgeheven om de taak te tonen",
+        "filter cleared to show the task" => "filter gewist om de taak te tonen",
+        "no automatic fix for this entry" => "geen automatisch herstel voor deze regel",
+        "staged edits discarded" => "klaargezette wijzigingen weggegooid",
+        "reordering works in file order (o)" => "herschikken werkt in bestandsvolgorde (o)",
+        "no other projects configured (see [projects] in the config)" => {
+            "geen andere projecten ingesteld (zie [projects] in de config)"
+        }
+        "not in safe mode: every state file loaded" => {
+            "geen veilige modus: alle statusbestanden zijn geladen"
+        }
+        "SAFE MODE (!)" => "VEILIGE MODUS (!)",
+        "no spec drift: done tasks match the current spec" => {
+            "geen spec-afwijking: klare taken passen bij de huidige spec"
+        }
+        "no redundant dependencies" => "geen overbodige afhankelijkheden",
+        "only alerts can be dismissed" => "alleen meldingen kunnen genegeerd worden",
+        "alert shown again" => "melding weer getoond",
+        "alert dismissed until its condition changes (d again brings it back)" => {
+            "melding genegeerd tot de oorzaak verandert (d opnieuw haalt haar terug)"
+        }
+        "no suggested dependencies for this task" => "geen voorgestelde afhankelijkheden voor deze taak",
+        "no similar id to point it at" => "geen vergelijkbaar id om naar te wijzen",
+        "sound muted" => "geluid gedempt",
+        "an earlier task has the same id; rename this one first (p)" => {
+            "een eerdere taak heeft hetzelfde id; hernoem deze eerst (p)"
+        }
+        "reset failed: {}" => "opzijzetten mislukt: {}",
+        "moved {} to {}" => "{} verplaatst naar {}",
+        "exported {} tasks as {} to {}" => "{} taken als {} geëxporteerd naar {}",
+        "export failed: {}" => "exporteren mislukt: {}",
+        "staged edits dropped: {}" => "klaargezette wijzigingen vervallen: {}",
+        "staged edit dropped ({}): {}" => "klaargezette wijziging vervallen ({}): {}",
+        "{} staged edits dropped, e.g. {}: {}" => {
+            "{} klaargezette wijzigingen vervallen, bv. {}: {}"
+        }
+        "{} has no links" => "{} heeft geen koppelingen",
+        "{} has {} link(s), not {}" => "{} heeft {} koppeling(en), niet {}",
+        "opening {}" => "{} openen",
+        "opening {}: {}" => "{} openen: {}",
+        "no task was finished in the last {} days" => {
+            "er is de afgelopen {} dagen geen taak afgerond"
+        }
+        "focus session on {} stopped (under a minute, not logged)" => {
+            "focussessie op {} gestopt (korter dan een minuut, niet vastgelegd)"
+        }
+        "focus session on {} stopped ({} min logged)" => {
+            "focussessie op {} gestopt ({} min vastgelegd)"
+        }
+        "focus session on {}: {}" => "focussessie op {}: {}",
+        "focus session on {} done ({} min logged)" => {
+            "focussessie op {} klaar ({} min vastgelegd)"
+        }
+        "staged {} (w review, W write)" => "klaargezet: {} (w bekijken, W schrijven)",
+        "wrote {} staged edits" => "{} klaargezette wijzigingen geschreven",
+        "write failed: {}" => "schrijven mislukt: {}",
+        "the reload left {} dependencies on missing tasks (p lists them)" => {
+            "na het herladen wijzen {} afhankelijkheden naar ontbrekende taken (p toont ze)"
+        }
+        "wrote {} dependency fixes" => "{} herstellingen van afhankelijkheden geschreven",
+        "staged {} dependency removals (w review, W write)" => {
+            "{} verwijderingen van afhankelijkheden klaargezet (w bekijken, W schrijven)"
+        }
+        "find: {}  (' next, Esc done)" => "zoeken: {}  (' volgende, Esc klaar)",
+        "find: {}  (no task starts with it)" => "zoeken: {}  (geen taak begint zo)",
+        "profile {}" => "profiel {}",
+        "sorted by {} {}" => "gesorteerd op {} {}",
+        "sorted in file order" => "gesorteerd in bestandsvolgorde",
+        "sorted by risk score (? shows the formula)" => {
+            "gesorteerd op risicoscore (? toont de formule)"
+        }
+        "no task with id {}" => "geen taak met id {}",
+        "at the start of the trail" => "aan het begin van het spoor",
+        "at the end of the trail" => "aan het einde van het spoor",
+        "{} is already at the edge of its priority group" => {
+            "{} staat al aan de rand van haar prioriteitsgroep"
+        }
+        "defer: {}" => "uitstellen: {}",
+        "{} staged edits: write (W) or discard them before switching projects" => {
+            "{} klaargezette wijzigingen: schrijf (W) of gooi ze weg voor je van project wisselt"
+        }
+        "{} staged edits are not written: W writes them, q again quits" => {
+            "{} klaargezette wijzigingen zijn niet geschreven: W schrijft ze, nogmaals q sluit af"
+        }
+        "{} links to no other task" => "{} verwijst naar geen andere taak",
+        "feed at http://{}/feed.atom" => "feed op http://{}/feed.atom",
+        "sound: {}" => "geluid: {}",
+        "editing the config: {}" => "config bewerken: {}",
+        "the file uses no {} values yet" => "het bestand gebruikt nog geen {}-waarden",
+        "the title cannot be empty" => "de titel mag niet leeg zijn",
+        "{} is not a number of days (e.g. 2 or 0.5)" => {
+            "{} is geen aantal dagen (bv. 2 of 0.5)"
+        }
+        "no {} to choose" => "geen {} om uit te kiezen",
+        // Command palette.
+        "command palette: every action by name, typing searches, Enter runs it" => {
+            "opdrachtenpalet: elke actie op naam, typen zoekt, Enter voert uit"
+        }
+        "commands" => "opdrachten",
+        "Commands" => "Opdrachten",
+        "type to search  ↑/↓ select  Enter run  Esc close" => {
+            "typ om te zoeken  ↑/↓ kiezen  Enter uitvoeren  Esc sluiten"
+        }
+        "jump to a task id" => "spring naar een taak-id",
+        "find a task as you type" => "taak zoeken tijdens het typen",
+        "back through recently viewed tasks" => "terug door recent bekeken taken",
+        "forward through recently viewed tasks" => "vooruit door recent bekeken taken",
+        "open the selected task's first link" => "eerste link van de geselecteerde taak openen",
+        "tasks linked to the selected one" => "taken gekoppeld aan de geselecteerde",
+        "filter the table" => "de tabel filteren",
+        "focus on the selected task's component" => {
+            "focus op de component van de geselecteerde taak"
+        }
+        "show or hide the sidebar" => "zijbalk tonen of verbergen",
+        "sort by risk score" => "sorteren op risicoscore",
+        "pick a column to sort by" => "een kolom kiezen om op te sorteren",
+        "workspace overview" => "werkruimteoverzicht",
+        "stage the next status" => "volgende status klaarzetten",
+        "stage the next priority" => "volgende prioriteit klaarzetten",
+        "edit a field of the selected task" => "een veld van de geselecteerde taak bewerken",
+        "defer the selected task" => "de geselecteerde taak uitstellen",
+        "redundant dependencies" => "overbodige afhankelijkheden",
+        "add the suggested dependency" => "de voorgestelde afhankelijkheid toevoegen",
+        "review the staged edits" => "klaargezette wijzigingen bekijken",
+        "annotate the selected task" => "de geselecteerde taak annoteren",
+        "comment on the selected task" => "opmerking bij de geselecteerde taak",
+        "start or stop a focus session" => "focussessie starten of stoppen",
+        "problems panel" => "problemenpaneel",
+        "stats panel" => "statistiekpaneel",
+        "component dependency matrix" => "afhankelijkheidsmatrix van componenten",
+        "spec drift panel" => "spec-afwijkingspaneel",
+        "review queue" => "reviewwachtrij",
+        "export the table" => "de tabel exporteren",
+        "view the large change" => "de grote wijziging bekijken",
+        "accept the large change" => "de grote wijziging accepteren",
+        "safe mode panel" => "paneel veilige modus",
+        "watch log" => "bewakingslog",
+        "reload at once, skipping the debounce" => "meteen herladen, zonder debounce",
+        "pause or resume auto-refresh" => "automatisch verversen pauzeren of hervatten",
+        "auto-refresh paused; file changes still reload" => {
+            "automatisch verversen gepauzeerd; bestandswijzigingen herladen nog wel"
+        }
+        "auto-refresh resumed" => "automatisch verversen hervat",
+        "help: every key" => "hulp: alle toetsen",
+        "next view profile" => "volgend weergaveprofiel",
+        "progress by task count or estimate" => "voortgang op aantal taken of schatting",
+        "compact details" => "compacte details",
+        "collapse or expand the header" => "de kop in- of uitklappen",
+        "show or hide the deferred tasks" => "uitgestelde taken tonen of verbergen",
+        "cycle the cue volume" => "volume van signalen wisselen",
+        "sort by" => "sorteren op",
+        "sort in file order" => "sorteren in bestandsvolgorde",
+        "view profile" => "weergaveprofiel",
+        "no view profile" => "geen weergaveprofiel",
+        "clear the filter" => "het filter wissen",
+        "edit the config ($EDITOR)" => "de config bewerken ($EDITOR)",
+        "rename the selected task's component" => "het component van de geselecteerde taak hernoemen",
+        "write (W) or discard (w, x) the staged edits before editing the config" => {
+            "schrijf (W) of gooi (w, x) de klaargezette wijzigingen weg voordat je de config bewerkt"
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,142 @@
+//! The Jira import file for `export --format jira`: CSV for Jira's CSV importer, one `Task`
+//! issue per task. The title is the summary, and the description is put together from the
+//! task's summary, deliverables and definition of done in Jira's wiki markup. Component and
+//! priority become labels, and `depends_on` entries between exported tasks become `Blocks`
+//! links; dependencies on anything else are listed in the description instead.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::tasks::{task_status, Task, TaskFile};
+
+/// Working hours in an estimated day, for `Original Estimate`, which Jira takes in seconds.
+pub const HOURS_PER_DAY: f64 = 8.0;
+
+/// Columns before the repeated `Labels` and link ones.
+const COLUMNS: [&str; 6] = [
+    "Issue Id",
+    "Issue Type",
+    "Summary",
+    "Description",
+    "Status",
+    "Original Estimate",
+];
+/// The importer reads the `Issue Id` in this column as an issue that blocks the row's.
+const BLOCKED_BY: &str = "Inward issue link (Blocks)";
+
+/// Writes the tasks as CSV. Issue ids number the tasks from 1 in file order; only the
+/// importer uses them, to resolve the links. A repeated task id links to its first task.
+pub fn write_csv(out: &mut impl Write, file: &TaskFile) -> Result<()> {
+    let mut number: HashMap<&str, usize> = HashMap::new();
+    for (i, t) in file.tasks.iter().enumerate() {
+        number.entry(t.id.as_str()).or_insert(i + 1);
+    }
+    // Per task: its labels, the issue ids it is blocked by, and the dependencies left over.
+    let rows: Vec<(Vec<String>, Vec<String>, Vec<&str>)> = file
+        .tasks
+        .iter()
+        .map(|t| {
+            let deps = t.depends_on.iter().map(|d| d.id.as_str());
+            let links = deps
+                .clone()
+                .filter_map(|id| number.get(id))
+                .map(usize::to_string);
+            let outside = deps.filter(|id| !number.contains_key(id)).collect();
+            (labels(t), links.collect(), outside)
+        })
+        .collect();
+    let label_columns = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+    let link_columns = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+
+    let mut header: Vec<&str> = COLUMNS.to_vec();
+    header.extend(std::iter::repeat_n("Labels", label_columns));
+    header.extend(std::iter::repeat_n(BLOCKED_BY, link_columns));
+    write_row(out, header)?;
+    for (i, (t, (labels, links, outside))) in file.tasks.iter().zip(rows).enumerate() {
+        let estimate = t
+            .estimate_days
+            .map(|days| ((days * HOURS_PER_DAY * 3600.0).round() as u64).to_string())
+            .unwrap_or_default();
+        let mut row = vec![
+            (i + 1).to_string(),
+            "Task".to_string(),
+            t.title.clone(),
+            description(t, &outside),
+            jira_status(&task_status(t)).to_string(),
+            estimate,
+        ];
+        row.extend(padded(labels, label_columns));
+        row.extend(padded(links, link_columns));
+        write_row(out, row)?;
+    }
+    Ok(())
+}
+
+/// The component and the priority, as labels: Jira's have no spaces.
+fn labels(t: &Task) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for value in [&t.component, &t.priority].into_iter().flatten() {
+        let label = value.split_whitespace().collect::<Vec<_>>().join("-");
+        if !label.is_empty() && !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// Jira's default workflow status for a task's status; anything not started is "To Do".
+fn jira_status(status: &str) -> &'static str {
+    match status {
+        "done" => "Done",
+        "in_progress" => "In Progress",
+        _ => "To Do",
+    }
+}
+
+/// The summary, deliverables and definition of done, then what the task waits on outside the
+/// import and the id it has in the tasks file.
+fn description(t: &Task, outside: &[&str]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(summary) = t.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        parts.push(summary.trim().to_string());
+    }
+    for (heading, items) in [
+        ("Deliverables", &t.deliverables),
+        ("Definition of done", &t.dod),
+    ] {
+        if !items.is_empty() {
+            let list: Vec<String> = items.iter().map(|item| format!("* {item}")).collect();
+            parts.push(format!("h3. {heading}\n{}", list.join("\n")));
+        }
+    }
+    if !outside.is_empty() {
+        parts.push(format!(
+            "Depends on (not in this import): {}",
+            outside.join(", ")
+        ));
+    }
+    parts.push(format!("Task id: {}", t.id));
+    parts.join("\n\n")
+}
+
+fn padded(mut values: Vec<String>, len: usize) -> Vec<String> {
+    values.resize(len, String::new());
+    values
+}
+
+fn write_row<S: AsRef<str>>(out: &mut impl Write, fields: Vec<S>) -> Result<()> {
+    let fields: Vec<String> = fields.iter().map(|f| csv_field(f.as_ref())).collect();
+    writeln!(out, "{}", fields.join(","))?;
+    Ok(())
+}
+
+/// Quoted when it holds a comma, a quote or a line break, with quotes doubled.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
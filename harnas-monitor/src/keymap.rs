@@ -0,0 +1,441 @@
+//! Every key binding as one table, grouped by the view it works in. The help modal (`?`) is
+//! generated from it, so a binding added here shows up there. `App::handle_key` does the
+//! dispatching; a test in `app` checks that the dashboard binds exactly the keys listed here.
+
+use crate::config::Profile;
+use crate::keys::KeyCode;
+use crate::risk::RISK_FORMULA;
+
+use KeyCode::{
+    Backspace, Char, Down, End, Enter, Esc, Home, Left, PageDown, PageUp, Right, Tab, Up, F,
+};
+use KeyGroup::*;
+
+/// Where a binding works. A profile's `keys` remap the `is_dashboard` groups only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyGroup {
+    Navigation,
+    Edits,
+    Panels,
+    Display,
+    Sidebar,
+    Header,
+    Problems,
+    Staged,
+    Replan,
+    Prune,
+    Triage,
+    Review,
+    Help,
+    Demo,
+}
+
+impl KeyGroup {
+    /// The English heading, the key for its translation.
+    pub fn title(self) -> &'static str {
+        match self {
+            KeyGroup::Navigation => "Moving around",
+            KeyGroup::Edits => "Editing (staged until W)",
+            KeyGroup::Panels => "Panels",
+            KeyGroup::Display => "Display",
+            KeyGroup::Sidebar => "Sidebar (Tab)",
+            KeyGroup::Header => "Table header (H)",
+            KeyGroup::Problems => "Problems panel (p)",
+            KeyGroup::Staged => "Staged edits (w)",
+            KeyGroup::Replan => "Replan (R)",
+            KeyGroup::Prune => "Redundant dependencies (X)",
+            KeyGroup::Triage => "Missing dependencies (after a reload)",
+            KeyGroup::Review => "Review queue (Q)",
+            KeyGroup::Help => "This help (?)",
+            KeyGroup::Demo => "Demo (--demo)",
+        }
+    }
+
+    /// Outside modals, where a profile's key remapping applies.
+    pub fn is_dashboard(self) -> bool {
+        matches!(
+            self,
+            KeyGroup::Navigation | KeyGroup::Edits | KeyGroup::Panels | KeyGroup::Display
+        )
+    }
+}
+
+pub struct Binding {
+    pub group: KeyGroup,
+    /// As the help shows them, e.g. `e/P`.
+    pub keys: &'static str,
+    /// The keys themselves, for matching a profile's remapping; empty for chords like `Alt-↑`.
+    pub codes: &'static [KeyCode],
+    /// English, the key for its translation.
+    pub action: &'static str,
+    /// A second line shown under the action, untranslated.
+    pub detail: Option<&'static str>,
+}
+
+const fn bind(
+    group: KeyGroup,
+    keys: &'static str,
+    codes: &'static [KeyCode],
+    action: &'static str,
+) -> Binding {
+    Binding {
+        group,
+        keys,
+        codes,
+        action,
+        detail: None,
+    }
+}
+
+pub const BINDINGS: &[Binding] = &[
+    bind(Navigation, "↑/↓", &[Up, Down], "select task"),
+    bind(Navigation, "Home/End", &[Home, End], "first / last task"),
+    bind(Navigation, "PgUp/PgDn", &[PageUp, PageDown], "scroll details pane"),
+    bind(Navigation, ":", &[Char(':')], "jump to task id"),
+    bind(
+        Navigation,
+        "'",
+        &[Char('\'')],
+        "find as you type: the next task whose id or title starts with it (' again: next); a key with no binding of its own starts it too",
+    ),
+    bind(
+        Navigation,
+        "[/]",
+        &[Char('['), Char(']')],
+        "back / forward through recently viewed tasks (breadcrumbs)",
+    ),
+    bind(
+        Navigation,
+        "g",
+        &[Char('g')],
+        "tasks linked by depends_on, related_to, duplicates, supersedes",
+    ),
+    bind(
+        Navigation,
+        "1-9/O",
+        &[
+            Char('1'),
+            Char('2'),
+            Char('3'),
+            Char('4'),
+            Char('5'),
+            Char('6'),
+            Char('7'),
+            Char('8'),
+            Char('9'),
+            Char('O'),
+        ],
+        "open the selected task's numbered link (O: the first) in the browser",
+    ),
+    bind(
+        Navigation,
+        "/",
+        &[Char('/')],
+        "filter tasks (status:blocked comp:api, OR, -negate, has:field)",
+    ),
+    bind(
+        Navigation,
+        "f",
+        &[Char('f')],
+        "focus on the selected task's component / leave focus mode",
+    ),
+    bind(
+        Navigation,
+        "b/Tab",
+        &[Char('b'), Tab],
+        "show/hide the sidebar / move between sidebar and table",
+    ),
+    bind(
+        Navigation,
+        "Tab",
+        &[Tab],
+        "on a task with dependencies: their status at a glance (Tab again: sidebar)",
+    ),
+    bind(
+        Navigation,
+        "H",
+        &[Char('H')],
+        "table header: ←/→ pick a column, Enter sorts by it (again reverses)",
+    ),
+    Binding {
+        detail: Some(RISK_FORMULA),
+        ..bind(
+            Navigation,
+            "o",
+            &[Char('o')],
+            "sort by risk score or file order; the score is",
+        )
+    },
+    bind(
+        Navigation,
+        "0",
+        &[Char('0')],
+        "workspace overview: every configured project; Enter opens one",
+    ),
+    bind(
+        Edits,
+        "e/P",
+        &[Char('e'), Char('P')],
+        "stage the next status / priority of the selected task",
+    ),
+    bind(
+        Edits,
+        "E",
+        &[Char('E')],
+        "edit a field: title, priority, component or estimate_days",
+    ),
+    bind(
+        Edits,
+        "L",
+        &[Char('L')],
+        "defer the selected task until a day; on a deferred task, bring it back",
+    ),
+    bind(
+        Edits,
+        "Alt-↑/↓",
+        &[],
+        "move task within its priority group (stages order)",
+    ),
+    bind(
+        Edits,
+        "X",
+        &[Char('X')],
+        "redundant dependencies (A→B→C and A→C): Space picks, Enter stages removal",
+    ),
+    bind(
+        Edits,
+        "+",
+        &[Char('+')],
+        "add the dependency the details pane suggests from shared spec_refs",
+    ),
+    bind(
+        Edits,
+        "w/W",
+        &[Char('w'), Char('W')],
+        "review staged edits (u unstage last, x discard) / write them",
+    ),
+    bind(
+        Edits,
+        "n",
+        &[Char('n')],
+        "annotate selected task (TASKS.notes.toml)",
+    ),
+    bind(
+        Edits,
+        "C",
+        &[Char('C')],
+        "comment on the selected task, or reply to one of its comments",
+    ),
+    bind(
+        Edits,
+        "t",
+        &[Char('t')],
+        "start a 25-minute focus session on the selected task, or stop it",
+    ),
+    bind(
+        Panels,
+        "p",
+        &[Char('p')],
+        "problems panel (Enter jumps to the task, f previews a fix, d dismisses an alert)",
+    ),
+    bind(Panels, "c", &[Char('c')], "spec coverage panel"),
+    bind(
+        Panels,
+        "s",
+        &[Char('s')],
+        "stats panel (counts, estimates, cost when day_rate is set)",
+    ),
+    bind(
+        Panels,
+        "m",
+        &[Char('m')],
+        "component dependency matrix (Enter filters to the row's component)",
+    ),
+    bind(
+        Panels,
+        "D",
+        &[Char('D')],
+        "spec drift: done tasks whose spec sections changed since (Enter jumps, d dismisses)",
+    ),
+    bind(
+        Panels,
+        "Q",
+        &[Char('Q')],
+        "review queue: tasks done lately, with their DoD items to verify",
+    ),
+    bind(
+        Panels,
+        "R",
+        &[Char('R')],
+        "replan: what fits before a deadline, what slips, what to cut",
+    ),
+    bind(
+        Panels,
+        "x",
+        &[Char('x')],
+        "export the table as shown: json, quickfix, dot or mermaid",
+    ),
+    bind(
+        Panels,
+        "d/a",
+        &[Char('d'), Char('a')],
+        "view / accept a large change (only while its banner is shown)",
+    ),
+    bind(
+        Panels,
+        "!",
+        &[Char('!')],
+        "safe mode: state files that failed to load (r moves one aside)",
+    ),
+    bind(
+        Panels,
+        "F12",
+        &[F(12)],
+        "watch log: file events, debounce windows, load attempts (debug)",
+    ),
+    bind(
+        Panels,
+        "Ctrl-P",
+        &[],
+        "command palette: every action by name, typing searches, Enter runs it",
+    ),
+    bind(Panels, "?", &[Char('?')], "toggle this help"),
+    bind(
+        Display,
+        "V",
+        &[Char('V')],
+        "next view profile from the config (columns, theme, filter, sort, keys)",
+    ),
+    bind(
+        Display,
+        "%",
+        &[Char('%')],
+        "progress gauge by task count or by estimate_days",
+    ),
+    bind(
+        Display,
+        "z",
+        &[Char('z')],
+        "compact details: only the first paragraph of long summaries and notes",
+    ),
+    bind(
+        Display,
+        "Z",
+        &[Char('Z')],
+        "collapse the header to one summary line, or expand it",
+    ),
+    bind(
+        Display,
+        "l",
+        &[Char('l')],
+        "show/hide the deferred tasks at the bottom of the table",
+    ),
+    bind(
+        Display,
+        "v",
+        &[Char('v')],
+        "cycle cue volume (100%, 50%, 25%, muted)",
+    ),
+    bind(Display, "r", &[Char('r')], "reload now"),
+    bind(
+        Display,
+        "F5",
+        &[F(5)],
+        "reload at once, without waiting for the debounce",
+    ),
+    bind(Display, "A", &[Char('A')], "pause/resume the periodic reload"),
+    bind(Display, "q", &[Char('q')], "quit"),
+    bind(Sidebar, "↑/↓ Home/End", &[], "pick an entry"),
+    bind(
+        Sidebar,
+        "Enter",
+        &[],
+        "show that component or status; again shows everything",
+    ),
+    bind(Sidebar, "Esc", &[], "back to the table"),
+    bind(Header, "←/→", &[], "pick a column"),
+    bind(Header, "Enter", &[], "sort by it; again reverses"),
+    bind(Header, "Esc/H", &[], "back to the rows"),
+    bind(Problems, "↑/↓", &[], "pick an entry"),
+    bind(Problems, "Enter", &[], "jump to its task"),
+    bind(
+        Problems,
+        "f",
+        &[],
+        "preview its fix; Enter or y stages it, Esc goes back",
+    ),
+    bind(Staged, "W/Enter", &[], "write the staged edits"),
+    bind(Staged, "u", &[], "unstage the last edit"),
+    bind(Staged, "x", &[], "discard every staged edit"),
+    bind(Replan, "←/→", &[], "deadline a day earlier / later"),
+    bind(Replan, "PgUp/PgDn", &[], "deadline a week earlier / later"),
+    bind(Replan, "+/-", &[], "one worker more / fewer"),
+    bind(Prune, "Space", &[], "pick or unpick a dependency"),
+    bind(Prune, "a", &[], "pick all, or none"),
+    bind(
+        Prune,
+        "Enter",
+        &[],
+        "stage the picked removals (or the selected one)",
+    ),
+    bind(Triage, "d", &[], "remove the dependency"),
+    bind(Triage, "r", &[], "point it at the suggested id"),
+    bind(Triage, "e", &[], "keep it as an external dependency"),
+    bind(Triage, "l", &[], "leave it as it is"),
+    bind(Triage, "Enter", &[], "write the chosen fixes"),
+    bind(Review, "↑/↓", &[], "pick a task or DoD item"),
+    bind(Review, "Space", &[], "mark the DoD item verified, or not"),
+    bind(Review, "Enter", &[], "jump to the task"),
+    bind(Help, "type", &[], "search keys and descriptions"),
+    bind(Help, "↑/↓ PgUp/PgDn", &[], "scroll"),
+    bind(Help, "Esc", &[], "clear the search, then close"),
+    bind(
+        Demo,
+        "t/T",
+        &[],
+        "next/previous tour tip (--demo only; Esc hides)",
+    ),
+];
+
+/// The keys a profile remaps onto `binding`, and whether the binding's own keys are all
+/// remapped away (so they no longer do what the table says).
+pub fn remapped(binding: &Binding, profile: Option<&Profile>) -> (Vec<KeyCode>, bool) {
+    let Some(profile) = profile.filter(|_| binding.group.is_dashboard()) else {
+        return (Vec::new(), false);
+    };
+    let mut extra: Vec<KeyCode> = profile
+        .keys
+        .iter()
+        .filter(|(from, to)| binding.codes.contains(to) && !binding.codes.contains(from))
+        .map(|(from, _)| *from)
+        .collect();
+    extra.sort_by_key(|k| key_name(*k));
+    let shadowed = !binding.codes.is_empty()
+        && binding
+            .codes
+            .iter()
+            .all(|c| profile.keys.get(c).is_some_and(|to| to != c));
+    (extra, shadowed)
+}
+
+/// A key as the help shows it; the inverse of the config's key names.
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        Char(' ') => "Space".to_string(),
+        Char(c) => c.to_string(),
+        Up => "↑".to_string(),
+        Down => "↓".to_string(),
+        Left => "←".to_string(),
+        Right => "→".to_string(),
+        Enter => "Enter".to_string(),
+        Esc => "Esc".to_string(),
+        Tab => "Tab".to_string(),
+        Home => "Home".to_string(),
+        End => "End".to_string(),
+        PageUp => "PgUp".to_string(),
+        PageDown => "PgDn".to_string(),
+        Backspace => "Backspace".to_string(),
+        F(n) => format!("F{n}"),
+        other => format!("{other:?}"),
+    }
+}
@@ -0,0 +1,119 @@
+//! Key events as the dashboard core sees them. `App`, the key map, the palette and profiles use
+//! these instead of crossterm's, so the core builds without a terminal backend (`tui` off);
+//! the terminal front end converts crossterm's events at the edge.
+
+use std::ops::BitOr;
+
+/// The keys the dashboard can bind; crossterm keys without a counterpart arrive as `Null`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KeyCode {
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Tab,
+    BackTab,
+    Delete,
+    Insert,
+    F(u8),
+    Char(char),
+    Null,
+    Esc,
+}
+
+/// Held modifiers, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(1);
+    pub const CONTROL: Self = Self(1 << 1);
+    pub const ALT: Self = Self(1 << 2);
+
+    /// Every modifier of `other` is held.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Any modifier of `other` is held.
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for KeyModifiers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// A key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyEvent {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<crossterm::event::KeyCode> for KeyCode {
+    fn from(code: crossterm::event::KeyCode) -> Self {
+        use crossterm::event::KeyCode as C;
+        match code {
+            C::Backspace => Self::Backspace,
+            C::Enter => Self::Enter,
+            C::Left => Self::Left,
+            C::Right => Self::Right,
+            C::Up => Self::Up,
+            C::Down => Self::Down,
+            C::Home => Self::Home,
+            C::End => Self::End,
+            C::PageUp => Self::PageUp,
+            C::PageDown => Self::PageDown,
+            C::Tab => Self::Tab,
+            C::BackTab => Self::BackTab,
+            C::Delete => Self::Delete,
+            C::Insert => Self::Insert,
+            C::F(n) => Self::F(n),
+            C::Char(c) => Self::Char(c),
+            C::Esc => Self::Esc,
+            _ => Self::Null,
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<crossterm::event::KeyModifiers> for KeyModifiers {
+    fn from(held: crossterm::event::KeyModifiers) -> Self {
+        use crossterm::event::KeyModifiers as M;
+        [
+            (M::SHIFT, Self::SHIFT),
+            (M::CONTROL, Self::CONTROL),
+            (M::ALT, Self::ALT),
+        ]
+        .into_iter()
+        .filter(|(m, _)| held.contains(*m))
+        .fold(Self::NONE, |all, (_, m)| all | m)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<crossterm::event::KeyEvent> for KeyEvent {
+    fn from(key: crossterm::event::KeyEvent) -> Self {
+        Self::new(key.code.into(), key.modifiers.into())
+    }
+}
@@ -0,0 +1,64 @@
+//! The dashboard's modules, shared by the `harnas-monitor` binary (`src/main.rs`) and the
+//! benchmarks in `benches/`. Built without the default `tui` feature, this is the core alone:
+//! no crossterm and no notify (see `keys` and `watch`).
+
+pub mod alerts;
+pub mod analysis;
+pub mod app;
+pub mod backup;
+pub mod beacon;
+pub mod browser;
+pub mod canonical;
+pub mod charts;
+pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod contributors;
+#[cfg(unix)]
+pub mod control;
+pub mod cost;
+pub mod coverage;
+pub mod demo;
+pub mod diff;
+pub mod discover;
+pub mod drift;
+pub mod export;
+pub mod feed;
+pub mod filter;
+pub mod gen;
+pub mod graph;
+#[cfg(feature = "graphics")]
+pub mod graphics;
+pub mod history;
+pub mod i18n;
+pub mod jira;
+pub mod keymap;
+pub mod keys;
+pub mod loader;
+pub mod matrix;
+pub mod merge;
+pub mod notes;
+pub mod palette;
+pub mod prune;
+pub mod report;
+pub mod review;
+pub mod risk;
+pub mod safe;
+pub mod schedule;
+pub mod schema;
+pub mod shutdown;
+pub mod sound;
+pub mod staging;
+pub mod store;
+pub mod suggest;
+pub mod targets;
+pub mod tasks;
+pub mod termcaps;
+pub mod timer;
+pub mod triage;
+pub mod ui;
+pub mod usage;
+pub mod watch;
+pub mod web;
+pub mod workspace;
+pub mod writer;
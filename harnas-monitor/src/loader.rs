@@ -0,0 +1,93 @@
+//! Loads the tasks file on a worker thread, so parsing a large file never stalls input handling.
+//! Requests that pile up while a load runs collapse into one, and only the result of the newest
+//! request is handed back.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use anyhow::Result;
+
+use crate::tasks::{reload_tasks, LoadedTasks};
+use crate::workspace::Workspace;
+
+/// Reads the tasks file, reusing `prev` for an incremental parse, and resolves cross-project
+/// dependencies against `projects`.
+pub fn load(
+    path: &Path,
+    prev: Option<&LoadedTasks>,
+    projects: &BTreeMap<String, PathBuf>,
+) -> Result<LoadedTasks> {
+    reload_tasks(path, prev, Workspace::load(projects))
+}
+
+struct Request {
+    generation: u64,
+    path: PathBuf,
+    projects: BTreeMap<String, PathBuf>,
+}
+
+struct Response {
+    generation: u64,
+    result: Result<LoadedTasks>,
+}
+
+pub struct Loader {
+    tx: Sender<Request>,
+    rx: Receiver<Response>,
+    generation: u64,
+}
+
+impl Loader {
+    pub fn spawn() -> Self {
+        let (tx, requests) = mpsc::channel::<Request>();
+        let (responses, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The worker's own copy of the last load, for incremental parses.
+            let mut prev: Option<LoadedTasks> = None;
+            while let Ok(mut req) = requests.recv() {
+                while let Ok(newer) = requests.try_recv() {
+                    req = newer;
+                }
+                let result = load(&req.path, prev.as_ref(), &req.projects);
+                if let Ok(next) = &result {
+                    prev = Some(next.clone());
+                }
+                let response = Response {
+                    generation: req.generation,
+                    result,
+                };
+                if responses.send(response).is_err() {
+                    break;
+                }
+            }
+        });
+        Loader {
+            tx,
+            rx,
+            generation: 0,
+        }
+    }
+
+    /// Queues a load, superseding any earlier one. False when the worker is gone.
+    pub fn request(&mut self, path: PathBuf, projects: BTreeMap<String, PathBuf>) -> bool {
+        self.generation += 1;
+        let req = Request {
+            generation: self.generation,
+            path,
+            projects,
+        };
+        self.tx.send(req).is_ok()
+    }
+
+    /// The result of the newest request once it is in; results of superseded ones are dropped.
+    pub fn poll(&mut self) -> Option<Result<LoadedTasks>> {
+        let mut latest = None;
+        while let Ok(res) = self.rx.try_recv() {
+            if res.generation == self.generation {
+                latest = Some(res.result);
+            }
+        }
+        latest
+    }
+}
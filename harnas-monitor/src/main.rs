@@ -1,231 +1,954 @@
-mod tasks;
-mod ui;
-
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::sync::mpsc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
-use anyhow::{Context, Result};
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use anyhow::{bail, Context, Result};
+use crossterm::event::{DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEventKind};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use crossterm::{execute, terminal};
-use notify::Watcher;
 
-use crate::tasks::{load_tasks, LoadedTasks};
-use crate::ui::{draw, Modal, UiTheme, ViewState};
+#[cfg(unix)]
+use harnas_monitor::control;
+use harnas_monitor::analysis::Severity;
+use harnas_monitor::app::App;
+use harnas_monitor::i18n::trf;
+use harnas_monitor::safe::{Failure, StateFile};
+use harnas_monitor::termcaps::{Caps, ColorDepth};
+use harnas_monitor::ui::{draw, draw_picker, UiTheme};
+use harnas_monitor::{
+    backup, canonical, cli, config, cost, discover, export, feed, gen, history, merge, notes, prune,
+    report, schema, shutdown, sound, staging, store, tasks, ui, usage, watch, workspace, writer,
+};
 
-const DEFAULT_TASKS_PATH: &str = "../2026-01-11-haakpatronenbuddy/TASKS.toml";
+fn main() -> ExitCode {
+    match run_main() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::from(cli::exit_code_for(&e))
+        }
+    }
+}
+
+fn run_main() -> Result<ExitCode> {
+    let args = cli::parse_args(std::env::args().skip(1))?;
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if args.demo {
+        run(Vec::new(), args)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if args.command == cli::Command::MergeDriver {
+        return run_merge_driver(&args.merge_inputs);
+    }
+    if args.command == cli::Command::Usage {
+        return run_usage(&args);
+    }
+    if args.command == cli::Command::Gen {
+        let plan = gen::generate(&gen_spec(&args));
+        std::io::stdout()
+            .lock()
+            .write_all(plan.as_bytes())
+            .context("writing output")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if args.command == cli::Command::Schema {
+        let text = serde_json::to_string_pretty(&schema::schema()).context("encoding schema")?;
+        println!("{text}");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let candidates = candidate_paths(&args)?;
+    match args.command {
+        cli::Command::Dashboard => run(candidates, args).map(|()| ExitCode::SUCCESS),
+        cli::Command::Export => {
+            run_export(&single_path(candidates)?, &args).map(|()| ExitCode::SUCCESS)
+        }
+        cli::Command::Stats if args.watch => run_stats_watch(&single_path(candidates)?, &args),
+        cli::Command::Lint | cli::Command::Stats => run_report(&single_path(candidates)?, &args),
+        cli::Command::Fmt => run_fmt(&single_path(candidates)?, &args),
+        cli::Command::Restore => run_restore(&single_path(candidates)?, &args),
+        cli::Command::PruneDeps => run_prune_deps(&single_path(candidates)?, &args),
+        cli::Command::RenameComponent => {
+            run_rename_component(&single_path(candidates)?, &args)
+        }
+        cli::Command::History => run_history_query(&single_path(candidates)?, &args),
+        cli::Command::Render => {
+            run_render(single_path(candidates)?, args).map(|()| ExitCode::SUCCESS)
+        }
+        cli::Command::MergeDriver
+        | cli::Command::Usage
+        | cli::Command::Gen
+        | cli::Command::Schema => {
+            unreachable!("handled before path discovery")
+        }
+    }
+}
 
-fn main() -> Result<()> {
-    let file_path = parse_args();
-    run(file_path)
+fn candidate_paths(args: &cli::Args) -> Result<Vec<PathBuf>> {
+    if let Some(p) = &args.path {
+        return Ok(vec![p.clone()]);
+    }
+    let cwd = std::env::current_dir().context("current directory")?;
+    if args.discover {
+        let root = discover::repo_root(&cwd).unwrap_or(cwd);
+        let found = discover::scan_repo(&root);
+        if found.is_empty() {
+            bail!("no {} found under {}", discover::TASKS_FILE_NAME, root.display());
+        }
+        return Ok(found);
+    }
+    let found = discover::find_tasks_files(&cwd);
+    if found.is_empty() {
+        bail!(
+            "no {} found in {}, its ancestors or sibling directories; pass a path\n{}",
+            discover::TASKS_FILE_NAME,
+            cwd.display(),
+            cli::USAGE
+        );
+    }
+    Ok(found)
 }
 
-fn parse_args() -> PathBuf {
-    let mut args = std::env::args().skip(1);
-    if let Some(p) = args.next() {
-        return PathBuf::from(p);
+/// Non-interactive commands cannot show the picker, so ambiguity is an error.
+fn single_path(mut candidates: Vec<PathBuf>) -> Result<PathBuf> {
+    if candidates.len() > 1 {
+        let list: Vec<String> = candidates.iter().map(|p| format!("  {}", p.display())).collect();
+        bail!("multiple tasks files found; pass one explicitly:\n{}", list.join("\n"));
     }
-    PathBuf::from(DEFAULT_TASKS_PATH)
+    Ok(candidates.remove(0))
 }
 
-fn run(file_path: PathBuf) -> Result<()> {
-    let canonical = std::fs::canonicalize(&file_path).unwrap_or(file_path);
+fn run_export(path: &std::path::Path, args: &cli::Args) -> Result<()> {
+    let default_format = if args.split { "markdown" } else { "json" };
+    let format = export::ExportFormat::parse(args.format.as_deref().unwrap_or(default_format))?;
+    let mut loaded = tasks::load_tasks(path)?;
+    let config = config::load_config(args.config.as_deref())?;
+    tasks::link_workspace(&mut loaded, workspace::Workspace::load(&config.projects));
+    if let Some(filter) = &args.filter {
+        let matching: Vec<usize> = (0..loaded.tasks.tasks.len())
+            .filter(|&i| filter.matches(&loaded.tasks.tasks[i], &config.owners))
+            .collect();
+        loaded = export::select_tasks(&loaded, &matching);
+    }
+    if let Some(dir) = &args.dir {
+        let written = export::write_split(dir, &loaded, format)?;
+        let mut out = headless_output(args);
+        writeln!(out, "wrote {} task files to {}", written.len(), dir.display())
+            .context("writing output")?;
+        return Ok(());
+    }
+    let cost = config
+        .day_rate
+        .map(|rate| cost::cost_rollup(&loaded.tasks, rate));
+    export::write_export(&mut headless_output(args), &loaded, format, cost.as_ref())
+}
 
+/// `lint` and `stats`: print a report and exit with `EXIT_PROBLEMS` when errors or warnings were
+/// found. Rules suppressed in `[meta.lint]` or the config's `[lint]` do not count.
+fn run_report(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let config = config::load_config(args.config.as_deref())?;
+    let loaded = load_checked(path, &config)?;
+    let mut out = headless_output(args);
+    match args.command {
+        cli::Command::Lint => report::write_lint(&mut out, &loaded)?,
+        _ => report::write_stats(&mut out, &loaded)?,
+    }
+    out.flush().context("writing output")?;
+    // Info-level problems are reported but do not fail the run.
+    Ok(if loaded.problems.iter().all(|p| p.severity() == Severity::Info) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(cli::EXIT_PROBLEMS)
+    })
+}
+
+/// The tasks file as the headless reports see it: linked to the workspace and checked against
+/// the config.
+fn load_checked(path: &std::path::Path, config: &config::Config) -> Result<tasks::LoadedTasks> {
+    let mut loaded = tasks::load_tasks(path)?;
+    tasks::link_workspace(&mut loaded, workspace::Workspace::load(&config.projects));
+    config.check(path, &mut loaded);
+    Ok(loaded)
+}
+
+/// `stats --watch`: the stats block, then another one under a timestamp each time the file's
+/// content changes, until interrupted. Events are debounced as in the dashboard
+/// (`[refresh] debounce`), and a load that fails is reported on stderr without stopping.
+fn run_stats_watch(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let config = config::load_config(args.config.as_deref())?;
+    let (watch_tx, watch_rx) = mpsc::channel::<watch::WatchEvent>();
+    let mut file_watch = watch::FileWatch::new(path, Vec::new(), watch_tx)?;
+    let mut out = headless_output(args);
+    // The content last printed, `None` after a failed load so the next good one prints again.
+    let mut printed = None;
+    let mut started = false;
+    let mut requested = Some(Instant::now());
+    loop {
+        match watch_rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(Ok(event)) if file_watch.is_relevant(&event) => {
+                requested = Some(Instant::now());
+            }
+            Ok(Ok(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Ok(Err(e)) => eprintln!("watcher error: {e}"),
+            Err(mpsc::RecvTimeoutError::Disconnected) => bail!("the file watcher stopped"),
+        }
+        if file_watch.poll_changed() {
+            requested.get_or_insert_with(Instant::now);
+        }
+        // The first block goes out at once; later ones wait for the writes to settle.
+        let due = requested.is_some_and(|at| {
+            !started || at.elapsed() >= config.refresh.debounce()
+        });
+        if !due {
+            continue;
+        }
+        requested = None;
+        started = true;
+        if let Err(e) = file_watch.revalidate() {
+            eprintln!("watcher error: {e:#}");
+        }
+        let loaded = match load_checked(path, &config) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                eprintln!("{}: {e:#}", humantime::format_rfc3339_seconds(SystemTime::now()));
+                printed = None;
+                continue;
+            }
+        };
+        if printed == Some(loaded.content_hash) {
+            continue;
+        }
+        writeln!(out, "{}", humantime::format_rfc3339_seconds(SystemTime::now()))
+            .context("writing output")?;
+        report::write_stats(&mut out, &loaded)?;
+        writeln!(out).context("writing output")?;
+        out.flush().context("writing output")?;
+        printed = Some(loaded.content_hash);
+    }
+}
+
+/// `fmt`: rewrite the file in the canonical layout, or with `--check` only report whether it
+/// already is (exiting with `EXIT_PROBLEMS` when not).
+fn run_fmt(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("reading {}", path.display()))?;
+    let formatted = canonical::canonical_format(&contents)
+        .with_context(|| format!("formatting {}", path.display()))?;
+    let mut out = headless_output(args);
+    if formatted == contents {
+        return Ok(ExitCode::SUCCESS);
+    }
+    if args.check {
+        writeln!(out, "{} is not canonically formatted", path.display())
+            .context("writing output")?;
+        return Ok(ExitCode::from(cli::EXIT_PROBLEMS));
+    }
+    let config = config::load_config(args.config.as_deref())?;
+    backup::take(path, config.backups())?;
+    writer::write_atomic(path, &formatted)?;
+    writeln!(out, "formatted {}", path.display()).context("writing output")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `prune-deps`: list redundant dependencies, or with `--write` drop them from the file.
+fn run_prune_deps(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let loaded = tasks::load_tasks(path)?;
+    let edges = prune::redundant_deps(&loaded.tasks);
+    let mut out = headless_output(args);
+    for e in &edges {
+        writeln!(out, "{}", e.describe()).context("writing output")?;
+    }
+    if edges.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !args.write {
+        return Ok(ExitCode::from(cli::EXIT_PROBLEMS));
+    }
+    let config = config::load_config(args.config.as_deref())?;
+    writer::edit_tasks_file(path, loaded.content_hash, config.backups(), |doc| {
+        edges
+            .iter()
+            .try_for_each(|e| writer::remove_dependency(doc, &e.task, &e.dep))
+    })?;
+    writeln!(out, "removed {} dependencies from {}", edges.len(), path.display())
+        .context("writing output")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `rename-component`: show the diff of moving every task of the old component to the new one,
+/// and with `--write` make it. `[meta.budgets]` follows; the config's keys are left alone.
+fn run_rename_component(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let [from, to] = [&args.rename[0], &args.rename[1]].map(|n| n.trim());
+    let loaded = tasks::load_tasks(path)?;
+    let mut doc: toml_edit::DocumentMut = loaded
+        .source
+        .parse()
+        .with_context(|| format!("parsing {}", path.display()))?;
+    let renamed = writer::rename_component(&mut doc, from, to)
+        .with_context(|| format!("renaming in {}", path.display()))?;
+    let mut out = headless_output(args);
+    for entry in staging::line_diff(&loaded.source, &doc.to_string()) {
+        let Some(d) = entry else {
+            writeln!(out, "      ⋮").context("writing output")?;
+            continue;
+        };
+        let sign = match d.kind {
+            staging::DiffKind::Same => ' ',
+            staging::DiffKind::Removed => '-',
+            staging::DiffKind::Added => '+',
+        };
+        writeln!(out, "{:>5} {sign} {}", d.line, d.text).context("writing output")?;
+    }
+    if !args.write {
+        return Ok(ExitCode::SUCCESS);
+    }
+    let config = config::load_config(args.config.as_deref())?;
+    writer::edit_tasks_file(path, loaded.content_hash, config.backups(), |doc| {
+        writer::rename_component(doc, from, to).map(|_| ())
+    })?;
+    writeln!(out, "renamed component {from} to {to} in {renamed} tasks of {}", path.display())
+        .context("writing output")?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `restore`: list the file's backups, newest first, or with `--backup <n>` put the n-th one
+/// back. The contents it replaces are backed up in turn, so a restore can be undone.
+fn run_restore(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let config = config::load_config(args.config.as_deref())?;
+    let backups = backup::list(path)?;
+    let mut out = headless_output(args);
+    let Some(n) = args.backup else {
+        if backups.is_empty() {
+            writeln!(out, "no backups of {} yet", path.display()).context("writing output")?;
+        }
+        for (i, b) in backups.iter().enumerate() {
+            let tasks = backup::task_count(b).map_or("?".to_string(), |n| n.to_string());
+            writeln!(out, "{:>3}  {}  {tasks:>4} tasks  {:>8} bytes", i + 1, b.taken, b.size)
+                .context("writing output")?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    };
+    let Some(chosen) = backups.get(n - 1) else {
+        bail!(
+            "--backup {n} names no backup of {} ({} kept; `restore` without it lists them)",
+            path.display(),
+            backups.len()
+        );
+    };
+    let saved = backup::restore(path, chosen, config.backups())?;
+    writeln!(out, "restored {} from the backup of {}", path.display(), chosen.taken)
+        .context("writing output")?;
+    if let Some(saved) = saved {
+        writeln!(out, "the replaced contents are in {}", saved.display())
+            .context("writing output")?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `usage`: the local usage statistics the dashboard recorded with `[usage] enabled`.
+fn run_usage(args: &cli::Args) -> Result<ExitCode> {
+    let config = config::load_config(args.config.as_deref())?;
+    let Some(path) = config.usage.path() else {
+        bail!("no place for usage statistics: set [usage] file in the config, or $HOME");
+    };
+    let mut out = headless_output(args);
+    match usage::load(&path)? {
+        Some(stats) => usage::write_report(&mut out, &path, &stats)?,
+        None => writeln!(out, "no usage statistics in {} yet", path.display())
+            .context("writing output")?,
+    }
+    if !config.usage.enabled {
+        writeln!(out, "recording is off; set [usage] enabled = true in the config to start")
+            .context("writing output")?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `history query`: the SQLite history's answer as a table.
+fn run_history_query(path: &std::path::Path, args: &cli::Args) -> Result<ExitCode> {
+    let query = args.query.as_deref().unwrap_or_default();
+    let table = store::query(&history::history_path(path), query)?;
+    store::write_table(&mut headless_output(args), &table)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `gen` options over `GenSpec::default`.
+fn gen_spec(args: &cli::Args) -> gen::GenSpec {
+    let default = gen::GenSpec::default();
+    gen::GenSpec {
+        tasks: args.tasks.unwrap_or(default.tasks),
+        max_deps: args.max_deps.unwrap_or(default.max_deps),
+        seed: args.seed.unwrap_or(default.seed),
+    }
+}
+
+/// `render`: the dashboard as it would look right after startup, drawn once to stdout as plain
+/// text. History and SLA alerts are updated like a dashboard load, so a cron job keeps them
+/// current.
+fn run_render(path: PathBuf, args: cli::Args) -> Result<()> {
+    // Surface a missing file as an I/O error; the dashboard would only show it in the header.
+    std::fs::metadata(&path).with_context(|| format!("reading {}", path.display()))?;
+    let mut app = App::new(std::path::absolute(&path).unwrap_or(path));
+    app.config = config::load_config(args.config.as_deref())?;
+    app.view.compact_details = app.config.details.compact;
+    app.view.compact_header = app.config.header.compact;
+    app.view.weighted_progress = app.config.progress.weighted;
+    // Plain text has no colours, so the screen is drawn as for a terminal without any.
+    app.set_caps(Caps {
+        colors: ColorDepth::Mono,
+        ..Caps::forced(args.ascii)
+    });
+    app.pending_select = args.select;
+    if args.deterministic {
+        app.set_deterministic();
+    }
+    app.set_focus(args.component);
+    app.apply_language();
+    select_profile(&mut app, args.profile)?;
+    app.maybe_reload(Duration::ZERO, Duration::MAX);
+    if let (None, Some(e)) = (&app.loaded, &app.load_error) {
+        bail!("{}", e.message);
+    }
+    let width = args.width.unwrap_or(cli::RENDER_WIDTH);
+    let height = args.height.unwrap_or(cli::RENDER_HEIGHT);
+    let theme = ui::profile_theme(&app).unwrap_or_default();
+    let text = ui::render_plain(&theme, &app, width, height)?;
+    let mut out = std::io::stdout().lock();
+    out.write_all(text.as_bytes()).context("writing output")?;
+    out.flush().context("writing output")
+}
+
+/// `--profile`: an unknown name is a config error, listing the configured ones.
+fn select_profile(app: &mut App, name: Option<String>) -> Result<()> {
+    let Some(name) = name else {
+        return Ok(());
+    };
+    if !app.config.profiles.contains_key(&name) {
+        let known: Vec<&str> = app.config.profiles.keys().map(String::as_str).collect();
+        bail!(
+            "unknown profile {name:?} (configured: {})",
+            if known.is_empty() { "none".to_string() } else { known.join(", ") }
+        );
+    }
+    app.apply_profile(Some(name));
+    Ok(())
+}
+
+/// `merge-driver <base> <ours> <theirs>`: git's `%O %A %B`. The result replaces `ours`; any
+/// conflict is listed on stderr and reported to git with `EXIT_PROBLEMS`.
+fn run_merge_driver(inputs: &[PathBuf]) -> Result<ExitCode> {
+    let read = |p: &PathBuf| {
+        std::fs::read_to_string(p).with_context(|| format!("reading {}", p.display()))
+    };
+    let [base, ours, theirs] = inputs else {
+        bail!("merge-driver needs exactly three files\n{}", cli::USAGE);
+    };
+    let outcome = merge::merge_tasks(&read(base)?, &read(ours)?, &read(theirs)?)?;
+    writer::write_atomic(ours, &outcome.merged)?;
+    if outcome.conflicts.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    for c in &outcome.conflicts {
+        eprintln!("conflict: {c}");
+    }
+    Ok(ExitCode::from(cli::EXIT_PROBLEMS))
+}
+
+/// Stdout, or a sink under `--quiet`.
+fn headless_output(args: &cli::Args) -> Box<dyn Write> {
+    if args.quiet {
+        Box::new(std::io::sink())
+    } else {
+        Box::new(std::io::stdout().lock())
+    }
+}
+
+fn run(candidates: Vec<PathBuf>, args: cli::Args) -> Result<()> {
+    // Only the dashboard: the headless commands have nothing to restore or log.
+    shutdown::install()?;
     enable_raw_mode().context("enable raw mode")?;
     let mut stdout = std::io::stdout();
     execute!(stdout, terminal::EnterAlternateScreen).context("enter alt screen")?;
     execute!(stdout, crossterm::cursor::Hide).ok();
+    // Terminals without focus reporting ignore this; the dashboard then stays "focused".
+    execute!(stdout, EnableFocusChange).ok();
 
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend).context("create terminal")?;
     terminal.clear().ok();
 
-    let (watch_tx, watch_rx) = mpsc::channel::<notify::Result<notify::Event>>();
-    let mut watcher = notify::recommended_watcher(move |res| {
-        let _ = watch_tx.send(res);
-    })
-    .context("create file watcher")?;
-    watcher
-        .watch(&canonical, notify::RecursiveMode::NonRecursive)
-        .with_context(|| format!("watch {}", canonical.display()))?;
-
-    let theme = UiTheme::default();
-    let mut view = ViewState {
-        selected_idx: 0,
-        details_scroll: 0,
-        modal: None,
+    let caps = terminal_caps(&args);
+    let theme = UiTheme::for_caps(caps);
+    let result = (|| -> Result<()> {
+        if args.demo {
+            let plan = args.tasks.map(|_| gen::generate(&gen_spec(&args)));
+            // The demo loop draws its charts with characters.
+            let caps = Caps { images: None, ..caps };
+            return run_demo(&mut terminal, &theme, caps, plan, args.component, args.deterministic);
+        }
+        let cwd = std::env::current_dir().context("current directory")?;
+        let root = match args.discover {
+            true => discover::repo_root(&cwd).unwrap_or(cwd),
+            false => cwd,
+        };
+        let found: Vec<discover::Found> =
+            candidates.iter().map(|p| discover::Found::read(p, &root)).collect();
+        // Opening them all makes the others the workspace's projects, by directory.
+        let mut workspace = BTreeMap::new();
+        let file_path = if found.len() == 1 {
+            found[0].path.clone()
+        } else {
+            match pick_file(&mut terminal, &theme, caps, &found, args.discover)? {
+                Some(Pick::One(i)) => found[i].path.clone(),
+                Some(Pick::All) => {
+                    for f in &found {
+                        let path = std::path::absolute(&f.path).unwrap_or(f.path.clone());
+                        workspace.insert(f.name.clone(), path);
+                    }
+                    found[0].path.clone()
+                }
+                None => return Ok(()),
+            }
+        };
+        // Absolute but not canonical: a symlink the generator repoints is followed on every load.
+        let mut path = std::path::absolute(&file_path).unwrap_or(file_path);
+        let mut args = args;
+        let mut compact_header = None;
+        // The workspace overview (`0`) can hand over to another project's file.
+        while let Some(next) =
+            run_dashboard(&mut terminal, &theme, path, args.clone(), &workspace, &mut compact_header)?
+        {
+            path = next;
+            args.select = None;
+            args.component = None;
+            args.debug_watch = false;
+        }
+        Ok(())
+    })();
+
+    restore_terminal()?;
+    result
+}
+
+/// `--deterministic` does not guess from the environment, so snapshots stay the same anywhere.
+fn terminal_caps(args: &cli::Args) -> Caps {
+    if args.deterministic {
+        Caps::forced(args.ascii)
+    } else {
+        Caps::detect(args.ascii)
+    }
+}
+
+/// What the picker opens.
+enum Pick {
+    One(usize),
+    /// The first file, with the others as workspace projects (`--discover` only).
+    All,
+}
+
+/// Lets the user choose between several discovered tasks files. `None` means they quit.
+fn pick_file<B: ratatui::backend::Backend>(
+    terminal: &mut ratatui::Terminal<B>,
+    theme: &UiTheme,
+    caps: Caps,
+    candidates: &[discover::Found],
+    discover: bool,
+) -> Result<Option<Pick>> {
+    let mut selected = 0usize;
+    loop {
+        if shutdown::requested() {
+            return Ok(None);
+        }
+        draw_picker(terminal, theme, caps, candidates, selected, discover)?;
+        if !crossterm::event::poll(Duration::from_millis(250)).context("poll events")? {
+            continue;
+        }
+        if let Event::Key(key) = crossterm::event::read().context("read event")? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Down => selected = (selected + 1).min(candidates.len() - 1),
+                KeyCode::Enter => return Ok(Some(Pick::One(selected))),
+                KeyCode::Char('a') if discover => return Ok(Some(Pick::All)),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Runs the dashboard on `path` until it quits, or hands over to another file (returned).
+/// `compact_header` is the header's state (`Z`) from an earlier dashboard of this run, which
+/// wins over the config's, and is left as this one ends.
+fn run_dashboard<B: ratatui::backend::Backend>(
+    terminal: &mut ratatui::Terminal<B>,
+    theme: &UiTheme,
+    path: PathBuf,
+    args: cli::Args,
+    workspace: &BTreeMap<String, PathBuf>,
+    compact_header: &mut Option<bool>,
+) -> Result<Option<PathBuf>> {
+    // A broken config starts the dashboard in safe mode with the defaults instead.
+    let config_path = args.config.clone().or_else(config::default_config_path);
+    let (mut config, config_failure) = match config::load_config(args.config.as_deref()) {
+        Ok(config) => (config, None),
+        Err(e) => {
+            let failure = config_path
+                .as_deref()
+                .map(|p| Failure::new(StateFile::Config, p, format!("{e:#}")));
+            (config::Config::default(), failure)
+        }
     };
+    // Configured projects win over discovered ones of the same name or file.
+    for (name, file) in workspace {
+        if *file != path
+            && !config.projects.contains_key(name)
+            && !config.projects.values().any(|p| p == file)
+        {
+            config.projects.insert(name.clone(), file.clone());
+        }
+    }
+    let caps = terminal_caps(&args);
+    let (watch_tx, watch_rx) = mpsc::channel::<watch::WatchEvent>();
+    let mut watched = vec![notes::notes_path(&path)];
+    watched.extend(config.projects.values().cloned());
+    let mut file_watch = watch::FileWatch::new(&path, watched, watch_tx)?;
 
-    let mut loaded: Option<LoadedTasks> = None;
-    let mut last_error: Option<String> = None;
-    let mut last_content_hash: Option<u64> = None;
+    let mut app = App::new(path);
+    app.config_path = config_path;
+    app.watch_desc = Some(file_watch.describe());
+    if args.debug_watch {
+        app.view.modal = Some(ui::Modal::WatchLog { scroll: 0 });
+    }
+    app.pending_select = args.select;
+    if args.deterministic {
+        app.set_deterministic();
+    }
+    app.start_loader();
+    app.set_focus(args.component);
+    app.config = config;
+    app.view.compact_details = app.config.details.compact;
+    app.view.compact_header = compact_header.unwrap_or(app.config.header.compact);
+    app.view.weighted_progress = app.config.progress.weighted;
+    app.volume = app.config.sound.volume.unwrap_or(100);
+    app.refresh_paused = app.config.refresh.paused;
+    app.set_caps(caps);
+    app.apply_retention();
+    app.apply_language();
+    app.start_usage();
+    // The profile lives in the config; with the defaults there is none to pick.
+    if config_failure.is_none() {
+        select_profile(&mut app, args.profile)?;
+    }
+    app.check_state_files(config_failure.into_iter().collect());
+    let mut player = sound::Player::new(app.config.sound.clone());
+    app.status_msg = player.unavailable().map(str::to_string);
+
+    #[cfg(unix)]
+    let control = args.socket.as_deref().map(control::spawn).transpose()?;
+    #[cfg(not(unix))]
+    if args.socket.is_some() {
+        anyhow::bail!("--socket is only supported on Unix");
+    }
+    let feed = args
+        .serve
+        .as_deref()
+        .map(|addr| feed::serve(addr, &app.path))
+        .transpose()?;
+    if let (Some(server), None) = (&feed, &app.status_msg) {
+        app.status_msg = Some(trf(
+            app.lang,
+            "feed at http://{}/feed.atom",
+            &[&server.addr()],
+        ));
+    }
 
-    let mut reload_requested_at: Option<Instant> = Some(Instant::now());
-    let debounce = Duration::from_millis(200);
-    let auto_refresh = Duration::from_secs(60);
-    let mut last_load_attempt = Instant::now();
     let tick_rate = Duration::from_millis(120);
+    // While the terminal is unfocused the loop wakes rarely and only redraws after a load, a key
+    // or a resize, so a dashboard left on a second screen costs next to no CPU.
+    let unfocused_tick_rate = Duration::from_secs(1);
+    let mut focused = true;
+    let mut redraw = true;
     let mut last_tick = Instant::now();
+    let mut ticker_slot = None;
+    #[cfg(feature = "graphics")]
+    let mut images = app.caps.images.map(harnas_monitor::graphics::Images::new);
 
-    let result = (|| -> Result<()> {
-        loop {
-            drain_watcher(&watch_rx, &mut reload_requested_at, &mut last_error);
-
-            if reload_requested_at.is_none() && last_load_attempt.elapsed() >= auto_refresh {
-                reload_requested_at = Some(Instant::now());
-            }
-
-            if let Some(t0) = reload_requested_at {
-                if t0.elapsed() >= debounce {
-                    reload_requested_at = None;
-                    last_load_attempt = Instant::now();
-                    match load_tasks(&canonical) {
-                        Ok(next) => {
-                            if let Some(prev) = last_content_hash {
-                                if prev != next.content_hash {
-                                    chime();
-                                }
+    loop {
+        if shutdown::requested() {
+            // Out through the same path as `q`, so the focus session and usage are logged.
+            app.switch_to = None;
+            break;
+        }
+        if drain_watcher(&watch_rx, &file_watch, &mut app) {
+            redraw = true;
+        }
+        if file_watch.poll_changed() {
+            app.watch_log.push("poll", "metadata changed (mtime, size or symlink target)");
+            if app.reload_requested_at.is_none() {
+                app.reload_requested_at = Some(Instant::now());
+                app.watch_log.push("debounce", "started by poll");
+            }
+        }
+        #[cfg(unix)]
+        if let Some((_, requests)) = &control {
+            while let Ok(req) = requests.try_recv() {
+                control::dispatch(&mut app, req);
+                redraw = true;
+            }
+        }
+
+        let attempt_before = app.last_load_attempt;
+        let was_loading = app.loading.is_some();
+        let refresh = &app.config.refresh;
+        if let Some(cue) = app.maybe_reload(refresh.debounce(), refresh.auto()) {
+            if let Err(e) = player.play(cue, app.volume) {
+                app.status_msg = Some(trf(app.lang, "sound: {}", &[&format!("{e:#}")]));
+            }
+        }
+        if was_loading && app.loading.is_none() {
+            redraw = true;
+        }
+        app.measure_retained();
+        if let Some(cue) = app.tick_timer() {
+            if let Err(e) = player.play(cue, app.volume) {
+                app.status_msg = Some(trf(app.lang, "sound: {}", &[&format!("{e:#}")]));
+            }
+            redraw = true;
+        }
+        if app.last_load_attempt != attempt_before {
+            // The debounce expired: re-resolve the symlink in case the generator repointed it,
+            // and follow the spec file the plan names.
+            if let Some(spec) = app.full().and_then(|l| l.coverage.spec_path.clone()) {
+                file_watch.watch_also(spec);
+            }
+            match file_watch.revalidate() {
+                Ok(true) => {
+                    app.reload_requested_at = Some(Instant::now());
+                    app.watch_log.push("symlink", file_watch.describe());
+                }
+                Ok(false) => {}
+                Err(e) => app.set_watch_error(format!("{e:#}")),
+            }
+            app.watch_desc = Some(file_watch.describe());
+            redraw = true;
+        }
+
+        // The ticker keeps rotating on an unfocused wall display.
+        if app.ticker_slot() != ticker_slot {
+            ticker_slot = app.ticker_slot();
+            redraw = true;
+        }
+
+        if focused || redraw {
+            let themed = ui::profile_theme(&app);
+            draw(terminal, themed.as_ref().unwrap_or(theme), &app)?;
+            #[cfg(feature = "graphics")]
+            if let Some(images) = &mut images {
+                let slots = app.view.chart_slots.borrow().clone();
+                if images.changed(&slots) {
+                    terminal.clear().context("clear terminal")?;
+                    draw(terminal, themed.as_ref().unwrap_or(theme), &app)?;
+                    images.paint(&slots, &mut std::io::stdout())?;
+                }
+            }
+            redraw = false;
+        }
+
+        let tick_rate = if focused {
+            tick_rate
+        } else {
+            unfocused_tick_rate
+        };
+        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        if crossterm::event::poll(timeout).context("poll events")? {
+            match crossterm::event::read().context("read event")? {
+                Event::Key(key) => {
+                    if key.kind == KeyEventKind::Press && app.handle_key(key.into())? {
+                        break;
+                    }
+                    if std::mem::take(&mut app.edit_config) {
+                        // Reopening the dashboard loads the edited config, or starts in safe
+                        // mode when it no longer parses.
+                        let config_path = app.config_path.clone().unwrap_or_default();
+                        match edit_in_terminal(terminal, &config_path) {
+                            Ok(()) => {
+                                app.switch_to = Some(app.path.clone());
+                                break;
                             }
-                            last_content_hash = Some(next.content_hash);
-                            loaded = Some(next);
-                            last_error = None;
-                            if let Some(l) = &loaded {
-                                if !l.tasks.tasks.is_empty() {
-                                    view.selected_idx =
-                                        view.selected_idx.min(l.tasks.tasks.len() - 1);
-                                } else {
-                                    view.selected_idx = 0;
-                                }
+                            Err(e) => {
+                                app.status_msg = Some(trf(
+                                    app.lang,
+                                    "editing the config: {}",
+                                    &[&format!("{e:#}")],
+                                ))
                             }
                         }
-                        Err(e) => {
-                            last_error = Some(e.to_string());
-                        }
                     }
+                    app.record_view();
+                    redraw = true;
                 }
-            }
-
-            draw(
-                &mut terminal,
-                &theme,
-                &view,
-                loaded.as_ref(),
-                last_error.as_deref(),
-            )?;
-
-            let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-            if crossterm::event::poll(timeout).context("poll events")? {
-                if let Event::Key(key) = crossterm::event::read().context("read event")? {
-                    if key.kind == KeyEventKind::Press {
-                        if handle_key(
-                            key.code,
-                            &mut view,
-                            loaded.as_ref(),
-                            &mut reload_requested_at,
-                        )? {
-                            break;
-                        }
+                Event::FocusGained => focused = true,
+                Event::FocusLost => focused = false,
+                Event::Resize(..) => {
+                    relayout(terminal)?;
+                    app.view.pane_cache.borrow_mut().forget();
+                    #[cfg(feature = "graphics")]
+                    if let Some(images) = &mut images {
+                        images.forget();
                     }
+                    redraw = true;
                 }
+                _ => {}
             }
+        }
 
-            if last_tick.elapsed() >= tick_rate {
-                last_tick = Instant::now();
-            }
+        if last_tick.elapsed() >= tick_rate {
+            last_tick = Instant::now();
         }
+    }
 
-        Ok(())
-    })();
+    app.stop_timer();
+    *compact_header = Some(app.view.compact_header);
+    #[cfg(feature = "graphics")]
+    if let Some(images) = &mut images {
+        images.paint(&[], &mut std::io::stdout()).ok();
+    }
+    if let (Some(session), Some(path)) = (app.usage.take(), app.config.usage.path()) {
+        session.record(&path).context("recording usage statistics")?;
+    }
+    Ok(app.switch_to.take())
+}
 
-    restore_terminal()?;
-    result
+/// The dashboard over embedded sample data, or a generated `plan`: no watcher, no control
+/// socket, no config.
+fn run_demo<B: ratatui::backend::Backend>(
+    terminal: &mut ratatui::Terminal<B>,
+    theme: &UiTheme,
+    caps: Caps,
+    plan: Option<String>,
+    component: Option<String>,
+    deterministic: bool,
+) -> Result<()> {
+    let mut app = App::demo(plan);
+    app.set_caps(caps);
+    if deterministic {
+        app.set_deterministic();
+    }
+    app.apply_language();
+    app.set_focus(component);
+    let tick_rate = Duration::from_millis(120);
+    while !shutdown::requested() {
+        // Only `r` requests a reload; there is nothing to refresh automatically.
+        app.maybe_reload(Duration::ZERO, Duration::MAX);
+        draw(terminal, theme, &app)?;
+        if crossterm::event::poll(tick_rate).context("poll events")? {
+            match crossterm::event::read().context("read event")? {
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.handle_key(key.into())? => {
+                    return Ok(());
+                }
+                Event::Resize(..) => {
+                    relayout(terminal)?;
+                    app.view.pane_cache.borrow_mut().forget();
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// After a resize, lays out for the size the terminal has now rather than the one the event
+/// carried (a dragged window edge sends a burst of them), and repaints every cell, since a
+/// re-attached tmux or screen session may not have kept what was on screen.
+fn relayout<B: ratatui::backend::Backend>(terminal: &mut ratatui::Terminal<B>) -> Result<()> {
+    terminal.autoresize().context("resize terminal")?;
+    terminal.clear().context("clear terminal")
 }
 
 fn restore_terminal() -> Result<()> {
+    execute!(std::io::stdout(), DisableFocusChange).ok();
     disable_raw_mode().ok();
     execute!(std::io::stdout(), terminal::LeaveAlternateScreen).ok();
     execute!(std::io::stdout(), crossterm::cursor::Show).ok();
     Ok(())
 }
 
-fn chime() {
-    use std::io::Write;
-    let mut out = std::io::stdout();
-    let _ = out.write_all(b"\x07");
-    let _ = out.flush();
+/// Hands the terminal to `$VISUAL`, `$EDITOR` or `vi` on `path`, and takes it back after.
+fn edit_in_terminal<B: ratatui::backend::Backend>(
+    terminal: &mut ratatui::Terminal<B>,
+    path: &Path,
+) -> Result<()> {
+    restore_terminal()?;
+    let edited = run_editor(path);
+    enable_raw_mode().context("enable raw mode")?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, terminal::EnterAlternateScreen).context("enter alt screen")?;
+    execute!(stdout, crossterm::cursor::Hide, EnableFocusChange).ok();
+    terminal.clear().ok();
+    edited
+}
+
+fn run_editor(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    // The variable may carry arguments (`code --wait`), so the shell splits it.
+    let line = format!("{editor} {}", sound::shell_quote(&path.to_string_lossy()));
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let status = std::process::Command::new(shell)
+        .args([flag, &line])
+        .status()
+        .with_context(|| format!("running {editor:?}"))?;
+    if !status.success() {
+        bail!("{editor} exited with {status}");
+    }
+    Ok(())
 }
 
+/// Handles queued watcher events; every event lands in the watch log. Returns true when any
+/// arrived.
 fn drain_watcher(
-    watch_rx: &mpsc::Receiver<notify::Result<notify::Event>>,
-    reload_requested_at: &mut Option<Instant>,
-    last_error: &mut Option<String>,
-) {
+    watch_rx: &mpsc::Receiver<watch::WatchEvent>,
+    file_watch: &watch::FileWatch,
+    app: &mut App,
+) -> bool {
+    let mut any = false;
     loop {
         match watch_rx.try_recv() {
-            Ok(Ok(_event)) => {
-                *reload_requested_at = Some(Instant::now());
+            Ok(Ok(event)) if file_watch.is_relevant(&event) => {
+                app.watcher_resumed();
+                app.watch_log
+                    .push("event", watch::WatchLog::describe_event(&event));
+                let restarted = app.reload_requested_at.is_some();
+                app.reload_requested_at = Some(Instant::now());
+                app.watch_log.push(
+                    "debounce",
+                    if restarted { "restarted by event" } else { "started by event" },
+                );
             }
-            Ok(Err(e)) => {
-                *last_error = Some(format!("watcher error: {e}"));
+            Ok(Ok(event)) => {
+                app.watcher_resumed();
+                app.watch_log
+                    .push("ignored", watch::WatchLog::describe_event(&event));
             }
+            Ok(Err(e)) => app.set_watch_error(e.to_string()),
             Err(mpsc::TryRecvError::Empty) => break,
             Err(mpsc::TryRecvError::Disconnected) => break,
         }
+        any = true;
     }
-}
-
-fn handle_key(
-    code: KeyCode,
-    view: &mut ViewState,
-    loaded: Option<&LoadedTasks>,
-    reload_requested_at: &mut Option<Instant>,
-) -> Result<bool> {
-    match code {
-        KeyCode::Char('q') => return Ok(true),
-        KeyCode::Char('?') => {
-            view.modal = match view.modal {
-                Some(Modal::Help) => None,
-                _ => Some(Modal::Help),
-            };
-        }
-        KeyCode::Char('r') => {
-            *reload_requested_at = Some(Instant::now());
-        }
-        KeyCode::Up => {
-            view.details_scroll = 0;
-            view.selected_idx = view.selected_idx.saturating_sub(1);
-        }
-        KeyCode::Down => {
-            view.details_scroll = 0;
-            let max = loaded.map(|l| l.tasks.tasks.len()).unwrap_or(0);
-            if max > 0 {
-                view.selected_idx = (view.selected_idx + 1).min(max - 1);
-            }
-        }
-        KeyCode::PageUp => {
-            view.details_scroll = view.details_scroll.saturating_sub(4);
-        }
-        KeyCode::PageDown => {
-            view.details_scroll = view.details_scroll.saturating_add(4);
-        }
-        KeyCode::Home => {
-            view.details_scroll = 0;
-            view.selected_idx = 0;
-        }
-        KeyCode::End => {
-            view.details_scroll = 0;
-            let max = loaded.map(|l| l.tasks.tasks.len()).unwrap_or(0);
-            if max > 0 {
-                view.selected_idx = max - 1;
-            }
-        }
-        _ => {}
-    }
-
-    if view.modal.is_some() {
-        // Keep selection/scroll keys functioning behind the modal? For now, no.
-    }
-
-    Ok(false)
+    any
 }
@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+
+use crate::tasks::{component_key, status_map, task_status, unmet_deps, TaskFile};
+
+/// Unmet dependencies aggregated to component level. `counts[row][col]` is the number of
+/// not-yet-done dependencies that tasks in component `row` wait on in component `col`, so the
+/// off-diagonal cells are cross-component coupling that is currently blocking progress.
+#[derive(Debug, Clone, Default)]
+pub struct DepMatrix {
+    pub components: Vec<String>,
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl DepMatrix {
+    pub fn max(&self) -> usize {
+        self.counts.iter().flatten().copied().max().unwrap_or(0)
+    }
+
+    /// Unmet dependencies that cross a component boundary.
+    pub fn cross_total(&self) -> usize {
+        self.counts
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.iter().enumerate().filter(move |(c, _)| *c != r))
+            .map(|(_, n)| n)
+            .sum()
+    }
+}
+
+pub fn dependency_matrix(file: &TaskFile) -> DepMatrix {
+    let components: Vec<String> = file
+        .tasks
+        .iter()
+        .map(component_key)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let index = |c: &str| components.iter().position(|x| x == c);
+    let component_by_id: std::collections::HashMap<&str, String> = file
+        .tasks
+        .iter()
+        .map(|t| (t.id.as_str(), component_key(t)))
+        .collect();
+    let status_by_id = status_map(file);
+
+    let mut counts = vec![vec![0usize; components.len()]; components.len()];
+    for t in file.tasks.iter().filter(|t| task_status(t) != "done") {
+        let Some(row) = index(&component_key(t)) else {
+            continue;
+        };
+        for dep in unmet_deps(t, &status_by_id) {
+            if let Some(col) = component_by_id.get(dep).and_then(|c| index(c)) {
+                counts[row][col] += 1;
+            }
+        }
+    }
+    DepMatrix { components, counts }
+}
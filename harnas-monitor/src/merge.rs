@@ -0,0 +1,392 @@
+//! Task-level three-way merge for `TASKS.toml`, used as a git merge driver:
+//!
+//! ```text
+//! git config merge.harnas-tasks.driver "harnas-monitor merge-driver %O %A %B"
+//! echo 'TASKS.toml merge=harnas-tasks' >> .gitattributes
+//! ```
+//!
+//! Tasks are matched by `id` and every field is merged on its own, so two branches that touch
+//! different fields (or different tasks) merge cleanly. `[meta]` and other tables are merged the
+//! same way, key by key. The result keeps the formatting of "ours". A field changed differently
+//! on both sides keeps our value and gets a `# CONFLICT` comment with theirs, so the file stays
+//! valid TOML while git still reports the conflict.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+
+pub struct MergeOutcome {
+    pub merged: String,
+    /// One line per conflicting field or task; empty for a clean merge.
+    pub conflicts: Vec<String>,
+}
+
+pub fn merge_tasks(base: &str, ours: &str, theirs: &str) -> Result<MergeOutcome> {
+    let base = parse(base, "base")?;
+    let mut ours = parse(ours, "ours")?;
+    let theirs = parse(theirs, "theirs")?;
+    let mut conflicts = Vec::new();
+
+    merge_table(
+        ours.as_table_mut(),
+        Some(base.as_table()),
+        theirs.as_table(),
+        "",
+        &mut conflicts,
+    );
+    let tasks = merge_task_lists(
+        &tasks_of(&base),
+        tasks_of(&ours).into_iter().cloned().collect(),
+        &tasks_of(&theirs),
+        &mut conflicts,
+    );
+    let first_position = ours
+        .get("tasks")
+        .and_then(Item::as_array_of_tables)
+        .and_then(|a| a.get(0))
+        .and_then(Table::position);
+    if !tasks.is_empty() || ours.contains_key("tasks") {
+        let mut array = ArrayOfTables::new();
+        for (i, mut table) in tasks.into_iter().enumerate() {
+            // Tasks taken from theirs carry positions from their document; renumber so every
+            // table is written in merge order.
+            if let Some(first) = first_position {
+                table.set_position(first + i);
+            }
+            array.push(table);
+        }
+        ours.insert("tasks", Item::ArrayOfTables(array));
+    }
+    Ok(MergeOutcome {
+        merged: ours.to_string(),
+        conflicts,
+    })
+}
+
+fn parse(contents: &str, side: &str) -> Result<DocumentMut> {
+    contents.parse().map_err(|e| anyhow!("parsing {side}: {e}"))
+}
+
+fn tasks_of(doc: &DocumentMut) -> Vec<&Table> {
+    doc.get("tasks")
+        .and_then(Item::as_array_of_tables)
+        .map(|a| a.iter().collect())
+        .unwrap_or_default()
+}
+
+fn task_id(t: &Table) -> Option<&str> {
+    t.get("id").and_then(Item::as_str)
+}
+
+/// Ours' task order, with tasks only theirs has inserted after their predecessor in theirs.
+fn merge_task_lists(
+    base: &[&Table],
+    ours: Vec<Table>,
+    theirs: &[&Table],
+    conflicts: &mut Vec<String>,
+) -> Vec<Table> {
+    let by_id = |tasks: &[&Table]| -> HashMap<String, usize> {
+        tasks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| task_id(t).map(|id| (id.to_string(), i)))
+            .collect()
+    };
+    let base_ids = by_id(base);
+    let theirs_ids = by_id(theirs);
+
+    let mut out: Vec<Table> = Vec::with_capacity(ours.len());
+    for mut task in ours {
+        let Some(id) = task_id(&task).map(str::to_string) else {
+            out.push(task);
+            continue;
+        };
+        let b = base_ids.get(&id).map(|&i| base[i]);
+        match (b, theirs_ids.get(&id).map(|&i| theirs[i])) {
+            (_, Some(t)) => {
+                merge_table(&mut task, b, t, &id, conflicts);
+                out.push(task);
+            }
+            (Some(b), None) if same_table(&task, b) => {}
+            (Some(_), None) => {
+                conflicts.push(format!("{id}: deleted in theirs, changed in ours"));
+                annotate_table(&mut task, "deleted in theirs, changed in ours; kept ours");
+                out.push(task);
+            }
+            (None, None) => out.push(task),
+        }
+    }
+
+    for (i, t) in theirs.iter().enumerate() {
+        let Some(id) = task_id(t) else {
+            continue;
+        };
+        if out.iter().any(|o| task_id(o) == Some(id)) {
+            continue;
+        }
+        let mut task = (*t).clone();
+        match base_ids.get(id).map(|&b| base[b]) {
+            None => {}
+            Some(b) if same_table(t, b) => continue,
+            Some(_) => {
+                conflicts.push(format!("{id}: deleted in ours, changed in theirs"));
+                annotate_table(&mut task, "deleted in ours, changed in theirs; kept theirs");
+            }
+        }
+        let after = theirs[..i]
+            .iter()
+            .rev()
+            .filter_map(|p| task_id(p))
+            .find_map(|prev| out.iter().position(|o| task_id(o) == Some(prev)));
+        out.insert(after.map_or(0, |p| p + 1), task);
+    }
+    out
+}
+
+/// Merges `theirs` into `ours` key by key against `base`. Nested tables are merged recursively;
+/// the task array is handled by `merge_task_lists`.
+fn merge_table(
+    ours: &mut Table,
+    base: Option<&Table>,
+    theirs: &Table,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) {
+    let mut keys: Vec<String> = ours.iter().map(|(k, _)| k.to_string()).collect();
+    for (k, _) in theirs.iter().chain(base.iter().flat_map(|b| b.iter())) {
+        if !keys.iter().any(|x| x == k) {
+            keys.push(k.to_string());
+        }
+    }
+    for key in keys {
+        if path.is_empty() && key == "tasks" {
+            continue;
+        }
+        let b = base.and_then(|b| b.get(&key));
+        let t = theirs.get(&key);
+        let field = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        if let (Some(Item::Table(o)), Some(Item::Table(t))) = (ours.get_mut(&key), t) {
+            merge_table(o, b.and_then(Item::as_table), t, &field, conflicts);
+            continue;
+        }
+        let o = ours.get(&key);
+        if same(o, t) || same(b, t) {
+            continue;
+        }
+        if same(b, o) {
+            match t {
+                Some(t) => {
+                    ours.insert(&key, t.clone());
+                }
+                None => {
+                    ours.remove(&key);
+                }
+            }
+            continue;
+        }
+        let theirs_desc = t.map_or("removed".to_string(), |t| format!("= {}", flat(t)));
+        conflicts.push(format!("{field}: ours and theirs both changed it"));
+        if o.is_none() {
+            // Removed here but changed there: keep the value so nothing is lost.
+            if let Some(t) = t {
+                ours.insert(&key, t.clone());
+            }
+        }
+        if let Some(mut k) = ours.key_mut(&key) {
+            let prefix = k
+                .leaf_decor()
+                .prefix()
+                .and_then(|p| p.as_str())
+                .unwrap_or("")
+                .to_string();
+            k.leaf_decor_mut()
+                .set_prefix(format!("{prefix}# CONFLICT: theirs {theirs_desc}\n"));
+        }
+    }
+}
+
+fn annotate_table(table: &mut Table, note: &str) {
+    let prefix = table
+        .decor()
+        .prefix()
+        .and_then(|p| p.as_str())
+        .unwrap_or("\n")
+        .to_string();
+    table
+        .decor_mut()
+        .set_prefix(format!("{prefix}# CONFLICT: {note}\n"));
+}
+
+fn same_table(a: &Table, b: &Table) -> bool {
+    a.len() == b.len() && a.iter().all(|(k, v)| same(Some(v), b.get(k)))
+}
+
+/// Semantic equality: formatting, comments and quoting styles do not count as changes.
+fn same(a: Option<&Item>, b: Option<&Item>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(Item::Table(a)), Some(Item::Table(b))) => same_table(a, b),
+        (Some(a), Some(b)) => match (semantic(a), semantic(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.to_string() == b.to_string(),
+        },
+        _ => false,
+    }
+}
+
+fn semantic(item: &Item) -> Option<toml::Value> {
+    let value = item.as_value()?;
+    let mut table: toml::Table = toml::from_str(&format!("v = {value}")).ok()?;
+    table.remove("v")
+}
+
+/// A value on one line, for conflict comments.
+fn flat(item: &Item) -> String {
+    let text = semantic(item).map_or_else(|| item.to_string(), |v| v.to_string());
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"[meta]
+spec_version = "1"
+
+[[tasks]]
+id = "T-1"
+title = "Parse the spec"
+status = "todo"
+priority = "high"
+
+[[tasks]]
+id = "T-2"
+title = "Render the table"
+status = "todo"
+"#;
+
+    fn tasks(merged: &str) -> Vec<toml::Table> {
+        let doc: toml::Table = toml::from_str(merged).unwrap();
+        let tasks = doc["tasks"].as_array().unwrap();
+        tasks
+            .iter()
+            .map(|t| t.as_table().unwrap().clone())
+            .collect()
+    }
+
+    fn ids(merged: &str) -> Vec<String> {
+        tasks(merged)
+            .iter()
+            .map(|t| t["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn field(merged: &str, id: &str, key: &str) -> Option<String> {
+        let task = tasks(merged)
+            .into_iter()
+            .find(|t| t["id"].as_str() == Some(id))?;
+        task.get(key).map(|v| v.as_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn fields_changed_on_one_side_each_merge_cleanly() {
+        let ours = BASE.replace("status = \"todo\"\npriority", "status = \"done\"\npriority");
+        let theirs = BASE
+            .replace("priority = \"high\"", "priority = \"low\"")
+            .replace("spec_version = \"1\"", "spec_version = \"2\"");
+        let out = merge_tasks(BASE, &ours, &theirs).unwrap();
+        assert!(out.conflicts.is_empty(), "{:?}", out.conflicts);
+        assert_eq!(field(&out.merged, "T-1", "status").as_deref(), Some("done"));
+        assert_eq!(
+            field(&out.merged, "T-1", "priority").as_deref(),
+            Some("low")
+        );
+        assert!(out.merged.contains("spec_version = \"2\""));
+        assert_eq!(ids(&out.merged), ["T-1", "T-2"]);
+    }
+
+    #[test]
+    fn same_field_changed_on_both_sides_keeps_ours_and_marks_the_conflict() {
+        let ours = BASE.replace("Render the table", "Render the task table");
+        let theirs = BASE.replace("Render the table", "Draw the table");
+        let out = merge_tasks(BASE, &ours, &theirs).unwrap();
+        assert_eq!(
+            out.conflicts,
+            ["T-2.title: ours and theirs both changed it"]
+        );
+        assert_eq!(
+            field(&out.merged, "T-2", "title").as_deref(),
+            Some("Render the task table")
+        );
+        assert!(out
+            .merged
+            .contains("# CONFLICT: theirs = \"Draw the table\"\ntitle"));
+    }
+
+    #[test]
+    fn the_same_change_on_both_sides_is_no_conflict() {
+        let both = BASE.replace("Render the table", "Draw the table");
+        let out = merge_tasks(BASE, &both, &both).unwrap();
+        assert!(out.conflicts.is_empty());
+        assert_eq!(
+            field(&out.merged, "T-2", "title").as_deref(),
+            Some("Draw the table")
+        );
+    }
+
+    #[test]
+    fn delete_on_one_side_and_change_on_the_other_keeps_the_changed_task() {
+        let without_t2 = BASE.split("\n[[tasks]]\nid = \"T-2\"").next().unwrap();
+        let changed = BASE.replace("Render the table", "Draw the table");
+
+        let out = merge_tasks(BASE, without_t2, &changed).unwrap();
+        assert_eq!(out.conflicts, ["T-2: deleted in ours, changed in theirs"]);
+        assert_eq!(
+            field(&out.merged, "T-2", "title").as_deref(),
+            Some("Draw the table")
+        );
+        assert!(out
+            .merged
+            .contains("# CONFLICT: deleted in ours, changed in theirs"));
+
+        let out = merge_tasks(BASE, &changed, without_t2).unwrap();
+        assert_eq!(out.conflicts, ["T-2: deleted in theirs, changed in ours"]);
+        assert_eq!(
+            field(&out.merged, "T-2", "title").as_deref(),
+            Some("Draw the table")
+        );
+    }
+
+    #[test]
+    fn delete_on_one_side_of_an_unchanged_task_removes_it() {
+        let without_t2 = BASE.split("\n[[tasks]]\nid = \"T-2\"").next().unwrap();
+        let out = merge_tasks(BASE, BASE, without_t2).unwrap();
+        assert!(out.conflicts.is_empty());
+        assert_eq!(ids(&out.merged), ["T-1"]);
+        let out = merge_tasks(BASE, without_t2, BASE).unwrap();
+        assert!(out.conflicts.is_empty());
+        assert_eq!(ids(&out.merged), ["T-1"]);
+    }
+
+    #[test]
+    fn tasks_only_theirs_added_follow_their_predecessor_in_theirs() {
+        let ours = format!("{BASE}\n[[tasks]]\nid = \"T-4\"\ntitle = \"Ours\"\n");
+        let theirs = BASE
+            .replace(
+                "[[tasks]]\nid = \"T-1\"",
+                "[[tasks]]\nid = \"T-0\"\ntitle = \"First\"\n\n[[tasks]]\nid = \"T-1\"",
+            )
+            .replace(
+                "\n[[tasks]]\nid = \"T-2\"",
+                "\n[[tasks]]\nid = \"T-3\"\ntitle = \"Between\"\n\n[[tasks]]\nid = \"T-2\"",
+            );
+        let out = merge_tasks(BASE, &ours, &theirs).unwrap();
+        assert!(out.conflicts.is_empty(), "{:?}", out.conflicts);
+        assert_eq!(ids(&out.merged), ["T-0", "T-1", "T-3", "T-2", "T-4"]);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::clock;
+use crate::writer::write_atomic;
+
+/// Personal annotations kept next to the generated tasks file. The monitor owns this file; the
+/// tasks file itself is never touched.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NotesFile {
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// Focus timer sessions (`t`), for time reporting.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sessions: Vec<FocusSession>,
+    /// Discussion threads (`C`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+    /// DoD items ticked off in the review queue (`Q`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verified: Vec<Verified>,
+    /// Alerts acknowledged with `d` in the problems or drift panel, by fingerprint.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dismissed: Vec<Dismissed>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Annotation {
+    pub id: String,
+    pub at: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FocusSession {
+    pub id: String,
+    pub started: String,
+    /// Whole minutes spent; less than the session length when stopped early.
+    pub minutes: u64,
+    pub completed: bool,
+}
+
+/// One comment on a task. `n` numbers the task's comments from 1; a reply names its parent's.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Comment {
+    pub id: String,
+    pub n: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<u32>,
+    pub author: String,
+    pub at: String,
+    pub body: String,
+}
+
+/// A DoD item of task `id` someone checked. The item is kept by its text, so reordering the
+/// list in the tasks file keeps the tick, and rewording it drops it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Verified {
+    pub id: String,
+    pub item: String,
+    pub by: String,
+    pub at: String,
+}
+
+/// An alert kept out of the header. The fingerprint names the alert's task and condition (see
+/// `alerts::Alert::fingerprint`), so a changed condition raises the alert again.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Dismissed {
+    pub fingerprint: String,
+    pub at: String,
+}
+
+impl NotesFile {
+    pub fn verified(&self, id: &str, item: &str) -> Option<&Verified> {
+        self.verified.iter().find(|v| v.id == id && v.item == item)
+    }
+
+    /// Ticks the item of task `id`, or unticks it when it already was.
+    pub fn toggle_verified(&mut self, id: &str, item: &str, by: &str) {
+        if self.verified(id, item).is_some() {
+            self.verified.retain(|v| v.id != id || v.item != item);
+            return;
+        }
+        self.verified.push(Verified {
+            id: id.to_string(),
+            item: item.to_string(),
+            by: by.to_string(),
+            at: humantime::format_rfc3339_seconds(clock::now()).to_string(),
+        });
+    }
+
+    pub fn is_dismissed(&self, fingerprint: &str) -> bool {
+        self.dismissed.iter().any(|d| d.fingerprint == fingerprint)
+    }
+
+    /// Dismisses the alert with `fingerprint`, or brings it back when it was dismissed.
+    pub fn toggle_dismissed(&mut self, fingerprint: &str) {
+        if self.is_dismissed(fingerprint) {
+            self.dismissed.retain(|d| d.fingerprint != fingerprint);
+            return;
+        }
+        self.dismissed.push(Dismissed {
+            fingerprint: fingerprint.to_string(),
+            at: humantime::format_rfc3339_seconds(clock::now()).to_string(),
+        });
+    }
+
+    pub fn for_task<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a Annotation> + 'a {
+        self.annotations.iter().filter(move |a| a.id == id)
+    }
+
+    pub fn sessions_for<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a FocusSession> + 'a {
+        self.sessions.iter().filter(move |s| s.id == id)
+    }
+
+    /// The task's comments in thread order, each with its depth: a thread's first comment, then
+    /// its replies, oldest first at every level. Replies to a comment that is not there (or
+    /// caught in a reply cycle edited in by hand) are shown as threads of their own.
+    pub fn comment_threads(&self, id: &str) -> Vec<(usize, &Comment)> {
+        let mut comments: Vec<&Comment> = self.comments.iter().filter(|c| c.id == id).collect();
+        comments.sort_by(|a, b| a.at.cmp(&b.at).then(a.n.cmp(&b.n)));
+        let known: Vec<u32> = comments.iter().map(|c| c.n).collect();
+        let is_root = |c: &Comment| c.reply_to.is_none_or(|p| p == c.n || !known.contains(&p));
+        let mut out: Vec<(usize, &Comment)> = Vec::new();
+        let mut shown = vec![false; comments.len()];
+        // Threads first; then whatever no thread reached, oldest first.
+        let starts = (0..comments.len()).filter(|&i| is_root(comments[i]));
+        for start in starts.chain(0..comments.len()) {
+            if shown[start] {
+                continue;
+            }
+            let mut stack = vec![(0, start)];
+            while let Some((depth, i)) = stack.pop() {
+                if std::mem::replace(&mut shown[i], true) {
+                    continue;
+                }
+                out.push((depth, comments[i]));
+                let parent = comments[i].n;
+                stack.extend(
+                    (0..comments.len())
+                        .rev()
+                        .filter(|&r| !shown[r] && !is_root(comments[r]))
+                        .filter(|&r| comments[r].reply_to == Some(parent))
+                        .map(|r| (depth + 1, r)),
+                );
+            }
+        }
+        out
+    }
+
+    /// The number the task's next comment gets.
+    pub fn next_comment_n(&self, id: &str) -> u32 {
+        self.comments
+            .iter()
+            .filter(|c| c.id == id)
+            .map(|c| c.n)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+}
+
+/// `TASKS.toml` -> `TASKS.notes.toml`, in the same directory.
+pub fn notes_path(tasks_path: &Path) -> PathBuf {
+    let stem = tasks_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "TASKS".to_string());
+    tasks_path.with_file_name(format!("{stem}.notes.toml"))
+}
+
+pub fn load_notes(path: &Path) -> Result<NotesFile> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(NotesFile::default()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    toml::from_str(&contents).map_err(|e| anyhow!("parsing {}: {e}", path.display()))
+}
+
+pub fn append_note(path: &Path, id: &str, text: &str) -> Result<NotesFile> {
+    update_notes(path, |notes| {
+        notes.annotations.push(Annotation {
+            id: id.to_string(),
+            at: Some(humantime::format_rfc3339_seconds(clock::now()).to_string()),
+            text: text.to_string(),
+        })
+    })
+}
+
+/// Adds a comment to task `id`, numbered after the ones in the file as it is now.
+pub fn append_comment(
+    path: &Path,
+    id: &str,
+    reply_to: Option<u32>,
+    author: &str,
+    body: &str,
+) -> Result<NotesFile> {
+    update_notes(path, |notes| {
+        let comment = Comment {
+            id: id.to_string(),
+            n: notes.next_comment_n(id),
+            reply_to,
+            author: author.to_string(),
+            at: humantime::format_rfc3339_seconds(clock::now()).to_string(),
+            body: body.to_string(),
+        };
+        notes.comments.push(comment);
+    })
+}
+
+pub fn append_session(path: &Path, session: FocusSession) -> Result<NotesFile> {
+    update_notes(path, |notes| notes.sessions.push(session))
+}
+
+pub fn toggle_verified(path: &Path, id: &str, item: &str, by: &str) -> Result<NotesFile> {
+    update_notes(path, |notes| notes.toggle_verified(id, item, by))
+}
+
+pub fn toggle_dismissed(path: &Path, fingerprint: &str) -> Result<NotesFile> {
+    update_notes(path, |notes| notes.toggle_dismissed(fingerprint))
+}
+
+/// Drops the dismissals whose fingerprint is not in `current`.
+pub fn prune_dismissed(path: &Path, current: &HashSet<String>) -> Result<NotesFile> {
+    update_notes(path, |notes| {
+        notes.dismissed.retain(|d| current.contains(&d.fingerprint))
+    })
+}
+
+fn update_notes(path: &Path, change: impl FnOnce(&mut NotesFile)) -> Result<NotesFile> {
+    // Re-read right before writing so edits made by hand in the meantime are kept.
+    let mut notes = load_notes(path)?;
+    change(&mut notes);
+    let contents = toml::to_string(&notes).context("serializing notes")?;
+    write_atomic(path, &contents)?;
+    Ok(notes)
+}
@@ -0,0 +1,114 @@
+//! The command palette (`Ctrl-P`): dashboard actions by name, for the ones whose key is
+//! forgotten and the ones without a key (sorting by a column, picking a profile, editing the
+//! config). Typing narrows the list by fuzzy match; `Enter` runs the selected command.
+
+use crate::app::TableColumn;
+use crate::keys::KeyCode;
+
+use KeyCode::{Char, F};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// What the key does on the dashboard, whatever a profile remaps it to.
+    Key(KeyCode),
+    Sort(TableColumn),
+    FileOrder,
+    Profile(Option<String>),
+    ClearFilter,
+    /// `$VISUAL` or `$EDITOR` on the config file; the dashboard restarts with the result.
+    EditConfig,
+    /// Give every task of the selected task's component another component, as a staged edit.
+    RenameComponent,
+}
+
+#[derive(Debug, Clone)]
+pub struct Command {
+    /// Translated, as listed and searched.
+    pub name: String,
+    /// The key that does the same, as the user types it under the current profile.
+    pub key: Option<KeyCode>,
+    pub action: Action,
+}
+
+/// Commands that stand for a dashboard key, in the help's order. English names, the keys for
+/// their translation.
+pub const KEY_COMMANDS: &[(&str, KeyCode)] = &[
+    ("jump to a task id", Char(':')),
+    ("find a task as you type", Char('\'')),
+    ("back through recently viewed tasks", Char('[')),
+    ("forward through recently viewed tasks", Char(']')),
+    ("tasks linked to the selected one", Char('g')),
+    ("open the selected task's first link", Char('O')),
+    ("filter the table", Char('/')),
+    ("focus on the selected task's component", Char('f')),
+    ("show or hide the sidebar", Char('b')),
+    ("sort by risk score", Char('o')),
+    ("pick a column to sort by", Char('H')),
+    ("workspace overview", Char('0')),
+    ("stage the next status", Char('e')),
+    ("stage the next priority", Char('P')),
+    ("edit a field of the selected task", Char('E')),
+    ("defer the selected task", Char('L')),
+    ("redundant dependencies", Char('X')),
+    ("add the suggested dependency", Char('+')),
+    ("review the staged edits", Char('w')),
+    ("write the staged edits", Char('W')),
+    ("annotate the selected task", Char('n')),
+    ("comment on the selected task", Char('C')),
+    ("start or stop a focus session", Char('t')),
+    ("problems panel", Char('p')),
+    ("spec coverage panel", Char('c')),
+    ("stats panel", Char('s')),
+    ("component dependency matrix", Char('m')),
+    ("spec drift panel", Char('D')),
+    ("review queue", Char('Q')),
+    ("replan", Char('R')),
+    ("export the table", Char('x')),
+    ("view the large change", Char('d')),
+    ("accept the large change", Char('a')),
+    ("safe mode panel", Char('!')),
+    ("watch log", F(12)),
+    ("help: every key", Char('?')),
+    ("next view profile", Char('V')),
+    ("progress by task count or estimate", Char('%')),
+    ("compact details", Char('z')),
+    ("collapse or expand the header", Char('Z')),
+    ("show or hide the deferred tasks", Char('l')),
+    ("cycle the cue volume", Char('v')),
+    ("reload now", Char('r')),
+    ("reload at once, skipping the debounce", F(5)),
+    ("pause or resume auto-refresh", Char('A')),
+    ("quit", Char('q')),
+];
+
+/// How well `query` matches `text`, ignoring case: `None` unless its characters appear in
+/// order, otherwise higher for characters that follow each other or start a word.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut at = 0;
+    let mut previous = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = at + text[at..].iter().position(|c| *c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        at = found + 1;
+    }
+    Some(score)
+}
+
+/// The commands matching `query`, best first; ties keep the list's order.
+pub fn search<'a>(commands: &'a [Command], query: &str) -> Vec<&'a Command> {
+    let mut scored: Vec<(i64, &Command)> = commands
+        .iter()
+        .filter_map(|c| fuzzy_score(query, &c.name).map(|s| (s, c)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, c)| c).collect()
+}
@@ -0,0 +1,158 @@
+//! Redundant dependencies: `A depends_on C` when A already waits on C through another of its
+//! dependencies (A → B → C). Dropping them leaves what blocks what unchanged, so the graph stays
+//! minimal. Listed by `prune-deps` and the pruning modal (`X`).
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::tasks::TaskFile;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redundant {
+    pub task: String,
+    pub dep: String,
+    /// A chain through the task's other dependencies that implies the edge: from the first
+    /// dependency on it down to `dep`.
+    pub via: Vec<String>,
+}
+
+impl Redundant {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} depends on {} via {}",
+            self.task,
+            self.dep,
+            self.via.join(" → ")
+        )
+    }
+}
+
+/// Every redundant `depends_on` entry, in file order. Dependencies on other projects and on
+/// unknown ids are left alone; for repeated ids the first task counts.
+pub fn redundant_deps(file: &TaskFile) -> Vec<Redundant> {
+    let mut deps: HashMap<&str, Vec<&str>> = HashMap::new();
+    for t in &file.tasks {
+        deps.entry(t.id.as_str())
+            .or_insert_with(|| t.depends_on.iter().map(|d| d.id.as_str()).collect());
+    }
+    let mut out = Vec::new();
+    for t in &file.tasks {
+        // Edges found redundant are taken out as they are found, so two dependencies that
+        // imply each other (a cycle) are not both dropped.
+        let mut kept: Vec<&str> = t
+            .depends_on
+            .iter()
+            .map(|d| d.id.as_str())
+            .filter(|id| deps.contains_key(id))
+            .collect();
+        for dep in kept.clone() {
+            let others = kept.iter().copied().filter(|&o| o != dep && o != t.id);
+            if let Some(via) = others
+                .into_iter()
+                .find_map(|start| path(&deps, start, dep, &t.id))
+            {
+                kept.retain(|&k| k != dep);
+                out.push(Redundant {
+                    task: t.id.clone(),
+                    dep: dep.to_string(),
+                    via,
+                });
+            }
+        }
+    }
+    out
+}
+
+/// The shortest chain of dependencies from `start` down to `target`, both included, that does
+/// not pass through `avoid` (the task itself, when the graph has a cycle).
+fn path(
+    deps: &HashMap<&str, Vec<&str>>,
+    start: &str,
+    target: &str,
+    avoid: &str,
+) -> Option<Vec<String>> {
+    let mut parent: HashMap<&str, &str> = HashMap::new();
+    let mut seen: HashSet<&str> = HashSet::from([start, avoid]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(id) = queue.pop_front() {
+        for &next in deps.get(id).into_iter().flatten() {
+            if !seen.insert(next) {
+                continue;
+            }
+            parent.insert(next, id);
+            if next == target {
+                let mut chain = vec![target.to_string()];
+                let mut at = target;
+                while let Some(&p) = parent.get(at) {
+                    chain.push(p.to_string());
+                    at = p;
+                }
+                chain.reverse();
+                return Some(chain);
+            }
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plan of `(id, depends_on)` pairs.
+    fn plan(tasks: &[(&str, &[&str])]) -> TaskFile {
+        let toml: String = tasks
+            .iter()
+            .map(|(id, deps)| {
+                format!("[[tasks]]\nid = \"{id}\"\ntitle = \"{id}\"\ndepends_on = {deps:?}\n\n")
+            })
+            .collect();
+        toml::from_str(&toml).unwrap()
+    }
+
+    fn found(file: &TaskFile) -> Vec<String> {
+        redundant_deps(file)
+            .iter()
+            .map(Redundant::describe)
+            .collect()
+    }
+
+    #[test]
+    fn a_dependency_implied_by_another_is_redundant() {
+        let file = plan(&[("A", &["B", "C"]), ("B", &["C"]), ("C", &[])]);
+        assert_eq!(found(&file), ["A depends on C via B → C"]);
+    }
+
+    #[test]
+    fn the_chain_may_be_longer_than_one_step() {
+        let file = plan(&[("A", &["D", "B"]), ("B", &["C"]), ("C", &["D"]), ("D", &[])]);
+        assert_eq!(found(&file), ["A depends on D via B → C → D"]);
+    }
+
+    #[test]
+    fn independent_dependencies_are_kept() {
+        let file = plan(&[("A", &["B", "C"]), ("B", &[]), ("C", &["D"]), ("D", &[])]);
+        assert_eq!(found(&file), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn of_two_dependencies_in_a_cycle_only_one_is_dropped() {
+        let file = plan(&[("A", &["B", "C"]), ("B", &["C"]), ("C", &["B"])]);
+        assert_eq!(found(&file), ["A depends on B via C → B"]);
+    }
+
+    #[test]
+    fn a_chain_back_through_the_task_itself_does_not_count() {
+        // C → A → B would imply A → B, but only by going through A.
+        let file = plan(&[("A", &["B", "C"]), ("B", &[]), ("C", &["A"])]);
+        assert_eq!(found(&file), [] as [&str; 0]);
+        let file = plan(&[("A", &["B", "C"]), ("B", &["C"]), ("C", &["A"])]);
+        assert_eq!(found(&file), ["A depends on C via B → C"]);
+    }
+
+    #[test]
+    fn unknown_ids_are_left_alone() {
+        let file = plan(&[("A", &["B", "X", "other:T-1"]), ("B", &["X", "other:T-1"])]);
+        assert_eq!(found(&file), [] as [&str; 0]);
+    }
+}
@@ -0,0 +1,81 @@
+//! Plain-text output of the headless `lint` and `stats` commands.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::analysis::{severity_counts, Fix, Severity};
+use crate::tasks::LoadedTasks;
+
+/// One `file:line:col: severity: [label] message` line per problem, quickfix-compatible like
+/// `export --format quickfix`.
+pub fn write_lint(out: &mut impl Write, loaded: &LoadedTasks) -> Result<()> {
+    for p in &loaded.problems {
+        // A repeated id points at the repeat rather than the first task using it.
+        let nth = match &p.fix {
+            Some(Fix::RenameDuplicateId { occurrence, .. }) => *occurrence,
+            _ => 0,
+        };
+        let line = p
+            .task_ids
+            .first()
+            .and_then(|id| {
+                let mut lines: Vec<usize> = loaded
+                    .tasks
+                    .tasks
+                    .iter()
+                    .filter(|t| t.id == *id)
+                    .filter_map(|t| t.line)
+                    .collect();
+                lines.sort_unstable();
+                lines.get(nth).copied()
+            })
+            .unwrap_or(1);
+        writeln!(
+            out,
+            "{}:{line}:1: {}: [{}] {}",
+            loaded.path,
+            p.severity().label(),
+            p.kind.label(),
+            p.message
+        )?;
+    }
+    Ok(())
+}
+
+/// A short multi-line summary block.
+pub fn write_stats(out: &mut impl Write, loaded: &LoadedTasks) -> Result<()> {
+    let s = &loaded.stats;
+    let pct = if s.total == 0 {
+        0.0
+    } else {
+        s.done as f64 * 100.0 / s.total as f64
+    };
+    writeln!(out, "{}", loaded.path)?;
+    writeln!(
+        out,
+        "  tasks: {}  done: {} ({pct:.0}%)  in progress: {}  blocked: {}  todo: {}",
+        s.total, s.segments.done, s.segments.in_progress, s.segments.blocked, s.segments.todo
+    )?;
+    let counts = severity_counts(&loaded.problems);
+    let by_severity: Vec<String> = Severity::ALL
+        .iter()
+        .zip(counts)
+        .map(|(sev, n)| format!("{n} {}", sev.label()))
+        .collect();
+    writeln!(
+        out,
+        "  problems: {} ({})  missing deps: {}  blocked by deps: {}",
+        loaded.problems.len(),
+        by_severity.join(", "),
+        s.missing_deps,
+        s.blocked_by_deps
+    )?;
+    let components: Vec<String> = s
+        .by_component
+        .iter()
+        .map(|(k, v)| format!("{k} {v}"))
+        .collect();
+    writeln!(out, "  components: {}", components.join(", "))?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! The review queue (`Q`): tasks that became done in the last `[review] days`, according to the
+//! history, each with its definition of done so the items can be ticked off as verified. The
+//! ticks are kept in the annotations file (`TASKS.notes.toml`); the tasks file is never touched.
+
+use std::time::{Duration, SystemTime};
+
+use crate::history::History;
+use crate::tasks::{task_status, TaskFile};
+
+/// How far back the queue looks unless `[review] days` says otherwise.
+pub const REVIEW_DAYS: u64 = 14;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewTask {
+    pub id: String,
+    pub title: String,
+    /// When the history first saw the task done, this time round.
+    pub done_at: SystemTime,
+    pub dod: Vec<String>,
+}
+
+/// One line of the queue: a task (`item` is `None`) or one of its DoD items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReviewRow {
+    pub task: usize,
+    pub item: Option<usize>,
+}
+
+/// Tasks done now that entered `done` within `days` of `now`, most recently done first. A task
+/// reopened and finished again counts from the last time.
+pub fn review_queue(
+    history: &History,
+    file: &TaskFile,
+    now: SystemTime,
+    days: u64,
+) -> Vec<ReviewTask> {
+    let since = now
+        .checked_sub(Duration::from_secs(days * 86_400))
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut out: Vec<ReviewTask> = file
+        .tasks
+        .iter()
+        .filter(|t| task_status(t) == "done")
+        .filter_map(|t| {
+            let (at, state) = history.task_changes(&t.id).pop()?;
+            if state.status != "done" {
+                return None;
+            }
+            let done_at = humantime::parse_rfc3339(at).ok()?;
+            (done_at >= since).then(|| ReviewTask {
+                id: t.id.clone(),
+                title: t.title.clone(),
+                done_at,
+                dod: t.dod.clone(),
+            })
+        })
+        .collect();
+    out.sort_by_key(|t| std::cmp::Reverse(t.done_at));
+    out
+}
+
+/// The queue's lines in display order: each task, then its DoD items.
+pub fn review_rows(queue: &[ReviewTask]) -> Vec<ReviewRow> {
+    queue
+        .iter()
+        .enumerate()
+        .flat_map(|(task, t)| {
+            std::iter::once(ReviewRow { task, item: None }).chain((0..t.dod.len()).map(move |i| {
+                ReviewRow {
+                    task,
+                    item: Some(i),
+                }
+            }))
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+//! Risk-weighted ordering (`o`): one score per task from its priority, its `risk` (low, medium,
+//! high) and how many open tasks wait on it, directly or through other tasks.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::schedule::priority_rank;
+use crate::tasks::{risk_key, task_status, Task};
+
+/// Shown in the help modal and the README; keep in step with `risk_score`.
+pub const RISK_FORMULA: &str =
+    "score = priority (must 4 … won't 0) × risk (low 1, medium or unset 2, high 3) × (1 + open \
+     tasks downstream)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskScore {
+    pub priority: u32,
+    pub risk: u32,
+    /// Open tasks that depend on this one, directly or transitively.
+    pub downstream: usize,
+}
+
+impl RiskScore {
+    pub fn value(&self) -> u64 {
+        u64::from(self.priority) * u64::from(self.risk) * (1 + self.downstream as u64)
+    }
+}
+
+/// Unknown values weigh like an unassessed task.
+pub fn risk_weight(t: &Task) -> u32 {
+    match risk_key(t).as_str() {
+        "low" => 1,
+        "high" => 3,
+        _ => 2,
+    }
+}
+
+/// Scores by task id; a repeated id keeps its first occurrence.
+pub fn risk_scores(tasks: &[Task]) -> HashMap<&str, RiskScore> {
+    // Reverse edges, to open dependents only: done work no longer waits on anything.
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for t in tasks.iter().filter(|t| task_status(t) != "done") {
+        for dep in &t.depends_on {
+            dependents.entry(dep.id.as_str()).or_default().push(&t.id);
+        }
+    }
+    let mut out = HashMap::new();
+    for t in tasks {
+        if out.contains_key(t.id.as_str()) {
+            continue;
+        }
+        let mut seen: HashSet<&str> = HashSet::from([t.id.as_str()]);
+        let mut queue = vec![t.id.as_str()];
+        while let Some(id) = queue.pop() {
+            for &next in dependents.get(id).into_iter().flatten() {
+                if seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        out.insert(
+            t.id.as_str(),
+            RiskScore {
+                priority: 4 - u32::from(priority_rank(t).min(4)),
+                risk: risk_weight(t),
+                downstream: seen.len() - 1,
+            },
+        );
+    }
+    out
+}
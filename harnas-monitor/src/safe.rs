@@ -0,0 +1,91 @@
+//! Safe mode: a config, history or usage file that cannot be read does not keep the dashboard
+//! from starting. It starts with defaults for what failed, says `SAFE MODE` in the header and
+//! lists the failures in a panel (`!`), from which `r` moves a broken file aside so the next
+//! start is a normal one. Nothing is deleted: the file keeps its content under a `.broken` name.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::history::Snapshot;
+use crate::writer::write_atomic;
+
+/// Which file failed, and how its reset treats it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFile {
+    Config,
+    /// The readable snapshots are written back after the reset, so only the broken lines go.
+    History,
+    Usage,
+}
+
+impl StateFile {
+    pub fn label(self) -> &'static str {
+        match self {
+            StateFile::Config => "config",
+            StateFile::History => "history",
+            StateFile::Usage => "usage",
+        }
+    }
+
+    /// What the dashboard uses instead while the file is broken.
+    pub fn fallback(self) -> &'static str {
+        match self {
+            StateFile::Config => "using the default settings",
+            StateFile::History => "using the readable snapshots only",
+            StateFile::Usage => "this session's usage is not recorded",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub file: StateFile,
+    pub path: PathBuf,
+    pub error: String,
+    /// Where `reset` moved the file, once it has.
+    pub moved_to: Option<PathBuf>,
+}
+
+impl Failure {
+    pub fn new(file: StateFile, path: &Path, error: String) -> Self {
+        Failure {
+            file,
+            path: path.to_path_buf(),
+            error,
+            moved_to: None,
+        }
+    }
+}
+
+/// Moves the failed file aside to `NAME.broken` (`NAME.broken.2`, … when taken) and returns
+/// the new path. A history file gets its readable snapshots back.
+pub fn reset(failure: &Failure) -> Result<PathBuf> {
+    let path = &failure.path;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let aside = (1..)
+        .map(|n| match n {
+            1 => path.with_file_name(format!("{name}.broken")),
+            n => path.with_file_name(format!("{name}.broken.{n}")),
+        })
+        .find(|p| !p.exists())
+        .unwrap_or_else(|| path.with_file_name(format!("{name}.broken")));
+    std::fs::rename(path, &aside)
+        .with_context(|| format!("moving {} to {}", path.display(), aside.display()))?;
+    if failure.file == StateFile::History {
+        let bytes = std::fs::read(&aside).with_context(|| format!("reading {}", aside.display()))?;
+        let mut kept = String::new();
+        for line in bytes.split(|b| *b == b'\n') {
+            let line = String::from_utf8_lossy(line);
+            if serde_json::from_str::<Snapshot>(&line).is_ok() {
+                kept.push_str(&line);
+                kept.push('\n');
+            }
+        }
+        write_atomic(path, &kept)?;
+    }
+    Ok(aside)
+}
@@ -0,0 +1,431 @@
+//! Forward scheduling of the remaining tasks from their estimates, dependencies and a number of
+//! parallel workers, and the re-planning built on it: what fits before a date, what slips, and
+//! which tasks to cut or defer so the rest fits.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::tasks::{estimate_range, priority_key, task_status, Task};
+
+/// Days assumed for a remaining task without `estimate_days`.
+pub const UNESTIMATED_DAYS: f64 = 1.0;
+
+/// Seconds since the epoch of 10000-01-01, the first instant without a `YYYY-MM-DD` date.
+const LAST_DATE_SECS: u64 = 253_402_300_800;
+
+/// A remaining task's place in the schedule, in days from now.
+#[derive(Debug, Clone)]
+pub struct Slot {
+    pub id: String,
+    pub start: f64,
+    pub finish: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    /// In the order they were scheduled.
+    pub slots: Vec<Slot>,
+    /// Remaining tasks without an estimate; scheduled with `UNESTIMATED_DAYS`.
+    pub unestimated: Vec<String>,
+    /// Remaining tasks caught in a dependency cycle; never scheduled.
+    pub unschedulable: Vec<String>,
+}
+
+impl Plan {
+    pub fn finish(&self) -> f64 {
+        self.slots.iter().map(|s| s.finish).fold(0.0, f64::max)
+    }
+}
+
+/// Lower ranks are scheduled first and cut last. Common MoSCoW, high/low and P0..P4 names are
+/// understood; anything else ranks just above the "could" tier.
+pub fn priority_rank(t: &Task) -> u8 {
+    known_priority_rank(&priority_key(t)).unwrap_or(2)
+}
+
+/// The rank of a lowercased priority name `priority_rank` understands, from 0 to 4.
+pub fn known_priority_rank(key: &str) -> Option<u8> {
+    match key {
+        "must" | "critical" | "urgent" | "high" | "p0" | "p1" => Some(0),
+        "should" | "medium" | "normal" | "p2" => Some(1),
+        "could" | "low" | "p3" => Some(3),
+        "won't" | "wont" | "won_t" | "later" | "p4" => Some(4),
+        _ => None,
+    }
+}
+
+/// Schedules every task that is not done and not in `skip`, as soon as its dependencies are
+/// finished and a worker is free. Ready tasks go in-progress first, then by priority rank, then
+/// in `tasks` order. Dependencies on done or unknown tasks count as met.
+pub fn schedule(tasks: &[Task], workers: usize, skip: &HashSet<&str>) -> Plan {
+    schedule_by(tasks, workers, skip, |t| t.estimate_days)
+}
+
+/// `schedule` with each task taking `days(task)` instead of its `estimate_days`.
+fn schedule_by(
+    tasks: &[Task],
+    workers: usize,
+    skip: &HashSet<&str>,
+    days: impl Fn(&Task) -> Option<f64>,
+) -> Plan {
+    let remaining: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| task_status(t) != "done" && !skip.contains(t.id.as_str()))
+        .collect();
+    let pending: HashSet<&str> = remaining.iter().map(|t| t.id.as_str()).collect();
+    let mut finish: HashMap<&str, f64> = HashMap::new();
+    let mut free = vec![0.0_f64; workers.max(1)];
+    let mut plan = Plan::default();
+    let mut left: Vec<&Task> = remaining;
+    loop {
+        let ready = left
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| {
+                t.depends_on
+                    .iter()
+                    .all(|d| !pending.contains(d.id.as_str()) || finish.contains_key(d.id.as_str()))
+            })
+            .min_by_key(|(i, t)| (task_status(t) != "in_progress", priority_rank(t), *i))
+            .map(|(i, _)| i);
+        let Some(i) = ready else {
+            break;
+        };
+        let t = left.remove(i);
+        let days = match days(t) {
+            Some(d) if d.is_finite() && d >= 0.0 => d,
+            _ => {
+                plan.unestimated.push(t.id.clone());
+                UNESTIMATED_DAYS
+            }
+        };
+        let deps_done = t
+            .depends_on
+            .iter()
+            .filter_map(|d| finish.get(d.id.as_str()))
+            .fold(0.0, |a: f64, b| a.max(*b));
+        let (worker, worker_free) = free
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        let start = worker_free.max(deps_done);
+        free[worker] = start + days;
+        finish.insert(&t.id, start + days);
+        plan.slots.push(Slot {
+            id: t.id.clone(),
+            start,
+            finish: start + days,
+        });
+    }
+    plan.unschedulable = left.iter().map(|t| t.id.clone()).collect();
+    plan
+}
+
+/// When the remaining work finishes with every ranged estimate (`tasks::estimate_range`) at its
+/// optimistic bound, and at its pessimistic one; `None` when no remaining task has a range.
+pub fn finish_range(
+    tasks: &[Task],
+    workers: usize,
+    skip: &HashSet<&str>,
+    spread: Option<f64>,
+) -> Option<(f64, f64)> {
+    let ranged = tasks.iter().any(|t| {
+        task_status(t) != "done"
+            && !skip.contains(t.id.as_str())
+            && estimate_range(t, spread).is_some()
+    });
+    if !ranged {
+        return None;
+    }
+    let at = |pessimistic: bool| {
+        let days = |t: &Task| match estimate_range(t, spread) {
+            Some((low, high)) => Some(if pessimistic { high } else { low }),
+            None => t.estimate_days,
+        };
+        schedule_by(tasks, workers, skip, days).finish()
+    };
+    // Greedy scheduling can finish earlier with a longer task; keep the range around the plan.
+    let finish = schedule(tasks, workers, skip).finish();
+    Some((at(false).min(finish), at(true).max(finish)))
+}
+
+/// How long the remaining work takes with a team, as the header shows it when `parallelism` is
+/// configured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Makespan {
+    pub workers: usize,
+    /// Days until the last scheduled task finishes.
+    pub days: f64,
+    /// Estimated days of all scheduled tasks, as one person would work through them.
+    pub effort: f64,
+    /// Remaining tasks counted with `UNESTIMATED_DAYS`.
+    pub unestimated: usize,
+    /// Remaining tasks left out because of a dependency cycle.
+    pub unschedulable: usize,
+    /// `days` with every ranged estimate at its optimistic and at its pessimistic bound.
+    pub range: Option<(f64, f64)>,
+}
+
+/// The `schedule` of the remaining tasks summed up; `None` when nothing remains. `spread` is
+/// the plan's `[meta] estimate_spread`.
+pub fn makespan(tasks: &[Task], workers: usize, spread: Option<f64>) -> Option<Makespan> {
+    let plan = schedule(tasks, workers, &HashSet::new());
+    if plan.slots.is_empty() && plan.unschedulable.is_empty() {
+        return None;
+    }
+    Some(Makespan {
+        workers: workers.max(1),
+        days: plan.finish(),
+        effort: plan.slots.iter().map(|s| s.finish - s.start).sum(),
+        unestimated: plan.unestimated.len(),
+        unschedulable: plan.unschedulable.len(),
+        range: finish_range(tasks, workers, &HashSet::new(), spread),
+    })
+}
+
+/// The `YYYY-MM-DD` day `days` after `now` (UTC); a negative `days` is `now`. `None` when the
+/// day overflows the clock or runs past year 9999.
+pub fn project_date(now: SystemTime, days: f64) -> Option<String> {
+    let at = Duration::try_from_secs_f64(days.max(0.0) * 86_400.0)
+        .ok()
+        .and_then(|d| now.checked_add(d))?;
+    if at
+        .duration_since(UNIX_EPOCH)
+        .map_or(true, |d| d.as_secs() >= LAST_DATE_SECS)
+    {
+        return None;
+    }
+    let stamp = humantime::format_rfc3339_seconds(at).to_string();
+    stamp.get(..10).map(str::to_string)
+}
+
+#[derive(Debug, Clone)]
+pub struct Replan {
+    /// The schedule with every remaining task.
+    pub plan: Plan,
+    /// Tasks to cut or defer, in the order they were picked.
+    pub cut: Vec<String>,
+    /// The schedule without `cut`.
+    pub after_cut: Plan,
+    /// When `plan` finishes with the estimates at their bounds (see `finish_range`).
+    pub range: Option<(f64, f64)>,
+}
+
+impl Replan {
+    /// Tasks of `plan` finishing after `deadline` days.
+    pub fn slipping(&self, deadline: f64) -> Vec<&Slot> {
+        self.plan
+            .slots
+            .iter()
+            .filter(|s| s.finish > deadline)
+            .collect()
+    }
+}
+
+/// Schedules the remaining tasks, then cuts tasks one at a time until the rest finishes within
+/// `deadline` days. Each cut is a slipping task that no kept task depends on, lowest priority
+/// first, then the one finishing last, so no kept task loses a dependency.
+pub fn replan(tasks: &[Task], workers: usize, deadline: f64, spread: Option<f64>) -> Replan {
+    let plan = schedule(tasks, workers, &HashSet::new());
+    let by_id: HashMap<&str, &Task> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut cut: Vec<String> = Vec::new();
+    let mut after_cut = plan.clone();
+    while after_cut.finish() > deadline {
+        let kept: HashSet<&str> = after_cut.slots.iter().map(|s| s.id.as_str()).collect();
+        let needed: HashSet<&str> = after_cut
+            .slots
+            .iter()
+            .filter_map(|s| by_id.get(s.id.as_str()))
+            .flat_map(|t| t.depends_on.iter().map(|d| d.id.as_str()))
+            .filter(|d| kept.contains(d))
+            .collect();
+        let pick = after_cut
+            .slots
+            .iter()
+            .filter(|s| s.finish > deadline && !needed.contains(s.id.as_str()))
+            .max_by(|a, b| {
+                let rank = |s: &Slot| by_id.get(s.id.as_str()).map_or(2, |t| priority_rank(t));
+                rank(a).cmp(&rank(b)).then(a.finish.total_cmp(&b.finish))
+            });
+        let Some(pick) = pick else {
+            break;
+        };
+        cut.push(pick.id.clone());
+        let skip: HashSet<&str> = cut.iter().map(String::as_str).collect();
+        after_cut = schedule(tasks, workers, &skip);
+    }
+    Replan {
+        plan,
+        cut,
+        after_cut,
+        range: finish_range(tasks, workers, &HashSet::new(), spread),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskFile;
+
+    /// A → B → D and A → C → D with estimates 2, 3, 1 and 1 days; E has no estimate, F waits
+    /// on an id no task has, and Z is done already.
+    const GRAPH: &str = r#"
+[[tasks]]
+id = "A"
+title = "a"
+estimate_days = 2.0
+
+[[tasks]]
+id = "B"
+title = "b"
+estimate_days = 3.0
+depends_on = ["A"]
+
+[[tasks]]
+id = "C"
+title = "c"
+estimate_days = 1.0
+depends_on = ["A"]
+
+[[tasks]]
+id = "D"
+title = "d"
+estimate_days = 1.0
+depends_on = ["B", "C", "Z"]
+
+[[tasks]]
+id = "E"
+title = "e"
+
+[[tasks]]
+id = "F"
+title = "f"
+estimate_days = 0.5
+depends_on = ["NOPE-1"]
+
+[[tasks]]
+id = "Z"
+title = "z"
+status = "done"
+estimate_days = 9.0
+"#;
+
+    fn tasks(toml: &str) -> Vec<Task> {
+        toml::from_str::<TaskFile>(toml).unwrap().tasks
+    }
+
+    fn slot<'a>(plan: &'a Plan, id: &str) -> &'a Slot {
+        plan.slots.iter().find(|s| s.id == id).unwrap()
+    }
+
+    #[test]
+    fn with_enough_workers_the_critical_path_sets_the_finish() {
+        let plan = schedule(&tasks(GRAPH), 10, &HashSet::new());
+        assert_eq!(plan.finish(), 6.0);
+        for (id, start, finish) in [
+            ("A", 0.0, 2.0),
+            ("B", 2.0, 5.0),
+            ("C", 2.0, 3.0),
+            ("D", 5.0, 6.0),
+        ] {
+            let s = slot(&plan, id);
+            assert_eq!((s.start, s.finish), (start, finish), "{id}");
+        }
+    }
+
+    #[test]
+    fn one_worker_does_everything_in_turn() {
+        let plan = schedule(&tasks(GRAPH), 1, &HashSet::new());
+        // 2 + 3 + 1 + 1, one unestimated day for E and half a day for F.
+        assert_eq!(plan.finish(), 8.5);
+        let ids: Vec<&str> = plan.slots.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["A", "B", "C", "D", "E", "F"]);
+    }
+
+    #[test]
+    fn missing_estimates_count_as_a_default_day() {
+        let plan = schedule(&tasks(GRAPH), 10, &HashSet::new());
+        assert_eq!(plan.unestimated, ["E"]);
+        let e = slot(&plan, "E");
+        assert_eq!(e.finish - e.start, UNESTIMATED_DAYS);
+    }
+
+    #[test]
+    fn unknown_and_done_dependencies_count_as_met() {
+        let plan = schedule(&tasks(GRAPH), 10, &HashSet::new());
+        assert_eq!(slot(&plan, "F").start, 0.0);
+        assert!(plan.slots.iter().all(|s| s.id != "Z"));
+        assert!(plan.unschedulable.is_empty());
+    }
+
+    #[test]
+    fn tasks_in_a_cycle_are_never_scheduled() {
+        let cycle = format!(
+            "{GRAPH}\n[[tasks]]\nid = \"G\"\ntitle = \"g\"\ndepends_on = [\"H\"]\n\n\
+             [[tasks]]\nid = \"H\"\ntitle = \"h\"\ndepends_on = [\"G\"]\n"
+        );
+        let span = makespan(&tasks(&cycle), 10, None).unwrap();
+        assert_eq!(span.unschedulable, 2);
+        assert_eq!(span.days, 6.0);
+    }
+
+    #[test]
+    fn makespan_sums_up_the_schedule() {
+        let span = makespan(&tasks(GRAPH), 2, None).unwrap();
+        assert_eq!(span.workers, 2);
+        assert_eq!(span.effort, 8.5);
+        assert_eq!(span.unestimated, 1);
+        assert!(span.days >= 6.0 && span.days <= span.effort);
+        assert_eq!(span.range, None);
+    }
+
+    #[test]
+    fn ranged_estimates_project_a_finish_range() {
+        let ranged = GRAPH.replace(
+            "estimate_days = 3.0",
+            "estimate_days = 3.0\nestimate_days_min = 2.0\nestimate_days_max = 6.0",
+        );
+        let span = makespan(&tasks(&ranged), 10, None).unwrap();
+        assert_eq!(span.range, Some((5.0, 9.0)));
+    }
+
+    #[test]
+    fn replanning_cuts_what_slips_past_the_deadline() {
+        let replan = replan(&tasks(GRAPH), 10, 5.0, None);
+        let slipping: Vec<&str> = replan.slipping(5.0).iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(slipping, ["D"]);
+        assert_eq!(replan.cut, ["D"]);
+        assert_eq!(replan.after_cut.finish(), 5.0);
+    }
+
+    #[test]
+    fn finish_days_project_to_calendar_dates() {
+        // 2026-03-02T12:00:00Z.
+        let now = UNIX_EPOCH + Duration::from_secs(1_772_452_800);
+        let plan = schedule(&tasks(GRAPH), 10, &HashSet::new());
+        assert_eq!(project_date(now, 0.0).as_deref(), Some("2026-03-02"));
+        assert_eq!(
+            project_date(now, plan.finish()).as_deref(),
+            Some("2026-03-08")
+        );
+        assert_eq!(project_date(now, 0.4).as_deref(), Some("2026-03-02"));
+        assert_eq!(project_date(now, 0.6).as_deref(), Some("2026-03-03"));
+        assert_eq!(project_date(now, -3.0).as_deref(), Some("2026-03-02"));
+    }
+
+    #[test]
+    fn dates_past_year_9999_are_not_projected() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_772_452_800);
+        assert_eq!(project_date(now, 3_000_000.0), None);
+        assert_eq!(project_date(now, 1e300), None);
+        assert_eq!(project_date(now, f64::INFINITY), None);
+    }
+
+    #[test]
+    fn nothing_remaining_has_no_makespan() {
+        let done = "[[tasks]]\nid = \"A\"\ntitle = \"a\"\nstatus = \"done\"\n";
+        assert!(makespan(&tasks(done), 1, None).is_none());
+    }
+}
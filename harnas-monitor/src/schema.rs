@@ -0,0 +1,304 @@
+//! The `TASKS.toml` format as a JSON Schema (draft 2020-12), printed by `harnas-monitor schema`
+//! so generator authors can check their output with any validator. It describes what
+//! `tasks::TaskFile` accepts, no more and no less; keep the two in step. The monitor checks a
+//! file against it when the file does not load, to name every value that is wrong by its path
+//! (`/tasks/3/estimate_days`) rather than only the first one serde stops at.
+
+use std::fmt;
+
+use serde_json::{json, Value};
+
+/// Violations listed under a load error; the rest are counted.
+const MAX_LISTED: usize = 10;
+
+pub fn schema() -> Value {
+    let string = json!({ "type": "string" });
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    let ids = json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": "Ids of other tasks; informational, they never block."
+    });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "TASKS.toml",
+        "description": "A task plan as read by harnas-monitor. Unknown keys in a task are kept \
+                        as custom fields; unknown keys elsewhere are ignored.",
+        "type": "object",
+        "properties": {
+            "meta": { "$ref": "#/$defs/meta" },
+            "tasks": { "type": "array", "items": { "$ref": "#/$defs/task" } }
+        },
+        "$defs": {
+            "meta": {
+                "type": "object",
+                "properties": {
+                    "title": {
+                        "type": "string",
+                        "description": "What the plan is for; the file picker lists it."
+                    },
+                    "spec_file": string,
+                    "spec_version": string,
+                    "output_file": string,
+                    "generated_utc": {
+                        "type": "string",
+                        "format": "date-time",
+                        "description": "RFC 3339 UTC, e.g. 2026-03-02T09:15:00Z."
+                    },
+                    "targets": { "type": "array", "items": { "$ref": "#/$defs/target" } },
+                    "budgets": {
+                        "type": "object",
+                        "additionalProperties": { "type": "number", "minimum": 0 },
+                        "description": "Effort budgets in estimated days, by component."
+                    },
+                    "estimate_spread": {
+                        "type": "number",
+                        "description": "± percent around estimates without their own bounds."
+                    },
+                    "lint": {
+                        "type": "object",
+                        "properties": { "suppress": strings },
+                        "description": "Validation rules this file opts out of."
+                    }
+                }
+            },
+            "target": {
+                "type": "object",
+                "required": ["percent", "by"],
+                "properties": {
+                    "status": {
+                        "type": "string",
+                        "description": "The status whose share is tracked; done when unset."
+                    },
+                    "percent": { "type": "number", "minimum": 0, "maximum": 100 },
+                    "by": {
+                        "type": "string",
+                        "description": "YYYY-MM-DD (midnight UTC) or an RFC 3339 timestamp."
+                    }
+                }
+            },
+            "task": {
+                "type": "object",
+                "required": ["id", "title"],
+                "properties": {
+                    "id": string,
+                    "title": string,
+                    "component": string,
+                    "priority": string,
+                    "risk": { "type": "string", "description": "low, medium or high." },
+                    "status": {
+                        "type": "string",
+                        "description": "todo, in_progress, blocked, done or deferred; others \
+                                        are reported."
+                    },
+                    "spec_refs": strings,
+                    "depends_on": { "type": "array", "items": { "$ref": "#/$defs/dependency" } },
+                    "related_to": ids,
+                    "duplicates": ids,
+                    "supersedes": ids,
+                    "summary": string,
+                    "deliverables": strings,
+                    "estimate_days": { "type": "number" },
+                    "estimate_days_min": { "type": "number" },
+                    "estimate_days_max": { "type": "number" },
+                    "dod": strings,
+                    "notes": string,
+                    "links": { "type": "array", "items": { "$ref": "#/$defs/link" } },
+                    "order": {
+                        "type": "integer",
+                        "description": "Position within the task's priority group."
+                    },
+                    "deferred_until": {
+                        "type": "string",
+                        "format": "date",
+                        "description": "With status deferred, the day the task comes back."
+                    }
+                },
+                "additionalProperties": true
+            },
+            "link": {
+                "type": "object",
+                "required": ["url"],
+                "properties": {
+                    "label": string,
+                    "url": {
+                        "type": "string",
+                        "description": "A URL, or a path relative to the tasks file."
+                    }
+                }
+            },
+            "dependency": {
+                "oneOf": [
+                    { "type": "string", "description": "A task id." },
+                    {
+                        "type": "object",
+                        "required": ["id"],
+                        "properties": {
+                            "id": string,
+                            "kind": {
+                                "type": "string",
+                                "description": "Free-form; external marks an id outside this \
+                                                plan, which is not reported missing."
+                            },
+                            "note": string
+                        },
+                        "description": "A task id and why this task waits on it."
+                    }
+                ]
+            }
+        }
+    })
+}
+
+/// A value that does not match the schema, by its JSON Pointer in the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Checks a parsed document against `schema()`. Paths into `tasks` name the task's id too.
+pub fn validate(doc: &toml::Value) -> Vec<Violation> {
+    let schema = schema();
+    let mut out = Vec::new();
+    check(&schema, &schema, doc, "", &mut out);
+    let tasks = doc.get("tasks").and_then(toml::Value::as_array);
+    for v in &mut out {
+        let index = v.path.strip_prefix("/tasks/").and_then(|rest| {
+            let end = rest.find('/').unwrap_or(rest.len());
+            rest[..end].parse::<usize>().ok()
+        });
+        let id = index
+            .and_then(|i| tasks?.get(i)?.get("id")?.as_str())
+            .map(|id| format!(" ({id})"));
+        v.path.push_str(&id.unwrap_or_default());
+    }
+    out
+}
+
+/// The violations of a document that did not load, one per line, for the load error.
+pub fn describe(doc: &toml::Value) -> Option<String> {
+    let violations = validate(doc);
+    if violations.is_empty() {
+        return None;
+    }
+    let mut lines: Vec<String> = violations
+        .iter()
+        .take(MAX_LISTED)
+        .map(|v| format!("schema: {v}"))
+        .collect();
+    if violations.len() > MAX_LISTED {
+        lines.push(format!(
+            "schema: … and {} more",
+            violations.len() - MAX_LISTED
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+/// Validates `value` at `path` against `schema`, a part of `root`. Covers the keywords
+/// `schema()` uses.
+fn check(root: &Value, schema: &Value, value: &toml::Value, path: &str, out: &mut Vec<Violation>) {
+    let at = |message: String| Violation {
+        path: if path.is_empty() {
+            "/".to_string()
+        } else {
+            path.to_string()
+        },
+        message,
+    };
+    if let Some(target) = schema["$ref"].as_str() {
+        let resolved = target
+            .strip_prefix("#/$defs/")
+            .map_or(&Value::Null, |name| &root["$defs"][name]);
+        return check(root, resolved, value, path, out);
+    }
+    if let Some(branches) = schema["oneOf"].as_array() {
+        // Report inside the branch of the value's type, if there is one.
+        let typed: Vec<&Value> = branches
+            .iter()
+            .filter(|b| b["type"].as_str().is_some_and(|t| has_type(t, value)))
+            .collect();
+        match typed[..] {
+            [branch] => check(root, branch, value, path, out),
+            _ => {
+                let types: Vec<&str> = branches.iter().filter_map(|b| b["type"].as_str()).collect();
+                let got = describe_value(value);
+                out.push(at(format!("expected {}, got {got}", types.join(" or "))));
+            }
+        }
+        return;
+    }
+    if let Some(ty) = schema["type"].as_str() {
+        if !has_type(ty, value) {
+            out.push(at(format!("expected {ty}, got {}", describe_value(value))));
+            return;
+        }
+    }
+    if let Some(n) = value.as_float().or(value.as_integer().map(|i| i as f64)) {
+        if let Some(min) = schema["minimum"].as_f64().filter(|min| n < *min) {
+            out.push(at(format!("{n} is below the minimum {min}")));
+        }
+        if let Some(max) = schema["maximum"].as_f64().filter(|max| n > *max) {
+            out.push(at(format!("{n} is above the maximum {max}")));
+        }
+    }
+    match value {
+        toml::Value::Table(table) => {
+            for key in schema["required"].as_array().into_iter().flatten() {
+                if let Some(key) = key.as_str().filter(|k| !table.contains_key(*k)) {
+                    out.push(at(format!("missing required key {key:?}")));
+                }
+            }
+            for (key, item) in table {
+                let item_path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match &schema["properties"][key] {
+                    Value::Null => {}
+                    property => {
+                        check(root, property, item, &item_path, out);
+                        continue;
+                    }
+                }
+                if let extra @ Value::Object(_) = &schema["additionalProperties"] {
+                    check(root, extra, item, &item_path, out);
+                }
+            }
+        }
+        toml::Value::Array(items) => {
+            if let items_schema @ Value::Object(_) = &schema["items"] {
+                for (i, item) in items.iter().enumerate() {
+                    check(root, items_schema, item, &format!("{path}/{i}"), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(ty: &str, value: &toml::Value) -> bool {
+    match ty {
+        "string" => value.is_str(),
+        "integer" => value.is_integer(),
+        "number" => value.is_integer() || value.is_float(),
+        "boolean" => value.is_bool(),
+        "array" => value.is_array(),
+        "object" => value.is_table(),
+        _ => true,
+    }
+}
+
+/// The TOML type of a value, with the value itself when it is a short scalar.
+fn describe_value(value: &toml::Value) -> String {
+    let text = value.to_string();
+    if value.is_array() || value.is_table() || text.chars().count() > 30 {
+        value.type_str().to_string()
+    } else {
+        format!("{} {text}", value.type_str())
+    }
+}
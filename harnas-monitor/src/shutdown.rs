@@ -0,0 +1,49 @@
+//! SIGTERM and SIGHUP (a closed terminal or a killed tmux session) end the dashboard the way
+//! `q` does: the run loops notice `requested()` within a tick, stop, log the running focus
+//! session and the usage statistics, and restore the terminal. Without `install` the signals
+//! keep their default action, so the headless commands still die on them at once.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Catches SIGTERM and SIGHUP from now on. Does nothing on platforms without them.
+pub fn install() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use anyhow::Context;
+        use signal_hook::consts::{SIGHUP, SIGTERM};
+
+        let mut signals =
+            signal_hook::iterator::Signals::new([SIGTERM, SIGHUP]).context("catching signals")?;
+        std::thread::spawn(move || {
+            // Kept open so reads of the replaced stdin find nothing yet, rather than its end.
+            let mut detached = None;
+            for signal in signals.forever() {
+                REQUESTED.store(true, Ordering::Relaxed);
+                if signal == SIGHUP && detached.is_none() {
+                    detached = detach_stdin().ok();
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Whether a caught signal asked the dashboard to stop.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::Relaxed)
+}
+
+/// Puts an empty pipe where the hung-up terminal was. Reading a terminal that is gone returns
+/// end-of-file at once, and crossterm's event poll retries that forever, so the run loop would
+/// never get to look at `requested()`; an empty non-blocking pipe lets the poll time out.
+#[cfg(unix)]
+fn detach_stdin() -> std::io::Result<rustix::fd::OwnedFd> {
+    let (read, write) = rustix::pipe::pipe()?;
+    rustix::fs::fcntl_setfl(&read, rustix::fs::OFlags::NONBLOCK)?;
+    rustix::stdio::dup2_stdin(&read)?;
+    Ok(write)
+}
@@ -0,0 +1,151 @@
+//! Audible cues for content changes and new alerts. The terminal bell is the default; in builds
+//! with the `sound` cargo feature, the `[sound]` config section maps cues to audio files played
+//! by `sound.command` (or the platform's command-line player).
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use crate::diff::ChangeClass;
+
+/// Something worth an audible cue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// The tasks file content changed.
+    Changed,
+    /// A new alert was raised.
+    Alert,
+    /// A focus timer session (`t`) ran out.
+    FocusDone,
+}
+
+impl Cue {
+    pub const ALL: [Cue; 3] = [Cue::Changed, Cue::Alert, Cue::FocusDone];
+
+    /// Name used in `[sound.files]`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Cue::Changed => "changed",
+            Cue::Alert => "alert",
+            Cue::FocusDone => "focus",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SoundConfig {
+    /// Shell command playing a file; `{file}`, `{volume}` (0-100) and `{gain}` (0.00-1.00)
+    /// are substituted.
+    pub command: Option<String>,
+    /// Audio file per cue key.
+    pub files: BTreeMap<String, PathBuf>,
+    /// Initial volume in percent.
+    pub volume: Option<u8>,
+    /// Kinds of reload that play the `changed` cue; `None` means status and structural changes.
+    pub chime_on: Option<Vec<ChangeClass>>,
+}
+
+impl SoundConfig {
+    pub fn chimes_on(&self, class: ChangeClass) -> bool {
+        self.chime_on
+            .as_ref()
+            .map_or(class != ChangeClass::Cosmetic, |c| c.contains(&class))
+    }
+}
+
+/// Volume levels the `v` key cycles through; 0 is muted.
+const VOLUME_STEPS: [u8; 4] = [100, 50, 25, 0];
+
+pub fn next_volume(volume: u8) -> u8 {
+    VOLUME_STEPS
+        .iter()
+        .copied()
+        .find(|&v| v < volume)
+        .unwrap_or(VOLUME_STEPS[0])
+}
+
+pub struct Player {
+    config: SoundConfig,
+    /// Players still running; reaped on the next cue.
+    children: Vec<Child>,
+}
+
+impl Player {
+    pub fn new(config: SoundConfig) -> Self {
+        Player {
+            config,
+            children: Vec::new(),
+        }
+    }
+
+    /// Why configured sound files will not be played, if they will not.
+    pub fn unavailable(&self) -> Option<&'static str> {
+        (!self.config.files.is_empty() && !cfg!(feature = "sound"))
+            .then_some("sound files need a build with --features sound; using the bell")
+    }
+
+    /// Plays the cue's file, or rings the bell when none is configured. Nothing plays at
+    /// volume 0.
+    pub fn play(&mut self, cue: Cue, volume: u8) -> Result<()> {
+        if volume == 0 {
+            return Ok(());
+        }
+        self.children
+            .retain_mut(|c| matches!(c.try_wait(), Ok(None)));
+        match self.config.files.get(cue.key()).cloned() {
+            Some(file) if cfg!(feature = "sound") => self.play_file(&file, volume),
+            _ => {
+                bell();
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs `sound.command`, or the platform's command-line player, in the background.
+    fn play_file(&mut self, file: &Path, volume: u8) -> Result<()> {
+        let command = self.config.command.as_deref().unwrap_or(DEFAULT_COMMAND);
+        let line = command
+            .replace("{file}", &shell_quote(&file.to_string_lossy()))
+            .replace("{volume}", &volume.to_string())
+            .replace("{gain}", &format!("{:.2}", f64::from(volume) / 100.0));
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let child = Command::new(shell)
+            .args([flag, &line])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("running sound command {line:?}"))?;
+        self.children.push(child);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+const DEFAULT_COMMAND: &str = "afplay -v {gain} {file}";
+#[cfg(windows)]
+const DEFAULT_COMMAND: &str = "powershell -c (New-Object Media.SoundPlayer {file}).PlaySync()";
+#[cfg(not(any(target_os = "macos", windows)))]
+const DEFAULT_COMMAND: &str = "paplay --volume=$(( {volume} * 655 )) {file}";
+
+pub fn bell() {
+    let mut out = std::io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
+
+/// One argument for `sh -c` (or `cmd /C`), as in `sound.command` and the config editor.
+pub fn shell_quote(s: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{s}\"")
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
@@ -0,0 +1,323 @@
+//! Pending in-TUI edits. Status flips, field edits, reorders and fixes are staged here and
+//! shown layered over the file until `W` writes them as one batch, so a file the generator also
+//! rewrites is never left half-edited.
+
+use anyhow::{anyhow, Result};
+use toml_edit::DocumentMut;
+
+use crate::analysis::Fix;
+use crate::writer::{
+    add_dependency, apply_fix, mark_dependency_external, remove_dependency, remove_task_field,
+    rename_component, set_task_field, set_task_number, set_task_orders,
+};
+
+/// Fields the inline editor (`E`) can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskField {
+    Title,
+    Priority,
+    Component,
+    Estimate,
+}
+
+impl TaskField {
+    pub const ALL: [TaskField; 4] = [
+        TaskField::Title,
+        TaskField::Priority,
+        TaskField::Component,
+        TaskField::Estimate,
+    ];
+
+    /// The key in `TASKS.toml`.
+    pub fn key(self) -> &'static str {
+        match self {
+            TaskField::Title => "title",
+            TaskField::Priority => "priority",
+            TaskField::Component => "component",
+            TaskField::Estimate => "estimate_days",
+        }
+    }
+
+    /// Picked from the values the file already uses rather than typed.
+    pub fn is_choice(self) -> bool {
+        matches!(self, TaskField::Priority | TaskField::Component)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Status {
+        task: String,
+        to: String,
+    },
+    Priority {
+        task: String,
+        to: String,
+    },
+    Title {
+        task: String,
+        to: String,
+    },
+    Component {
+        task: String,
+        to: String,
+    },
+    Estimate {
+        task: String,
+        days: f64,
+    },
+    /// Explicit `order` fields for a priority group after `task` was moved within it.
+    Orders {
+        task: String,
+        group: String,
+        orders: Vec<(String, i64)>,
+    },
+    /// Set the task aside until a day (`YYYY-MM-DD`, `L`), or with `None` bring it back as
+    /// `todo`.
+    Defer {
+        task: String,
+        until: Option<String>,
+    },
+    Fix(Fix),
+    /// Drop a redundant `depends_on` entry (see `prune`), or one on a task that went away.
+    DropDependency {
+        task: String,
+        dep: String,
+    },
+    /// Add a `depends_on` entry, as suggested from shared `spec_refs` (see `suggest`).
+    AddDependency {
+        task: String,
+        dep: String,
+    },
+    /// Keep a `depends_on` entry naming no task, as a dependency outside the plan.
+    ExternalDependency {
+        task: String,
+        dep: String,
+    },
+    /// Give every task of component `from` the component `to` (`rename-component`).
+    RenameComponent {
+        from: String,
+        to: String,
+    },
+}
+
+impl Edit {
+    pub fn describe(&self) -> String {
+        match self {
+            Edit::Status { task, to } => format!("{task}: status = {to}"),
+            Edit::Priority { task, to } => format!("{task}: priority = {to}"),
+            Edit::Title { task, to } => format!("{task}: title = {to:?}"),
+            Edit::Component { task, to } => format!("{task}: component = {to}"),
+            Edit::Estimate { task, days } => format!("{task}: estimate_days = {days}"),
+            Edit::Orders { task, group, .. } => format!("{task}: moved within priority '{group}'"),
+            Edit::Defer {
+                task,
+                until: Some(day),
+            } => format!("{task}: deferred until {day}"),
+            Edit::Defer { task, until: None } => format!("{task}: back from deferred"),
+            Edit::Fix(fix) => format!("fix: {}", fix.describe()),
+            Edit::DropDependency { task, dep } => format!("{task}: drop depends_on {dep}"),
+            Edit::AddDependency { task, dep } => format!("{task}: add depends_on {dep}"),
+            Edit::ExternalDependency { task, dep } => {
+                format!("{task}: depends_on {dep} is external")
+            }
+            Edit::RenameComponent { from, to } => format!("component {from} → {to}"),
+        }
+    }
+
+    /// The task the edit is about, for marking it in the table.
+    pub fn task(&self) -> Option<&str> {
+        match self {
+            Edit::Status { task, .. }
+            | Edit::Priority { task, .. }
+            | Edit::Title { task, .. }
+            | Edit::Component { task, .. }
+            | Edit::Estimate { task, .. }
+            | Edit::Orders { task, .. }
+            | Edit::Defer { task, .. }
+            | Edit::DropDependency { task, .. }
+            | Edit::AddDependency { task, .. }
+            | Edit::ExternalDependency { task, .. } => Some(task),
+            Edit::Fix(Fix::RenameReference { task, .. } | Fix::NormalizeStatus { task, .. }) => {
+                Some(task)
+            }
+            Edit::Fix(Fix::RenameDuplicateId { to, .. }) => Some(to),
+            Edit::RenameComponent { .. } => None,
+        }
+    }
+
+    /// Whether `self` makes an earlier staged `other` redundant.
+    fn supersedes(&self, other: &Edit) -> bool {
+        match (self, other) {
+            (Edit::Status { task: a, .. }, Edit::Status { task: b, .. })
+            | (Edit::Priority { task: a, .. }, Edit::Priority { task: b, .. })
+            | (Edit::Title { task: a, .. }, Edit::Title { task: b, .. })
+            | (Edit::Component { task: a, .. }, Edit::Component { task: b, .. })
+            | (Edit::Estimate { task: a, .. }, Edit::Estimate { task: b, .. })
+            | (Edit::Defer { task: a, .. }, Edit::Defer { task: b, .. })
+            | (Edit::Defer { task: a, .. }, Edit::Status { task: b, .. })
+            | (Edit::Status { task: a, .. }, Edit::Defer { task: b, .. }) => a == b,
+            (Edit::Orders { group: a, .. }, Edit::Orders { group: b, .. }) => a == b,
+            (
+                Edit::DropDependency { task: a, dep: x },
+                Edit::DropDependency { task: b, dep: y },
+            ) => a == b && x == y,
+            (Edit::AddDependency { task: a, dep: x }, Edit::AddDependency { task: b, dep: y }) => {
+                a == b && x == y
+            }
+            (Edit::RenameComponent { from: a, .. }, Edit::RenameComponent { from: b, .. }) => {
+                a.trim().eq_ignore_ascii_case(b.trim())
+            }
+            _ => false,
+        }
+    }
+
+    pub fn apply(&self, doc: &mut DocumentMut) -> Result<()> {
+        match self {
+            Edit::Status { task, to } => set_task_field(doc, task, "status", to),
+            Edit::Priority { task, to } => set_task_field(doc, task, "priority", to),
+            Edit::Title { task, to } => set_task_field(doc, task, "title", to),
+            Edit::Component { task, to } => set_task_field(doc, task, "component", to),
+            Edit::Estimate { task, days } => set_task_number(doc, task, "estimate_days", *days),
+            Edit::Orders { orders, .. } => set_task_orders(doc, orders),
+            Edit::Defer {
+                task,
+                until: Some(day),
+            } => {
+                set_task_field(doc, task, "status", "deferred")?;
+                set_task_field(doc, task, "deferred_until", day)
+            }
+            Edit::Defer { task, until: None } => {
+                set_task_field(doc, task, "status", "todo")?;
+                remove_task_field(doc, task, "deferred_until")
+            }
+            Edit::Fix(fix) => apply_fix(doc, fix),
+            Edit::DropDependency { task, dep } => remove_dependency(doc, task, dep),
+            Edit::AddDependency { task, dep } => add_dependency(doc, task, dep),
+            Edit::ExternalDependency { task, dep } => mark_dependency_external(doc, task, dep),
+            Edit::RenameComponent { from, to } => rename_component(doc, from, to).map(|_| ()),
+        }
+    }
+}
+
+/// Adds `edit`, replacing a staged edit of the same field (or the same priority group's orders).
+pub fn stage(edits: &mut Vec<Edit>, edit: Edit) {
+    edits.retain(|e| !edit.supersedes(e));
+    edits.push(edit);
+}
+
+/// Applies `edits` to `source` in order. Edits that no longer apply (the generator removed the
+/// task, or already made the change) are skipped and returned by index with the reason.
+pub fn apply_edits(source: &str, edits: &[Edit]) -> Result<(String, Vec<(usize, String)>)> {
+    let mut doc: DocumentMut = source.parse().map_err(|e| anyhow!("parsing TOML: {e}"))?;
+    let mut skipped = Vec::new();
+    for (i, edit) in edits.iter().enumerate() {
+        // Edits change one value each; on failure the document is left as it was.
+        let before = doc.clone();
+        if let Err(e) = edit.apply(&mut doc) {
+            doc = before;
+            skipped.push((i, e.to_string()));
+        }
+    }
+    Ok((doc.to_string(), skipped))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    Removed,
+    Added,
+}
+
+/// One line of a line diff, with its 1-based number in the old (`Same`, `Removed`) or new
+/// (`Added`) text.
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub line: usize,
+    pub text: String,
+}
+
+/// Lines of context kept around each change.
+const CONTEXT: usize = 1;
+
+/// Changed lines between `before` and `after` with `CONTEXT` lines around each change. `None`
+/// entries separate hunks.
+pub fn line_diff(before: &str, after: &str) -> Vec<Option<DiffLine>> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    // Longest common subsequence over the differing middle, which staged edits keep small.
+    let mut lcs = vec![vec![0u32; mid_b.len() + 1]; mid_a.len() + 1];
+    for i in (0..mid_a.len()).rev() {
+        for j in (0..mid_b.len()).rev() {
+            lcs[i][j] = if mid_a[i] == mid_b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut full: Vec<DiffLine> = a[..prefix]
+        .iter()
+        .enumerate()
+        .map(|(i, s)| same(i, s))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < mid_a.len() || j < mid_b.len() {
+        if i < mid_a.len() && j < mid_b.len() && mid_a[i] == mid_b[j] {
+            full.push(same(prefix + i, mid_a[i]));
+            (i, j) = (i + 1, j + 1);
+        } else if i < mid_a.len() && (j == mid_b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            full.push(DiffLine {
+                kind: DiffKind::Removed,
+                line: prefix + i + 1,
+                text: mid_a[i].to_string(),
+            });
+            i += 1;
+        } else {
+            full.push(DiffLine {
+                kind: DiffKind::Added,
+                line: prefix + j + 1,
+                text: mid_b[j].to_string(),
+            });
+            j += 1;
+        }
+    }
+    let tail = a.len() - suffix;
+    full.extend(a[tail..].iter().enumerate().map(|(k, s)| same(tail + k, s)));
+
+    let changed: Vec<usize> = (0..full.len())
+        .filter(|&k| full[k].kind != DiffKind::Same)
+        .collect();
+    let keep = |k: usize| changed.iter().any(|&c| c.abs_diff(k) <= CONTEXT);
+    let mut out = Vec::new();
+    let mut last = None;
+    for (k, line) in full.into_iter().enumerate() {
+        if !keep(k) {
+            continue;
+        }
+        if last.is_some_and(|l| l + 1 != k) {
+            out.push(None);
+        }
+        out.push(Some(line));
+        last = Some(k);
+    }
+    out
+}
+
+fn same(i: usize, s: &str) -> DiffLine {
+    DiffLine {
+        kind: DiffKind::Same,
+        line: i + 1,
+        text: s.to_string(),
+    }
+}
@@ -0,0 +1,335 @@
+//! The history as an SQLite database, in builds with the `sqlite` cargo feature. Every snapshot
+//! the history records also becomes a row in `loads` of a sibling `TASKS.history.sqlite`, with
+//! one row per task in `task_states` that adds the task's title, component, priority and
+//! estimate, so `harnas-monitor history query` can answer questions like "how long does `api`
+//! take from todo to done" without a script. The JSONL history stays what the dashboard reads;
+//! a new database is first filled from it (without the extra columns, which it lacks).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::history::Snapshot;
+use crate::tasks::LoadedTasks;
+
+/// Bumped when the tables change; a database with another version is not written to.
+#[cfg(feature = "sqlite")]
+const SCHEMA_VERSION: i64 = 1;
+
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS loads (
+    id INTEGER PRIMARY KEY,
+    at TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    spec_version TEXT
+);
+CREATE TABLE IF NOT EXISTS task_states (
+    load_id INTEGER NOT NULL REFERENCES loads(id),
+    task_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    blocked INTEGER NOT NULL,
+    title TEXT,
+    component TEXT,
+    priority TEXT,
+    estimate_days REAL,
+    PRIMARY KEY (load_id, task_id)
+);
+CREATE INDEX IF NOT EXISTS task_states_task ON task_states (task_id, load_id);
+CREATE VIEW IF NOT EXISTS states AS
+    SELECT l.at, s.* FROM task_states s JOIN loads l ON l.id = s.load_id;
+CREATE VIEW IF NOT EXISTS transitions AS
+    SELECT at, load_id, task_id, component, prev AS from_status, status AS to_status FROM (
+        SELECT at, load_id, task_id, component, status,
+               LAG(status) OVER (PARTITION BY task_id ORDER BY load_id) AS prev
+        FROM states)
+    WHERE prev IS NOT status;
+";
+
+/// The named queries `history query` accepts besides SQL: name, what it answers, and the SQL.
+pub const QUERIES: [(&str, &str, &str); 4] = [
+    (
+        "cycle-time",
+        "days from first todo to done, per component",
+        "WITH started AS (
+             SELECT task_id, MIN(at) AS at FROM states WHERE status = 'todo' GROUP BY task_id
+         ), finished AS (
+             SELECT s.task_id, MIN(s.at) AS at FROM states s JOIN started USING (task_id)
+             WHERE s.status = 'done' AND s.at > started.at GROUP BY s.task_id
+         ), latest AS (
+             SELECT task_id, component FROM task_states t
+             WHERE load_id = (SELECT MAX(load_id) FROM task_states u WHERE u.task_id = t.task_id)
+         ), days AS (
+             SELECT COALESCE(latest.component, '(none)') AS component,
+                    julianday(finished.at) - julianday(started.at) AS days
+             FROM started JOIN finished USING (task_id) LEFT JOIN latest USING (task_id)
+         )
+         SELECT component, COUNT(*) AS tasks, AVG(days) AS avg_days,
+                MIN(days) AS min_days, MAX(days) AS max_days
+         FROM days GROUP BY component ORDER BY avg_days DESC",
+    ),
+    (
+        "throughput",
+        "tasks finished per week (weeks start on Monday)",
+        "SELECT date(at, '-6 days', 'weekday 1') AS week, COUNT(*) AS done
+         FROM transitions WHERE to_status = 'done' AND from_status IS NOT NULL
+         GROUP BY week ORDER BY week",
+    ),
+    (
+        "transitions",
+        "the 50 newest status changes",
+        "SELECT at, task_id, from_status, to_status FROM transitions
+         WHERE from_status IS NOT NULL ORDER BY load_id DESC, task_id LIMIT 50",
+    ),
+    (
+        "progress",
+        "task, done and blocked counts at each recorded load",
+        "SELECT at, COUNT(*) AS tasks, SUM(status = 'done') AS done, SUM(blocked) AS blocked
+         FROM states GROUP BY load_id ORDER BY load_id",
+    ),
+];
+
+/// `TASKS.history.jsonl` -> `TASKS.history.sqlite`, in the same directory.
+pub fn store_path(history_path: &Path) -> PathBuf {
+    history_path.with_extension("sqlite")
+}
+
+/// A query's result, every value already rendered as text.
+#[derive(Debug, Default)]
+pub struct Table {
+    pub columns: Vec<String>,
+    /// Cells with whether they are numbers, which the table aligns to the right.
+    pub rows: Vec<Vec<(String, bool)>>,
+}
+
+/// Adds `snapshot` of `loaded` to the database next to the JSONL history at `history_path`,
+/// creating the database from that history first. Call it before the snapshot is appended to
+/// the JSONL file, or a new database gets it twice.
+#[cfg(feature = "sqlite")]
+pub fn record(history_path: &Path, snapshot: &Snapshot, loaded: &LoadedTasks) -> Result<()> {
+    use anyhow::Context;
+    use rusqlite::params;
+
+    use crate::tasks::id_occurrences;
+
+    let path = store_path(history_path);
+    let mut conn = open(&path, history_path)?;
+    let tx = conn.transaction().context("starting a transaction")?;
+    let load = insert_load(&tx, snapshot)?;
+    {
+        let mut insert = tx
+            .prepare_cached(
+                "INSERT INTO task_states (load_id, task_id, status, blocked, title, component,
+                 priority, estimate_days) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )
+            .context("preparing the task insert")?;
+        // The snapshot has one state per id, the first task's; repeats get no row of their own.
+        let occurrences = id_occurrences(&loaded.tasks.tasks);
+        for (t, _) in loaded.tasks.tasks.iter().zip(&occurrences).filter(|(_, &n)| n == 0) {
+            let Some(state) = snapshot.tasks.get(&t.id) else {
+                continue;
+            };
+            insert
+                .execute(params![
+                    load,
+                    t.id,
+                    state.status,
+                    state.blocked,
+                    t.title,
+                    t.component,
+                    t.priority,
+                    t.estimate_days
+                ])
+                .with_context(|| format!("recording {} in {}", t.id, path.display()))?;
+        }
+    }
+    tx.commit()
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn record(_history_path: &Path, _snapshot: &Snapshot, _loaded: &LoadedTasks) -> Result<()> {
+    Ok(())
+}
+
+/// Runs `query`, one of `QUERIES` by name or a read-only SQL statement, against the database
+/// next to the JSONL history at `history_path`. A missing database is created from the JSONL
+/// history first.
+#[cfg(feature = "sqlite")]
+pub fn query(history_path: &Path, query: &str) -> Result<Table> {
+    use anyhow::{bail, Context};
+    use rusqlite::types::ValueRef;
+    use rusqlite::{Connection, OpenFlags};
+
+    let path = store_path(history_path);
+    let sql = match QUERIES.iter().find(|(name, _, _)| *name == query.trim()) {
+        Some((_, _, sql)) => *sql,
+        None if !query.trim().contains(char::is_whitespace) => {
+            bail!(
+                "unknown query {:?}; give SQL or one of:\n{}",
+                query.trim(),
+                query_list()
+            )
+        }
+        None => query,
+    };
+    if !path.exists() {
+        if !history_path.exists() {
+            bail!("no history yet: {} does not exist", history_path.display());
+        }
+        open(&path, history_path)?;
+    }
+    let conn = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("opening {}", path.display()))?;
+    let mut stmt = conn.prepare(sql).context("preparing the query")?;
+    if !stmt.readonly() {
+        bail!("history queries cannot change the database; use SELECT");
+    }
+    let mut table = Table {
+        columns: stmt.column_names().iter().map(|c| c.to_string()).collect(),
+        rows: Vec::new(),
+    };
+    let mut rows = stmt.query([]).context("running the query")?;
+    while let Some(row) = rows.next().context("running the query")? {
+        let mut cells = Vec::with_capacity(table.columns.len());
+        for i in 0..table.columns.len() {
+            cells.push(match row.get_ref(i)? {
+                ValueRef::Null => (String::new(), false),
+                ValueRef::Integer(n) => (n.to_string(), true),
+                ValueRef::Real(x) => (format!("{x:.2}"), true),
+                ValueRef::Text(s) => (String::from_utf8_lossy(s).into_owned(), false),
+                ValueRef::Blob(b) => (format!("<{} bytes>", b.len()), false),
+            });
+        }
+        table.rows.push(cells);
+    }
+    Ok(table)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn query(_history_path: &Path, _query: &str) -> Result<Table> {
+    anyhow::bail!("history queries need a build with --features sqlite")
+}
+
+/// Opens the database at `path`, creating the tables and importing the JSONL history at
+/// `history_path` when it has no loads yet.
+#[cfg(feature = "sqlite")]
+fn open(path: &Path, history_path: &Path) -> Result<rusqlite::Connection> {
+    use anyhow::{bail, Context};
+    use rusqlite::params;
+
+    let mut conn =
+        rusqlite::Connection::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |r| r.get(0))
+        .with_context(|| format!("reading {}", path.display()))?;
+    if version != 0 && version != SCHEMA_VERSION {
+        bail!(
+            "{} has schema version {version}; this build writes version {SCHEMA_VERSION}",
+            path.display()
+        );
+    }
+    conn.execute_batch(SCHEMA)
+        .and_then(|()| conn.pragma_update(None, "user_version", SCHEMA_VERSION))
+        .with_context(|| format!("creating the tables in {}", path.display()))?;
+    let loads: i64 = conn
+        .query_row("SELECT COUNT(*) FROM loads", [], |r| r.get(0))
+        .with_context(|| format!("reading {}", path.display()))?;
+    if loads > 0 {
+        return Ok(conn);
+    }
+    let history = crate::history::History::load_limited(history_path, usize::MAX)?;
+    let tx = conn.transaction().context("starting a transaction")?;
+    for snapshot in &history.snapshots {
+        let load = insert_load(&tx, snapshot)?;
+        let mut insert = tx
+            .prepare_cached(
+                "INSERT INTO task_states (load_id, task_id, status, blocked)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .context("preparing the task insert")?;
+        for (id, state) in &snapshot.tasks {
+            insert
+                .execute(params![load, id, state.status, state.blocked])
+                .with_context(|| format!("importing {}", history_path.display()))?;
+        }
+    }
+    tx.commit()
+        .with_context(|| format!("importing {}", history_path.display()))?;
+    Ok(conn)
+}
+
+/// Adds the `loads` row for `snapshot` and returns its id.
+#[cfg(feature = "sqlite")]
+fn insert_load(tx: &rusqlite::Transaction, snapshot: &Snapshot) -> Result<i64> {
+    use anyhow::Context;
+
+    tx.execute(
+        "INSERT INTO loads (at, hash, spec_version) VALUES (?1, ?2, ?3)",
+        rusqlite::params![
+            snapshot.at,
+            format!("{:016x}", snapshot.hash),
+            snapshot.spec.as_ref().and_then(|s| s.version.clone())
+        ],
+    )
+    .context("recording the load")?;
+    Ok(tx.last_insert_rowid())
+}
+
+/// The named queries, one per line, for `--help`-style listings.
+pub fn query_list() -> String {
+    let lines: Vec<String> = QUERIES
+        .iter()
+        .map(|(name, about, _)| format!("  {name:<12} {about}"))
+        .collect();
+    lines.join("\n")
+}
+
+/// `table` as aligned columns under a header, numbers to the right.
+pub fn write_table(out: &mut dyn Write, table: &Table) -> Result<()> {
+    use anyhow::Context;
+
+    let mut widths: Vec<usize> = table.columns.iter().map(|c| c.chars().count()).collect();
+    for row in &table.rows {
+        for (w, (cell, _)) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<(&str, bool)>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|((cell, number), &w)| {
+                if *number {
+                    format!("{cell:>w$}")
+                } else {
+                    format!("{cell:<w$}")
+                }
+            })
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+    // Headers line up with their column's first value.
+    let header = line(
+        table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.as_str(), table.rows.first().is_some_and(|r| r[i].1)))
+            .collect(),
+    );
+    writeln!(out, "{header}")?;
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    writeln!(out, "{}", rule.join("  "))?;
+    for row in &table.rows {
+        writeln!(
+            out,
+            "{}",
+            line(row.iter().map(|(c, n)| (c.as_str(), *n)).collect())
+        )?;
+    }
+    let rows = table.rows.len();
+    writeln!(out, "({rows} row{})", if rows == 1 { "" } else { "s" })?;
+    out.flush().context("writing output")
+}
@@ -0,0 +1,135 @@
+//! Dependencies the file may be missing: two tasks that cite the same `spec_refs`, where one
+//! title reads as an earlier stage of the work than the other ("Implement X" and "Test X"). The
+//! details pane lists them for the selected task as hints, and `+` stages the first one as a
+//! `depends_on` entry. They never count as problems.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::tasks::{task_status, Task, TaskFile};
+
+/// Stages of the work by a title's leading verb, earliest first. Verbs of one stage suggest
+/// nothing between each other.
+const STAGES: [(&str, &[&str]); 4] = [
+    (
+        "design",
+        &[
+            "design",
+            "define",
+            "specify",
+            "spec",
+            "plan",
+            "research",
+            "prototype",
+        ],
+    ),
+    (
+        "implement",
+        &[
+            "implement",
+            "build",
+            "add",
+            "create",
+            "write",
+            "develop",
+            "introduce",
+            "expose",
+            "migrate",
+            "refactor",
+        ],
+    ),
+    (
+        "test",
+        &["test", "verify", "validate", "benchmark", "qa", "document"],
+    ),
+    (
+        "release",
+        &["release", "ship", "deploy", "launch", "announce"],
+    ),
+];
+/// Where testing sits in `STAGES`.
+const TEST_STAGE: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// The task that would wait, and the one it would wait on.
+    pub task: String,
+    pub dep: String,
+    /// The `spec_refs` both cite.
+    pub shared: Vec<String>,
+    /// The stages the two titles read as, the dependency's first.
+    pub stages: (&'static str, &'static str),
+}
+
+/// What `task` may be missing in its `depends_on`, in file order: earlier-stage tasks citing a
+/// spec ref it cites, that it does not already wait on, even indirectly, and that do not wait on
+/// it. A done task gets none.
+pub fn suggested_dependencies(file: &TaskFile, task: &Task) -> Vec<Suggestion> {
+    let Some(stage) = stage_index(&task.title) else {
+        return Vec::new();
+    };
+    if task.spec_refs.is_empty() || task_status(task) == "done" {
+        return Vec::new();
+    }
+    let refs: HashSet<&str> = task.spec_refs.iter().map(|r| r.trim()).collect();
+    let mut by_id: HashMap<&str, &Task> = HashMap::new();
+    for t in &file.tasks {
+        by_id.entry(t.id.as_str()).or_insert(t);
+    }
+    let upstream = waits_on(&by_id, &task.id);
+    file.tasks
+        .iter()
+        .filter(|u| u.id != task.id && !upstream.contains(u.id.as_str()))
+        .filter_map(|u| {
+            let earlier = stage_index(&u.title).filter(|s| *s < stage)?;
+            let shared: Vec<String> = u
+                .spec_refs
+                .iter()
+                .filter(|r| refs.contains(r.trim()))
+                .cloned()
+                .collect();
+            if shared.is_empty() || waits_on(&by_id, &u.id).contains(task.id.as_str()) {
+                return None;
+            }
+            Some(Suggestion {
+                task: task.id.clone(),
+                dep: u.id.clone(),
+                shared,
+                stages: (STAGES[earlier].0, STAGES[stage].0),
+            })
+        })
+        .collect()
+}
+
+/// The stage of a title by its first word; "Add tests for …" and the like count as testing.
+fn stage_index(title: &str) -> Option<usize> {
+    let mut words = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase);
+    let first = words.next()?;
+    let stage = STAGES
+        .iter()
+        .position(|(_, verbs)| verbs.contains(&first.as_str()))?;
+    match words.next().as_deref() {
+        Some("test" | "tests") if stage < TEST_STAGE => Some(TEST_STAGE),
+        _ => Some(stage),
+    }
+}
+
+/// Every id `id` waits on through `depends_on`, directly or not.
+fn waits_on<'a>(by_id: &HashMap<&str, &'a Task>, id: &str) -> HashSet<&'a str> {
+    let mut seen: HashSet<&'a str> = HashSet::new();
+    let mut queue: Vec<&'a str> = by_id
+        .get(id)
+        .map(|t| t.depends_on.iter().map(|d| d.id.as_str()).collect())
+        .unwrap_or_default();
+    while let Some(next) = queue.pop() {
+        if !seen.insert(next) {
+            continue;
+        }
+        if let Some(t) = by_id.get(next) {
+            queue.extend(t.depends_on.iter().map(|d| d.id.as_str()));
+        }
+    }
+    seen
+}
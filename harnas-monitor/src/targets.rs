@@ -0,0 +1,182 @@
+//! Progress targets such as "80% done by 2026-03-01", from the config's `[[targets]]` and the
+//! tasks file's `[[meta.targets]]`. Each target is projected to its deadline with the velocity
+//! recorded in the history, which tells whether the plan is ahead of or behind schedule.
+
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+
+use crate::history::History;
+use crate::tasks::{normalize_status, task_status, LoadedTasks};
+
+/// How far back the velocity looks.
+const VELOCITY_WINDOW: Duration = Duration::from_secs(14 * 86_400);
+/// Less history than this gives no velocity; a few edits in one sitting say little about pace.
+const MIN_VELOCITY_SPAN: Duration = Duration::from_secs(86_400);
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// A target as written in TOML.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TargetSpec {
+    /// Status whose share is tracked; defaults to `done`.
+    pub status: Option<String>,
+    pub percent: f64,
+    /// `YYYY-MM-DD` (midnight UTC) or an RFC 3339 timestamp.
+    pub by: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// Normalized status.
+    pub status: String,
+    pub percent: f64,
+    pub by: SystemTime,
+    /// `by` as written.
+    pub by_label: String,
+}
+
+pub fn parse_target(spec: &TargetSpec) -> Result<Target> {
+    if !(0.0..=100.0).contains(&spec.percent) {
+        bail!("percent must be 0-100, got {}", spec.percent);
+    }
+    let by = spec.by.trim();
+    let parsed = if by.len() == 10 {
+        humantime::parse_rfc3339(&format!("{by}T00:00:00Z"))
+    } else {
+        humantime::parse_rfc3339_weak(by)
+    };
+    Ok(Target {
+        status: normalize_status(spec.status.as_deref().unwrap_or("done")),
+        percent: spec.percent,
+        by: parsed.map_err(|e| anyhow!("by = {by:?}: {e}"))?,
+        by_label: by.to_string(),
+    })
+}
+
+/// Where a target stands, ordered from least to most in need of attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Schedule {
+    Met,
+    Ahead,
+    /// Not met yet, and the history is too short for a velocity.
+    Unknown,
+    Behind,
+    /// The deadline passed without the target being met.
+    Missed,
+}
+
+impl Schedule {
+    pub fn label(self) -> &'static str {
+        match self {
+            Schedule::Met => "met",
+            Schedule::Ahead => "ahead",
+            Schedule::Unknown => "no velocity yet",
+            Schedule::Behind => "behind",
+            Schedule::Missed => "missed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TargetProgress {
+    pub target: Target,
+    /// Current share of tasks in the target's status, in percent.
+    pub current: f64,
+    /// Percentage points per day over the velocity window.
+    pub velocity: Option<f64>,
+    /// Share expected at the deadline at the current velocity.
+    pub projected: Option<f64>,
+    pub schedule: Schedule,
+}
+
+/// Tracks each target against the current load. `include` limits the history to the tasks in
+/// view, as in focus mode.
+pub fn track_targets(
+    targets: &[Target],
+    loaded: &LoadedTasks,
+    history: &History,
+    include: impl Fn(&str) -> bool,
+    now: SystemTime,
+) -> Vec<TargetProgress> {
+    targets
+        .iter()
+        .map(|target| {
+            let tasks = &loaded.tasks.tasks;
+            let matching = tasks
+                .iter()
+                .filter(|t| task_status(t) == target.status)
+                .count();
+            let current = percent(matching, tasks.len());
+            let velocity = velocity(history, &target.status, current, &include, now);
+            let days_left = target
+                .by
+                .duration_since(now)
+                .map(|d| d.as_secs_f64() / SECS_PER_DAY)
+                .ok();
+            let projected = velocity
+                .zip(days_left)
+                .map(|(v, days)| (current + v * days).clamp(0.0, 100.0));
+            let schedule = match (current >= target.percent, days_left, projected) {
+                (true, _, _) => Schedule::Met,
+                (false, None, _) => Schedule::Missed,
+                (false, Some(_), None) => Schedule::Unknown,
+                (false, Some(_), Some(p)) if p >= target.percent => Schedule::Ahead,
+                (false, Some(_), Some(_)) => Schedule::Behind,
+            };
+            TargetProgress {
+                target: target.clone(),
+                current,
+                velocity,
+                projected,
+                schedule,
+            }
+        })
+        .collect()
+}
+
+/// The target most in need of attention; among equals, the one due first.
+pub fn headline(progress: &[TargetProgress]) -> Option<&TargetProgress> {
+    progress.iter().max_by(|a, b| {
+        a.schedule
+            .cmp(&b.schedule)
+            .then_with(|| b.target.by.cmp(&a.target.by))
+    })
+}
+
+/// Change in `status`'s share per day, from the last snapshot taken before the window (or the
+/// oldest one) to now.
+fn velocity(
+    history: &History,
+    status: &str,
+    current: f64,
+    include: impl Fn(&str) -> bool,
+    now: SystemTime,
+) -> Option<f64> {
+    let dated = history
+        .snapshots
+        .iter()
+        .filter_map(|s| Some((humantime::parse_rfc3339(&s.at).ok()?, s)));
+    let window_start = now.checked_sub(VELOCITY_WINDOW)?;
+    let (at, base) = dated
+        .clone()
+        .filter(|(at, _)| *at <= window_start)
+        .max_by_key(|(at, _)| *at)
+        .or_else(|| dated.min_by_key(|(at, _)| *at))?;
+    let span = now.duration_since(at).ok()?;
+    if span < MIN_VELOCITY_SPAN {
+        return None;
+    }
+    let tasks: Vec<_> = base.tasks.iter().filter(|(id, _)| include(id)).collect();
+    let matching = tasks.iter().filter(|(_, s)| s.status == status).count();
+    let then = percent(matching, tasks.len());
+    Some((current - then) / (span.as_secs_f64() / SECS_PER_DAY))
+}
+
+fn percent(n: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        n as f64 * 100.0 / total as f64
+    }
+}
@@ -3,21 +3,49 @@ use std::path::Path;
 use std::time::SystemTime;
 
 use anyhow::{anyhow, Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{find_problems, Problem};
+use crate::clock;
+use crate::coverage::{compute_coverage, Coverage};
+use crate::targets::TargetSpec;
+use crate::workspace::Workspace;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct TaskFile {
     pub meta: Option<Meta>,
     #[serde(default)]
     pub tasks: Vec<Task>,
+    /// Other projects' tasks, for `project:ID` dependencies; filled by `link_workspace`.
+    #[serde(skip)]
+    pub workspace: Workspace,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Meta {
+    /// What the plan is for, e.g. the package's name; listed by the file picker.
+    pub title: Option<String>,
     pub spec_file: Option<String>,
     pub spec_version: Option<String>,
     pub output_file: Option<String>,
     pub generated_utc: Option<String>,
+    /// Progress targets shipped with the plan.
+    #[serde(default)]
+    pub targets: Vec<TargetSpec>,
+    /// Effort budgets in estimated days, by component.
+    #[serde(default)]
+    pub budgets: BTreeMap<String, f64>,
+    /// ± percent around every estimate without bounds of its own (see `estimate_range`).
+    pub estimate_spread: Option<f64>,
+    #[serde(default)]
+    pub lint: LintMeta,
+}
+
+/// `[meta.lint]`: validation rules this file opts out of (see `analysis::Suppression`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LintMeta {
+    #[serde(default)]
+    pub suppress: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -26,18 +54,160 @@ pub struct Task {
     pub title: String,
     pub component: Option<String>,
     pub priority: Option<String>,
+    /// `low`, `medium` or `high`; weighs into the risk sort (`o`).
+    pub risk: Option<String>,
     pub status: Option<String>,
     #[serde(default)]
     pub spec_refs: Vec<String>,
     #[serde(default)]
-    pub depends_on: Vec<String>,
+    pub depends_on: Vec<Dependency>,
+    /// Informational links; unlike `depends_on` they never block.
+    #[serde(default)]
+    pub related_to: Vec<String>,
+    #[serde(default)]
+    pub duplicates: Vec<String>,
+    #[serde(default)]
+    pub supersedes: Vec<String>,
     pub summary: Option<String>,
     #[serde(default)]
     pub deliverables: Vec<String>,
     pub estimate_days: Option<f64>,
+    /// Optimistic and pessimistic bounds around `estimate_days` (see `estimate_range`).
+    pub estimate_days_min: Option<f64>,
+    pub estimate_days_max: Option<f64>,
     #[serde(default)]
     pub dod: Vec<String>,
     pub notes: Option<String>,
+    /// Pull requests, design docs and dashboards tied to the task, opened with `1`–`9`.
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// Explicit position within the task's priority group, set by the monitor's reorder keys.
+    pub order: Option<i64>,
+    /// `YYYY-MM-DD`: with status `deferred`, the day the task comes back (set by `L`).
+    pub deferred_until: Option<String>,
+    /// 1-based line of the task's `[[tasks]]` header, when it could be located.
+    #[serde(skip)]
+    pub line: Option<usize>,
+    /// Keys the generator added beyond the known schema (e.g. `reviewer`, `ticket`).
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, toml::Value>,
+}
+
+/// One `links` entry: `{ label = "PR", url = "https://…" }`. A `url` without a scheme is a path
+/// relative to the tasks file.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Link {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub url: String,
+}
+
+impl Link {
+    /// The label, or the URL when there is none.
+    pub fn name(&self) -> &str {
+        self.label
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .unwrap_or(&self.url)
+    }
+}
+
+/// The `kind` of a dependency on something outside the plan; its id is not reported missing.
+pub const EXTERNAL_KIND: &str = "external";
+
+/// One `depends_on` entry: a plain id, or a table that also says why the task waits on it
+/// (`{ id = "T-3", kind = "api", note = "needs schema" }`).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawDependency")]
+pub struct Dependency {
+    pub id: String,
+    pub kind: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDependency {
+    Id(String),
+    Annotated {
+        id: String,
+        kind: Option<String>,
+        note: Option<String>,
+    },
+}
+
+impl From<RawDependency> for Dependency {
+    fn from(raw: RawDependency) -> Self {
+        match raw {
+            RawDependency::Id(id) => Self {
+                id,
+                kind: None,
+                note: None,
+            },
+            RawDependency::Annotated { id, kind, note } => Self { id, kind, note },
+        }
+    }
+}
+
+/// Exports write a plain id unless the entry is annotated, as in the file.
+impl Serialize for Dependency {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        if self.kind.is_none() && self.note.is_none() {
+            return s.serialize_str(&self.id);
+        }
+        let mut map = s.serialize_map(None)?;
+        map.serialize_entry("id", &self.id)?;
+        if let Some(kind) = &self.kind {
+            map.serialize_entry("kind", kind)?;
+        }
+        if let Some(note) = &self.note {
+            map.serialize_entry("note", note)?;
+        }
+        map.end()
+    }
+}
+
+impl Dependency {
+    pub fn is_external(&self) -> bool {
+        self.kind.as_deref().map(str::trim) == Some(EXTERNAL_KIND)
+    }
+
+    /// `kind: note`, or whichever of the two is set; `None` for a plain id.
+    pub fn reason(&self) -> Option<String> {
+        let kind = self.kind.as_deref().map(str::trim).filter(|k| !k.is_empty());
+        let note = self.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+        match (kind, note) {
+            (Some(kind), Some(note)) => Some(format!("{kind}: {note}")),
+            (Some(only), None) | (None, Some(only)) => Some(only.to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Task {
+    /// The non-blocking relationship lists, by field name.
+    pub fn relations(&self) -> [(&'static str, &[String]); 3] {
+        [
+            ("related_to", &self.related_to),
+            ("duplicates", &self.duplicates),
+            ("supersedes", &self.supersedes),
+        ]
+    }
+
+    /// Every task id this one names: dependencies first, then the relationships.
+    pub fn linked_ids(&self) -> Vec<(&'static str, &str)> {
+        let deps = self
+            .depends_on
+            .iter()
+            .map(|dep| ("depends_on", dep.id.as_str()));
+        let relations = self
+            .relations()
+            .into_iter()
+            .flat_map(|(kind, ids)| ids.iter().map(move |id| (kind, id.as_str())));
+        deps.chain(relations).collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,67 +215,479 @@ pub struct LoadedTasks {
     pub path: String,
     pub file_mtime: Option<SystemTime>,
     pub loaded_at: SystemTime,
+    /// Hash of the raw bytes; edits are checked against it before writing.
     pub content_hash: u64,
+    /// Hash of each task's parsed content, in `tasks` order, and of `[meta]`. Comments, whitespace
+    /// and line numbers do not enter them, so a reload can tell edits from reformatting.
+    pub task_hashes: Vec<(String, u64)>,
+    pub meta_hash: u64,
     pub tasks: TaskFile,
     pub stats: Stats,
+    pub problems: Vec<Problem>,
+    pub coverage: Coverage,
+    /// Raw file contents, kept so the next reload can reparse only the blocks that changed.
+    pub source: String,
+    pub parse_mode: ParseMode,
+}
+
+/// How the tasks of a load were parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    Full,
+    /// Only the `[[tasks]]` blocks whose text changed were reparsed.
+    Incremental { reparsed: usize },
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Stats {
     pub total: usize,
     pub done: usize,
     pub blocked_by_deps: usize,
     pub missing_deps: usize,
+    pub segments: Segments,
     pub by_status: BTreeMap<String, usize>,
     pub by_priority: BTreeMap<String, usize>,
     pub by_component: BTreeMap<String, usize>,
 }
 
+/// Task counts per progress-bar segment. Every task lands in exactly one segment.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Segments {
+    pub done: usize,
+    pub in_progress: usize,
+    pub blocked: usize,
+    pub todo: usize,
+}
+
 pub fn load_tasks(path: &Path) -> Result<LoadedTasks> {
+    reload_tasks(path, None, Workspace::default())
+}
+
+/// Loads `path`, reusing the unchanged `[[tasks]]` blocks of `prev` when the file's structure
+/// (everything before the first task, and the number of tasks) is the same. Anything else falls
+/// back to a full parse. Only parsing is incremental: stats, problems and coverage are computed
+/// over every task, once, with `workspace` already linked.
+pub fn reload_tasks(
+    path: &Path,
+    prev: Option<&LoadedTasks>,
+    workspace: Workspace,
+) -> Result<LoadedTasks> {
     let contents = std::fs::read_to_string(path)
         .with_context(|| format!("reading {}", path.display()))?;
-    let content_hash = fnv1a_64(contents.as_bytes());
-    let parsed: TaskFile =
-        toml::from_str(&contents).map_err(|e| anyhow!("parsing TOML: {e}"))?;
-
     let file_mtime = std::fs::metadata(path)
         .ok()
         .and_then(|m| m.modified().ok());
+    let (mut parsed, parse_mode) = match prev.and_then(|p| parse_incremental(p, &contents)) {
+        Some((file, reparsed)) => (file, ParseMode::Incremental { reparsed }),
+        None => (parse_full(&contents)?, ParseMode::Full),
+    };
+    parsed.workspace = workspace;
+    Ok(finish_load(
+        contents,
+        path.display().to_string(),
+        file_mtime,
+        parsed,
+        parse_mode,
+        |file| compute_coverage(path, file),
+    ))
+}
+
+/// Parses already-read contents. `coverage` is computed by the caller because it may need the
+/// spec file next to the tasks file (or, in `--demo`, an embedded one).
+pub fn parse_tasks(
+    contents: &str,
+    path: String,
+    file_mtime: Option<SystemTime>,
+    coverage: impl FnOnce(&TaskFile) -> Coverage,
+) -> Result<LoadedTasks> {
+    let parsed = parse_full(contents)?;
+    Ok(finish_load(
+        contents.to_string(),
+        path,
+        file_mtime,
+        parsed,
+        ParseMode::Full,
+        coverage,
+    ))
+}
+
+/// A file that is valid TOML but not a valid tasks file also gets every schema violation,
+/// by path, under serde's error.
+fn parse_full(contents: &str) -> Result<TaskFile> {
+    toml::from_str(contents).map_err(|e| {
+        let violations = toml::from_str::<toml::Value>(contents)
+            .ok()
+            .and_then(|doc| crate::schema::describe(&doc));
+        match violations {
+            Some(violations) => anyhow!("parsing TOML: {}\n{violations}", e.to_string().trim_end()),
+            None => anyhow!("parsing TOML: {e}"),
+        }
+    })
+}
+
+/// Reparses only the task blocks whose text differs from `prev.source`. Returns `None` when the
+/// structure changed (prefix, block count, tables other than the task's own) or a changed block
+/// does not parse on its own, so the caller does a full parse and reports errors properly.
+fn parse_incremental(prev: &LoadedTasks, contents: &str) -> Option<(TaskFile, usize)> {
+    let (old_prefix, old_blocks) = split_task_blocks(&prev.source);
+    let (prefix, blocks) = split_task_blocks(contents);
+    if prefix != old_prefix
+        || blocks.is_empty()
+        || blocks.len() != old_blocks.len()
+        || old_blocks.len() != prev.tasks.tasks.len()
+    {
+        return None;
+    }
+    // `prev.tasks.tasks` is in display order; blocks are in file order.
+    let mut by_line: Vec<&Task> = prev.tasks.tasks.iter().collect();
+    if by_line.iter().any(|t| t.line.is_none()) {
+        return None;
+    }
+    by_line.sort_by_key(|t| t.line);
+
+    let mut tasks = Vec::with_capacity(blocks.len());
+    let mut reparsed = 0;
+    for ((old, new), prev_task) in old_blocks.iter().zip(&blocks).zip(by_line) {
+        if old == new {
+            tasks.push(prev_task.clone());
+            continue;
+        }
+        if has_foreign_table(old) || has_foreign_table(new) {
+            return None;
+        }
+        let block: TaskFile = toml::from_str(new).ok()?;
+        if block.meta.is_some() || block.tasks.len() != 1 {
+            return None;
+        }
+        tasks.extend(block.tasks);
+        reparsed += 1;
+    }
+    Some((
+        TaskFile {
+            meta: prev.tasks.meta.clone(),
+            tasks,
+            workspace: Workspace::default(),
+        },
+        reparsed,
+    ))
+}
+
+/// Splits contents at `[[tasks]]` header lines: the text before the first header, then one
+/// slice per task block (header included).
+fn split_task_blocks(contents: &str) -> (&str, Vec<&str>) {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in contents.split_inclusive('\n') {
+        if is_tasks_header(line.trim_end_matches(['\r', '\n'])) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    let Some(&first) = starts.first() else {
+        return (contents, Vec::new());
+    };
+    let mut blocks = Vec::with_capacity(starts.len());
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(contents.len());
+        blocks.push(&contents[start..end]);
+    }
+    (&contents[..first], blocks)
+}
+
+/// A table header inside a task block that does not belong to the task (e.g. `[meta]` written
+/// after the tasks); such blocks cannot be reparsed in isolation.
+fn has_foreign_table(block: &str) -> bool {
+    block.lines().skip(1).any(|l| {
+        let l = l.trim_start();
+        l.starts_with('[') && !l.starts_with("[tasks.") && !l.starts_with("[[tasks.")
+    })
+}
+
+fn finish_load(
+    source: String,
+    path: String,
+    file_mtime: Option<SystemTime>,
+    mut parsed: TaskFile,
+    parse_mode: ParseMode,
+    coverage: impl FnOnce(&TaskFile) -> Coverage,
+) -> LoadedTasks {
+    let content_hash = fnv1a_64(source.as_bytes());
+    let header_lines = task_header_lines(&source);
+    if header_lines.len() == parsed.tasks.len() {
+        for (t, line) in parsed.tasks.iter_mut().zip(header_lines) {
+            t.line = Some(line);
+        }
+    }
+    apply_order(&mut parsed.tasks);
+    let task_hashes = parsed
+        .tasks
+        .iter()
+        .map(|t| (t.id.clone(), task_hash(t)))
+        .collect();
+    let meta_hash = fnv1a_64(format!("{:?}", parsed.meta).as_bytes());
 
-    let stats = compute_stats(&parsed);
+    // Derived data is linear in the number of tasks; the TOML parse above is what dominates.
+    let stats = compute_stats(&parsed, |_| true);
+    let loaded_at = clock::now();
+    let problems = find_problems(&parsed, file_mtime, loaded_at);
+    let coverage = coverage(&parsed);
 
-    Ok(LoadedTasks {
-        path: path.display().to_string(),
+    LoadedTasks {
+        path,
         file_mtime,
-        loaded_at: SystemTime::now(),
+        loaded_at,
         content_hash,
+        task_hashes,
+        meta_hash,
         tasks: parsed,
         stats,
-    })
+        problems,
+        coverage,
+        source,
+        parse_mode,
+    }
+}
+
+/// Resolves `project:ID` dependencies against `workspace` and recomputes the stats and problems
+/// that depend on them.
+pub fn link_workspace(loaded: &mut LoadedTasks, workspace: Workspace) {
+    if workspace.is_empty() && loaded.tasks.workspace.is_empty() {
+        return;
+    }
+    loaded.tasks.workspace = workspace;
+    loaded.stats = compute_stats(&loaded.tasks, |_| true);
+    loaded.problems = find_problems(&loaded.tasks, loaded.file_mtime, loaded.loaded_at);
+}
+
+/// The load restricted to one component (a `component_key`), as the focus mode shows it: only
+/// that component's tasks, stats and problems. Dependencies on other components still count,
+/// and spec coverage is left as computed for the whole file.
+pub fn scope_to_component(full: &LoadedTasks, component: &str) -> LoadedTasks {
+    let in_scope = |t: &Task| component_key(t) == component;
+    let ids: HashSet<&str> = full
+        .tasks
+        .tasks
+        .iter()
+        .filter(|t| in_scope(t))
+        .map(|t| t.id.as_str())
+        .collect();
+    let problems = full
+        .problems
+        .iter()
+        .filter(|p| p.task_ids.iter().any(|id| ids.contains(id.as_str())))
+        .cloned()
+        .collect();
+    let stats = compute_stats(&full.tasks, in_scope);
+    let mut scoped = full.clone();
+    scoped.tasks.tasks.retain(in_scope);
+    scoped.stats = stats;
+    scoped.problems = problems;
+    scoped
 }
 
 pub fn normalize_status(raw: &str) -> String {
     raw.trim().to_lowercase().replace('-', "_")
 }
 
-fn compute_stats(file: &TaskFile) -> Stats {
-    let mut stats = Stats::default();
-    stats.total = file.tasks.len();
+/// 1-based line numbers of every `[[tasks]]` header, in file order. Callers should only trust
+/// the result when it matches the number of parsed tasks (inline arrays have no headers).
+pub fn task_header_lines(contents: &str) -> Vec<usize> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| is_tasks_header(l))
+        .map(|(i, _)| i + 1)
+        .collect()
+}
 
-    let mut status_by_id = HashMap::<&str, String>::new();
-    for t in &file.tasks {
-        let status = t
-            .status
-            .as_deref()
-            .map(normalize_status)
-            .unwrap_or_else(|| "unknown".to_string());
-        status_by_id.insert(&t.id, status);
+fn is_tasks_header(line: &str) -> bool {
+    let Some(rest) = line.trim_start().strip_prefix("[[") else {
+        return false;
+    };
+    let Some((name, tail)) = rest.split_once("]]") else {
+        return false;
+    };
+    let tail = tail.trim_start();
+    name.trim() == "tasks" && (tail.is_empty() || tail.starts_with('#'))
+}
+
+/// Normalized status of a task, "unknown" when absent.
+pub fn task_status(t: &Task) -> String {
+    t.status
+        .as_deref()
+        .map(normalize_status)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// The day a deferred task comes back: its `deferred_until`, from midnight UTC.
+pub fn deferred_until(t: &Task) -> Option<SystemTime> {
+    let day = t.deferred_until.as_deref()?.trim();
+    humantime::parse_rfc3339(&format!("{day}T00:00:00Z")).ok()
+}
+
+/// Set aside with status `deferred` and not due back yet. Without a readable `deferred_until`
+/// it stays deferred until someone brings it back.
+pub fn is_deferred(t: &Task, now: SystemTime) -> bool {
+    task_status(t) == "deferred" && deferred_until(t).is_none_or(|day| day > now)
+}
+
+/// Normalized status by id, including other projects' tasks as `project:ID`. When an id is
+/// used twice, the first task in the file answers for it, as in `analysis::duplicate_ids`.
+pub fn status_map(file: &TaskFile) -> HashMap<&str, String> {
+    let mut out: HashMap<&str, String> = file
+        .workspace
+        .statuses
+        .iter()
+        .map(|(id, s)| (id.as_str(), s.clone()))
+        .collect();
+    let mut by_line: Vec<&Task> = file.tasks.iter().collect();
+    by_line.sort_by_key(|t| t.line);
+    for t in by_line {
+        out.entry(t.id.as_str()).or_insert_with(|| task_status(t));
     }
+    out
+}
+
+/// For each task in `tasks`, how many earlier tasks in the file use the same id: 0 for the
+/// first (or only) one, 1 for the first repeat, and so on. Without header lines, slice order
+/// stands in for file order.
+pub fn id_occurrences(tasks: &[Task]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..tasks.len()).collect();
+    order.sort_by_key(|&i| (tasks[i].line, i));
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut out = vec![0; tasks.len()];
+    for i in order {
+        let count = seen.entry(tasks[i].id.as_str()).or_insert(0);
+        out[i] = *count;
+        *count += 1;
+    }
+    out
+}
+
+/// A task's id as the dashboard shows it: repeats of an id get `#2`, `#3`, ... so both tasks
+/// stay apart until the duplicate is renamed.
+pub fn display_id(id: &str, occurrence: usize) -> String {
+    match occurrence {
+        0 => id.to_string(),
+        n => format!("{id}#{}", n + 1),
+    }
+}
+
+/// Dependencies of `t` that exist but are not done yet.
+pub fn unmet_deps<'a>(t: &'a Task, status_by_id: &HashMap<&str, String>) -> Vec<&'a str> {
+    t.depends_on
+        .iter()
+        .filter(|dep| matches!(status_by_id.get(dep.id.as_str()), Some(s) if s != "done"))
+        .map(|dep| dep.id.as_str())
+        .collect()
+}
+
+/// A task counts as blocked when it is not done and either says so or waits on unmet deps.
+pub fn is_blocked(t: &Task, status_by_id: &HashMap<&str, String>) -> bool {
+    let status = task_status(t);
+    status != "done" && (status == "blocked" || !unmet_deps(t, status_by_id).is_empty())
+}
+
+/// A task not done that waits on unmet deps, as `Stats::blocked_by_deps` counts it.
+pub fn is_waiting(t: &Task, status_by_id: &HashMap<&str, String>) -> bool {
+    task_status(t) != "done" && !unmet_deps(t, status_by_id).is_empty()
+}
+
+/// Grouping key for a task's priority (trimmed, lowercased, "unknown" when absent).
+pub fn priority_key(t: &Task) -> String {
+    t.priority
+        .as_deref()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
 
-    let all_ids: HashSet<&str> = file.tasks.iter().map(|t| t.id.as_str()).collect();
+/// A task's risk (trimmed, lowercased, "unknown" when absent).
+pub fn risk_key(t: &Task) -> String {
+    t.risk
+        .as_deref()
+        .map(|r| r.trim().to_lowercase())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Grouping key for a task's component (trimmed, lowercased, "unknown" when absent).
+pub fn component_key(t: &Task) -> String {
+    t.component
+        .as_deref()
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// The plan's `[meta] estimate_spread`, when it is a usable percentage.
+pub fn estimate_spread(file: &TaskFile) -> Option<f64> {
+    file.meta
+        .as_ref()?
+        .estimate_spread
+        .filter(|s| s.is_finite() && *s > 0.0)
+}
+
+/// The optimistic and pessimistic days for `t`: its `estimate_days_min` and `estimate_days_max`,
+/// or `spread` percent either side of `estimate_days` for a bound it leaves out. `None` without
+/// an estimate, or when neither gives a range. Bounds on the wrong side of the estimate are
+/// taken as the estimate itself (the `estimate-range` lint rule reports them).
+pub fn estimate_range(t: &Task, spread: Option<f64>) -> Option<(f64, f64)> {
+    let days = t.estimate_days.filter(|d| d.is_finite() && *d >= 0.0)?;
+    let margin = spread.map(|s| days * s / 100.0);
+    let bound = |b: Option<f64>| b.filter(|b| b.is_finite());
+    let low = bound(t.estimate_days_min).or(margin.map(|m| days - m));
+    let high = bound(t.estimate_days_max).or(margin.map(|m| days + m));
+    if low.is_none() && high.is_none() {
+        return None;
+    }
+    Some((
+        low.unwrap_or(days).clamp(0.0, days),
+        high.unwrap_or(days).max(days),
+    ))
+}
+
+/// Reorders tasks within each priority group by their explicit `order` (tasks without one keep
+/// their file position after ordered ones). The slots each group occupies in the file are kept,
+/// so groups stay interleaved exactly as the generator wrote them.
+fn apply_order(tasks: &mut Vec<Task>) {
+    if tasks.iter().all(|t| t.order.is_none()) {
+        return;
+    }
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, t) in tasks.iter().enumerate() {
+        groups.entry(priority_key(t)).or_default().push(i);
+    }
+    let mut slots: Vec<Option<Task>> = std::mem::take(tasks).into_iter().map(Some).collect();
+    let mut out: Vec<Option<Task>> = (0..slots.len()).map(|_| None).collect();
+    for positions in groups.values() {
+        let mut members = positions.clone();
+        members.sort_by_key(|&i| {
+            (
+                slots[i].as_ref().and_then(|t| t.order).unwrap_or(i64::MAX),
+                i,
+            )
+        });
+        for (&slot, &member) in positions.iter().zip(&members) {
+            out[slot] = slots[member].take();
+        }
+    }
+    *tasks = out.into_iter().flatten().collect();
+}
+
+/// Counts the tasks `include` accepts. Dependencies are always resolved against the whole file,
+/// so a scoped view still sees which of its tasks wait on work elsewhere.
+pub fn compute_stats(file: &TaskFile, include: impl Fn(&Task) -> bool) -> Stats {
+    let mut stats = Stats {
+        total: file.tasks.iter().filter(|t| include(t)).count(),
+        ..Stats::default()
+    };
+
+    let status_by_id = status_map(file);
+    let all_ids: HashSet<&str> = status_by_id.keys().copied().collect();
     let mut missing_deps: HashSet<String> = HashSet::new();
 
-    for t in &file.tasks {
+    for t in file.tasks.iter().filter(|t| include(t)) {
         let status = t
             .status
             .as_deref()
@@ -139,11 +721,13 @@ fn compute_stats(file: &TaskFile) -> Stats {
 
         let mut blocked = false;
         for dep in &t.depends_on {
-            if !all_ids.contains(dep.as_str()) {
-                missing_deps.insert(dep.clone());
+            if !all_ids.contains(dep.id.as_str()) {
+                if !dep.is_external() {
+                    missing_deps.insert(dep.id.clone());
+                }
                 continue;
             }
-            if let Some(dep_status) = status_by_id.get(dep.as_str()) {
+            if let Some(dep_status) = status_by_id.get(dep.id.as_str()) {
                 if dep_status != "done" {
                     blocked = true;
                 }
@@ -152,13 +736,29 @@ fn compute_stats(file: &TaskFile) -> Stats {
         if blocked && status != "done" {
             stats.blocked_by_deps += 1;
         }
+
+        // An explicit status wins; otherwise unmet dependencies count the task as blocked.
+        match status.as_str() {
+            "done" => stats.segments.done += 1,
+            "in_progress" => stats.segments.in_progress += 1,
+            "blocked" => stats.segments.blocked += 1,
+            _ if blocked => stats.segments.blocked += 1,
+            _ => stats.segments.todo += 1,
+        }
     }
 
     stats.missing_deps = missing_deps.len();
     stats
 }
 
-fn fnv1a_64(bytes: &[u8]) -> u64 {
+/// Hash of a task's parsed fields, custom ones included; its line number is left out.
+pub fn task_hash(t: &Task) -> u64 {
+    let mut t = t.clone();
+    t.line = None;
+    fnv1a_64(format!("{t:?}").as_bytes())
+}
+
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
     const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
     const PRIME: u64 = 0x100000001b3;
     let mut hash = OFFSET_BASIS;
@@ -168,3 +768,123 @@ fn fnv1a_64(bytes: &[u8]) -> u64 {
     }
     hash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"# Release plan
+[meta]
+spec_version = "1"
+
+[[tasks]]
+id = "T-1"
+title = "Parse the file"
+status = "done"
+
+[[tasks]]
+id = "T-2"
+title = "Render the table"
+depends_on = ["T-1"]
+
+[[tasks]]
+id = "T-3"
+title = "Watch for changes"
+"#;
+
+    fn load(contents: &str) -> LoadedTasks {
+        parse_tasks(contents, "TASKS.toml".into(), None, |_| Coverage::default()).unwrap()
+    }
+
+    /// What `reload_tasks` does after reading the file, without the file.
+    fn reload(prev: &LoadedTasks, contents: &str) -> LoadedTasks {
+        let (parsed, parse_mode) = match parse_incremental(prev, contents) {
+            Some((file, reparsed)) => (file, ParseMode::Incremental { reparsed }),
+            None => (parse_full(contents).unwrap(), ParseMode::Full),
+        };
+        finish_load(
+            contents.to_string(),
+            "TASKS.toml".into(),
+            None,
+            parsed,
+            parse_mode,
+            |_| Coverage::default(),
+        )
+    }
+
+    /// Reloads `edited` over `PLAN`, checks it against a full parse of `edited` and returns how
+    /// it was parsed.
+    fn reload_matches_full_parse(edited: &str) -> ParseMode {
+        let reloaded = reload(&load(PLAN), edited);
+        let full = load(edited);
+        assert_eq!(
+            format!("{:?}", reloaded.tasks.tasks),
+            format!("{:?}", full.tasks.tasks)
+        );
+        assert_eq!(reloaded.task_hashes, full.task_hashes);
+        assert_eq!(reloaded.meta_hash, full.meta_hash);
+        assert_eq!(reloaded.content_hash, full.content_hash);
+        assert_eq!(
+            serde_json::to_string(&reloaded.stats).unwrap(),
+            serde_json::to_string(&full.stats).unwrap()
+        );
+        reloaded.parse_mode
+    }
+
+    #[test]
+    fn an_edited_block_is_reparsed_alone() {
+        let edited = PLAN.replace(
+            "title = \"Render the table\"",
+            "title = \"Draw the table\"\nstatus = \"in_progress\"",
+        );
+        assert_eq!(
+            reload_matches_full_parse(&edited),
+            ParseMode::Incremental { reparsed: 1 }
+        );
+    }
+
+    #[test]
+    fn an_inserted_block_falls_back_to_a_full_parse() {
+        let edited = PLAN.replace(
+            "[[tasks]]\nid = \"T-3\"",
+            "[[tasks]]\nid = \"T-4\"\ntitle = \"Package it\"\n\n[[tasks]]\nid = \"T-3\"",
+        );
+        assert_eq!(reload_matches_full_parse(&edited), ParseMode::Full);
+    }
+
+    #[test]
+    fn a_deleted_block_falls_back_to_a_full_parse() {
+        let edited = PLAN.replace(
+            "[[tasks]]\nid = \"T-2\"\ntitle = \"Render the table\"\ndepends_on = [\"T-1\"]\n\n",
+            "",
+        );
+        assert_eq!(reload_matches_full_parse(&edited), ParseMode::Full);
+    }
+
+    #[test]
+    fn a_preamble_change_falls_back_to_a_full_parse() {
+        let edited = PLAN.replace("spec_version = \"1\"", "spec_version = \"2\"");
+        assert_eq!(reload_matches_full_parse(&edited), ParseMode::Full);
+    }
+
+    #[test]
+    fn a_trailing_comment_reparses_only_the_last_block() {
+        let edited = format!("{PLAN}\n# T-4 once the watcher is in\n");
+        assert_eq!(
+            reload_matches_full_parse(&edited),
+            ParseMode::Incremental { reparsed: 1 }
+        );
+    }
+
+    #[test]
+    fn line_numbers_follow_the_edit() {
+        let edited = PLAN.replace(
+            "title = \"Parse the file\"",
+            "title = \"Parse the file\"\nnotes = \"toml only\"",
+        );
+        let lines = |loaded: &LoadedTasks| -> Vec<_> {
+            loaded.tasks.tasks.iter().map(|t| t.line).collect()
+        };
+        assert_eq!(lines(&reload(&load(PLAN), &edited)), lines(&load(&edited)));
+    }
+}
@@ -0,0 +1,224 @@
+//! What the terminal can show: Unicode glyphs and how many colours, if any. Screens are drawn as
+//! usual and then brought down to the terminal's level cell by cell (`Caps::apply`), so borders,
+//! gauges, arrows, spinners and `[cells]` glyphs all fall back the same way, e.g. for old PuTTY
+//! sessions and the Linux console.
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// No colours: `NO_COLOR` is set or the terminal has none. The dashboard switches to the
+    /// "mono" theme, which emphasizes with bold, underline and reverse video.
+    Mono,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// How the terminal takes images, for the stats panel's charts (`graphics` feature).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    Kitty,
+    Sixel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caps {
+    /// Only ASCII symbols: `+-|` borders, `#` gauges, `^v<>` arrows.
+    pub ascii: bool,
+    pub colors: ColorDepth,
+    /// Charts are drawn as images; never without the `graphics` feature.
+    pub images: Option<ImageProtocol>,
+}
+
+impl Default for Caps {
+    fn default() -> Self {
+        Caps {
+            ascii: false,
+            colors: ColorDepth::TrueColor,
+            images: None,
+        }
+    }
+}
+
+/// `$TERM` values of terminals without Unicode glyphs (the Linux console lacks braille and
+/// most arrows).
+const ASCII_TERMS: [&str; 4] = ["dumb", "linux", "ansi", "cons25"];
+
+impl Caps {
+    /// Guesses from the environment: a locale that is set but not UTF-8, or a `$TERM` like
+    /// `linux` or `vt100`, means ASCII; `$COLORTERM` and `$TERM` give the colour depth, and
+    /// a non-empty `NO_COLOR` or a `$TERM` like `dumb` or `vt220` means none. `ascii` forces
+    /// ASCII (`--ascii`), and turns images off with it, as does a terminal without colours.
+    pub fn detect(ascii: bool) -> Caps {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let term = var("TERM").unwrap_or_default().to_lowercase();
+        let locale = var("LC_ALL")
+            .or_else(|| var("LC_CTYPE"))
+            .or_else(|| var("LANG"))
+            .map(|l| l.to_lowercase());
+        let non_utf8 = locale.is_some_and(|l| !l.contains("utf-8") && !l.contains("utf8"));
+        let ascii_term = ASCII_TERMS.contains(&term.as_str()) || term.starts_with("vt");
+        let colorterm = var("COLORTERM").unwrap_or_default().to_lowercase();
+        let mono_term = term == "dumb" || term.starts_with("vt");
+        let colors = if var("NO_COLOR").is_some() || mono_term {
+            ColorDepth::Mono
+        } else if matches!(colorterm.as_str(), "truecolor" | "24bit") || term.contains("direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+        let ascii = ascii || non_utf8 || ascii_term;
+        #[cfg(feature = "graphics")]
+        let images = crate::graphics::detect().filter(|_| !ascii && colors != ColorDepth::Mono);
+        #[cfg(not(feature = "graphics"))]
+        let images = None;
+        Caps {
+            ascii,
+            colors,
+            images,
+        }
+    }
+
+    /// No guessing, for renders and `--deterministic`: full colour, ASCII only when asked.
+    pub fn forced(ascii: bool) -> Caps {
+        Caps {
+            ascii,
+            ..Caps::default()
+        }
+    }
+
+    /// Rewrites a drawn screen to what the terminal can show.
+    pub fn apply(self, buf: &mut Buffer) {
+        if !self.ascii && self.colors == ColorDepth::TrueColor {
+            return;
+        }
+        for cell in &mut buf.content {
+            if self.ascii && !cell.symbol().is_ascii() {
+                let mut ascii = [0u8; 1];
+                let c = cell.symbol().chars().next().map_or(' ', ascii_glyph);
+                cell.set_symbol(c.encode_utf8(&mut ascii));
+            }
+            if self.colors < ColorDepth::TrueColor {
+                cell.fg = self.color(cell.fg);
+                cell.bg = self.color(cell.bg);
+            }
+        }
+    }
+
+    fn color(self, color: Color) -> Color {
+        if self.colors == ColorDepth::Mono {
+            return Color::Reset;
+        }
+        let rgb = match color {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Indexed(i) if self.colors == ColorDepth::Ansi16 => {
+                if i < 16 {
+                    return ANSI16[usize::from(i)].0;
+                }
+                indexed_rgb(i)
+            }
+            other => return other,
+        };
+        match self.colors {
+            ColorDepth::Ansi256 => Color::Indexed(cube_index(rgb)),
+            _ => nearest_ansi16(rgb),
+        }
+    }
+}
+
+/// The 16 colours with xterm's default values.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The RGB value of a 256-colour palette entry from 16 up: the 6×6×6 cube, then 24 greys.
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    if i >= 232 {
+        let v = 8 + (i - 232) * 10;
+        return (v, v, v);
+    }
+    let i = i - 16;
+    (
+        CUBE[usize::from(i / 36)],
+        CUBE[usize::from(i / 6 % 6)],
+        CUBE[usize::from(i % 6)],
+    )
+}
+
+fn cube_index((r, g, b): (u8, u8, u8)) -> u8 {
+    let level = |v: u8| {
+        (0..CUBE.len())
+            .min_by_key(|&i| CUBE[i].abs_diff(v))
+            .unwrap_or(0) as u8
+    };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+fn nearest_ansi16((r, g, b): (u8, u8, u8)) -> Color {
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map_or(Color::Reset, |(c, _)| *c)
+}
+
+/// Latin-1 letters U+00C0 to U+00FF without their accents; `x` and `/` for × and ÷.
+const LATIN1: &[u8; 64] = b"AAAAAAACEEEEIIIIDNOOOOOxOUUUUYPsaaaaaaaceeeeiiiidnooooo/ouuuuypy";
+
+/// An ASCII stand-in for a glyph the dashboard or a tasks file may contain.
+fn ascii_glyph(c: char) -> char {
+    match c {
+        '─' | '━' | '═' | '–' | '—' => '-',
+        '│' | '┃' | '║' | '▌' | '▐' => '|',
+        '\u{2500}'..='\u{257f}' => '+',
+        '█' | '▇' | '▆' | '■' => '#',
+        '▅' | '▄' | '▃' => '=',
+        '▂' | '▁' | '▏' => '_',
+        '░' => '.',
+        '▓' => '=',
+        '▒' => ':',
+        '↑' | '▲' => '^',
+        '↓' | '▼' => 'v',
+        '←' => '<',
+        '→' | '↳' | '▸' | '›' => '>',
+        '●' | '•' => '*',
+        '·' | '…' => '.',
+        '⋮' => ':',
+        '≈' => '~',
+        '±' => '+',
+        'Σ' => 'S',
+        '‘' | '’' => '\'',
+        '“' | '”' => '"',
+        // The loading spinner's braille frames, as a `|/-\` spinner.
+        '\u{2800}'..='\u{28ff}' => {
+            let frame = "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏".chars().position(|f| f == c).unwrap_or(0);
+            ['|', '/', '-', '\\'][frame % 4]
+        }
+        '\u{c0}'..='\u{ff}' => char::from(LATIN1[c as usize - 0xc0]),
+        _ => '?',
+    }
+}
@@ -0,0 +1,53 @@
+//! Focus timer (`t`): a fixed-length work session on one task, logged to the notes file when it
+//! ends or is stopped.
+
+use std::time::{Duration, SystemTime};
+
+use crate::clock::Instant;
+use crate::notes::FocusSession;
+
+pub const FOCUS_SESSION: Duration = Duration::from_secs(25 * 60);
+
+#[derive(Debug, Clone)]
+pub struct FocusTimer {
+    pub task: String,
+    started: SystemTime,
+    began: Instant,
+    length: Duration,
+}
+
+impl FocusTimer {
+    pub fn start(task: String, started: SystemTime, length: Duration) -> Self {
+        FocusTimer {
+            task,
+            started,
+            began: Instant::now(),
+            length,
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.length.saturating_sub(self.began.elapsed())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// The session as logged: whole minutes spent, capped at the session length.
+    pub fn session(&self) -> FocusSession {
+        let spent = self.began.elapsed().min(self.length);
+        FocusSession {
+            id: self.task.clone(),
+            started: humantime::format_rfc3339_seconds(self.started).to_string(),
+            minutes: spent.as_secs() / 60,
+            completed: self.is_done(),
+        }
+    }
+}
+
+/// `MM:SS`, for the footer countdown.
+pub fn format_countdown(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
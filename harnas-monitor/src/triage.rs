@@ -0,0 +1,123 @@
+//! Missing-dependency triage: when a reload leaves `depends_on` entries naming tasks the file no
+//! longer has (the generator dropped or renamed them), a modal lists each broken edge and lets
+//! the user remove it, point it at a similar id, or keep it as a dependency outside the plan.
+//! The choices are written in one atomic replace.
+
+use std::collections::HashSet;
+
+use crate::analysis::{jaccard, one_edit_apart, title_tokens, Fix};
+use crate::staging::Edit;
+use crate::tasks::TaskFile;
+use crate::workspace::split_ref;
+
+/// A remap by title is only suggested from this token similarity on.
+const SIMILAR_TITLE: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenEdge {
+    pub task: String,
+    pub dep: String,
+    /// The missing task's title in the previous load.
+    pub was: Option<String>,
+    /// An existing id the edge likely meant: one character away, or the task whose title is
+    /// closest to the one that went away.
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Left as it is; it stays a `missing dep` problem.
+    Leave,
+    Remove,
+    /// Pointed at `BrokenEdge::suggestion`.
+    Remap,
+    /// Kept with `kind = "external"`.
+    External,
+}
+
+impl Resolution {
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Leave => "leave",
+            Resolution::Remove => "remove",
+            Resolution::Remap => "remap",
+            Resolution::External => "external",
+        }
+    }
+}
+
+impl BrokenEdge {
+    /// The file edit for `resolution`; `None` for leaving it, or remapping without a suggestion.
+    pub fn edit(&self, resolution: Resolution) -> Option<Edit> {
+        let (task, dep) = (self.task.clone(), self.dep.clone());
+        match resolution {
+            Resolution::Leave => None,
+            Resolution::Remove => Some(Edit::DropDependency { task, dep }),
+            Resolution::Remap => self.suggestion.clone().map(|to| {
+                Edit::Fix(Fix::RenameReference {
+                    task,
+                    field: "depends_on",
+                    from: dep,
+                    to,
+                })
+            }),
+            Resolution::External => Some(Edit::ExternalDependency { task, dep }),
+        }
+    }
+}
+
+/// `depends_on` entries naming no task of the file and not marked external. References to
+/// other projects (`project:ID`) are left out: the triage can only fix this file's ids.
+fn missing_edges(file: &TaskFile) -> Vec<(String, String)> {
+    let ids: HashSet<&str> = file.tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut out = Vec::new();
+    for t in &file.tasks {
+        for dep in &t.depends_on {
+            let missing = !ids.contains(dep.id.as_str()) && split_ref(&dep.id).is_none();
+            if missing && !dep.is_external() && !out.contains(&(t.id.clone(), dep.id.clone())) {
+                out.push((t.id.clone(), dep.id.clone()));
+            }
+        }
+    }
+    out
+}
+
+/// The missing dependencies of `next` that `prev` did not have, in file order.
+pub fn new_missing_deps(prev: &TaskFile, next: &TaskFile) -> Vec<BrokenEdge> {
+    let before: HashSet<(String, String)> = missing_edges(prev).into_iter().collect();
+    let ids: Vec<&str> = next.tasks.iter().map(|t| t.id.as_str()).collect();
+    let old_ids: HashSet<&str> = prev.tasks.iter().map(|t| t.id.as_str()).collect();
+    missing_edges(next)
+        .into_iter()
+        .filter(|edge| !before.contains(edge))
+        .map(|(task, dep)| {
+            let was = prev
+                .tasks
+                .iter()
+                .find(|t| t.id == dep)
+                .map(|t| t.title.clone());
+            let mut near = ids.iter().filter(|id| one_edit_apart(id, &dep));
+            let typo = match (near.next(), near.next()) {
+                (Some(id), None) => Some(id.to_string()),
+                _ => None,
+            };
+            // A renamed task shows up as a new id with (nearly) the old title.
+            let renamed = was.as_deref().and_then(|title| {
+                let wanted = title_tokens(title);
+                next.tasks
+                    .iter()
+                    .filter(|t| t.id != task && !old_ids.contains(t.id.as_str()))
+                    .map(|t| (jaccard(&wanted, &title_tokens(&t.title)), t))
+                    .filter(|(score, _)| *score >= SIMILAR_TITLE)
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(_, t)| t.id.clone())
+            });
+            BrokenEdge {
+                suggestion: typo.or(renamed),
+                task,
+                dep,
+                was,
+            }
+        })
+        .collect()
+}
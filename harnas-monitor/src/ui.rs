@@ -1,12 +1,53 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
+use ratatui::symbols::Marker;
+use ratatui::widgets::canvas::{Canvas, Line as CanvasLine, Points};
 use ratatui::widgets::{
-    Block, BorderType, Borders, Cell, Clear, Gauge, Paragraph, Row, Table, TableState,
+    Axis, Bar, BarChart, BarGroup, Block, BorderType, Borders, Cell, Clear, Dataset, GraphType,
+    Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Sparkline, StatefulWidget,
+    Table, TableState, Widget,
 };
 use ratatui::{Frame, Terminal};
 
-use crate::tasks::{normalize_status, LoadedTasks, Task};
+use crate::alerts::format_age;
+use crate::analysis::{
+    generated_at, severity_counts, Fix, Severity, CLOCK_SKEW_TOLERANCE, STALE_GENERATION,
+};
+use crate::app::{App, SidebarEntry, TableColumn, TimedError};
+use crate::charts::{self, Chart, ChartSlot};
+use crate::clock::{self, Instant};
+use crate::config::PriorityConfig;
+use crate::cost::{effort_segments, format_amount};
+use crate::demo::{Tour, TOUR};
+use crate::export::VIEW_FORMATS;
+use crate::feed::{ChangeKind, FeedEntry};
+use crate::filter::{custom_field, display_value, Filter};
+use crate::i18n::{tr, trf, Lang};
+use crate::matrix::dependency_matrix;
+use crate::palette::search;
+use crate::prune::Redundant;
+use crate::keymap::{key_name, remapped, Binding, BINDINGS};
+use crate::risk::{risk_scores, RiskScore};
+use crate::schedule::{project_date, Makespan, Replan, Slot, UNESTIMATED_DAYS};
+use crate::staging::{DiffKind, DiffLine, TaskField};
+use crate::targets::{headline, Schedule, TargetProgress};
+use crate::tasks::{
+    component_key, deferred_until, display_id, estimate_range, estimate_spread, id_occurrences,
+    is_deferred, normalize_status, risk_key, status_map, task_status, LoadedTasks, ParseMode, Task,
+};
+use crate::termcaps::{Caps, ColorDepth};
+use crate::timer::format_countdown;
+use crate::review::{review_rows, ReviewTask};
+use crate::triage::{BrokenEdge, Resolution};
+use crate::workspace::split_ref;
 
 pub struct UiTheme {
     pub border: Style,
@@ -16,6 +57,12 @@ pub struct UiTheme {
     pub err: Style,
     pub selected: Style,
     pub dim: Style,
+    pub note: Style,
+    /// In-progress and todo tasks, next to `ok` for done and `warn` for blocked.
+    pub active: Style,
+    pub todo: Style,
+    /// The progress gauge's glyphs for done, in-progress, blocked and todo.
+    pub fills: [&'static str; 4],
 }
 
 impl Default for UiTheme {
@@ -30,72 +77,691 @@ impl Default for UiTheme {
                 .bg(Color::DarkGray)
                 .add_modifier(Modifier::BOLD),
             dim: Style::default().fg(Color::DarkGray),
+            note: Style::default().fg(Color::Magenta),
+            active: Style::default().fg(Color::Cyan),
+            todo: Style::default().fg(Color::White),
+            fills: ["█"; 4],
+        }
+    }
+}
+
+/// Themes a profile may name; "dark" is the default.
+pub const THEMES: [&str; 3] = ["dark", "light", "mono"];
+
+impl UiTheme {
+    pub fn named(name: &str) -> Option<UiTheme> {
+        match name {
+            "dark" => Some(UiTheme::default()),
+            // Darker accents that stay readable on a white background.
+            "light" => Some(Self {
+                border: Style::default().fg(Color::Gray),
+                title: Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                ok: Style::default().fg(Color::Green),
+                warn: Style::default().fg(Color::Indexed(130)),
+                err: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                selected: Style::default().bg(Color::Gray).add_modifier(Modifier::BOLD),
+                dim: Style::default().fg(Color::DarkGray),
+                note: Style::default().fg(Color::Magenta),
+                active: Style::default().fg(Color::Cyan),
+                todo: Style::default().fg(Color::White),
+                fills: ["█"; 4],
+            }),
+            // No colours at all, for monochrome terminals and screen captures: emphasis is bold,
+            // underline and reverse video, and the gauge tells its parts apart by shade.
+            "mono" => Some(Self {
+                border: Style::default(),
+                title: Style::default().add_modifier(Modifier::BOLD),
+                ok: Style::default(),
+                warn: Style::default().add_modifier(Modifier::UNDERLINED),
+                err: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                selected: Style::default().add_modifier(Modifier::REVERSED),
+                dim: Style::default().add_modifier(Modifier::DIM),
+                note: Style::default().add_modifier(Modifier::ITALIC),
+                active: Style::default().add_modifier(Modifier::BOLD),
+                todo: Style::default(),
+                fills: ["█", "▓", "▒", "░"],
+            }),
+            _ => None,
         }
     }
+
+    /// The default theme for what the terminal shows: "mono" without colours, "dark"
+    /// otherwise.
+    pub fn for_caps(caps: Caps) -> UiTheme {
+        match caps.colors {
+            ColorDepth::Mono => UiTheme::named("mono").unwrap_or_default(),
+            _ => UiTheme::default(),
+        }
+    }
+}
+
+/// The active profile's theme, if it sets one. A terminal without colours always gets "mono",
+/// whatever the profile asks for.
+pub fn profile_theme(app: &App) -> Option<UiTheme> {
+    if app.caps.colors == ColorDepth::Mono {
+        return UiTheme::named("mono");
+    }
+    app.profile()
+        .and_then(|p| p.theme.as_deref())
+        .and_then(UiTheme::named)
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Snapshots shown in the header's blocked-count sparkline.
+const TREND_SAMPLES: usize = 16;
+/// A rise over this many consecutive snapshots colours the sparkline as a warning.
+const TREND_RISING_SAMPLES: usize = 4;
+/// Columns of the navigation sidebar (`b`), borders included.
+const SIDEBAR_WIDTH: u16 = 24;
+/// Status changes listed under a task's status history; older ones are summarized.
+const HISTORY_CHANGES: usize = 6;
+
+#[derive(Debug, Clone)]
 pub enum Modal {
-    Help,
+    /// Key reference (`?`): bindings matching `query`, scrolled by `scroll` lines.
+    Help { query: String, scroll: u16 },
+    Note { input: String },
+    /// Compose a comment on the selected task. `reply` 0 starts a thread; otherwise it is the
+    /// position, from 1, of the comment answered in the task's thread order.
+    Comment { reply: usize, input: String },
+    Jump { input: String },
+    /// The day to defer the selected task to (`L`).
+    Defer { input: String },
+    /// The new name for every task's component `from` (palette).
+    RenameComponent { from: String, input: String },
+    Problems { selected: usize },
+    Coverage { scroll: u16 },
+    Stats { scroll: u16 },
+    Filter { input: String },
+    Matrix { selected: usize },
+    /// The pending large change, task by task.
+    Diff { scroll: u16 },
+    /// The staged edits and the TOML diff they make. `diff` is recomputed when the staged
+    /// edits or the file change, not per frame.
+    Staged {
+        scroll: u16,
+        diff: Vec<Option<DiffLine>>,
+    },
+    /// What fits before `deadline` (days from now) with `workers` in parallel, and what to cut.
+    /// `plan` is recomputed when either changes, not per frame.
+    Replan {
+        deadline: u32,
+        workers: usize,
+        scroll: u16,
+        plan: Box<Replan>,
+    },
+    /// Inline field editor (`E`) on `task`, the id selected when it opened: `field` is `None`
+    /// while picking the field. Title and estimate are typed into `input`; priority and
+    /// component are chosen by `selected` among the values the file uses.
+    EditField {
+        task: String,
+        field: Option<TaskField>,
+        selected: usize,
+        input: String,
+        error: Option<String>,
+    },
+    /// One row per workspace project (`0`); `Enter` opens the selected one's dashboard.
+    Overview { selected: usize },
+    /// Recent watcher activity, newest at the bottom; `scroll` counts lines up from the end.
+    WatchLog { scroll: u16 },
+    /// Tasks linked from the selected one (`depends_on` and the relationships).
+    Links { selected: usize },
+    /// Export the table as shown (`x`); `selected` indexes `VIEW_FORMATS`.
+    Export { selected: usize },
+    /// Done tasks whose spec sections changed since (`D`); `selected` indexes `App::drift`.
+    Drift { selected: usize },
+    /// Redundant dependencies (`X`); `picked` marks the ones `Enter` stages for removal.
+    Prune {
+        edges: Vec<Redundant>,
+        selected: usize,
+        picked: Vec<bool>,
+    },
+    /// The selected task's dependencies and their status (`Tab` in the table); closed by the
+    /// next key.
+    QuickLook,
+    /// Preview of a problem fix; `back` is the problems panel row it was opened from.
+    Fix {
+        fix: Fix,
+        preview: Result<Vec<(usize, String, String)>, String>,
+        back: usize,
+    },
+    /// The state files that failed to load (`!`, opened at startup in safe mode); `selected`
+    /// indexes `App::safe_mode`.
+    SafeMode { selected: usize },
+    /// The command palette (`Ctrl-P`); `selected` indexes the commands matching `query`.
+    Palette { query: String, selected: usize },
+    /// Dependencies a reload left pointing at missing tasks, each with what `Enter` writes.
+    Triage {
+        edges: Vec<BrokenEdge>,
+        choices: Vec<Resolution>,
+        selected: usize,
+    },
+    /// Tasks finished lately with their DoD items (`Q`); `selected` indexes
+    /// `review::review_rows(queue)`.
+    Review {
+        queue: Vec<ReviewTask>,
+        selected: usize,
+    },
+}
+
+impl Modal {
+    /// A short name for the usage statistics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Modal::Help { .. } => "help",
+            Modal::Note { .. } => "note",
+            Modal::Comment { .. } => "comment",
+            Modal::Jump { .. } => "jump",
+            Modal::Defer { .. } => "defer",
+            Modal::RenameComponent { .. } => "rename-component",
+            Modal::Problems { .. } => "problems",
+            Modal::Coverage { .. } => "coverage",
+            Modal::Stats { .. } => "stats",
+            Modal::Filter { .. } => "filter",
+            Modal::Matrix { .. } => "matrix",
+            Modal::Diff { .. } => "diff",
+            Modal::Staged { .. } => "staged",
+            Modal::Replan { .. } => "replan",
+            Modal::EditField { .. } => "edit-field",
+            Modal::Overview { .. } => "overview",
+            Modal::WatchLog { .. } => "watch-log",
+            Modal::Links { .. } => "links",
+            Modal::Export { .. } => "export",
+            Modal::Drift { .. } => "drift",
+            Modal::Prune { .. } => "prune",
+            Modal::QuickLook => "quick-look",
+            Modal::Fix { .. } => "fix",
+            Modal::SafeMode { .. } => "safe-mode",
+            Modal::Palette { .. } => "palette",
+            Modal::Triage { .. } => "triage",
+            Modal::Review { .. } => "review",
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ViewState {
     pub selected_idx: usize,
     pub details_scroll: u16,
+    /// Long summary and notes fields show only their first paragraph (`z`).
+    pub compact_details: bool,
+    /// The header is one summary line (`Z`); see `header_collapsed`.
+    pub compact_header: bool,
+    /// The progress gauge weighs tasks by `estimate_days` instead of counting them (`%`).
+    pub weighted_progress: bool,
+    /// Navigation sidebar (`b`); `None` shows it for big files only.
+    pub sidebar: Option<bool>,
+    /// The deferred section at the bottom of the table is expanded (`l`).
+    pub show_deferred: bool,
+    /// The sidebar has the keyboard (`Tab`).
+    pub sidebar_focus: bool,
+    pub sidebar_idx: usize,
+    /// The table header has the keyboard (`H`): the focused column, among `App::table_columns`.
+    pub header_focus: Option<usize>,
     pub modal: Option<Modal>,
+    pub pane_cache: RefCell<PaneCache>,
+    /// Where the last frame left room for charts drawn as images.
+    pub chart_slots: RefCell<Vec<ChartSlot>>,
+    /// Entries the watch log modal (`F12`) had room for in the last frame; scrolling stops
+    /// with the oldest one on screen.
+    pub watch_log_rows: std::cell::Cell<usize>,
 }
 
 pub fn draw<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     theme: &UiTheme,
-    view: &ViewState,
-    loaded: Option<&LoadedTasks>,
-    last_error: Option<&str>,
+    app: &App,
+) -> anyhow::Result<()> {
+    draw_screen(terminal, theme, app, app.caps.images.is_some())
+}
+
+/// `images` leaves the charts' cells blank and lists them in `ViewState::chart_slots` for the
+/// caller to draw over; off-screen renders (`render`) never do.
+fn draw_screen<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &UiTheme,
+    app: &App,
+    images: bool,
 ) -> anyhow::Result<()> {
+    let view = &app.view;
+    let loaded = app.loaded.as_ref();
+    if images {
+        view.chart_slots.borrow_mut().clear();
+    }
     terminal.draw(|f| {
         let root = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(6), Constraint::Min(1), Constraint::Length(2)])
+            .constraints([
+                Constraint::Length(if header_collapsed(app) {
+                    1
+                } else {
+                    6 + u16::from(!app.active_alerts().is_empty())
+                        + u16::from(app.watch_error.is_some())
+                        + u16::from(app.focus.is_some())
+                        + u16::from(app.makespan.is_some())
+                        + u16::from(!app.targets.is_empty() || app.target_error.is_some())
+                        + u16::from(app.large_diff.is_some())
+                }),
+                Constraint::Min(1),
+                Constraint::Length(2 + u16::from(app.ticker_slot().is_some())),
+            ])
             .split(f.area());
 
-        draw_header(f, theme, root[0], loaded, last_error);
-        draw_body(f, theme, view, root[1], loaded);
-        draw_footer(f, theme, root[2], loaded);
+        draw_header(f, theme, root[0], app);
+        draw_body(f, theme, root[1], app);
+        draw_footer(f, theme, root[2], app);
 
-        if view.modal.is_some() {
-            draw_help_modal(f, theme, f.area());
+        match &view.modal {
+            Some(Modal::Help { query, scroll }) => {
+                draw_help_modal(f, theme, f.area(), app, query, *scroll)
+            }
+            Some(Modal::Note { input }) => draw_input_modal(
+                f,
+                theme,
+                f.area(),
+                app.lang,
+                &tr(app.lang, "Add annotation"),
+                input,
+            ),
+            Some(Modal::Comment { reply, input }) => {
+                draw_comment_modal(f, theme, f.area(), app, *reply, input)
+            }
+            Some(Modal::Export { selected }) => {
+                draw_export_modal(f, theme, f.area(), app, *selected)
+            }
+            Some(Modal::Jump { input }) => draw_input_modal(
+                f,
+                theme,
+                f.area(),
+                app.lang,
+                &tr(app.lang, "Jump to task id"),
+                input,
+            ),
+            Some(Modal::Defer { input }) => draw_input_modal(
+                f,
+                theme,
+                f.area(),
+                app.lang,
+                &tr(
+                    app.lang,
+                    "Defer until (YYYY-MM-DD, or from today: 3d, 2w; empty: 1 week)",
+                ),
+                input,
+            ),
+            Some(Modal::RenameComponent { from, input }) => draw_input_modal(
+                f,
+                theme,
+                f.area(),
+                app.lang,
+                &trf(app.lang, "Rename component {} in every task to", &[from]),
+                input,
+            ),
+            Some(Modal::Filter { input }) => draw_input_modal(
+                f,
+                theme,
+                f.area(),
+                app.lang,
+                &tr(
+                    app.lang,
+                    "Filter (status:blocked component:api, OR, -negate, has:field)",
+                ),
+                input,
+            ),
+            Some(Modal::Problems { selected }) => {
+                draw_problems_modal(f, theme, f.area(), app, *selected)
+            }
+            Some(Modal::Coverage { scroll }) => {
+                draw_coverage_modal(f, theme, f.area(), app.lang, loaded, *scroll)
+            }
+            Some(Modal::Stats { scroll }) => {
+                draw_stats_modal(f, theme, f.area(), app, *scroll, images)
+            }
+            Some(Modal::Matrix { selected }) => {
+                draw_matrix_modal(f, theme, f.area(), app.lang, loaded, *selected)
+            }
+            Some(Modal::Diff { scroll }) => draw_diff_modal(f, theme, f.area(), app, *scroll),
+            Some(Modal::Staged { scroll, diff }) => {
+                draw_staged_modal(f, theme, f.area(), app, *scroll, diff)
+            }
+            Some(Modal::EditField {
+                task,
+                field,
+                selected,
+                input,
+                error,
+            }) => draw_field_editor_modal(
+                f,
+                theme,
+                f.area(),
+                app,
+                task,
+                (*field, *selected, input),
+                error.as_deref(),
+            ),
+            Some(Modal::Overview { selected }) => {
+                draw_overview_modal(f, theme, f.area(), app, *selected)
+            }
+            Some(Modal::WatchLog { scroll }) => {
+                draw_watch_log_modal(f, theme, f.area(), app, *scroll)
+            }
+            Some(Modal::Replan {
+                deadline,
+                workers,
+                scroll,
+                plan,
+            }) => draw_replan_modal(f, theme, f.area(), app, (*deadline, *workers, plan), *scroll),
+            Some(Modal::Links { selected }) => draw_links_modal(f, theme, f.area(), app, *selected),
+            Some(Modal::Drift { selected }) => draw_drift_modal(f, theme, f.area(), app, *selected),
+            Some(Modal::SafeMode { selected }) => {
+                draw_safe_mode_modal(f, theme, f.area(), app, *selected)
+            }
+            Some(Modal::Palette { query, selected }) => {
+                draw_palette_modal(f, theme, f.area(), app, query, *selected)
+            }
+            Some(Modal::QuickLook) => draw_quick_look(f, theme, root[1], app),
+            Some(Modal::Prune {
+                edges,
+                selected,
+                picked,
+            }) => draw_prune_modal(f, theme, f.area(), app.lang, edges, *selected, picked),
+            Some(Modal::Fix { fix, preview, .. }) => {
+                draw_fix_modal(f, theme, f.area(), app.lang, fix, preview)
+            }
+            Some(Modal::Triage {
+                edges,
+                choices,
+                selected,
+            }) => draw_triage_modal(f, theme, f.area(), app.lang, edges, choices, *selected),
+            Some(Modal::Review { queue, selected }) => {
+                draw_review_modal(f, theme, f.area(), app, queue, *selected)
+            }
+            None => {}
+        }
+        if let (None, Some(tour)) = (&view.modal, app.demo) {
+            if tour.visible {
+                draw_tour_tooltip(f, theme, root[1], tour);
+            }
         }
+        app.caps.apply(f.buffer_mut());
     })?;
     Ok(())
 }
 
-fn draw_header(
-    f: &mut Frame<'_>,
+/// The dashboard drawn once into an off-screen buffer of `width` x `height`.
+fn render_buffer(theme: &UiTheme, app: &App, width: u16, height: u16) -> anyhow::Result<Buffer> {
+    let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(width, height))?;
+    draw_screen(&mut terminal, theme, app, false)?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// The dashboard drawn once off-screen, as plain text with trailing spaces trimmed.
+pub fn render_plain(theme: &UiTheme, app: &App, width: u16, height: u16) -> anyhow::Result<String> {
+    let buffer = render_buffer(theme, app, width, height)?;
+    let mut out = String::new();
+    for y in 0..height {
+        let mut line = String::new();
+        let mut skip = 0;
+        for x in 0..width {
+            // A wide character covers the following cell, which holds a blank.
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let symbol = buffer[(x, y)].symbol();
+            skip = Span::raw(symbol).width().saturating_sub(1);
+            line.push_str(symbol);
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The dashboard drawn once off-screen, as one ANSI frame for a browser terminal (`web`): home,
+/// then every row with SGR colours and attributes, rows separated by CRLF.
+pub fn render_ansi(theme: &UiTheme, app: &App, width: u16, height: u16) -> anyhow::Result<String> {
+    let buffer = render_buffer(theme, app, width, height)?;
+    let mut out = String::from("\x1b[H");
+    for y in 0..height {
+        if y > 0 {
+            out.push_str("\r\n");
+        }
+        let mut style = None;
+        let mut skip = 0;
+        for x in 0..width {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let cell = &buffer[(x, y)];
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(current);
+            }
+            out.push_str(cell.symbol());
+            skip = Span::raw(cell.symbol()).width().saturating_sub(1);
+        }
+        out.push_str("\x1b[0m");
+    }
+    Ok(out)
+}
+
+/// The escape sequence that sets exactly this style, starting from a reset.
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    const ATTRIBUTES: [(Modifier, u8); 9] = [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::SLOW_BLINK, 5),
+        (Modifier::RAPID_BLINK, 6),
+        (Modifier::REVERSED, 7),
+        (Modifier::HIDDEN, 8),
+        (Modifier::CROSSED_OUT, 9),
+    ];
+    let mut codes = vec!["0".to_string()];
+    codes.extend(
+        ATTRIBUTES
+            .iter()
+            .filter(|(m, _)| modifier.contains(*m))
+            .map(|(_, code)| code.to_string()),
+    );
+    codes.extend(color_code(fg, 30));
+    codes.extend(color_code(bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameters for a colour; `base` is 30 for the foreground and 40 for the background.
+/// `None` for the terminal's default.
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let basic = |n: u8| Some((if n < 8 { base + n } else { base + 60 + n - 8 }).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => basic(0),
+        Color::Red => basic(1),
+        Color::Green => basic(2),
+        Color::Yellow => basic(3),
+        Color::Blue => basic(4),
+        Color::Magenta => basic(5),
+        Color::Cyan => basic(6),
+        Color::Gray => basic(7),
+        Color::DarkGray => basic(8),
+        Color::LightRed => basic(9),
+        Color::LightGreen => basic(10),
+        Color::LightYellow => basic(11),
+        Color::LightBlue => basic(12),
+        Color::LightMagenta => basic(13),
+        Color::LightCyan => basic(14),
+        Color::White => basic(15),
+        Color::Indexed(n) => Some(format!("{};5;{n}", base + 8)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{r};{g};{b}", base + 8)),
+    }
+}
+
+pub fn draw_picker<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
     theme: &UiTheme,
-    area: Rect,
-    loaded: Option<&LoadedTasks>,
-    last_error: Option<&str>,
-) {
+    caps: Caps,
+    candidates: &[crate::discover::Found],
+    selected: usize,
+    discover: bool,
+) -> anyhow::Result<()> {
+    terminal.draw(|f| {
+        let area = centered_rect(80, 60, f.area());
+        let hint = if discover {
+            " ↑/↓ select  Enter open  a open all as a workspace  q quit "
+        } else {
+            " ↑/↓ select  Enter open  q quit "
+        };
+        let block = Block::default()
+            .title(Span::styled("Multiple TASKS.toml files found", theme.title))
+            .title_bottom(Span::styled(hint, theme.dim))
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .border_type(BorderType::Rounded);
+        let rows = candidates.iter().map(|c| {
+            let count = match c.tasks {
+                Some(1) => Cell::from("1 task"),
+                Some(n) => Cell::from(format!("{n} tasks")),
+                None => Cell::from(Span::styled("does not load", theme.err)),
+            };
+            Row::new(vec![
+                Cell::from(c.name.clone()),
+                Cell::from(Span::styled(c.title.clone().unwrap_or_default(), theme.dim)),
+                count,
+            ])
+        });
+        let widths = [Constraint::Fill(2), Constraint::Fill(3), Constraint::Length(13)];
+        let table = Table::new(rows, widths)
+            .block(block)
+            .row_highlight_style(theme.selected);
+        let mut state = TableState::default().with_selected(Some(selected));
+        f.render_stateful_widget(table, area, &mut state);
+        caps.apply(f.buffer_mut());
+    })?;
+    Ok(())
+}
+
+/// Whether the header is drawn as one line: collapsed with `Z`, but expanded again while there
+/// is a load error or a large change to look at.
+fn header_collapsed(app: &App) -> bool {
+    app.view.compact_header
+        && app.load_error.is_none()
+        && app.watch_error.is_none()
+        && app.large_diff.is_none()
+}
+
+/// The collapsed header: progress, blocked tasks and what needs attention, on one line.
+fn draw_header_line(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App) {
+    let lang = app.lang;
+    let mut spans = vec![Span::styled("harnas-monitor", theme.title)];
+    if app.in_safe_mode() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(tr(lang, "SAFE MODE (!)"), theme.err));
+    }
+    match app.loaded.as_ref() {
+        Some(l) => {
+            let (done, total) = (l.stats.segments.done, l.stats.total);
+            let percent = if total == 0 { 0.0 } else { done as f64 / total as f64 * 100.0 };
+            spans.push(Span::styled(
+                format!("  {done}/{total} {} ({percent:.0}%)", tr(lang, "done")),
+                theme.ok,
+            ));
+            if l.stats.segments.blocked > 0 {
+                let blocked = format!("  {} {}", l.stats.segments.blocked, tr(lang, "blocked"));
+                spans.push(Span::styled(blocked, theme.warn));
+            }
+            if let Some(component) = &app.focus {
+                spans.push(Span::styled("  Focus: ", theme.dim));
+                spans.push(Span::styled(component.clone(), theme.title));
+            }
+            let counts = severity_counts(&l.problems);
+            if let Some(sev) = Severity::ALL.into_iter().zip(counts).find(|(_, n)| *n > 0) {
+                spans.push(Span::styled(tr(lang, "  Problems: "), theme.dim));
+                let n = l.problems.len();
+                spans.push(Span::styled(n.to_string(), severity_style(theme, sev.0)));
+            }
+        }
+        None => {
+            let waiting = format!("  {}", tr(lang, "Waiting for first load…"));
+            spans.push(Span::styled(waiting, theme.dim));
+        }
+    }
+    let alerts = app.active_alerts().len();
+    if alerts > 0 {
+        spans.push(Span::styled(format!("  {}", tr(lang, "Alerts: ")), theme.dim));
+        spans.push(Span::styled(alerts.to_string(), theme.err));
+    }
+    if let Some(since) = app.loading {
+        let reloading = format!("  {} {}", tr(lang, "reloading"), spinner(since));
+        spans.push(Span::styled(reloading, theme.warn));
+    }
+    spans.extend(memory_spans(theme, app));
+    spans.push(Span::styled("  (Z)", theme.dim));
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// `  Memory: ≈180 KiB`: what the dashboard retains between loads (see `App::retained_bytes`).
+fn memory_spans(theme: &UiTheme, app: &App) -> Vec<Span<'static>> {
+    let Some(bytes) = app.retained() else {
+        return Vec::new();
+    };
+    vec![
+        Span::styled(tr(app.lang, "  Memory: "), theme.dim),
+        Span::raw(format!("≈{}", format_bytes(bytes))),
+    ]
+}
+
+fn draw_header(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App) {
+    if header_collapsed(app) {
+        return draw_header_line(f, theme, area, app);
+    }
+    let lang = app.lang;
+    let loaded = app.loaded.as_ref();
+    let load_error = app.load_error.as_ref();
+    let alerts = app.active_alerts();
+    let watching = app.watch_desc.as_deref();
+    // The border takes the colour of the target most in need of attention.
+    let border = headline(&app.targets).map_or(theme.border, |t| schedule_style(theme, t.schedule));
+    let mut title = vec![
+        Span::styled("harnas-monitor", theme.title),
+        Span::raw("  "),
+        Span::styled(tr(lang, "TASKS.toml dashboard"), theme.dim),
+    ];
+    if app.in_safe_mode() {
+        title.push(Span::raw("  "));
+        title.push(Span::styled(tr(lang, "SAFE MODE (!)"), theme.err));
+    }
     let block = Block::default()
-        .title(Line::from(vec![
-            Span::styled("harnas-monitor", theme.title),
-            Span::raw("  "),
-            Span::styled("TASKS.toml dashboard (read-only)", theme.dim),
-        ]))
+        .title(Line::from(title))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(theme.border);
+        .border_style(border);
 
     let inner = block.inner(area);
     f.render_widget(block, area);
 
+    // In focus mode only the focused component's tasks count towards the trend.
+    let in_focus: Option<HashSet<&str>> = app.focus.as_ref().and(loaded).map(|l| {
+        l.tasks.tasks.iter().map(|t| t.id.as_str()).collect()
+    });
+    let trend = app.history.waiting_counts(TREND_SAMPLES, |id| {
+        in_focus.as_ref().is_none_or(|ids| ids.contains(id))
+    });
+    let trend_width = if trend.len() < 2 { 0 } else { TREND_SAMPLES as u16 + 2 };
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(40), Constraint::Length(36)])
+        .constraints([
+            Constraint::Min(40),
+            Constraint::Length(trend_width),
+            Constraint::Length(36),
+        ])
         .split(inner);
     let left = chunks[0];
-    let right = chunks[1];
+    let right = chunks[2];
+    if trend_width > 0 {
+        draw_waiting_trend(f, theme, lang, chunks[1], &trend);
+    }
 
     let mut lines: Vec<Line> = Vec::new();
     if let Some(l) = loaded {
@@ -112,22 +778,30 @@ fn draw_header(
         let gen = meta
             .and_then(|m| m.generated_utc.as_deref())
             .unwrap_or("?");
-        lines.push(Line::from(vec![
-            Span::styled("File: ", theme.dim),
+        let mut file_line = vec![
+            Span::styled(tr(lang, "File: "), theme.dim),
             Span::raw(l.path.clone()),
-        ]));
+        ];
+        if let Some(w) = watching {
+            file_line.push(Span::styled(tr(lang, "  watching: "), theme.dim));
+            file_line.push(Span::raw(w.to_string()));
+        }
+        lines.push(Line::from(file_line));
+        if let Some(err) = &app.watch_error {
+            lines.push(timed_error_line(theme, lang, "Watcher error: ", err, left.width));
+        }
         lines.push(Line::from(vec![
             Span::styled("Spec: ", theme.dim),
             Span::raw(spec.to_string()),
             Span::styled(" (", theme.dim),
             Span::raw(spec_file.to_string()),
             Span::styled(")", theme.dim),
-            Span::styled("  Output: ", theme.dim),
+            Span::styled(tr(lang, "  Output: "), theme.dim),
             Span::raw(output_file.to_string()),
-            Span::styled("  Generated: ", theme.dim),
+            Span::styled(tr(lang, "  Generated: "), theme.dim),
             Span::raw(gen.to_string()),
         ]));
-        lines.push(Line::from(vec![
+        let mut load_line = vec![
             Span::styled("mtime: ", theme.dim),
             Span::raw(
                 l.file_mtime
@@ -135,83 +809,550 @@ fn draw_header(
                     .map(|d| d.to_string())
                     .unwrap_or_else(|| "?".to_string()),
             ),
+            generation_delta(theme, app, l),
             Span::styled("  loaded_at: ", theme.dim),
             Span::raw(humantime::format_rfc3339_seconds(l.loaded_at).to_string()),
-        ]));
+        ];
+        load_line.extend(refresh_spans(theme, app));
+        load_line.extend(memory_spans(theme, app));
+        lines.push(Line::from(load_line));
 
-        if let Some(err) = last_error {
-            lines.push(Line::from(vec![
-                Span::styled("Last load error: ", theme.dim),
-                Span::styled(truncate(err, left.width as usize - 18), theme.err),
-            ]));
+        if let Some(err) = load_error {
+            lines.push(timed_error_line(theme, lang, "Last load error: ", err, left.width));
         } else {
-            lines.push(Line::from(vec![
-                Span::styled("Last load: ", theme.dim),
+            let mut spans = vec![
+                Span::styled(tr(lang, "Last load: "), theme.dim),
                 Span::styled("OK", theme.ok),
+            ];
+            if let ParseMode::Incremental { reparsed } = l.parse_mode {
+                spans.push(Span::styled(
+                    trf(
+                        lang,
+                        " (incremental, {}/{} reparsed)",
+                        &[&reparsed, &l.tasks.tasks.len()],
+                    ),
+                    theme.dim,
+                ));
+            }
+            if let Some(class) = app.last_change {
+                spans.push(Span::styled(tr(lang, "  Last change: "), theme.dim));
+                spans.push(Span::raw(class.key()));
+            }
+            if let Some(since) = app.loading {
+                let reloading = format!("  {} {}", tr(lang, "reloading"), spinner(since));
+                spans.push(Span::styled(reloading, theme.warn));
+            }
+            let over: Vec<String> = app
+                .budgets()
+                .into_iter()
+                .filter(|b| b.over())
+                .map(|b| b.component)
+                .collect();
+            if !over.is_empty() {
+                spans.push(Span::styled(tr(lang, "  Over budget: "), theme.dim));
+                spans.push(Span::styled(over.join(", "), theme.warn));
+                spans.push(Span::styled(" (s)", theme.dim));
+            }
+            if !l.problems.is_empty() {
+                spans.push(Span::styled(tr(lang, "  Problems: "), theme.dim));
+                let counts = severity_counts(&l.problems);
+                let mut first = true;
+                for (sev, n) in Severity::ALL.into_iter().zip(counts) {
+                    if n == 0 {
+                        continue;
+                    }
+                    if !first {
+                        spans.push(Span::styled(", ", theme.dim));
+                    }
+                    first = false;
+                    let label = match (sev, n) {
+                        (Severity::Error, 1) => "error",
+                        (Severity::Error, _) => "errors",
+                        (Severity::Warning, 1) => "warning",
+                        (Severity::Warning, _) => "warnings",
+                        (Severity::Info, _) => "info",
+                    };
+                    let style = severity_style(theme, sev);
+                    spans.push(Span::styled(format!("{n} {}", tr(lang, label)), style));
+                }
+                spans.push(Span::styled(" (p)", theme.dim));
+            }
+            let drift = app.active_drift().len();
+            if drift > 0 {
+                spans.push(Span::styled(tr(lang, "  Drift: "), theme.dim));
+                spans.push(Span::styled(drift.to_string(), theme.warn));
+                spans.push(Span::styled(" (D)", theme.dim));
+            }
+            lines.push(Line::from(spans));
+        }
+        if let Some(pending) = &app.large_diff {
+            let d = &pending.diff;
+            lines.push(Line::from(vec![
+                Span::styled(tr(lang, "Large change: "), theme.err),
+                Span::raw(trf(
+                    lang,
+                    "{}% of tasks ({} added, {} removed, {} changed)",
+                    &[
+                        &format!("{:.0}", d.percent()),
+                        &d.added.len(),
+                        &d.removed.len(),
+                        &d.changed.len(),
+                    ],
+                )),
+                Span::styled("  d", theme.title),
+                Span::styled(tr(lang, " view diff  "), theme.dim),
+                Span::styled("a", theme.title),
+                Span::styled(tr(lang, " accept"), theme.dim),
             ]));
         }
-    } else if let Some(err) = last_error {
-        lines.push(Line::from(vec![
-            Span::styled("Load error: ", theme.dim),
-            Span::styled(truncate(err, left.width as usize - 12), theme.err),
-        ]));
+        if let Some(m) = &app.makespan {
+            lines.push(makespan_line(theme, lang, m));
+        }
+        if let Some(line) = target_line(theme, app) {
+            lines.push(line);
+        }
+        if let (Some(component), Some(full)) = (&app.focus, app.unscoped()) {
+            lines.push(focus_banner(theme, lang, component, full, l));
+        }
+        if let Some(first) = alerts.first() {
+            let mut spans = vec![
+                Span::styled(tr(lang, "Alerts: "), theme.dim),
+                Span::styled(alerts.len().to_string(), theme.err),
+                Span::raw("  "),
+                Span::styled(first.message.clone(), theme.err),
+            ];
+            if alerts.len() > 1 {
+                spans.push(Span::styled(" … (p)", theme.dim));
+            }
+            lines.push(Line::from(spans));
+        }
     } else {
-        lines.push(Line::from(vec![
-            Span::styled("Waiting for first load…", theme.dim),
-        ]));
+        match load_error {
+            Some(err) => lines.push(timed_error_line(theme, lang, "Load error: ", err, left.width)),
+            None => {
+                let mut spans =
+                    vec![Span::styled(tr(lang, "Waiting for first load…"), theme.dim)];
+                if let Some(since) = app.loading {
+                    spans.push(Span::styled(format!(" {}", spinner(since)), theme.warn));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        if let Some(err) = &app.watch_error {
+            lines.push(timed_error_line(theme, lang, "Watcher error: ", err, left.width));
+        }
     }
 
     f.render_widget(Paragraph::new(lines).block(Block::default()), left);
 
-    let (done, total, blocked, missing) = loaded
-        .map(|l| {
-            (
-                l.stats.done,
-                l.stats.total,
-                l.stats.blocked_by_deps,
-                l.stats.missing_deps,
-            )
-        })
-        .unwrap_or((0, 0, 0, 0));
+    let (segments, total, missing) = loaded
+        .map(|l| (l.stats.segments, l.stats.total, l.stats.missing_deps))
+        .unwrap_or_default();
 
     let ratio = if total == 0 {
         0.0
     } else {
-        (done as f64) / (total as f64)
+        (segments.done as f64) / (total as f64)
+    };
+    // Weighted by estimate (`%`): the bar and legend show days, the title both percentages.
+    let effort = loaded
+        .filter(|_| app.view.weighted_progress)
+        .map(|l| effort_segments(&l.tasks, app.config.progress.unestimated_days()));
+
+    let title = match effort.filter(|e| e.total() > 0.0) {
+        Some(e) => vec![
+            Span::styled(tr(lang, "Progress "), theme.dim),
+            Span::styled(format!("{}/{total}", segments.done), theme.ok),
+            Span::styled(" · ", theme.dim),
+            Span::styled(
+                trf(lang, "{}% est", &[&format!("{:.0}", e.done / e.total() * 100.0)]),
+                theme.ok,
+            ),
+            Span::styled(
+                trf(lang, ", {}% count", &[&format!("{:.0}", ratio * 100.0)]),
+                theme.dim,
+            ),
+        ],
+        None => vec![
+            Span::styled(tr(lang, "Progress "), theme.dim),
+            Span::styled(
+                format!(
+                    "{}/{total} {} ({:.0}%)",
+                    segments.done,
+                    tr(lang, "done"),
+                    ratio * 100.0
+                ),
+                theme.ok,
+            ),
+        ],
+    };
+    let mut block = Block::default()
+        .title(Line::from(title))
+        .borders(Borders::ALL)
+        .border_style(theme.border);
+    if missing > 0 {
+        block = block.title_bottom(Span::styled(
+            trf(lang, " {} missing deps ", &[&missing]),
+            theme.warn,
+        ));
+    }
+    let inner = block.inner(right);
+    f.render_widget(block, right);
+
+    let parts = [
+        (segments.done, "done", theme.ok),
+        (segments.in_progress, "wip", theme.active),
+        (segments.blocked, "blk", theme.warn),
+        (segments.todo, "todo", theme.todo),
+    ];
+    // Days go to the bar in hundredths, so the widths stay whole numbers.
+    let days = effort.map(|e| [e.done, e.in_progress, e.blocked, e.todo]);
+    let weights = match days {
+        Some(days) => days.map(|d| (d * 100.0).round() as usize),
+        None => parts.map(|(n, _, _)| n),
+    };
+    let widths = segment_widths(&weights, inner.width as usize);
+    let mut cells: Vec<(&str, Style)> = Vec::new();
+    if weights.iter().sum::<usize>() == 0 {
+        cells.resize(inner.width as usize, ("░", theme.dim));
+    } else {
+        for (((_, _, style), fill), w) in parts.iter().zip(theme.fills).zip(widths) {
+            cells.extend(std::iter::repeat_n((fill, *style), w));
+        }
+    }
+    // The gauge fills with done tasks from the left, so only done targets can be marked on it.
+    let done_targets: Vec<TargetProgress> = app
+        .targets
+        .iter()
+        .filter(|t| t.target.status == "done")
+        .cloned()
+        .collect();
+    if let Some(t) = headline(&done_targets) {
+        let col = (t.target.percent / 100.0 * inner.width as f64).round() as usize;
+        let style = schedule_style(theme, t.schedule).bg(Color::Black);
+        if let Some(cell) = cells.get_mut(col.min(inner.width.saturating_sub(1) as usize)) {
+            *cell = ("┃", style);
+        }
+    }
+    let bar: Vec<Span> = cells.into_iter().map(|(s, style)| Span::styled(s, style)).collect();
+
+    let mut legend: Vec<Span> = Vec::new();
+    for (i, (n, name, style)) in parts.into_iter().enumerate() {
+        if !legend.is_empty() {
+            legend.push(Span::raw(" "));
+        }
+        // Coloured gauges are keyed by a small square; shaded ones by the shade itself.
+        let key = if theme.fills == ["█"; 4] { "■" } else { theme.fills[i] };
+        legend.push(Span::styled(key, style));
+        legend.push(Span::raw(match days {
+            Some(days) => format!("{}d ", format_days(days[i])),
+            None => format!("{n} "),
+        }));
+        legend.push(Span::styled(name, theme.dim));
+    }
+
+    f.render_widget(
+        Paragraph::new(vec![Line::from(bar), Line::from(legend)]),
+        inner,
+    );
+}
+
+fn status_style(theme: &UiTheme, status: &str) -> Style {
+    match status {
+        "done" => theme.ok,
+        "in_progress" => theme.active,
+        "blocked" => theme.warn,
+        "todo" => theme.todo,
+        _ => theme.dim,
+    }
+}
+
+/// The most urgent priority in the error colour, the rest of the top third in the warning
+/// colour and the least urgent dimmed; names `[priorities]` does not rank stay plain.
+fn priority_style(theme: &UiTheme, priorities: &PriorityConfig, key: &str) -> Style {
+    let levels = priorities.levels();
+    match priorities.rank(key) {
+        Some(0) => theme.err,
+        Some(r) if r + 1 == levels && levels >= 3 => theme.dim,
+        Some(r) if r * 3 < levels => theme.warn,
+        _ => Style::default(),
+    }
+}
+
+/// Cells of the `Wait` column's bar.
+const DEPS_BAR: usize = 3;
+
+/// The `Wait` column: `met/total` of the task's dependencies and a bar, green once every one is
+/// done. Any progress fills a cell, and only a finished set fills the bar. The fraction comes
+/// first, so a squeezed column cuts the bar.
+fn deps_cell(theme: &UiTheme, met: usize, total: usize) -> Cell<'static> {
+    if total == 0 {
+        return Cell::from(Span::styled("-", theme.dim));
+    }
+    let filled = match met * DEPS_BAR / total {
+        0 if met > 0 => 1,
+        n => n,
     };
-    let label = format!(
-        "{done}/{total} done ({:.0}%) | {blocked} blocked | {missing} missing deps",
-        ratio * 100.0
+    Cell::from(Line::from(vec![
+        Span::styled(
+            format!("{:<4}", format!("{met}/{total}")),
+            if met == total { theme.ok } else { theme.warn },
+        ),
+        Span::styled("█".repeat(filled), theme.ok),
+        Span::styled("░".repeat(DEPS_BAR - filled), theme.dim),
+    ]))
+}
+
+fn schedule_style(theme: &UiTheme, schedule: Schedule) -> Style {
+    match schedule {
+        Schedule::Met | Schedule::Ahead => theme.ok,
+        Schedule::Unknown => theme.dim,
+        Schedule::Behind => theme.warn,
+        Schedule::Missed => theme.err,
+    }
+}
+
+/// `Last load error: 2026-05-01T10:00:00Z …`, the message cut to fit `width`.
+fn timed_error_line<'a>(
+    theme: &UiTheme,
+    lang: Lang,
+    label: &'static str,
+    err: &TimedError,
+    width: u16,
+) -> Line<'a> {
+    let prefix = format!(
+        "{}{} ",
+        tr(lang, label),
+        humantime::format_rfc3339_seconds(err.at)
     );
+    let room = (width as usize).saturating_sub(prefix.chars().count());
+    Line::from(vec![
+        Span::styled(prefix, theme.dim),
+        Span::styled(truncate(&err.message, room), theme.err),
+    ])
+}
+
+/// How long before the file's mtime it was generated, warn-coloured past `STALE_GENERATION` or
+/// when generation came after the mtime, unless those problems were dismissed. Left out in
+/// deterministic mode, whose mtime is pinned.
+fn generation_delta<'a>(theme: &UiTheme, app: &App, l: &LoadedTasks) -> Span<'a> {
+    let (Some(generated), Some(mtime), false) =
+        (generated_at(&l.tasks), l.file_mtime, app.is_deterministic())
+    else {
+        return Span::raw("");
+    };
+    let dismissed = app.timestamp_dismissed();
+    let flag = |bad: bool, style: Style| if bad && !dismissed { style } else { theme.dim };
+    let lang = app.lang;
+    match (generated, mtime) {
+        (Err(_), _) => Span::styled(
+            tr(lang, " (generated_utc not RFC 3339)"),
+            flag(true, theme.err),
+        ),
+        (Ok(at), mtime) => match mtime.duration_since(at) {
+            Ok(behind) => Span::styled(
+                trf(lang, " (generated {} earlier)", &[&format_age(behind)]),
+                flag(behind > STALE_GENERATION, theme.warn),
+            ),
+            Err(e) => Span::styled(
+                trf(lang, " (generated {} later)", &[&format_age(e.duration())]),
+                flag(e.duration() > CLOCK_SKEW_TOLERANCE, theme.warn),
+            ),
+        },
+    }
+}
+
+/// `  Refresh: every 1m, next in 42s (A)`, or `paused`. Left out in the demo, which does not
+/// refresh, and in deterministic mode, where the countdown would differ between runs.
+fn refresh_spans<'a>(theme: &UiTheme, app: &App) -> Vec<Span<'a>> {
+    if app.demo.is_some() || app.is_deterministic() {
+        return Vec::new();
+    }
+    let lang = app.lang;
+    let whole = |d: Duration| humantime::format_duration(Duration::from_secs(d.as_secs()));
+    let policy = if app.refresh_paused {
+        Span::styled(tr(lang, "paused").into_owned(), theme.warn)
+    } else {
+        let every = app.config.refresh.auto();
+        // Rounded up, so the countdown reaches 0s as the reload starts.
+        let left = every.saturating_sub(app.last_load_attempt.elapsed());
+        let left = left + Duration::from_millis(999);
+        Span::raw(format!(
+            "{}{}{}{}",
+            tr(lang, "every "),
+            whole(every),
+            tr(lang, ", next in "),
+            whole(left)
+        ))
+    };
+    vec![
+        Span::styled(tr(lang, "  Refresh: ").into_owned(), theme.dim),
+        policy,
+        Span::styled(" (A)", theme.dim),
+    ]
+}
 
-    let label = Span::styled(
-        label,
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::Green)
-            .add_modifier(Modifier::BOLD),
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// The spinner frame for a wait that began at `since`, one step per 100ms.
+fn spinner(since: Instant) -> &'static str {
+    SPINNER[(since.elapsed().as_millis() / 100) as usize % SPINNER.len()]
+}
+
+/// `80% done by 2026-03-01: now 33%, projected 61% (+2.1/day)`
+fn target_summary(t: &TargetProgress) -> String {
+    let mut s = format!(
+        "{}% {} by {}: now {:.0}%",
+        t.target.percent, t.target.status, t.target.by_label, t.current
     );
-    let gauge = Gauge::default()
+    if let (Some(p), Some(v)) = (t.projected, t.velocity) {
+        s.push_str(&format!(", projected {p:.0}% ({v:+.1}/day)"));
+    }
+    s
+}
+
+/// The header's target line: the target most in need of attention, and any invalid target.
+fn target_line<'a>(theme: &UiTheme, app: &App) -> Option<Line<'a>> {
+    let lang = app.lang;
+    let mut spans = vec![Span::styled(tr(lang, "Target: "), theme.dim)];
+    if let Some(t) = headline(&app.targets) {
+        spans.push(Span::styled(t.schedule.label(), schedule_style(theme, t.schedule)));
+        spans.push(Span::raw(format!("  {}", target_summary(t))));
+        if app.targets.len() > 1 {
+            let more = app.targets.len() - 1;
+            spans.push(Span::styled(trf(lang, "  (+{} more, s)", &[&more]), theme.dim));
+        }
+    }
+    if let Some(err) = &app.target_error {
+        spans.push(Span::styled(format!("  {err}"), theme.err));
+    }
+    (spans.len() > 1).then(|| Line::from(spans))
+}
+
+/// What focus mode hides: the remainder of the file outside the focused component.
+fn focus_banner<'a>(
+    theme: &UiTheme,
+    lang: Lang,
+    component: &str,
+    full: &LoadedTasks,
+    shown: &LoadedTasks,
+) -> Line<'a> {
+    let hidden = full.stats.total - shown.stats.total;
+    let done = full.stats.segments.done - shown.stats.segments.done;
+    let blocked = full.stats.segments.blocked - shown.stats.segments.blocked;
+    let problems = full.problems.len() - shown.problems.len();
+    let mut spans = vec![
+        Span::styled("Focus: ", theme.dim),
+        Span::styled(component.to_string(), theme.title),
+        Span::styled(tr(lang, "  hidden: "), theme.dim),
+        Span::raw(trf(
+            lang,
+            "{} tasks ({} done, {} blocked, {} open)",
+            &[&hidden, &done, &blocked, &(hidden - done - blocked)],
+        )),
+    ];
+    if problems > 0 {
+        spans.push(Span::styled(trf(lang, ", {} problems", &[&problems]), theme.warn));
+    }
+    spans.push(Span::styled(tr(lang, "  (f to leave)"), theme.dim));
+    Line::from(spans)
+}
+
+/// Blocked-by-deps count over the most recent history snapshots, turning warn-coloured while
+/// the count keeps rising.
+fn draw_waiting_trend(f: &mut Frame<'_>, theme: &UiTheme, lang: Lang, area: Rect, trend: &[u64]) {
+    let rising = is_rising(trend, TREND_RISING_SAMPLES);
+    let style = if rising { theme.warn } else { theme.dim };
+    let last = trend.last().copied().unwrap_or(0);
+    let mut title = vec![
+        Span::styled(tr(lang, "Waiting "), theme.dim),
+        Span::styled(last.to_string(), style),
+    ];
+    if rising {
+        title.push(Span::styled(" ↑", theme.warn));
+    }
+    let sparkline = Sparkline::default()
         .block(
             Block::default()
-                .title(Span::styled("Progress", theme.dim))
+                .title(Line::from(title))
                 .borders(Borders::ALL)
                 .border_style(theme.border),
         )
-        .gauge_style(Style::default().fg(Color::Green))
-        .ratio(ratio)
-        .label(label);
+        .data(trend)
+        .max(trend.iter().copied().max().unwrap_or(0).max(1))
+        .style(style);
+    f.render_widget(sparkline, area);
+}
 
-    f.render_widget(gauge, right);
+/// True when the last `samples` snapshots never went down and ended higher than they started.
+fn is_rising(trend: &[u64], samples: usize) -> bool {
+    if samples < 2 || trend.len() < samples {
+        return false;
+    }
+    let tail = &trend[trend.len() - samples..];
+    tail.windows(2).all(|w| w[1] >= w[0]) && tail[samples - 1] > tail[0]
 }
 
-fn draw_body(
-    f: &mut Frame<'_>,
-    theme: &UiTheme,
-    view: &ViewState,
-    area: Rect,
-    loaded: Option<&LoadedTasks>,
-) {
+/// `8.5`, or `5` for whole days.
+fn format_days(days: f64) -> String {
+    let rounded = format!("{days:.1}");
+    rounded.strip_suffix(".0").unwrap_or(&rounded).to_string()
+}
+
+/// Splits `width` cells proportionally over `counts` (largest remainder), so segments always
+/// add up to the full bar and non-zero counts are never rounded away entirely when room allows.
+fn segment_widths<const N: usize>(counts: &[usize; N], width: usize) -> [usize; N] {
+    let total: usize = counts.iter().sum();
+    let mut out = [0usize; N];
+    if total == 0 || width == 0 {
+        return out;
+    }
+    let mut remainders: Vec<(usize, usize)> = Vec::with_capacity(N);
+    for (i, &c) in counts.iter().enumerate() {
+        out[i] = c * width / total;
+        remainders.push((c * width % total, i));
+    }
+    let mut left = width - out.iter().sum::<usize>();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, i) in remainders {
+        if left == 0 {
+            break;
+        }
+        out[i] += 1;
+        left -= 1;
+    }
+    for i in 0..N {
+        if counts[i] > 0 && out[i] == 0 {
+            if let Some(j) = (0..N).filter(|&j| out[j] > 1).max_by_key(|&j| out[j]) {
+                out[j] -= 1;
+                out[i] = 1;
+            }
+        }
+    }
+    out
+}
+
+fn draw_body(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App) {
+    let mut cache = app.view.pane_cache.borrow_mut();
+    let area = if app.sidebar_shown() {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(0)])
+            .split(area);
+        let key = pane_key((
+            load_identity(app),
+            &app.filter.as_ref().map(Filter::query),
+            &app.focus,
+            app.view.sidebar_focus,
+            app.view.sidebar_idx,
+        ));
+        cache.draw(f, "sidebar", cols[0], key, |buf| {
+            draw_sidebar(buf, theme, cols[0], app)
+        });
+        cols[1]
+    } else {
+        area
+    };
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
@@ -219,31 +1360,203 @@ fn draw_body(
     let left = chunks[0];
     let right = chunks[1];
 
-    draw_task_table(f, theme, view.selected_idx, left, loaded);
-    draw_details(f, theme, view, right, loaded);
+    let key = pane_key((
+        load_identity(app),
+        &app.filter.as_ref().map(Filter::query),
+        &app.focus,
+        app.sort_by_risk,
+        &app.column_sort,
+        &app.profile,
+        app.view.header_focus,
+        &app.visible,
+        app.view.selected_idx,
+    ));
+    cache.draw(f, "table", left, key, |buf| draw_task_table(buf, theme, left, app));
+    if app.trail.is_empty() {
+        draw_details(f, theme, right, app);
+    } else {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(right);
+        draw_trail(f, theme, rows[0], app);
+        draw_details(f, theme, rows[1], app);
+    }
+}
+
+/// Panes drawn off-screen and kept until their inputs change, so a frame that only scrolls the
+/// details pane (or only turns the spinner) copies the task table and sidebar instead of
+/// rebuilding them. The terminal backend already sends only the cells that changed; this saves
+/// the work of producing them.
+#[derive(Debug, Default)]
+pub struct PaneCache {
+    panes: HashMap<&'static str, (u64, Buffer)>,
+    /// Pane draws served from the cache, and rebuilt; shown in the watch log (`F12`).
+    pub reused: u64,
+    pub rebuilt: u64,
 }
 
-fn draw_task_table(
-    f: &mut Frame<'_>,
-    theme: &UiTheme,
-    selected_idx: usize,
-    area: Rect,
-    loaded: Option<&LoadedTasks>,
-) {
-    let mut rows: Vec<Row> = Vec::new();
+impl PaneCache {
+    /// Drops every kept pane, so the next frame draws them all again.
+    pub fn forget(&mut self) {
+        self.panes.clear();
+    }
 
-    let tasks: &[Task] = loaded.map(|l| l.tasks.tasks.as_slice()).unwrap_or(&[]);
-    let mut status_by_id: std::collections::HashMap<&str, String> = std::collections::HashMap::new();
-    for t in tasks {
-        let status = t
-            .status
-            .as_deref()
-            .map(normalize_status)
-            .unwrap_or_else(|| "unknown".to_string());
-        status_by_id.insert(t.id.as_str(), status);
+    /// Draws pane `name` into `area` with `draw`, unless the buffer kept for it was drawn with
+    /// the same `key` at the same place.
+    fn draw(
+        &mut self,
+        f: &mut Frame<'_>,
+        name: &'static str,
+        area: Rect,
+        key: u64,
+        draw: impl FnOnce(&mut Buffer),
+    ) {
+        let fresh = matches!(self.panes.get(name), Some((k, buf)) if *k == key && buf.area == area);
+        if fresh {
+            self.reused += 1;
+        } else {
+            let mut buf = Buffer::empty(area);
+            draw(&mut buf);
+            self.panes.insert(name, (key, buf));
+            self.rebuilt += 1;
+        }
+        let (_, buf) = &self.panes[name];
+        let out = f.buffer_mut();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                out[(x, y)] = buf[(x, y)].clone();
+            }
+        }
+    }
+}
+
+fn pane_key(inputs: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    inputs.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Changes with every load and every staged edit.
+fn load_identity(app: &App) -> (Option<(u64, std::time::SystemTime)>, String) {
+    let load = app.loaded.as_ref().map(|l| (l.content_hash, l.loaded_at));
+    (load, format!("{:?}", app.staged))
+}
+
+/// Components and statuses with counts; `Enter` scopes the table to one (`Tab` to get here).
+fn draw_sidebar(buf: &mut Buffer, theme: &UiTheme, area: Rect, app: &App) {
+    let lang = app.lang;
+    let focused = app.view.sidebar_focus;
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Navigate"), theme.title))
+        .borders(Borders::ALL)
+        .border_style(if focused { theme.title } else { theme.border })
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let entries = app.sidebar_entries();
+    let selected = app.view.sidebar_idx.min(entries.len().saturating_sub(1));
+    let width = inner.width as usize;
+    let mut lines = Vec::new();
+    let mut selected_line = 0;
+    let mut section = "";
+    for (i, entry) in entries.iter().enumerate() {
+        let (heading, name, count) = match entry {
+            SidebarEntry::All(n) => ("", tr(lang, "all tasks"), *n),
+            SidebarEntry::Component(c, n) => ("Components", c.as_str().into(), *n),
+            SidebarEntry::Unowned(n) => ("Components", tr(lang, "(unowned)"), *n),
+            SidebarEntry::Status(s, n) => ("Statuses", s.as_str().into(), *n),
+        };
+        if heading != section {
+            section = heading;
+            lines.push(Line::from(Span::styled(tr(lang, heading), theme.dim)));
+        }
+        let marker = if app.sidebar_active(entry) { "●" } else { " " };
+        let count = count.to_string();
+        let name_width = width.saturating_sub(count.len() + 3);
+        let text = format!("{marker} {:<name_width$} {count}", truncate(&name, name_width));
+        let mut line = Line::from(text);
+        if i == selected && focused {
+            line = line.style(theme.selected);
+            selected_line = lines.len();
+        } else if app.sidebar_active(entry) {
+            line = line.style(theme.title);
+        }
+        lines.push(line);
+    }
+    // Keep the selected entry in view.
+    let scroll = selected_line.saturating_sub(inner.height.saturating_sub(1) as usize);
+    Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .render(inner, buf);
+}
+
+/// Breadcrumbs of recently viewed tasks, current one highlighted. Older entries are dropped from
+/// the left when the line is too narrow.
+fn draw_trail(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App) {
+    let (ids, pos) = app.trail.entries();
+    let ids: Vec<&str> = ids.collect();
+    let on_trail = app
+        .selected_id()
+        .is_some_and(|id| ids.get(pos).is_some_and(|cur| *cur == id));
+    let sep = " › ";
+    let width = |i: usize| ids[i].chars().count() + if i > 0 { sep.chars().count() } else { 0 };
+    // Keep the newest entries that fit; "… " marks the dropped ones.
+    let budget = (area.width as usize).saturating_sub(2);
+    let mut first = ids.len();
+    let mut used = 0;
+    while first > 0 && used + width(first - 1) <= budget {
+        used += width(first - 1);
+        first -= 1;
+    }
+    let mut spans = Vec::new();
+    if first > 0 {
+        spans.push(Span::styled("… ", theme.dim));
+    }
+    for (i, id) in ids.iter().enumerate().skip(first) {
+        if i > first {
+            spans.push(Span::styled(sep, theme.dim));
+        }
+        let style = match (i == pos, on_trail) {
+            (true, true) => theme.selected,
+            (true, false) => theme.title,
+            _ => theme.dim,
+        };
+        spans.push(Span::styled(*id, style));
     }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn draw_task_table(buf: &mut Buffer, theme: &UiTheme, area: Rect, app: &App) {
+    let mut rows: Vec<Row> = Vec::new();
+
+    // Dependency state comes from every task; only the filtered ones get rows.
+    let tasks: &[Task] = app
+        .loaded
+        .as_ref()
+        .map(|l| l.tasks.tasks.as_slice())
+        .unwrap_or(&[]);
+    let status_by_id = app
+        .loaded
+        .as_ref()
+        .map(|l| status_map(&l.tasks))
+        .unwrap_or_default();
+    let occurrences = id_occurrences(tasks);
+    let staged: std::collections::HashSet<&str> =
+        app.staged.iter().filter_map(|e| e.task()).collect();
+    // The score column shows while sorting by it.
+    let columns = app.table_columns();
+    let scores = app.sort_by_risk.then(|| risk_scores(tasks));
+    let score_at = columns
+        .iter()
+        .position(|c| *c == TableColumn::Component)
+        .or_else(|| columns.iter().position(|c| *c == TableColumn::Title))
+        .unwrap_or(columns.len());
 
-    for (_idx, t) in tasks.iter().enumerate() {
+    let now = app.now();
+    for (&i, t) in app.visible.iter().zip(app.visible_tasks()) {
+        let id = display_id(&t.id, occurrences[i]);
         let status = t
             .status
             .as_deref()
@@ -263,76 +1576,219 @@ fn draw_task_table(
         let waiting_on = t
             .depends_on
             .iter()
-            .filter(|dep| matches!(status_by_id.get(dep.as_str()), Some(s) if s != "done"))
+            .filter(|dep| matches!(status_by_id.get(dep.id.as_str()), Some(s) if s != "done"))
             .count();
-        let waiting_cell = if t.depends_on.is_empty() {
-            Cell::from(Span::styled("-", theme.dim))
-        } else if waiting_on == 0 {
-            Cell::from(Span::styled("0", theme.ok))
-        } else {
-            Cell::from(Span::styled(waiting_on.to_string(), theme.warn))
-        };
+        let total = t.depends_on.len();
+        let waiting_cell = deps_cell(theme, total - waiting_on, total);
 
-        let status_style = match status.as_str() {
-            "done" => theme.ok,
-            "in_progress" => Style::default().fg(Color::Cyan),
-            "blocked" => theme.warn,
-            "todo" => Style::default().fg(Color::White),
-            _ => theme.dim,
+        let status_style = status_style(theme, &status);
+        let cells = &app.config.cells;
+
+        let unset = || Cell::from(Span::styled("-", theme.dim));
+        let list = |items: Vec<&str>| match items.is_empty() {
+            true => unset(),
+            false => Cell::from(items.join(", ")),
         };
+        let repeated = occurrences[i] > 0;
+        let mut row = Vec::new();
+        for (n, column) in columns.iter().enumerate() {
+            if let (true, Some(scores)) = (n == score_at, &scores) {
+                let score = scores.get(t.id.as_str()).map_or(0, RiskScore::value);
+                row.push(Cell::from(score.to_string()));
+            }
+            row.push(match column {
+                TableColumn::Id if staged.contains(t.id.as_str()) => {
+                    Cell::from(Span::styled(format!("{id}*"), theme.note))
+                }
+                TableColumn::Id if repeated => {
+                    Cell::from(Span::styled(id.clone(), theme.err))
+                }
+                TableColumn::Id => Cell::from(id.clone()),
+                TableColumn::Status => Cell::from(Span::styled(
+                    cells.display("status", &status).to_string(),
+                    status_style,
+                )),
+                TableColumn::Wait => waiting_cell.clone(),
+                TableColumn::Priority => Cell::from(Span::styled(
+                    cells.display("priority", &prio).to_string(),
+                    priority_style(theme, &app.config.priorities, &prio),
+                )),
+                TableColumn::Risk => match t.risk.as_ref() {
+                    Some(_) => Cell::from(risk_key(t)),
+                    None => unset(),
+                },
+                TableColumn::Component => {
+                    Cell::from(cells.display("component", &comp).to_string())
+                }
+                TableColumn::Title => Cell::from(truncate(&t.title, 70)),
+                TableColumn::Estimate => match t.estimate_days {
+                    Some(days) => Cell::from(format!("{days}d")),
+                    None => unset(),
+                },
+                TableColumn::SpecRefs => list(t.spec_refs.iter().map(String::as_str).collect()),
+                TableColumn::DependsOn => {
+                    list(t.depends_on.iter().map(|d| d.id.as_str()).collect())
+                }
+                TableColumn::Dod if t.dod.is_empty() => unset(),
+                TableColumn::Dod => Cell::from(t.dod.len().to_string()),
+                TableColumn::Field(name) => match custom_field(t, name) {
+                    Some(v) => Cell::from(truncate(&display_value(v), 40)),
+                    None => unset(),
+                },
+            });
+        }
+        if score_at == columns.len() {
+            if let Some(scores) = &scores {
+                let score = scores.get(t.id.as_str()).map_or(0, RiskScore::value);
+                row.push(Cell::from(score.to_string()));
+            }
+        }
+        let style = if is_deferred(t, now) { theme.dim } else { Style::default() };
+        rows.push(Row::new(row).style(style));
+    }
 
-        rows.push(
-            Row::new(vec![
-                Cell::from(t.id.clone()),
-                Cell::from(Span::styled(status, status_style)),
-                waiting_cell,
-                Cell::from(prio),
-                Cell::from(comp),
-                Cell::from(truncate(&t.title, 70)),
-            ])
-            .style(Style::default()),
-        );
+    // Keep the selected row near the middle of the viewport once possible, so scrolling down
+    // reveals upcoming rows while the cursor stays in view.
+    let viewport_rows = area
+        .height
+        .saturating_sub(2) // borders
+        .saturating_sub(1) as usize; // header row
+    let row_count = rows.len();
+    let selected_idx = app.view.selected_idx.min(row_count.saturating_sub(1));
+    let offset = compute_table_offset(selected_idx, row_count, viewport_rows);
+
+    let mut title = vec![
+        Span::styled(tr(app.lang, "Tasks"), theme.title),
+        Span::styled(range_label(app.lang, offset, viewport_rows, row_count), theme.dim),
+    ];
+    if let Some(filter) = &app.filter {
+        title.push(Span::styled(
+            format!(" [/{} · {row_count}/{}]", filter.query(), tasks.len()),
+            theme.warn,
+        ));
+    }
+    if app.sort_by_risk {
+        title.push(Span::styled(tr(app.lang, " by risk score"), theme.note));
+    }
+    if let Some(sort) = &app.column_sort {
+        let arrow = if sort.descending { "▼" } else { "▲" };
+        let label = column_label(app.lang, &sort.column);
+        title.push(Span::styled(format!(" {} {label} {arrow}", tr(app.lang, "by")), theme.note));
     }
 
-    let table = Table::new(
-        rows,
-        [
-            Constraint::Length(8),
-            Constraint::Length(12),
-            Constraint::Length(5),
-            Constraint::Length(8),
-            Constraint::Length(10),
-            Constraint::Min(20),
-        ],
-    )
-    .header(
-        Row::new(vec!["ID", "Status", "Wait", "Prio", "Comp", "Title"])
-            .style(theme.dim)
-            .bottom_margin(0),
-    )
-    .block(
-        Block::default()
-            .title(Span::styled("Tasks", theme.title))
-            .borders(Borders::ALL)
-            .border_style(theme.border),
-    )
-    .row_highlight_style(theme.selected);
+    // The sorted column carries an arrow; the focused one (`H`) is highlighted.
+    let mut header = Vec::new();
+    let mut widths = Vec::new();
+    for (i, column) in columns.iter().enumerate() {
+        if i == score_at && scores.is_some() {
+            header.push(Cell::from(tr(app.lang, "Score")));
+            widths.push(Constraint::Length(6));
+        }
+        let label = match &app.column_sort {
+            Some(s) if s.column == *column => {
+                let arrow = if s.descending { "▼" } else { "▲" };
+                format!("{} {arrow}", column_label(app.lang, column))
+            }
+            _ => column_label(app.lang, column).into_owned(),
+        };
+        let style = match app.view.header_focus {
+            Some(focus) if focus == i => theme.selected,
+            _ => Style::default(),
+        };
+        let width = match column {
+            TableColumn::Id => 8,
+            TableColumn::Status => 12,
+            TableColumn::Wait => 7,
+            TableColumn::Priority => 8,
+            TableColumn::Risk => 7,
+            TableColumn::Component => 10,
+            TableColumn::Title => 20,
+            TableColumn::Estimate | TableColumn::Dod => 6,
+            TableColumn::SpecRefs | TableColumn::DependsOn => 14,
+            TableColumn::Field(_) => 12,
+        };
+        // Narrow columns widen to keep the arrow visible.
+        let width = width.max(Span::raw(label.as_str()).width() as u16);
+        header.push(Cell::from(Span::styled(label, style)));
+        widths.push(match column {
+            TableColumn::Title => Constraint::Min(width),
+            _ => Constraint::Length(width),
+        });
+    }
+    if score_at == columns.len() && scores.is_some() {
+        header.push(Cell::from(tr(app.lang, "Score")));
+        widths.push(Constraint::Length(6));
+    }
+
+    // The deferred section, folded or not, is named on the bottom border.
+    let mut block = Block::default()
+        .title(Line::from(title))
+        .borders(Borders::ALL)
+        .border_style(theme.border);
+    if app.deferred > 0 {
+        let label = if app.view.show_deferred {
+            format!("▾ {} {} (l)", app.deferred, tr(app.lang, "deferred, at the bottom"))
+        } else {
+            format!("▸ {} {} (l)", app.deferred, tr(app.lang, "deferred"))
+        };
+        block = block.title_bottom(Span::styled(label, theme.dim));
+    }
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(theme.dim).bottom_margin(0))
+        .block(block)
+        .row_highlight_style(theme.selected);
 
     let mut state = TableState::default();
-    if !tasks.is_empty() {
-        let selected_idx = selected_idx.min(tasks.len() - 1);
+    if row_count > 0 {
         state.select(Some(selected_idx));
-
-        // Keep the selected row near the middle of the viewport once possible, so scrolling down
-        // reveals upcoming rows while the cursor stays in view.
-        let viewport_rows = area
-            .height
-            .saturating_sub(2) // borders
-            .saturating_sub(1); // header row
-        let offset = compute_table_offset(selected_idx, tasks.len(), viewport_rows as usize);
         state = state.with_offset(offset);
     }
-    f.render_stateful_widget(table, area, &mut state);
+    StatefulWidget::render(table, area, buf, &mut state);
+    draw_scrollbar(buf, theme, area, offset, viewport_rows, row_count);
+}
+
+/// A column's header in `lang`; custom fields show their name as written.
+fn column_label<'a>(lang: Lang, column: &'a TableColumn) -> Cow<'a, str> {
+    match column.builtin_label() {
+        Some(label) => tr(lang, label),
+        None => Cow::Borrowed(column.label()),
+    }
+}
+
+/// " 11–40 of 55" style position counter for pane titles; empty when everything fits.
+fn range_label(lang: Lang, offset: usize, viewport: usize, total: usize) -> String {
+    if total == 0 || total <= viewport {
+        return String::new();
+    }
+    let end = (offset + viewport).min(total);
+    trf(lang, " {}–{} of {}", &[&(offset + 1), &end, &total])
+}
+
+/// Vertical scrollbar on the right border of `area`, only when content overflows.
+fn draw_scrollbar(
+    buf: &mut Buffer,
+    theme: &UiTheme,
+    area: Rect,
+    offset: usize,
+    viewport: usize,
+    total: usize,
+) {
+    if total <= viewport {
+        return;
+    }
+    let mut state = ScrollbarState::new(total.saturating_sub(viewport))
+        .position(offset)
+        .viewport_content_length(viewport);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_style(theme.border)
+        .thumb_style(theme.dim);
+    let area = area.inner(ratatui::layout::Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    scrollbar.render(area, buf, &mut state);
 }
 
 fn compute_table_offset(selected: usize, rows: usize, viewport_rows: usize) -> usize {
@@ -345,24 +1801,70 @@ fn compute_table_offset(selected: usize, rows: usize, viewport_rows: usize) -> u
     desired.min(max_offset)
 }
 
-fn draw_details(
-    f: &mut Frame<'_>,
+/// Deliverables and definition-of-done lines listed unless the config says otherwise.
+const DELIVERABLES_SHOWN: usize = 8;
+const DOD_SHOWN: usize = 6;
+/// Fields shorter than this are never shortened, and get no length in their heading.
+const LONG_FIELD_WORDS: usize = 40;
+const WORDS_PER_MINUTE: usize = 200;
+
+/// A free-text field under a heading with its length. In compact mode a long field shows only
+/// its first paragraph, followed by how much is hidden.
+fn push_long_field(
+    text: &mut Text<'_>,
     theme: &UiTheme,
-    view: &ViewState,
-    area: Rect,
-    loaded: Option<&LoadedTasks>,
+    lang: Lang,
+    name: &'static str,
+    value: &str,
+    compact: bool,
 ) {
+    let words = value.split_whitespace().count();
+    let mut heading = vec![Span::styled(tr(lang, name), theme.dim)];
+    if words >= LONG_FIELD_WORDS {
+        heading.push(Span::styled(
+            trf(
+                lang,
+                "  ({} words, ~{} min read)",
+                &[&words, &words.div_ceil(WORDS_PER_MINUTE)],
+            ),
+            theme.dim,
+        ));
+    }
+    text.lines.push(Line::from(heading));
+    let value = value.trim();
+    let first = value
+        .split("\n\n")
+        .next()
+        .unwrap_or(value)
+        .trim_end();
+    if !compact || words < LONG_FIELD_WORDS || first.len() == value.len() {
+        text.lines.push(Line::from(value.to_string()));
+        return;
+    }
+    text.lines.push(Line::from(first.to_string()));
+    let hidden = words - first.split_whitespace().count();
+    text.lines.push(Line::from(vec![
+        Span::styled(trf(lang, "… (+{} words, ", &[&hidden]), theme.dim),
+        Span::styled("z", theme.title),
+        Span::styled(tr(lang, " to expand)"), theme.dim),
+    ]));
+}
+
+fn draw_details(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App) {
+    let lang = app.lang;
+    let view = &app.view;
+    let notes = &app.notes;
     let block = Block::default()
-        .title(Span::styled("Details", theme.title))
+        .title(Span::styled(tr(lang, "Details"), theme.title))
         .borders(Borders::ALL)
         .border_style(theme.border);
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let Some(loaded) = loaded else {
+    let Some(loaded) = &app.loaded else {
         f.render_widget(
             Paragraph::new(Line::from(Span::styled(
-                "No data loaded yet.",
+                tr(lang, "No data loaded yet."),
                 theme.dim,
             ))),
             inner,
@@ -371,224 +1873,2552 @@ fn draw_details(
     };
     if loaded.tasks.tasks.is_empty() {
         f.render_widget(
-            Paragraph::new(Line::from(Span::styled("No tasks.", theme.dim))),
+            Paragraph::new(Line::from(Span::styled(tr(lang, "No tasks."), theme.dim))),
             inner,
         );
         return;
     }
 
-    let idx = view.selected_idx.min(loaded.tasks.tasks.len() - 1);
-    let t = &loaded.tasks.tasks[idx];
+    let Some(t) = app.selected_task() else {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                tr(lang, "No tasks match the filter (/ to change it)."),
+                theme.dim,
+            ))),
+            inner,
+        );
+        return;
+    };
     let mut text = Text::default();
 
+    let occurrence = app.selected_occurrence();
     text.lines.push(Line::from(vec![
-        Span::styled(&t.id, theme.title),
+        Span::styled(display_id(&t.id, occurrence), theme.title),
         Span::raw("  "),
         Span::raw(&t.title),
     ]));
+    if occurrence > 0 {
+        text.lines.push(Line::from(Span::styled(
+            trf(
+                lang,
+                "duplicate id: an earlier task is also {} (p to fix)",
+                &[&t.id],
+            ),
+            theme.err,
+        )));
+    }
 
     let status = t
         .status
         .as_deref()
         .map(normalize_status)
         .unwrap_or_else(|| "unknown".to_string());
-    let prio = t.priority.as_deref().unwrap_or("unknown");
-    let comp = t.component.as_deref().unwrap_or("unknown");
+    let unknown = tr(lang, "unknown");
+    let prio = t.priority.as_deref().unwrap_or(&unknown);
+    let comp = t.component.as_deref().unwrap_or(&unknown);
 
-    text.lines.push(Line::from(vec![
-        Span::styled("status: ", theme.dim),
-        Span::raw(status),
-        Span::styled("  priority: ", theme.dim),
+    let mut fields = vec![
+        Span::styled(tr(lang, "status: "), theme.dim),
+        Span::raw(if t.status.is_some() { status.clone() } else { unknown.to_string() }),
+        Span::styled(tr(lang, "  priority: "), theme.dim),
         Span::raw(prio.to_string()),
-        Span::styled("  component: ", theme.dim),
-        Span::raw(comp.to_string()),
-    ]));
-
-    if !t.depends_on.is_empty() {
+    ];
+    if let Some(risk) = &t.risk {
+        fields.push(Span::styled(tr(lang, "  risk: "), theme.dim));
+        let known = matches!(risk_key(t).as_str(), "low" | "medium" | "high");
+        fields.push(Span::styled(risk.clone(), if known { Style::default() } else { theme.warn }));
+    }
+    fields.push(Span::styled(tr(lang, "  component: "), theme.dim));
+    fields.push(Span::raw(comp.to_string()));
+    if !app.config.owners.is_empty() {
+        fields.push(Span::styled(tr(lang, "  owned by: "), theme.dim));
+        fields.push(match app.config.owners.get(&component_key(t)) {
+            Some(owner) => Span::raw(owner.clone()),
+            None => Span::styled(tr(lang, "nobody"), theme.warn),
+        });
+    }
+    text.lines.push(Line::from(fields));
+    if let (Some(day), "deferred") = (t.deferred_until.as_deref(), status.as_str()) {
+        let back = deferred_until(t).is_some_and(|d| d <= app.now());
         text.lines.push(Line::from(vec![
-            Span::styled("depends_on: ", theme.dim),
-            Span::raw(t.depends_on.join(", ")),
+            Span::styled(tr(lang, "deferred until: "), theme.dim),
+            Span::raw(day.trim().to_string()),
+            Span::styled(
+                tr(lang, if back { "  (back; L clears)" } else { "  (L brings it back now)" }),
+                if back { theme.warn } else { theme.dim },
+            ),
         ]));
     }
+    if app.sort_by_risk {
+        if let Some(s) = risk_scores(&loaded.tasks.tasks).get(t.id.as_str()) {
+            text.lines.push(Line::from(vec![
+                Span::styled(tr(lang, "risk score: "), theme.dim),
+                Span::raw(trf(
+                    lang,
+                    "{} = {} × {} × (1 + {} downstream)",
+                    &[&s.value(), &s.priority, &s.risk, &s.downstream],
+                )),
+            ]));
+        }
+    }
+
+    if !t.depends_on.is_empty() {
+        // Other projects' tasks carry their project and status.
+        let workspace = &loaded.tasks.workspace;
+        let mut deps = vec![Span::styled(tr(lang, "depends_on: "), theme.dim)];
+        for (i, dep) in t.depends_on.iter().enumerate() {
+            if i > 0 {
+                deps.push(Span::raw(", "));
+            }
+            let local = loaded.tasks.tasks.iter().any(|x| x.id == dep.id);
+            match split_ref(&dep.id).filter(|_| !local) {
+                Some((project, id)) => {
+                    deps.push(Span::raw(id.to_string()));
+                    deps.push(match workspace.statuses.get(&dep.id) {
+                        Some(s) => {
+                            Span::styled(format!(" ({project}: {s})"), status_style(theme, s))
+                        }
+                        None => Span::styled(trf(lang, " ({}: not found)", &[&project]), theme.err),
+                    });
+                }
+                None => deps.push(Span::raw(dep.id.clone())),
+            }
+            if let Some(reason) = dep.reason() {
+                deps.push(Span::styled(format!(" [{reason}]"), theme.note));
+            }
+        }
+        text.lines.push(Line::from(deps));
+    }
     if !t.spec_refs.is_empty() {
         text.lines.push(Line::from(vec![
-            Span::styled("spec_refs: ", theme.dim),
+            Span::styled(tr(lang, "spec_refs: "), theme.dim),
             Span::raw(t.spec_refs.join(", ")),
         ]));
     }
+    // Hints only: they never count as problems.
+    for (i, s) in app.suggestions().iter().enumerate() {
+        let mut spans = vec![
+            Span::styled(tr(lang, "maybe depends on: "), theme.dim),
+            Span::styled(s.dep.clone(), theme.note),
+            Span::styled(
+                trf(
+                    lang,
+                    " ({} → {}, shares {})",
+                    &[&s.stages.0, &s.stages.1, &s.shared.join(", ")],
+                ),
+                theme.dim,
+            ),
+        ];
+        if i == 0 {
+            spans.push(Span::styled(tr(lang, "  (+ stages it)"), theme.dim));
+        }
+        text.lines.push(Line::from(spans));
+    }
+    if !t.links.is_empty() {
+        text.lines.push(Line::from(vec![
+            Span::styled(tr(lang, "links"), theme.dim),
+            Span::styled(tr(lang, "  (1-9 to open)"), theme.dim),
+        ]));
+        for (i, link) in t.links.iter().enumerate() {
+            let mut spans = vec![
+                Span::styled(format!(" {} ", i + 1), if i < 9 { theme.title } else { theme.dim }),
+                Span::raw(link.name().to_string()),
+            ];
+            if link.name() != link.url {
+                spans.push(Span::styled(format!("  {}", link.url), theme.dim));
+            }
+            text.lines.push(Line::from(spans));
+        }
+    }
 
     if let Some(est) = t.estimate_days {
-        text.lines.push(Line::from(vec![
-            Span::styled("estimate_days: ", theme.dim),
+        let mut spans = vec![
+            Span::styled(tr(lang, "estimate_days: "), theme.dim),
             Span::raw(format!("{est:.2}")),
+        ];
+        let spread = app.loaded.as_ref().and_then(|l| estimate_spread(&l.tasks));
+        if let Some(range) = estimate_range(t, spread) {
+            spans.push(Span::styled(format!("  ({})", days_range(range)), theme.dim));
+        }
+        text.lines.push(Line::from(spans));
+    }
+    let (sessions, minutes) = notes
+        .sessions_for(&t.id)
+        .fold((0, 0), |(n, m), s| (n + 1, m + s.minutes));
+    if sessions > 0 {
+        text.lines.push(Line::from(vec![
+            Span::styled(tr(lang, "focus time: "), theme.dim),
+            Span::styled(
+                trf(lang, "{} min in {} session(s)", &[&minutes, &sessions]),
+                theme.note,
+            ),
         ]));
     }
 
     if let Some(summary) = t.summary.as_deref().filter(|s| !s.trim().is_empty()) {
         text.lines.push(Line::from(""));
-        text.lines
-            .push(Line::from(Span::styled("summary", theme.dim)));
-        text.lines.push(Line::from(summary.to_string()));
+        push_long_field(&mut text, theme, lang, "summary", summary, view.compact_details);
     }
 
+    let details = &app.config.details;
+    let max_deliverables = details.max_deliverables.unwrap_or(DELIVERABLES_SHOWN);
     if !t.deliverables.is_empty() {
         text.lines.push(Line::from(""));
         text.lines
-            .push(Line::from(Span::styled("deliverables", theme.dim)));
-        for d in t.deliverables.iter().take(8) {
+            .push(Line::from(Span::styled(tr(lang, "deliverables"), theme.dim)));
+        for d in t.deliverables.iter().take(max_deliverables) {
             text.lines.push(Line::from(format!("• {d}")));
         }
-        if t.deliverables.len() > 8 {
+        if t.deliverables.len() > max_deliverables {
             text.lines.push(Line::from(Span::styled(
-                format!("… (+{} more)", t.deliverables.len() - 8),
+                trf(lang, "… (+{} more)", &[&(t.deliverables.len() - max_deliverables)]),
                 theme.dim,
             )));
         }
     }
 
+    let max_dod = details.max_dod.unwrap_or(DOD_SHOWN);
     if !t.dod.is_empty() {
         text.lines.push(Line::from(""));
-        text.lines.push(Line::from(Span::styled("DoD", theme.dim)));
-        for d in t.dod.iter().take(6) {
+        text.lines.push(Line::from(Span::styled(tr(lang, "DoD"), theme.dim)));
+        for d in t.dod.iter().take(max_dod) {
             text.lines.push(Line::from(format!("• {d}")));
         }
-        if t.dod.len() > 6 {
+        if t.dod.len() > max_dod {
+            text.lines.push(Line::from(Span::styled(
+                trf(lang, "… (+{} more)", &[&(t.dod.len() - max_dod)]),
+                theme.dim,
+            )));
+        }
+    }
+
+    // Links may point outside the focused component.
+    let all_tasks = app.full().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+    for (kind, ids) in t.relations() {
+        if ids.is_empty() {
+            continue;
+        }
+        text.lines.push(Line::from(""));
+        text.lines.push(Line::from(vec![
+            Span::styled(kind, theme.dim),
+            Span::styled(tr(lang, "  (g to jump)"), theme.dim),
+        ]));
+        for id in ids {
+            text.lines.push(link_line(theme, lang, all_tasks, id));
+        }
+    }
+
+    let changes = app.history.task_changes(&t.id);
+    if !changes.is_empty() {
+        text.lines.push(Line::from(""));
+        text.lines.push(Line::from(Span::styled(tr(lang, "status history"), theme.dim)));
+        // One cell per snapshot, newest on the right; gaps are loads without the task.
+        let cells = app.history.task_states(&t.id, inner.width as usize);
+        text.lines.push(Line::from(
+            cells
+                .into_iter()
+                .map(|state| match state {
+                    Some(s) => Span::styled("▇", status_style(theme, &s.status)),
+                    None => Span::styled("·", theme.dim),
+                })
+                .collect::<Vec<_>>(),
+        ));
+        let skip = changes.len().saturating_sub(HISTORY_CHANGES);
+        if skip > 0 {
             text.lines.push(Line::from(Span::styled(
-                format!("… (+{} more)", t.dod.len() - 6),
+                trf(lang, "… ({} earlier)", &[&skip]),
                 theme.dim,
             )));
         }
+        for (at, state) in &changes[skip..] {
+            let when = at.get(..16).unwrap_or(at).replace('T', " ");
+            let mut line = vec![
+                Span::styled(format!("{when}  "), theme.dim),
+                Span::styled(state.status.clone(), status_style(theme, &state.status)),
+            ];
+            if state.blocked && state.status != "blocked" {
+                line.push(Span::styled(tr(lang, " (waiting on deps)"), theme.warn));
+            }
+            text.lines.push(Line::from(line));
+        }
     }
 
     if let Some(notes) = t.notes.as_deref().filter(|s| !s.trim().is_empty()) {
         text.lines.push(Line::from(""));
-        text.lines.push(Line::from(Span::styled("notes", theme.dim)));
-        text.lines.push(Line::from(notes.to_string()));
+        push_long_field(&mut text, theme, lang, "notes", notes, view.compact_details);
+    }
+
+    if !t.extra.is_empty() {
+        text.lines.push(Line::from(""));
+        text.lines
+            .push(Line::from(Span::styled(tr(lang, "custom fields"), theme.dim)));
+        for (k, v) in &t.extra {
+            text.lines.push(Line::from(vec![
+                Span::styled(format!("{k}: "), theme.dim),
+                Span::raw(display_value(v)),
+            ]));
+        }
+    }
+
+    let annotations: Vec<_> = notes.for_task(&t.id).collect();
+    if !annotations.is_empty() {
+        text.lines.push(Line::from(""));
+        text.lines.push(Line::from(vec![
+            Span::styled(tr(lang, "annotations"), theme.note),
+            Span::styled(tr(lang, " (notes file)"), theme.dim),
+        ]));
+        for a in annotations {
+            text.lines.push(Line::from(vec![
+                Span::styled(a.at.as_deref().unwrap_or("?").to_string(), theme.dim),
+                Span::raw("  "),
+                Span::styled(a.text.clone(), theme.note),
+            ]));
+        }
+    }
+
+    let comments = notes.comment_threads(&t.id);
+    if !comments.is_empty() {
+        text.lines.push(Line::from(""));
+        text.lines.push(Line::from(vec![
+            Span::styled(tr(lang, "comments"), theme.note),
+            Span::styled(tr(lang, " (C to add or reply)"), theme.dim),
+        ]));
+        for (depth, c) in comments {
+            let indent = "  ".repeat(depth);
+            let arrow = if depth == 0 { "" } else { "↳ " };
+            text.lines.push(Line::from(vec![
+                Span::raw(format!("{indent}{arrow}")),
+                Span::styled(c.author.clone(), theme.title),
+                Span::styled(format!("  {}  #{}", c.at, c.n), theme.dim),
+            ]));
+            let pad = if depth == 0 { "  ".to_string() } else { format!("{indent}    ") };
+            text.lines.push(Line::from(format!("{pad}{}", c.body)));
+        }
     }
 
-    let p = Paragraph::new(text)
-        .scroll((view.details_scroll, 0))
-        .wrap(ratatui::widgets::Wrap { trim: false });
-    f.render_widget(p, inner);
+    let p = Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: false });
+    let total = p.line_count(inner.width);
+    let viewport = inner.height as usize;
+    let scroll = (view.details_scroll as usize).min(total.saturating_sub(viewport));
+    f.render_widget(
+        Block::default()
+            .title(Line::from(vec![
+                Span::styled(tr(lang, "Details"), theme.title),
+                Span::styled(range_label(lang, scroll, viewport, total), theme.dim),
+            ]))
+            .borders(Borders::ALL)
+            .border_style(theme.border),
+        area,
+    );
+    f.render_widget(p.scroll((scroll as u16, 0)), inner);
+    draw_scrollbar(f.buffer_mut(), theme, area, scroll, viewport, total);
+}
+
+/// `• T-009  title  [status]`, or the id marked unknown when no task has it.
+fn link_line<'a>(theme: &UiTheme, lang: Lang, tasks: &[Task], id: &str) -> Line<'a> {
+    match tasks.iter().find(|t| t.id == id) {
+        Some(t) => Line::from(vec![
+            Span::raw(format!("• {id}  ")),
+            Span::raw(t.title.clone()),
+            Span::styled(format!("  [{}]", task_status(t)), theme.dim),
+        ]),
+        None => Line::from(vec![
+            Span::raw(format!("• {id}  ")),
+            Span::styled(tr(lang, "unknown task"), theme.warn),
+        ]),
+    }
 }
 
-fn draw_footer(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, loaded: Option<&LoadedTasks>) {
-    let mut line = vec![
+fn draw_footer(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App) {
+    let lang = app.lang;
+    let mut line = Vec::new();
+    if app.demo.is_some() {
+        line.extend([
+            Span::styled(tr(lang, "DEMO "), theme.warn),
+            Span::styled("t", theme.title),
+            Span::styled(tr(lang, " tour  "), theme.dim),
+        ]);
+    }
+    if app.large_diff.is_some() {
+        line.extend([
+            Span::styled(tr(lang, "LARGE CHANGE "), theme.err),
+            Span::styled("d", theme.title),
+            Span::styled(tr(lang, " diff  "), theme.dim),
+            Span::styled("a", theme.title),
+            Span::styled(tr(lang, " accept  "), theme.dim),
+        ]);
+    }
+    if !app.staged.is_empty() {
+        line.extend([
+            Span::styled(format!("{} {} ", tr(lang, "STAGED"), app.staged.len()), theme.note),
+            Span::styled("w", theme.title),
+            Span::styled(tr(lang, " review  "), theme.dim),
+            Span::styled("W", theme.title),
+            Span::styled(tr(lang, " write  "), theme.dim),
+        ]);
+    }
+    let drift = app.active_drift().len();
+    if drift > 0 {
+        line.extend([
+            Span::styled(format!("{} {} ", tr(lang, "DRIFT"), drift), theme.warn),
+            Span::styled("D", theme.title),
+            Span::styled(tr(lang, " review  "), theme.dim),
+        ]);
+    }
+    if let Some(timer) = &app.timer {
+        line.extend([
+            Span::styled(
+                format!("FOCUS {} {} ", timer.task, format_countdown(timer.remaining())),
+                theme.note,
+            ),
+            Span::styled("t", theme.title),
+            Span::styled(tr(lang, " stop  "), theme.dim),
+        ]);
+    }
+    if !app.config.projects.is_empty() {
+        line.extend([
+            Span::styled("0", theme.title),
+            Span::styled(tr(lang, " workspace  "), theme.dim),
+        ]);
+    }
+    line.extend([
+        Span::styled("Ctrl-P", theme.title),
+        Span::styled(tr(lang, " commands  "), theme.dim),
         Span::styled("q", theme.title),
-        Span::styled(" quit  ", theme.dim),
+        Span::styled(tr(lang, " quit  "), theme.dim),
         Span::styled("↑/↓", theme.title),
-        Span::styled(" select  ", theme.dim),
+        Span::styled(tr(lang, " select  "), theme.dim),
         Span::styled("PgUp/PgDn", theme.title),
-        Span::styled(" scroll details  ", theme.dim),
+        Span::styled(tr(lang, " scroll details  "), theme.dim),
         Span::styled("r", theme.title),
-        Span::styled(" reload  ", theme.dim),
+        Span::styled(tr(lang, " reload  "), theme.dim),
+        Span::styled("n", theme.title),
+        Span::styled(tr(lang, " note  "), theme.dim),
+        Span::styled("C", theme.title),
+        Span::styled(tr(lang, " comment  "), theme.dim),
+        Span::styled("x", theme.title),
+        Span::styled(tr(lang, " export view  "), theme.dim),
+        Span::styled(":", theme.title),
+        Span::styled(tr(lang, " jump  "), theme.dim),
+        Span::styled("g", theme.title),
+        Span::styled(tr(lang, " links  "), theme.dim),
+        Span::styled("[/]", theme.title),
+        Span::styled(tr(lang, " back/fwd  "), theme.dim),
+        Span::styled("t", theme.title),
+        Span::styled(tr(lang, " timer  "), theme.dim),
+        Span::styled("e/P", theme.title),
+        Span::styled(tr(lang, " status/prio  "), theme.dim),
+        Span::styled("E", theme.title),
+        Span::styled(tr(lang, " edit  "), theme.dim),
+        Span::styled("R", theme.title),
+        Span::styled(tr(lang, " replan  "), theme.dim),
+        Span::styled("X", theme.title),
+        Span::styled(tr(lang, " prune deps  "), theme.dim),
+        Span::styled("/", theme.title),
+        Span::styled(tr(lang, " filter  "), theme.dim),
+        Span::styled("p", theme.title),
+        Span::styled(tr(lang, " problems  "), theme.dim),
+        Span::styled("c", theme.title),
+        Span::styled(tr(lang, " coverage  "), theme.dim),
+        Span::styled("s", theme.title),
+        Span::styled(tr(lang, " stats  "), theme.dim),
+        Span::styled("m", theme.title),
+        Span::styled(tr(lang, " dep matrix  "), theme.dim),
+        Span::styled("f", theme.title),
+        Span::styled(tr(lang, " focus  "), theme.dim),
+        Span::styled("b", theme.title),
+        Span::styled(tr(lang, " sidebar  "), theme.dim),
+        Span::styled("Tab", theme.title),
+        Span::styled(tr(lang, " deps/sidebar  "), theme.dim),
+        Span::styled("o", theme.title),
+        Span::styled(
+            tr(
+                lang,
+                if app.sort_by_risk || app.column_sort.is_some() {
+                    " file order  "
+                } else {
+                    " risk sort  "
+                },
+            ),
+            theme.dim,
+        ),
+        Span::styled("H", theme.title),
+        Span::styled(tr(lang, " sort by column  "), theme.dim),
+        Span::styled("V", theme.title),
+        Span::styled(
+            match &app.profile {
+                Some(p) => format!(" {} {p}  ", tr(lang, "profile")),
+                None => format!(" {}  ", tr(lang, "profile")),
+            },
+            theme.dim,
+        ),
+        Span::styled("z", theme.title),
+        Span::styled(
+            tr(lang, if app.view.compact_details { " expand  " } else { " compact  " }),
+            theme.dim,
+        ),
+        Span::styled("%", theme.title),
+        Span::styled(
+            tr(lang, if app.view.weighted_progress { " by count  " } else { " by estimate  " }),
+            theme.dim,
+        ),
+        Span::styled("v", theme.title),
+        Span::styled(tr(lang, if app.volume == 0 { " muted  " } else { " volume  " }), theme.dim),
         Span::styled("?", theme.title),
-        Span::styled(" help", theme.dim),
-    ];
+        Span::styled(tr(lang, " help"), theme.dim),
+    ]);
 
-    if let Some(l) = loaded {
-        line.push(Span::styled("   |   ", theme.dim));
+    // Second row: what the file and the last action reported, so it is not pushed off-screen
+    // by the key hints.
+    let mut status: Vec<Span> = Vec::new();
+    if let Some(l) = &app.loaded {
         let status_summary = summarize_map(&l.stats.by_status, 3);
-        line.push(Span::styled("statuses: ", theme.dim));
-        line.push(Span::raw(status_summary));
+        status.push(Span::styled(tr(lang, "statuses: "), theme.dim));
+        status.push(Span::raw(status_summary));
+    }
+    let reports = [
+        app.notes_error.as_ref().map(|e| Span::styled(truncate(e, 60), theme.err)),
+        app.status_file_error.as_ref().map(|e| Span::styled(truncate(e, 60), theme.err)),
+        app.status_msg.as_ref().map(|m| Span::styled(m.clone(), theme.warn)),
+    ];
+    for span in reports.into_iter().flatten() {
+        if !status.is_empty() {
+            status.push(Span::styled("   |   ", theme.dim));
+        }
+        status.push(span);
     }
 
+    let mut lines = vec![Line::from(line), Line::from(status)];
+    if let Some((i, entry)) = app.ticker_entry() {
+        lines.push(ticker_line(theme, app, i, entry));
+    }
     let block = Block::default().borders(Borders::NONE);
-    f.render_widget(Paragraph::new(Line::from(line)).block(block), area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// `▸ T-031 → done 4m ago  (2/10)`, or `▸ new: T-090 'Add cache layer' 1h ago`.
+fn ticker_line<'a>(theme: &UiTheme, app: &App, i: usize, entry: &FeedEntry) -> Line<'a> {
+    let title = app
+        .loaded
+        .as_ref()
+        .and_then(|l| l.tasks.tasks.iter().find(|t| t.id == entry.task))
+        .map(|t| format!(" '{}'", t.title))
+        .unwrap_or_default();
+    let mut spans = vec![Span::styled("▸ ", theme.dim)];
+    match entry.kind {
+        ChangeKind::Done => spans.extend([
+            Span::styled(entry.task.clone(), theme.title),
+            Span::styled(tr(app.lang, " → done"), theme.ok),
+        ]),
+        ChangeKind::Blocked => spans.extend([
+            Span::styled(entry.task.clone(), theme.title),
+            Span::styled(tr(app.lang, " → blocked"), theme.warn),
+        ]),
+        ChangeKind::Added => spans.extend([
+            Span::styled(tr(app.lang, "new: "), theme.note),
+            Span::styled(entry.task.clone(), theme.title),
+            Span::raw(title),
+        ]),
+    }
+    if let Ok(at) = humantime::parse_rfc3339(&entry.at) {
+        let age = app.now().duration_since(at).unwrap_or_default();
+        let age = if age.as_secs() < 60 {
+            tr(app.lang, " just now").into_owned()
+        } else {
+            trf(app.lang, " {} ago", &[&format_age(age)])
+        };
+        spans.push(Span::styled(age, theme.dim));
+    }
+    spans.push(Span::styled(
+        format!("  ({}/{})", i + 1, app.ticker.len()),
+        theme.dim,
+    ));
+    Line::from(spans)
 }
 
-fn draw_help_modal(f: &mut Frame<'_>, theme: &UiTheme, area: Rect) {
-    let modal_area = centered_rect(70, 65, area);
+/// The key bindings of `keymap::BINDINGS` matching `query`, grouped, with the active profile's
+/// remappings. Scrolled by `scroll` lines.
+fn draw_help_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    query: &str,
+    scroll: u16,
+) {
+    let lang = app.lang;
+    let modal_area = centered_rect(70, 75, area);
     f.render_widget(Clear, modal_area);
 
+    let q = query.trim().to_lowercase();
+    let matches = |b: &Binding| {
+        q.is_empty()
+            || [
+                b.keys.to_lowercase(),
+                b.action.to_lowercase(),
+                tr(lang, b.action).to_lowercase(),
+                b.group.title().to_lowercase(),
+                tr(lang, b.group.title()).to_lowercase(),
+            ]
+            .iter()
+            .any(|text| text.contains(&q))
+    };
+    let shown: Vec<&Binding> = BINDINGS.iter().filter(|b| matches(b)).collect();
+
+    let mut title = vec![Span::styled(tr(lang, "Help"), theme.title)];
+    if !q.is_empty() {
+        title.push(Span::styled(
+            format!(" · {}/{}", shown.len(), BINDINGS.len()),
+            theme.dim,
+        ));
+    }
     let block = Block::default()
-        .title(Span::styled("Help", theme.title))
+        .title(Line::from(title))
+        .title_bottom(Span::styled(
+            tr(lang, " type to search  ↑/↓ scroll  Esc clear/close "),
+            theme.dim,
+        ))
         .borders(Borders::ALL)
         .border_style(theme.border)
         .border_type(BorderType::Rounded);
     let inner = block.inner(modal_area);
     f.render_widget(block, modal_area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner);
 
-    let text = vec![
-        Line::from(vec![
-            Span::styled("Read-only dashboard for a sibling ", theme.dim),
-            Span::styled("TASKS.toml", theme.title),
-            Span::styled(" file.", theme.dim),
-        ]),
-        Line::from(""),
-        Line::from("Keys:"),
-        Line::from(vec![
-            Span::styled("  q", theme.title),
-            Span::raw(" quit"),
-        ]),
-        Line::from(vec![
-            Span::styled("  r", theme.title),
-            Span::raw(" reload now"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ↑/↓", theme.title),
-            Span::raw(" select task"),
-        ]),
-        Line::from(vec![
-            Span::styled("  PgUp/PgDn", theme.title),
-            Span::raw(" scroll details pane"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ?", theme.title),
-            Span::raw(" toggle this help"),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Auto reload", theme.title),
-            Span::raw(": watches file changes and refreshes."),
+    f.render_widget(
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled(tr(lang, "Search: "), theme.dim),
+                Span::raw(query.to_string()),
+                Span::styled("▏", theme.title),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(lang, "Dashboard for a sibling "), theme.dim),
+                Span::styled("TASKS.toml", theme.title),
+                Span::styled(tr(lang, " file."), theme.dim),
+                Span::raw(" "),
+                Span::styled(tr(lang, "Auto reload"), theme.title),
+                Span::styled(tr(lang, ": watches file changes and refreshes."), theme.dim),
+            ]),
         ]),
-    ];
+        rows[0],
+    );
 
-    f.render_widget(Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: true }), inner);
+    let profile = app.profile();
+    let key_width = shown.iter().map(|b| b.keys.chars().count()).max().unwrap_or(0) + 2;
+    let mut lines: Vec<Line> = Vec::new();
+    let mut group = None;
+    for b in &shown {
+        if group != Some(b.group) {
+            group = Some(b.group);
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(tr(lang, b.group.title()), theme.title)));
+        }
+        let (extra, shadowed) = remapped(b, profile);
+        let key_style = if shadowed { theme.dim } else { theme.title };
+        let mut spans = vec![
+            Span::styled(format!("  {:<key_width$}", b.keys), key_style),
+            Span::raw(tr(lang, b.action)),
+        ];
+        if !extra.is_empty() {
+            let names: Vec<String> = extra.into_iter().map(key_name).collect();
+            spans.push(Span::styled(
+                format!("  ({} {})", tr(lang, "profile:"), names.join(", ")),
+                theme.note,
+            ));
+        }
+        if shadowed {
+            spans.push(Span::styled(tr(lang, "  (remapped by the profile)"), theme.warn));
+        }
+        lines.push(Line::from(spans));
+        if let Some(detail) = b.detail {
+            lines.push(Line::from(Span::styled(
+                format!("  {:<key_width$}{detail}", ""),
+                theme.dim,
+            )));
+        }
+    }
+    if shown.is_empty() {
+        lines.push(Line::from(Span::styled(tr(lang, "No key matches."), theme.dim)));
+    }
+    let max_scroll = lines.len().saturating_sub(rows[1].height as usize) as u16;
+    f.render_widget(
+        Paragraph::new(lines).scroll((scroll.min(max_scroll), 0)),
+        rows[1],
+    );
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Percentage((100 - percent_y) / 2),
-                Constraint::Percentage(percent_y),
-                Constraint::Percentage((100 - percent_y) / 2),
-            ]
-            .as_ref(),
-        )
-        .split(r);
+fn draw_problems_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    selected: usize,
+) {
+    let modal_area = centered_rect(80, 70, area);
+    f.render_widget(Clear, modal_area);
 
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(
-            [
-                Constraint::Percentage((100 - percent_x) / 2),
-                Constraint::Percentage(percent_x),
-                Constraint::Percentage((100 - percent_x) / 2),
-            ]
-            .as_ref(),
-        )
-        .split(popup_layout[1])[1]
-}
+    let lang = app.lang;
+    let problems = app.panel_entries();
+    let block = Block::default()
+        .title(Span::styled(trf(lang, "Problems ({})", &[&problems.len()]), theme.title))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  Enter jump  f fix  d dismiss  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
 
-fn truncate(s: &str, max: usize) -> String {
-    if max == 0 {
-        return String::new();
-    }
-    if s.chars().count() <= max {
-        return s.to_string();
+    if problems.is_empty() {
+        let inner = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(tr(lang, "No problems found."), theme.ok))),
+            inner,
+        );
+        return;
     }
-    let mut out = String::new();
-    for (i, ch) in s.chars().enumerate() {
-        if i + 1 >= max {
-            break;
+
+    let rows = problems.iter().map(|p| {
+        let dismissed = p
+            .fingerprint
+            .as_deref()
+            .is_some_and(|fp| app.notes.is_dismissed(fp));
+        let style = if dismissed {
+            theme.dim
+        } else if p.alert {
+            theme.err
+        } else {
+            severity_style(theme, p.severity)
+        };
+        let fix = if p.fix.is_some() { tr(lang, "fix") } else { "".into() };
+        let mut message = vec![Span::raw(p.message.clone())];
+        if dismissed {
+            message.push(Span::styled(tr(lang, "  (dismissed)"), theme.dim));
         }
-        out.push(ch);
-    }
-    out.push('…');
-    out
+        Row::new(vec![
+            Cell::from(Span::styled(p.severity.label(), style)),
+            Cell::from(Span::styled(p.label, style)),
+            Cell::from(Span::styled(fix, theme.ok)),
+            Cell::from(Line::from(message)),
+        ])
+    });
+    let widths = [
+        Constraint::Length(7),
+        Constraint::Length(12),
+        Constraint::Length(3),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state = TableState::default().with_selected(Some(selected.min(problems.len() - 1)));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+/// `Remaining: ≈ 9 workdays (7–12) with 2 devs (14.5d of work, 2 unestimated)`; the range
+/// only when estimates have one.
+fn makespan_line(theme: &UiTheme, lang: Lang, m: &Makespan) -> Line<'static> {
+    let days = m.days.ceil() as u64;
+    let workdays = tr(lang, if days == 1 { "workday" } else { "workdays" });
+    let devs = tr(lang, if m.workers == 1 { "dev" } else { "devs" });
+    let mut spans = vec![
+        Span::styled(tr(lang, "Remaining: "), theme.dim),
+        Span::styled(format!("≈ {days} {workdays}"), theme.title),
+    ];
+    if let Some((low, high)) = m.range {
+        spans.push(Span::styled(
+            format!(" ({}–{})", low.ceil() as u64, high.ceil() as u64),
+            theme.title,
+        ));
+    }
+    spans.push(Span::styled(
+        format!(" {} {} {devs}", tr(lang, "with"), m.workers),
+        theme.dim,
+    ));
+    let mut notes = vec![format!("{:.1}d {}", m.effort, tr(lang, "of work"))];
+    if m.unestimated > 0 {
+        notes.push(format!("{} {}", m.unestimated, tr(lang, "unestimated")));
+    }
+    if m.unschedulable > 0 {
+        notes.push(format!("{} {}", m.unschedulable, tr(lang, "in a cycle")));
+    }
+    spans.push(Span::styled(format!(" ({})", notes.join(", ")), theme.dim));
+    Line::from(spans)
+}
+
+/// `2.5–4d`: days to one decimal, without a trailing `.0`.
+fn days_range((low, high): (f64, f64)) -> String {
+    let days = |d: f64| ((d * 10.0).round() / 10.0).to_string();
+    format!("{}–{}d", days(low), days(high))
+}
+
+fn severity_style(theme: &UiTheme, severity: Severity) -> Style {
+    match severity {
+        Severity::Error => theme.err,
+        Severity::Warning => theme.warn,
+        Severity::Info => theme.dim,
+    }
+}
+
+fn draw_diff_modal(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App, scroll: u16) {
+    let lang = app.lang;
+    let modal_area = centered_rect(85, 80, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            tr(lang, "Changes since the last accepted load"),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ scroll  a accept  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let Some(d) = app.large_diff.as_ref().map(|p| &p.diff) else {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(tr(lang, "No pending change."), theme.dim))),
+            inner,
+        );
+        return;
+    };
+    let mut lines: Vec<Line> = Vec::new();
+    for (title, entries, sign, style) in [
+        ("Added ({})", &d.added, "+", theme.ok),
+        ("Removed ({})", &d.removed, "-", theme.err),
+    ] {
+        lines.push(Line::from(Span::styled(
+            trf(lang, title, &[&entries.len()]),
+            theme.title,
+        )));
+        for (id, task_title) in entries {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{sign} {id:<10}"), style),
+                Span::raw(task_title.clone()),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        trf(lang, "Changed ({})", &[&d.changed.len()]),
+        theme.title,
+    )));
+    let width = inner.width.saturating_sub(20) as usize / 2;
+    for c in &d.changed {
+        lines.push(Line::from(Span::styled(format!("~ {}", c.id), theme.warn)));
+        for fc in &c.fields {
+            lines.push(Line::from(vec![
+                Span::styled(format!("    {:<14}", fc.field), theme.dim),
+                Span::raw(truncate(&fc.before, width)),
+                Span::styled(" → ", theme.dim),
+                Span::raw(truncate(&fc.after, width)),
+            ]));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), inner);
+}
+
+fn draw_palette_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    query: &str,
+    selected: usize,
+) {
+    let lang = app.lang;
+    let modal_area = centered_rect(60, 60, area);
+    f.render_widget(Clear, modal_area);
+
+    let commands = app.palette_commands();
+    let found = search(&commands, query);
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::styled(tr(lang, "Commands"), theme.title),
+            Span::styled(format!(" · {}/{}", found.len(), commands.len()), theme.dim),
+        ]))
+        .title_bottom(Span::styled(
+            tr(lang, " type to search  ↑/↓ select  Enter run  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(tr(lang, "Search: "), theme.dim),
+            Span::raw(query.to_string()),
+            Span::styled("▏", theme.title),
+        ])),
+        parts[0],
+    );
+    let rows = found.iter().map(|c| {
+        Row::new(vec![
+            Cell::from(c.name.clone()),
+            Cell::from(Span::styled(c.key.map(key_name).unwrap_or_default(), theme.title)),
+        ])
+    });
+    let widths = [Constraint::Min(10), Constraint::Length(6)];
+    let table = Table::new(rows, widths).row_highlight_style(theme.selected);
+    let mut state =
+        TableState::default().with_selected(Some(selected.min(found.len().saturating_sub(1))));
+    f.render_stateful_widget(table, parts[1], &mut state);
+}
+
+fn draw_links_modal(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App, selected: usize) {
+    let modal_area = centered_rect(70, 50, area);
+    f.render_widget(Clear, modal_area);
+
+    let task = app.selected_task();
+    let links = task.map(Task::linked_ids).unwrap_or_default();
+    let lang = app.lang;
+    let title = task.map_or(tr(lang, "Links").into_owned(), |t| {
+        trf(lang, "Links of {}", &[&t.id])
+    });
+    let block = Block::default()
+        .title(Span::styled(title, theme.title))
+        .title_bottom(Span::styled(tr(lang, " ↑/↓ select  Enter jump  Esc close "), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let tasks = app.full().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+    let rows = links.iter().map(|(kind, id)| {
+        let target = tasks.iter().find(|t| t.id == *id);
+        Row::new(vec![
+            Cell::from(Span::styled(*kind, theme.dim)),
+            Cell::from(id.to_string()),
+            match target {
+                Some(t) => Cell::from(format!("{}  [{}]", t.title, task_status(t))),
+                None => Cell::from(Span::styled(tr(lang, "unknown task"), theme.warn)),
+            },
+        ])
+    });
+    let widths = [Constraint::Length(12), Constraint::Length(10), Constraint::Min(10)];
+    let table = Table::new(rows, widths)
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state =
+        TableState::default().with_selected(Some(selected.min(links.len().saturating_sub(1))));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+fn draw_prune_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    lang: Lang,
+    edges: &[Redundant],
+    selected: usize,
+    picked: &[bool],
+) {
+    let modal_area = centered_rect(75, 60, area);
+    f.render_widget(Clear, modal_area);
+
+    let count = picked.iter().filter(|p| **p).count();
+    let block = Block::default()
+        .title(Span::styled(
+            trf(lang, "Redundant dependencies: {} ({} picked)", &[&edges.len(), &count]),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  Space pick  a all  Enter stage removal  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let rows = edges.iter().zip(picked).map(|(e, p)| {
+        Row::new(vec![
+            Cell::from(Span::styled(if *p { "[x]" } else { "[ ]" }, theme.ok)),
+            Cell::from(e.task.clone()),
+            Cell::from(Span::styled(format!("→ {}", e.dep), theme.warn)),
+            Cell::from(Span::styled(trf(lang, "via {}", &[&e.via.join(" → ")]), theme.dim)),
+        ])
+    });
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+fn draw_triage_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    lang: Lang,
+    edges: &[BrokenEdge],
+    choices: &[Resolution],
+    selected: usize,
+) {
+    let modal_area = centered_rect(80, 60, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            trf(lang, "The reload left {} dependencies on missing tasks", &[&edges.len()]),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  d remove  r remap  e external  l leave  Enter write  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let rows = edges.iter().zip(choices).map(|(e, choice)| {
+        let action = match (choice, &e.suggestion) {
+            (Resolution::Remap, Some(to)) => Span::styled(trf(lang, "remap → {}", &[to]), theme.ok),
+            (Resolution::Leave, _) => Span::styled(tr(lang, "leave"), theme.dim),
+            (other, _) => Span::styled(tr(lang, other.label()), theme.ok),
+        };
+        let mut hint = vec![];
+        if let Some(was) = &e.was {
+            hint.push(Span::styled(trf(lang, "was {}", &[&format!("{was:?}")]), theme.dim));
+        }
+        if let Some(to) = &e.suggestion {
+            hint.push(Span::styled(trf(lang, "  similar: {}", &[to]), theme.dim));
+        }
+        Row::new(vec![
+            Cell::from(e.task.clone()),
+            Cell::from(Span::styled(format!("→ {}", e.dep), theme.err)),
+            Cell::from(action),
+            Cell::from(Line::from(hint)),
+        ])
+    });
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(14),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+fn draw_review_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    queue: &[ReviewTask],
+    selected: usize,
+) {
+    let modal_area = centered_rect(80, 70, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let notes = &app.notes;
+    let checked = |t: &ReviewTask| t.dod.iter().filter(|d| notes.verified(&t.id, d).is_some()).count();
+    let (done, items) = queue
+        .iter()
+        .fold((0, 0), |(done, items), t| (done + checked(t), items + t.dod.len()));
+    let block = Block::default()
+        .title(Span::styled(
+            trf(
+                lang,
+                "Review queue: {} tasks done in the last {} days, {}/{} DoD items verified",
+                &[&queue.len(), &app.config.review.days(), &done, &items],
+            ),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  Space verified  Enter jump  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let now = clock::now();
+    let rows = review_rows(queue).into_iter().map(|row| {
+        let t = &queue[row.task];
+        let Some(i) = row.item else {
+            let ago = now.duration_since(t.done_at).unwrap_or_default();
+            let progress = match t.dod.len() {
+                0 => Span::styled(tr(lang, "no DoD items"), theme.dim),
+                n if checked(t) == n => Span::styled(trf(lang, "{}/{} verified", &[&n, &n]), theme.ok),
+                n => Span::styled(trf(lang, "{}/{} verified", &[&checked(t), &n]), theme.warn),
+            };
+            return Row::new(vec![
+                Cell::from(Span::styled(t.id.clone(), theme.title)),
+                Cell::from(t.title.clone()),
+                Cell::from(Line::from(vec![
+                    Span::styled(trf(lang, "done {} ago  ", &[&format_age(ago)]), theme.dim),
+                    progress,
+                ])),
+            ]);
+        };
+        let item = &t.dod[i];
+        let (mark, by) = match notes.verified(&t.id, item) {
+            Some(v) => (
+                Span::styled("[x] ", theme.ok),
+                Span::styled(format!("{} {}", v.by, v.at), theme.dim),
+            ),
+            None => (Span::styled("[ ] ", theme.dim), Span::raw("")),
+        };
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(Line::from(vec![mark, Span::raw(item.clone())])),
+            Cell::from(by),
+        ])
+    });
+    let widths = [Constraint::Length(10), Constraint::Min(20), Constraint::Length(36)];
+    let table = Table::new(rows, widths)
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+/// A small card over the table's bottom left listing the selected task's dependencies with
+/// their status; done ones are green, unknown ones red.
+fn draw_quick_look(f: &mut Frame<'_>, theme: &UiTheme, body: Rect, app: &App) {
+    let (Some(task), Some(loaded)) = (app.selected_task(), app.full()) else {
+        return;
+    };
+    let tasks = &loaded.tasks.tasks;
+    let lines: Vec<Line> = task
+        .depends_on
+        .iter()
+        .map(|dep| {
+            let mut spans = vec![Span::styled(format!("{:<10} ", dep.id), theme.title)];
+            match tasks.iter().find(|t| t.id == dep.id) {
+                Some(t) => {
+                    let status = task_status(t);
+                    let style = status_style(theme, &status);
+                    spans.push(Span::styled(format!("{status:<12} "), style));
+                    spans.push(Span::raw(t.title.clone()));
+                }
+                None => match loaded.tasks.workspace.statuses.get(&dep.id) {
+                    Some(s) => {
+                        spans.push(Span::styled(format!("{s:<12} "), status_style(theme, s)));
+                        spans.push(Span::styled(tr(app.lang, "(other project)"), theme.dim));
+                    }
+                    None => spans.push(Span::styled(tr(app.lang, "unknown task"), theme.err)),
+                },
+            }
+            if let Some(reason) = dep.reason() {
+                spans.push(Span::styled(format!("  [{reason}]"), theme.note));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    let widest = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let width = body.width.min((widest + 4).max(30));
+    let height = body.height.min(lines.len() as u16 + 2);
+    let area = Rect {
+        x: body.x + 2.min(body.width - width),
+        y: body.y + body.height - height,
+        width,
+        height,
+    };
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(Span::styled(trf(app.lang, "{} depends on", &[&task.id]), theme.title))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_drift_modal(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App, selected: usize) {
+    let lang = app.lang;
+    let modal_area = centered_rect(70, 50, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            trf(lang, "Spec drift: {} done tasks to verify again", &[&app.drift.len()]),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  Enter jump  d dismiss  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let tasks = app.full().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+    let rows = app.drift.iter().map(|d| {
+        let title = tasks.iter().find(|t| t.id == d.task_id).map_or("", |t| t.title.as_str());
+        let versions = match &d.versions {
+            (then, now) if then != now => format!(
+                "  ({} → {})",
+                then.as_deref().unwrap_or(&tr(lang, "unversioned")),
+                now.as_deref().unwrap_or(&tr(lang, "unversioned"))
+            ),
+            _ => String::new(),
+        };
+        let dismissed = app.notes.is_dismissed(&d.fingerprint);
+        let mut title = vec![Span::raw(title.to_string()), Span::styled(versions, theme.dim)];
+        if dismissed {
+            title.push(Span::styled(tr(lang, "  (dismissed)"), theme.dim));
+        }
+        Row::new(vec![
+            Cell::from(d.task_id.clone()),
+            Cell::from(Span::styled(
+                d.changed.join(", "),
+                if dismissed { theme.dim } else { theme.warn },
+            )),
+            Cell::from(Line::from(title)),
+        ])
+    });
+    let widths = [Constraint::Length(10), Constraint::Length(16), Constraint::Min(10)];
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(["Task", "Changed", "Title"].map(|h| tr(lang, h))).style(theme.dim),
+        )
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state =
+        TableState::default().with_selected(Some(selected.min(app.drift.len().saturating_sub(1))));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+fn draw_safe_mode_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    selected: usize,
+) {
+    let modal_area = centered_rect(70, 50, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let broken = app.safe_mode.iter().filter(|s| s.moved_to.is_none()).count();
+    let block = Block::default()
+        .title(Span::styled(
+            trf(
+                lang,
+                "Safe mode: {} state files failed to load, {} not moved aside yet",
+                &[&app.safe_mode.len(), &broken],
+            ),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  r move the file aside  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let mut lines = Vec::new();
+    for (i, failure) in app.safe_mode.iter().enumerate() {
+        let marker = if i == selected { "▸ " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::raw(marker),
+            Span::styled(format!("{:<8}", failure.file.label()), theme.title),
+            Span::raw(failure.path.display().to_string()),
+        ]));
+        // TOML errors quote the offending line over several lines.
+        for line in failure.error.lines().filter(|l| !l.trim().is_empty()) {
+            lines.push(Line::from(Span::styled(format!("    {line}"), theme.err)));
+        }
+        lines.push(Line::from(match &failure.moved_to {
+            Some(aside) => Span::styled(
+                trf(
+                    lang,
+                    "    moved to {}; the next start is a normal one",
+                    &[&aside.display()],
+                ),
+                theme.ok,
+            ),
+            None => Span::styled(format!("    {}", tr(lang, failure.file.fallback())), theme.dim),
+        }));
+        lines.push(Line::from(""));
+    }
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(ratatui::widgets::Wrap { trim: false }),
+        modal_area,
+    );
+}
+
+fn draw_export_modal(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App, selected: usize) {
+    let modal_area = centered_rect(60, 25, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let title = match &app.filter {
+        Some(filter) => trf(
+            lang,
+            "Export {} tasks matching {}",
+            &[&app.visible.len(), &filter.query()],
+        ),
+        None => trf(lang, "Export {} tasks", &[&app.visible.len()]),
+    };
+    let block = Block::default()
+        .title(Span::styled(title, theme.title))
+        .title_bottom(Span::styled(tr(lang, " ↑/↓ select  Enter write  Esc close "), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let rows = VIEW_FORMATS.iter().map(|(_, name, ext)| {
+        let path = app.path.with_extension(format!("view.{ext}"));
+        Row::new(vec![
+            Cell::from(*name),
+            Cell::from(Span::styled(path.display().to_string(), theme.dim)),
+        ])
+    });
+    let widths = [Constraint::Length(10), Constraint::Min(10)];
+    let table = Table::new(rows, widths)
+        .block(block)
+        .row_highlight_style(theme.selected);
+    let mut state =
+        TableState::default().with_selected(Some(selected.min(VIEW_FORMATS.len() - 1)));
+    f.render_stateful_widget(table, modal_area, &mut state);
+}
+
+fn draw_comment_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    reply: usize,
+    input: &str,
+) {
+    let modal_area = centered_rect(70, 60, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let id = app.selected_id().unwrap_or_default();
+    let block = Block::default()
+        .title(Span::styled(trf(lang, "Comment on {}", &[&id]), theme.title))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ reply to  Enter post  Esc cancel "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+    let [list_area, input_area] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(inner);
+
+    let threads = app.notes.comment_threads(&id);
+    let mut rows = vec![Row::new(vec![Cell::from(Span::styled(
+        tr(lang, "(start a new thread)"),
+        theme.dim,
+    ))])];
+    rows.extend(threads.iter().map(|(depth, c)| {
+        let indent = if *depth == 0 {
+            String::new()
+        } else {
+            format!("{}↳ ", "  ".repeat(depth - 1))
+        };
+        Row::new(vec![Cell::from(Line::from(vec![
+            Span::styled(format!("{indent}#{} {}: ", c.n, c.author), theme.dim),
+            Span::raw(c.body.clone()),
+        ]))])
+    }));
+    let table = Table::new(rows, [Constraint::Min(10)]).row_highlight_style(theme.selected);
+    let mut state = TableState::default().with_selected(Some(reply.min(threads.len())));
+    f.render_stateful_widget(table, list_area, &mut state);
+
+    let target = match reply.checked_sub(1).and_then(|i| threads.get(i)) {
+        Some((_, c)) => trf(lang, "reply to #{} ", &[&c.n]),
+        None => tr(lang, "new thread ").into_owned(),
+    };
+    let text = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(target, theme.dim),
+            Span::raw(input.to_string()),
+            Span::styled("▏", theme.title),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(text), input_area);
+}
+
+fn draw_replan_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    (deadline, workers, r): (u32, usize, &Replan),
+    scroll: u16,
+) {
+    let modal_area = centered_rect(85, 80, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Replan"), theme.title))
+        .title_bottom(Span::styled(
+            tr(
+                lang,
+                " ←/→ deadline ±1 day  PgUp/PgDn ±1 week  +/- workers  ↑/↓ scroll  Esc close ",
+            ),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let tasks = app.loaded.as_ref().map_or(&[][..], |l| l.tasks.tasks.as_slice());
+    let now = app.now();
+    let date = |days: f64| project_date(now, days).unwrap_or_else(|| "—".to_string());
+    let title = |id: &str| {
+        tasks
+            .iter()
+            .find(|t| t.id == id)
+            .map_or(String::new(), |t| t.title.clone())
+    };
+    let deadline_days = f64::from(deadline);
+    let mut finish = vec![
+        Span::styled(tr(lang, "All remaining tasks finish by "), theme.dim),
+        Span::raw(date(r.plan.finish())),
+        Span::styled(
+            trf(lang, " ({} days)", &[&format!("{:.1}", r.plan.finish())]),
+            theme.dim,
+        ),
+    ];
+    if let Some((low, high)) = r.range {
+        finish.push(Span::styled(
+            trf(lang, ", between {} and {}", &[&date(low), &date(high)]),
+            theme.dim,
+        ));
+    }
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(tr(lang, "Deadline "), theme.dim),
+            Span::styled(date(deadline_days), theme.title),
+            Span::styled(trf(lang, " (in {} days)   workers ", &[&deadline]), theme.dim),
+            Span::styled(workers.to_string(), theme.title),
+        ]),
+        Line::from(finish),
+        Line::from(""),
+    ];
+    let slot_line = |s: &Slot, style: Style| {
+        let late = s.finish - deadline_days;
+        let mut spans = vec![
+            Span::styled(format!("  {:<10}", s.id), style),
+            Span::styled(format!("{} → {}  ", date(s.start), date(s.finish)), theme.dim),
+        ];
+        if late > 0.0 {
+            spans.push(Span::styled(format!("+{late:.1}d  "), theme.warn));
+        }
+        spans.push(Span::raw(title(&s.id)));
+        Line::from(spans)
+    };
+    let slipping = r.slipping(deadline_days);
+    let fits: Vec<&Slot> = r
+        .plan
+        .slots
+        .iter()
+        .filter(|s| s.finish <= deadline_days)
+        .collect();
+    lines.push(Line::from(Span::styled(trf(lang, "Fits ({})", &[&fits.len()]), theme.ok)));
+    lines.extend(fits.iter().map(|s| slot_line(s, theme.ok)));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        trf(lang, "Slips ({})", &[&slipping.len()]),
+        if slipping.is_empty() { theme.dim } else { theme.warn },
+    )));
+    lines.extend(slipping.iter().map(|s| slot_line(s, theme.warn)));
+    if !r.cut.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            trf(lang, "Cut or defer to fit ({})", &[&r.cut.len()]),
+            theme.err,
+        )));
+        for id in &r.cut {
+            let prio = tasks
+                .iter()
+                .find(|t| t.id == *id)
+                .and_then(|t| t.priority.clone())
+                .unwrap_or_else(|| tr(lang, "unknown").into_owned());
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {id:<10}"), theme.err),
+                Span::styled(format!("[{prio}]  "), theme.dim),
+                Span::raw(title(id)),
+            ]));
+        }
+        lines.push(Line::from(Span::styled(
+            trf(
+                lang,
+                "  without them the rest finishes by {}",
+                &[&date(r.after_cut.finish())],
+            ),
+            theme.dim,
+        )));
+    }
+    if !r.plan.unestimated.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            trf(
+                lang,
+                "{} tasks without estimate_days count as {} day: {}",
+                &[
+                    &r.plan.unestimated.len(),
+                    &UNESTIMATED_DAYS,
+                    &r.plan.unestimated.join(", "),
+                ],
+            ),
+            theme.dim,
+        )));
+    }
+    if !r.plan.unschedulable.is_empty() {
+        lines.push(Line::from(Span::styled(
+            trf(
+                lang,
+                "In a dependency cycle, not scheduled: {}",
+                &[&r.plan.unschedulable.join(", ")],
+            ),
+            theme.err,
+        )));
+    }
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .scroll((scroll, 0)),
+        inner,
+    );
+}
+
+fn draw_field_editor_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    id: &str,
+    (field, selected, input): (Option<TaskField>, usize, &str),
+    error: Option<&str>,
+) {
+    let modal_area = centered_rect(60, 50, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let (title, hint) = match field {
+        None => (
+            trf(lang, "Edit {}", &[&id]),
+            " ↑/↓ select  Enter edit  Esc close ",
+        ),
+        Some(fl) if fl.is_choice() => (
+            trf(lang, "Edit {}: {}", &[&id, &fl.key()]),
+            " ↑/↓ select  Enter stage  Esc back ",
+        ),
+        Some(fl) => (
+            trf(lang, "Edit {}: {}", &[&id, &fl.key()]),
+            " Enter stage  Esc back ",
+        ),
+    };
+    let block = Block::default()
+        .title(Span::styled(title, theme.title))
+        .title_bottom(Span::styled(tr(lang, hint), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let option = |i: usize, text: String| {
+        let line = Line::from(format!("{} {text}", if i == selected { "›" } else { " " }));
+        if i == selected {
+            line.style(theme.selected)
+        } else {
+            line
+        }
+    };
+    let mut lines: Vec<Line> = match field {
+        None => {
+            let task = app.task(id);
+            TaskField::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, fl)| {
+                    let current = match (fl, task) {
+                        (TaskField::Title, Some(t)) => Some(t.title.clone()),
+                        (TaskField::Priority, Some(t)) => t.priority.clone(),
+                        (TaskField::Component, Some(t)) => t.component.clone(),
+                        (TaskField::Estimate, Some(t)) => t.estimate_days.map(|d| d.to_string()),
+                        (_, None) => None,
+                    };
+                    let current = current.unwrap_or_else(|| "-".to_string());
+                    option(i, format!("{:<14} {}", fl.key(), truncate(&current, 60)))
+                })
+                .collect()
+        }
+        Some(fl) if fl.is_choice() => app
+            .field_choices(fl)
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| option(i, c))
+            .collect(),
+        Some(_) => vec![Line::from(vec![
+            Span::raw(input.to_string()),
+            Span::styled("▏", theme.title),
+        ])],
+    };
+    if let Some(e) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(e.to_string(), theme.err)));
+    }
+    f.render_widget(Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: false }), inner);
+}
+
+fn draw_overview_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    selected: usize,
+) {
+    // Nearly full screen: this is the view for a wall-mounted monitor.
+    let modal_area = centered_rect(96, 92, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Workspace"), theme.title))
+        .title_bottom(Span::styled(tr(lang, " ↑/↓ select  Enter open  Esc close "), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    const GAUGE: usize = 24;
+    let now = app.now();
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "  {:<20} {:<w$} {:>9} {:>8} {:>9} {:>7}",
+            tr(lang, "project"),
+            tr(lang, "progress"),
+            tr(lang, "done"),
+            tr(lang, "blocked"),
+            tr(lang, "updated"),
+            tr(lang, "health"),
+            w = GAUGE + 6
+        ),
+        theme.dim,
+    ))];
+    for (i, row) in app.overview().iter().enumerate() {
+        let marker = if i == selected { "› " } else { "  " };
+        let name = format!("{marker}{:<20} ", truncate(&row.name, 20));
+        let name_style = if row.current { theme.title } else { Style::default() };
+        let s = match &row.summary {
+            Ok(s) => s,
+            Err(e) => {
+                lines.push(Line::from(vec![
+                    Span::styled(name, name_style),
+                    Span::styled(truncate(e, 80), theme.err),
+                ]));
+                continue;
+            }
+        };
+        let share = if s.total == 0 {
+            0.0
+        } else {
+            s.done as f64 / s.total as f64
+        };
+        let filled = (share * GAUGE as f64).round() as usize;
+        let updated = s
+            .file_mtime
+            .and_then(|m| now.duration_since(m).ok())
+            .map_or_else(|| "?".to_string(), |d| trf(lang, "{} ago", &[&format_age(d)]));
+        let health = s.health();
+        let health_style = match health {
+            80.. => theme.ok,
+            50..=79 => theme.warn,
+            _ => theme.err,
+        };
+        let mut line = Line::from(vec![
+            Span::styled(name, name_style),
+            Span::styled("█".repeat(filled), theme.ok),
+            Span::styled("░".repeat(GAUGE - filled), theme.dim),
+            Span::raw(format!(" {:>4.0}%", share * 100.0)),
+            Span::raw(format!(" {:>9}", format!("{}/{}", s.done, s.total))),
+            Span::styled(
+                format!(" {:>8}", s.blocked),
+                if s.blocked > 0 { theme.warn } else { theme.dim },
+            ),
+            Span::styled(format!(" {updated:>9}"), theme.dim),
+            Span::styled(format!(" {health:>7}"), health_style),
+        ]);
+        if i == selected {
+            line = line.style(theme.selected);
+        }
+        lines.push(line);
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// `1.2 MiB`, `340 KiB`, `512 B`.
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / f64::from(1 << 20)),
+        b if b >= 1 << 10 => format!("{} KiB", b >> 10),
+        b => format!("{b} B"),
+    }
+}
+
+fn draw_watch_log_modal(f: &mut Frame<'_>, theme: &UiTheme, area: Rect, app: &App, scroll: u16) {
+    let modal_area = centered_rect(90, 85, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Watch log (debug)"), theme.title))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ PgUp/PgDn scroll  End newest  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let (reused, rebuilt) = {
+        let cache = app.view.pane_cache.borrow();
+        (cache.reused, cache.rebuilt)
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::styled(tr(lang, "watching: "), theme.dim),
+        Span::raw(app.watch_desc.clone().unwrap_or_else(|| "-".to_string())),
+        Span::styled("  pane cache: ", theme.dim),
+        Span::raw(format!("{reused} reused, {rebuilt} rebuilt")),
+    ])];
+    let history = &app.history;
+    let mut retained = vec![
+        Span::styled("retained: ", theme.dim),
+        Span::raw(format!("≈{}", format_bytes(app.retained_bytes()))),
+        Span::styled("  history ", theme.dim),
+        Span::raw(format!("{}/{}", history.snapshots.len(), history.limit())),
+    ];
+    if history.spilled() > 0 {
+        retained.push(Span::styled(
+            format!(" (+{} on disk only)", history.spilled()),
+            theme.dim,
+        ));
+    }
+    retained.extend([
+        Span::styled("  watch log ", theme.dim),
+        Span::raw(format!("{}/{}", app.watch_log.entries().len(), app.watch_log.limit())),
+    ]);
+    lines.push(Line::from(retained));
+    let height = inner.height.saturating_sub(2) as usize;
+    app.view.watch_log_rows.set(height);
+    let entries = app.watch_log.entries();
+    let end = entries.len().saturating_sub(scroll as usize);
+    let start = end.saturating_sub(height);
+    if end == 0 {
+        lines.push(Line::from(Span::styled(tr(lang, "No watcher activity yet."), theme.dim)));
+    }
+    for e in entries.skip(start).take(end - start) {
+        let at = humantime::format_rfc3339_millis(e.at).to_string();
+        let style = match e.kind {
+            "error" => theme.err,
+            "event" | "poll" | "symlink" => theme.ok,
+            "load" if e.detail.starts_with("failed") => theme.err,
+            "load" => theme.title,
+            _ => theme.dim,
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", at.get(11..23).unwrap_or(&at)), theme.dim),
+            Span::styled(format!("{:<9}", e.kind), style),
+            Span::raw(e.detail.clone()),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_staged_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    scroll: u16,
+    diff: &[Option<DiffLine>],
+) {
+    let modal_area = centered_rect(85, 80, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Staged edits"), theme.title))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ scroll  W write  u unstage last  x discard all  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, edit) in app.staged.iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>3}. ", i + 1), theme.dim),
+            Span::raw(edit.describe()),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(tr(lang, "Resulting change to the file"), theme.title)));
+    for entry in diff {
+        let Some(d) = entry else {
+            lines.push(Line::from(Span::styled("  ⋮", theme.dim)));
+            continue;
+        };
+        let (sign, style) = match d.kind {
+            DiffKind::Same => (" ", theme.dim),
+            DiffKind::Removed => ("-", theme.err),
+            DiffKind::Added => ("+", theme.ok),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>5} ", d.line), theme.dim),
+            Span::styled(format!("{sign} {}", d.text), style),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), inner);
+}
+
+fn draw_fix_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    lang: Lang,
+    fix: &Fix,
+    preview: &Result<Vec<(usize, String, String)>, String>,
+) {
+    let modal_area = centered_rect(70, 40, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Fix"), theme.title))
+        .title_bottom(Span::styled(tr(lang, " Enter stage  Esc back "), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+
+    let mut lines = vec![Line::from(fix.describe()), Line::from("")];
+    match preview {
+        Ok(changes) if changes.is_empty() => {
+            lines.push(Line::from(Span::styled(
+                tr(lang, "The file already has this change."),
+                theme.dim,
+            )))
+        }
+        Ok(changes) => {
+            for (line, before, after) in changes {
+                lines.push(Line::from(Span::styled(trf(lang, "line {}", &[line]), theme.dim)));
+                lines.push(Line::from(Span::styled(format!("- {before}"), theme.err)));
+                lines.push(Line::from(Span::styled(format!("+ {after}"), theme.ok)));
+            }
+        }
+        Err(e) => lines.push(Line::from(Span::styled(e.clone(), theme.err))),
+    }
+    f.render_widget(
+        Paragraph::new(lines).block(block).wrap(ratatui::widgets::Wrap { trim: false }),
+        modal_area,
+    );
+}
+
+fn draw_coverage_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    lang: Lang,
+    loaded: Option<&LoadedTasks>,
+    scroll: u16,
+) {
+    let modal_area = centered_rect(70, 70, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Spec coverage"), theme.title))
+        .title_bottom(Span::styled(tr(lang, " ↑/↓ scroll  Esc close "), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let Some(cov) = loaded.map(|l| &l.coverage) else {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(tr(lang, "No data loaded yet."), theme.dim))),
+            inner,
+        );
+        return;
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(p) = &cov.spec_path {
+        lines.push(Line::from(vec![
+            Span::styled("Spec: ", theme.dim),
+            Span::raw(p.display().to_string()),
+        ]));
+    }
+    if let Some(err) = &cov.spec_error {
+        lines.push(Line::from(Span::styled(err.clone(), theme.warn)));
+    } else {
+        let total = cov.anchors.len();
+        let pct = if total == 0 {
+            100.0
+        } else {
+            cov.covered() as f64 * 100.0 / total as f64
+        };
+        let style = if cov.uncovered.is_empty() { theme.ok } else { theme.warn };
+        lines.push(Line::from(vec![
+            Span::styled(tr(lang, "Anchors referenced: "), theme.dim),
+            Span::styled(format!("{}/{total} ({pct:.0}%)", cov.covered()), style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        trf(lang, "Spec anchors without tasks ({})", &[&cov.uncovered.len()]),
+        theme.title,
+    )));
+    if cov.uncovered.is_empty() {
+        lines.push(Line::from(Span::styled(tr(lang, "  none"), theme.dim)));
+    } else {
+        for chunk in cov.uncovered.chunks(6) {
+            lines.push(Line::from(format!("  {}", chunk.join("  "))));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        trf(lang, "Tasks without spec_refs ({})", &[&cov.tasks_without_refs.len()]),
+        theme.title,
+    )));
+    if cov.tasks_without_refs.is_empty() {
+        lines.push(Line::from(Span::styled(tr(lang, "  none"), theme.dim)));
+    } else {
+        let titles: std::collections::HashMap<&str, &str> = loaded
+            .map(|l| {
+                l.tasks
+                    .tasks
+                    .iter()
+                    .map(|t| (t.id.as_str(), t.title.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for id in &cov.tasks_without_refs {
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {id}  ")),
+                Span::styled(titles.get(id.as_str()).copied().unwrap_or("").to_string(), theme.dim),
+            ]));
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), inner);
+}
+
+/// Rows the charts take under the stats text, when the panel is tall enough for both.
+const CHART_ROWS: u16 = 12;
+
+/// Burndown, estimate histogram and dependency graph side by side: as characters, or as empty
+/// cells recorded in `chart_slots` when the terminal draws them as images.
+fn draw_charts(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    loaded: &crate::tasks::LoadedTasks,
+    images: bool,
+) {
+    let open = charts::burndown(&app.history);
+    let layout = charts::graph_layout(&loaded.tasks);
+    let lang = app.lang;
+    let titles = [
+        trf(lang, "Open tasks, {} loads", &[&open.len()]),
+        tr(lang, "Tasks by estimate (days)").into_owned(),
+        trf(lang, "Dependencies ({} edges)", &[&layout.edges.len()]),
+    ];
+    let all = [
+        Chart::Burndown(open),
+        Chart::Estimates(charts::estimates(&loaded.tasks, app.now())),
+        Chart::Graph(layout),
+    ];
+    let columns = Layout::horizontal([Constraint::Ratio(1, 3); 3])
+        .spacing(2)
+        .split(area);
+    let marker = if app.caps.ascii {
+        Marker::Dot
+    } else {
+        Marker::Braille
+    };
+    for ((title, chart), column) in titles.into_iter().zip(all).zip(columns.iter()) {
+        let block = Block::default()
+            .title(Span::styled(title, theme.title))
+            .borders(Borders::TOP)
+            .border_style(theme.border);
+        let inner = block.inner(*column);
+        f.render_widget(block, *column);
+        if matches!(&chart, Chart::Burndown(open) if open.len() < 2) {
+            let text = Span::styled(tr(lang, "Not enough history yet."), theme.dim);
+            f.render_widget(Paragraph::new(Line::from(text)), inner);
+            continue;
+        }
+        if images {
+            let slot = ChartSlot { area: inner, chart };
+            app.view.chart_slots.borrow_mut().push(slot);
+            continue;
+        }
+        match &chart {
+            Chart::Burndown(open) => {
+                let points: Vec<(f64, f64)> = open
+                    .iter()
+                    .enumerate()
+                    .map(|(i, n)| (i as f64, *n as f64))
+                    .collect();
+                let max = open.iter().max().copied().unwrap_or(0).max(1);
+                let line = Dataset::default()
+                    .marker(marker)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(Color::Cyan))
+                    .data(&points);
+                let widget = ratatui::widgets::Chart::new(vec![line])
+                    .x_axis(
+                        Axis::default()
+                            .bounds([0.0, (points.len() - 1) as f64])
+                            .style(theme.dim),
+                    )
+                    .y_axis(
+                        Axis::default()
+                            .bounds([0.0, max as f64])
+                            .labels(["0".to_string(), max.to_string()])
+                            .style(theme.dim),
+                    );
+                f.render_widget(widget, inner);
+            }
+            Chart::Estimates(counts) => {
+                let bars: Vec<Bar> = counts
+                    .iter()
+                    .zip(charts::ESTIMATE_LABELS)
+                    .map(|(n, label)| Bar::default().value(*n).label(Line::from(label)))
+                    .collect();
+                let width = (inner.width / bars.len() as u16).saturating_sub(1).max(1);
+                let widget = BarChart::default()
+                    .data(BarGroup::default().bars(&bars))
+                    .bar_width(width)
+                    .bar_gap(1)
+                    .bar_style(Style::default().fg(Color::Cyan))
+                    .value_style(theme.selected)
+                    .label_style(theme.dim);
+                f.render_widget(widget, inner);
+            }
+            Chart::Graph(layout) => {
+                let widget = Canvas::default()
+                    .marker(marker)
+                    .x_bounds([0.0, 1.0])
+                    .y_bounds([0.0, 1.0])
+                    .paint(|ctx| {
+                        let nodes = &layout.nodes;
+                        for &(from, to, unmet) in &layout.edges {
+                            let (a, b) = (&nodes[from], &nodes[to]);
+                            ctx.draw(&CanvasLine {
+                                x1: a.x,
+                                y1: 1.0 - a.y,
+                                x2: b.x,
+                                y2: 1.0 - b.y,
+                                color: if unmet { Color::Yellow } else { Color::DarkGray },
+                            });
+                        }
+                        ctx.layer();
+                        for n in nodes {
+                            ctx.draw(&Points {
+                                coords: &[(n.x, 1.0 - n.y)],
+                                color: status_style(theme, &n.status).fg.unwrap_or(Color::Reset),
+                            });
+                        }
+                    });
+                f.render_widget(widget, inner);
+            }
+        }
+    }
+}
+
+fn draw_stats_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    app: &App,
+    scroll: u16,
+    images: bool,
+) {
+    let modal_area = centered_rect(70, 70, area);
+    f.render_widget(Clear, modal_area);
+
+    let lang = app.lang;
+    let block = Block::default()
+        .title(Span::styled(tr(lang, "Stats"), theme.title))
+        .title_bottom(Span::styled(tr(lang, " ↑/↓ scroll  Esc close "), theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let Some(l) = &app.loaded else {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(tr(lang, "No data loaded yet."), theme.dim))),
+            inner,
+        );
+        return;
+    };
+    let stats = &l.stats;
+    let text_area = if inner.height >= CHART_ROWS * 2 {
+        let [text, charts] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(CHART_ROWS)]).areas(inner);
+        draw_charts(f, theme, charts, app, l, images);
+        text
+    } else {
+        inner
+    };
+
+    let mut lines: Vec<Line> = vec![Line::from(vec![
+        Span::styled(tr(lang, "Tasks: "), theme.dim),
+        Span::raw(stats.total.to_string()),
+        Span::styled(tr(lang, "  done: "), theme.dim),
+        Span::styled(stats.done.to_string(), theme.ok),
+        Span::styled(tr(lang, "  blocked by deps: "), theme.dim),
+        Span::styled(stats.blocked_by_deps.to_string(), theme.warn),
+    ])];
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(tr(lang, "By status"), theme.title)));
+    for (k, v) in &stats.by_status {
+        lines.push(Line::from(format!("  {k:<16} {v:>5}")));
+    }
+    // Most urgent first; names the order does not know after it, alphabetically.
+    let priorities = &app.config.priorities;
+    let mut by_priority: Vec<(&String, &usize)> = stats.by_priority.iter().collect();
+    by_priority.sort_by_key(|(k, _)| priorities.rank(k).unwrap_or(usize::MAX));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(tr(lang, "By priority"), theme.title)));
+    for (k, v) in by_priority {
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(format!("{k:<16}"), priority_style(theme, priorities, k)),
+            Span::raw(format!(" {v:>5}")),
+        ]));
+    }
+
+    // Components carry estimates and, with a day rate, cost.
+    let cost = app.cost();
+    let mut days: std::collections::BTreeMap<String, f64> = Default::default();
+    let mut ranges: std::collections::BTreeMap<String, (f64, f64)> = Default::default();
+    let spread = estimate_spread(&l.tasks);
+    let ranged = l.tasks.tasks.iter().any(|t| estimate_range(t, spread).is_some());
+    for t in &l.tasks.tasks {
+        let est = t.estimate_days.unwrap_or(0.0);
+        *days.entry(crate::tasks::component_key(t)).or_default() += est;
+        let (low, high) = estimate_range(t, spread).unwrap_or((est, est));
+        let range = ranges.entry(crate::tasks::component_key(t)).or_default();
+        *range = (range.0 + low, range.1 + high);
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(tr(lang, "By component"), theme.title)));
+    let owners = &app.config.owners;
+    let mut header = format!(
+        "  {:<16} {:>5} {:>9}",
+        tr(lang, "component"),
+        tr(lang, "tasks"),
+        tr(lang, "est.days")
+    );
+    if ranged {
+        header.push_str(&format!(" {:>11}", tr(lang, "range")));
+    }
+    if cost.is_some() {
+        header.push_str(&format!(" {:>12} {:>12}", tr(lang, "spent"), tr(lang, "remaining")));
+    }
+    if !owners.is_empty() {
+        header.push_str(&tr(lang, "  owner"));
+    }
+    lines.push(Line::from(Span::styled(header, theme.dim)));
+    for (comp, count) in &stats.by_component {
+        let mut row = format!(
+            "  {:<16} {count:>5} {:>9.1}",
+            truncate(comp, 16),
+            days.get(comp).copied().unwrap_or(0.0)
+        );
+        if ranged {
+            let range = ranges.get(comp).copied().unwrap_or_default();
+            row.push_str(&format!(" {:>11}", days_range(range)));
+        }
+        if let Some(c) = cost.as_ref().and_then(|c| c.by_component.get(comp)) {
+            row.push_str(&format!(
+                " {:>12} {:>12}",
+                format_amount(c.spent),
+                format_amount(c.remaining)
+            ));
+        }
+        let mut row = vec![Span::raw(row)];
+        if !owners.is_empty() {
+            row.push(match owners.get(comp) {
+                Some(owner) => Span::raw(format!("  {owner}")),
+                None => Span::styled(tr(lang, "  nobody"), theme.warn),
+            });
+        }
+        lines.push(Line::from(row));
+    }
+
+    let budgets = app.budgets();
+    if !budgets.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(tr(lang, "Budgets (est. days)"), theme.title)));
+        for b in &budgets {
+            let mut row = vec![Span::raw(format!(
+                "  {:<16} {:>7.1} / {:<7.1}",
+                truncate(&b.component, 16),
+                b.estimated_days,
+                b.budget_days
+            ))];
+            if b.over() {
+                row.push(Span::styled(
+                    trf(
+                        lang,
+                        " over by {}",
+                        &[&format!("{:.1}", b.estimated_days - b.budget_days)],
+                    ),
+                    theme.warn,
+                ));
+            }
+            lines.push(Line::from(row));
+        }
+    }
+
+    lines.push(Line::from(""));
+    match &cost {
+        Some(c) => {
+            lines.push(Line::from(Span::styled(
+                trf(lang, "Cost (day rate {})", &[&format_amount(c.day_rate)]),
+                theme.title,
+            )));
+            let range = (c.total.remaining_low_days, c.total.remaining_high_days);
+            let remaining_days = match range.0 < range.1 {
+                true => format!(" ({:.1}d, {})", c.total.remaining_days, days_range(range)),
+                false => format!(" ({:.1}d)", c.total.remaining_days),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(tr(lang, "  spent: "), theme.dim),
+                Span::styled(format_amount(c.total.spent), theme.ok),
+                Span::styled(format!(" ({:.1}d)", c.total.spent_days), theme.dim),
+                Span::styled(tr(lang, "  remaining: "), theme.dim),
+                Span::styled(format_amount(c.total.remaining), theme.warn),
+                Span::styled(remaining_days, theme.dim),
+                Span::styled(tr(lang, "  total: "), theme.dim),
+                Span::raw(format_amount(c.total.spent + c.total.remaining)),
+            ]));
+            if c.total.unestimated > 0 {
+                lines.push(Line::from(Span::styled(
+                    trf(
+                        lang,
+                        "  {} task(s) without estimate_days are not counted",
+                        &[&c.total.unestimated],
+                    ),
+                    theme.dim,
+                )));
+            }
+        }
+        None => lines.push(Line::from(Span::styled(
+            tr(lang, "Set day_rate in the config file to see cost estimates."),
+            theme.dim,
+        ))),
+    }
+
+    if !app.targets.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(tr(lang, "Targets"), theme.title)));
+        for t in &app.targets {
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {}  ", target_summary(t))),
+                Span::styled(t.schedule.label(), schedule_style(theme, t.schedule)),
+            ]));
+        }
+    }
+
+    // Who finished what, by the commits that set tasks done.
+    match &app.contributors {
+        Some(Ok(people)) => {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(tr(lang, "Completed by (git)"), theme.title)));
+            if people.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    tr(lang, "  no commit has set a task done yet"),
+                    theme.dim,
+                )));
+            } else {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {:<20} {:>5}  {:<10}  {}",
+                        tr(lang, "author"),
+                        tr(lang, "done"),
+                        tr(lang, "last"),
+                        tr(lang, "tasks")
+                    ),
+                    theme.dim,
+                )));
+            }
+            for p in people {
+                let last = humantime::format_rfc3339_seconds(p.last).to_string()[..10].to_string();
+                lines.push(Line::from(vec![
+                    Span::raw(format!(
+                        "  {:<20} {:>5}  {last}  ",
+                        truncate(&p.name, 20),
+                        p.tasks.len()
+                    )),
+                    Span::styled(truncate(&p.tasks.join(", "), 60), theme.dim),
+                ]));
+            }
+        }
+        Some(Err(e)) => {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                trf(lang, "Completed by (git): {}", &[e]),
+                theme.dim,
+            )));
+        }
+        None => {}
+    }
+
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), text_area);
+}
+
+/// Heat table of unmet dependencies between components: rows wait on columns.
+fn draw_matrix_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    lang: Lang,
+    loaded: Option<&LoadedTasks>,
+    selected: usize,
+) {
+    let modal_area = centered_rect(85, 70, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(
+            tr(lang, "Component dependencies (row waits on column)"),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(
+            tr(lang, " ↑/↓ select  Enter filter to component  Esc close "),
+            theme.dim,
+        ))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let Some(l) = loaded else {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(tr(lang, "No data loaded yet."), theme.dim))),
+            inner,
+        );
+        return;
+    };
+    let matrix = dependency_matrix(&l.tasks);
+    let max = matrix.max();
+    let heat = |n: usize, diagonal: bool| -> Style {
+        match n {
+            0 => theme.dim,
+            _ if diagonal => Style::default(),
+            _ if n * 3 >= max * 2 => theme.err,
+            _ if n * 3 >= max => theme.warn,
+            _ => Style::default().fg(Color::LightYellow),
+        }
+    };
+
+    let col_width = matrix
+        .components
+        .iter()
+        .map(|c| c.chars().count())
+        .max()
+        .unwrap_or(4)
+        .clamp(4, 10) as u16;
+    let mut header = vec![Cell::from("")];
+    header.extend(
+        matrix
+            .components
+            .iter()
+            .map(|c| Cell::from(truncate(c, col_width as usize))),
+    );
+    header.push(Cell::from("Σ"));
+
+    let rows = matrix.components.iter().enumerate().map(|(r, comp)| {
+        let mut cells = vec![Cell::from(Span::styled(comp.clone(), theme.title))];
+        for (c, &n) in matrix.counts[r].iter().enumerate() {
+            let text = if n == 0 { "·".to_string() } else { n.to_string() };
+            cells.push(Cell::from(Span::styled(text, heat(n, r == c))));
+        }
+        cells.push(Cell::from(matrix.counts[r].iter().sum::<usize>().to_string()));
+        Row::new(cells)
+    });
+
+    let mut widths = vec![Constraint::Length(14)];
+    widths.extend(std::iter::repeat_n(
+        Constraint::Length(col_width),
+        matrix.components.len(),
+    ));
+    widths.push(Constraint::Length(5));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(theme.dim))
+        .row_highlight_style(theme.selected);
+    let mut state = TableState::default()
+        .with_selected(Some(selected.min(matrix.components.len().saturating_sub(1))));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let cross = matrix.cross_total();
+    let summary = if cross == 0 {
+        Span::styled(
+            tr(lang, "No unmet dependencies cross component boundaries."),
+            theme.ok,
+        )
+    } else {
+        Span::styled(
+            trf(lang, "{} unmet dependencies cross component boundaries.", &[&cross]),
+            theme.warn,
+        )
+    };
+    f.render_widget(Paragraph::new(Line::from(summary)), chunks[1]);
+}
+
+/// Bottom-right tooltip for the `--demo` tour, drawn over the body without a backdrop so the
+/// part of the UI being described stays visible.
+fn draw_tour_tooltip(f: &mut Frame<'_>, theme: &UiTheme, body: Rect, tour: Tour) {
+    let Some(step) = TOUR.get(tour.step) else {
+        return;
+    };
+    let width = body.width.min(52);
+    let height = body.height.min(7);
+    let area = Rect {
+        x: body.x + body.width - width,
+        y: body.y + body.height - height,
+        width,
+        height,
+    };
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(Span::styled(
+            format!("Tour {}/{}: {}", tour.step + 1, TOUR.len(), step.title),
+            theme.title,
+        ))
+        .title_bottom(Span::styled(" t next  T back  Esc hide ", theme.dim))
+        .borders(Borders::ALL)
+        .border_style(theme.warn)
+        .border_type(BorderType::Rounded);
+    f.render_widget(
+        Paragraph::new(step.text)
+            .wrap(ratatui::widgets::Wrap { trim: true })
+            .block(block),
+        area,
+    );
+}
+
+fn draw_input_modal(
+    f: &mut Frame<'_>,
+    theme: &UiTheme,
+    area: Rect,
+    lang: Lang,
+    title: &str,
+    input: &str,
+) {
+    let modal_area = centered_rect(60, 20, area);
+    f.render_widget(Clear, modal_area);
+
+    let block = Block::default()
+        .title(Span::styled(title.to_string(), theme.title))
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let text = vec![
+        Line::from(vec![Span::raw(input.to_string()), Span::styled("▏", theme.title)]),
+        Line::from(""),
+        Line::from(Span::styled(tr(lang, "Enter confirm  Esc cancel"), theme.dim)),
+    ];
+    f.render_widget(Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: false }), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(popup_layout[1])[1]
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    for (i, ch) in s.chars().enumerate() {
+        if i + 1 >= max {
+            break;
+        }
+        out.push(ch);
+    }
+    out.push('…');
+    out
 }
 
 fn summarize_map(map: &std::collections::BTreeMap<String, usize>, max_items: usize) -> String {
@@ -600,3 +4430,85 @@ fn summarize_map(map: &std::collections::BTreeMap<String, usize>, max_items: usi
     }
     parts.join("  ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Profile;
+    use ratatui::backend::TestBackend;
+
+    const REPEATED_ID: &str = r#"
+[[tasks]]
+id = "T-1"
+title = "first"
+status = "todo"
+
+[[tasks]]
+id = "T-2"
+title = "second"
+status = "todo"
+
+[[tasks]]
+id = "T-1"
+title = "again"
+status = "todo"
+"#;
+
+    /// The first cell where `text` starts, scanning rows top down.
+    fn find(buffer: &Buffer, text: &str) -> Option<(u16, u16)> {
+        let area = buffer.area;
+        let chars: Vec<String> = text.chars().map(String::from).collect();
+        (area.top()..area.bottom())
+            .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                chars.iter().enumerate().all(|(n, c)| {
+                    let x = x + n as u16;
+                    x < area.right() && buffer[(x, y)].symbol() == c
+                })
+            })
+    }
+
+    #[test]
+    fn repeated_id_is_marked_whatever_the_column_order() {
+        let mut app = App::demo(Some(REPEATED_ID.to_string()));
+        let columns = vec![
+            TableColumn::Status,
+            TableColumn::Priority,
+            TableColumn::Title,
+            TableColumn::Id,
+        ];
+        let profile = Profile {
+            columns: Some(columns),
+            ..Profile::default()
+        };
+        app.config.profiles.insert("late".to_string(), profile);
+        app.profile = Some("late".to_string());
+        app.maybe_reload(Duration::ZERO, Duration::MAX);
+
+        let theme = UiTheme::default();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        draw_screen(&mut terminal, &theme, &app, false).unwrap();
+        let buffer = terminal.backend().buffer();
+        let fg = |text: &str| find(buffer, text).map(|at| buffer[at].fg);
+        let err = theme.err.fg.unwrap_or(Color::Reset);
+        assert_eq!(fg("T-1#2"), Some(err));
+        assert_ne!(fg("T-2"), Some(err));
+    }
+
+    #[test]
+    fn the_header_shows_the_retained_memory() {
+        let mut app = App::demo(Some(REPEATED_ID.to_string()));
+        app.maybe_reload(Duration::ZERO, Duration::MAX);
+        let theme = UiTheme::default();
+        let mut terminal = Terminal::new(TestBackend::new(200, 40)).unwrap();
+        draw_screen(&mut terminal, &theme, &app, false).unwrap();
+        assert_eq!(find(terminal.backend().buffer(), "Memory: "), None);
+
+        app.measure_retained();
+        for compact in [false, true] {
+            app.view.compact_header = compact;
+            draw_screen(&mut terminal, &theme, &app, false).unwrap();
+            assert!(find(terminal.backend().buffer(), "Memory: ≈").is_some());
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Local usage statistics (`[usage]` in the config, off by default): which dashboard keys, panels
+//! and profiles get used and how long sessions last, summed into one JSON file on this machine
+//! and shown by `harnas-monitor usage`. Nothing is sent anywhere. Only counts are kept: no task
+//! ids, file names or anything typed into an input.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Instant;
+use crate::keymap::key_name;
+use crate::keys::{KeyCode, KeyModifiers};
+use crate::writer::write_atomic;
+
+/// Bumped when a field changes meaning or goes away; new fields do not bump it.
+const VERSION: u32 = 1;
+
+/// Session length buckets: the upper bound in minutes and the report's label.
+const LENGTHS: [(u64, &str); 4] = [
+    (1, "under 1m"),
+    (10, "1-10m"),
+    (60, "10-60m"),
+    (u64::MAX, "over 1h"),
+];
+
+/// Stands in for "no profile" in the profile times.
+const NO_PROFILE: &str = "(none)";
+
+/// `$XDG_STATE_HOME/harnas-monitor/usage.json`, falling back to `~/.local/state`.
+pub fn default_usage_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))?;
+    Some(base.join("harnas-monitor").join("usage.json"))
+}
+
+/// The totals over every recorded session, as stored in the usage file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub version: u32,
+    pub sessions: u64,
+    /// Seconds over all sessions, and of the longest one.
+    pub seconds: u64,
+    pub longest: u64,
+    /// RFC 3339 times of the first and the latest session's start.
+    pub first: Option<String>,
+    pub last: Option<String>,
+    /// Sessions by `LENGTHS` label.
+    #[serde(default)]
+    pub lengths: BTreeMap<String, u64>,
+    /// Presses by key name, after the profile's remapping, outside modals and inputs.
+    #[serde(default)]
+    pub keys: BTreeMap<String, u64>,
+    /// Times each panel or modal was opened, by `Modal::kind`.
+    #[serde(default)]
+    pub views: BTreeMap<String, u64>,
+    /// Seconds spent in each profile; `NO_PROFILE` for none.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, u64>,
+}
+
+/// One dashboard session's counts, kept in memory until it ends.
+#[derive(Debug)]
+pub struct Session {
+    started: SystemTime,
+    clock: Instant,
+    keys: BTreeMap<String, u64>,
+    views: BTreeMap<String, u64>,
+    profiles: BTreeMap<String, Duration>,
+    /// The active profile and since when.
+    profile: (Option<String>, Instant),
+    view: Option<&'static str>,
+}
+
+impl Session {
+    pub fn new(started: SystemTime) -> Self {
+        let clock = Instant::now();
+        Session {
+            started,
+            clock,
+            keys: BTreeMap::new(),
+            views: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            profile: (None, clock),
+            view: None,
+        }
+    }
+
+    pub fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let mut name = key_name(code);
+        if modifiers.contains(KeyModifiers::ALT) {
+            name = format!("Alt-{name}");
+        }
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            name = format!("Ctrl-{name}");
+        }
+        *self.keys.entry(name).or_default() += 1;
+    }
+
+    /// Counts `view` when it differs from the one open before.
+    pub fn view(&mut self, view: Option<&'static str>) {
+        if let Some(v) = view.filter(|v| self.view != Some(*v)) {
+            *self.views.entry(v.to_string()).or_default() += 1;
+        }
+        self.view = view;
+    }
+
+    pub fn profile(&mut self, name: Option<&str>) {
+        if self.profile.0.as_deref() == name {
+            return;
+        }
+        self.close_profile();
+        self.profile.0 = name.map(str::to_string);
+    }
+
+    fn close_profile(&mut self) {
+        let (name, since) = &mut self.profile;
+        let key = name.clone().unwrap_or_else(|| NO_PROFILE.to_string());
+        *self.profiles.entry(key).or_default() += since.elapsed();
+        *since = Instant::now();
+    }
+
+    /// Adds this session to the totals in the usage file at `path`, creating it if needed.
+    pub fn record(mut self, path: &Path) -> Result<()> {
+        self.close_profile();
+        let mut stats = load(path)?.unwrap_or_default();
+        let seconds = self.clock.elapsed().as_secs();
+        let started = humantime::format_rfc3339_seconds(self.started).to_string();
+        stats.version = VERSION;
+        stats.sessions += 1;
+        stats.seconds += seconds;
+        stats.longest = stats.longest.max(seconds);
+        stats.first.get_or_insert_with(|| started.clone());
+        stats.last = Some(started);
+        let (_, label) = LENGTHS
+            .iter()
+            .find(|(max, _)| seconds < max.saturating_mul(60))
+            .unwrap_or(&LENGTHS[LENGTHS.len() - 1]);
+        *stats.lengths.entry(label.to_string()).or_default() += 1;
+        for (key, n) in self.keys {
+            *stats.keys.entry(key).or_default() += n;
+        }
+        for (view, n) in self.views {
+            *stats.views.entry(view).or_default() += n;
+        }
+        for (profile, d) in self.profiles {
+            *stats.profiles.entry(profile).or_default() += d.as_secs();
+        }
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let json = serde_json::to_string_pretty(&stats).context("encoding usage statistics")?;
+        write_atomic(path, &format!("{json}\n")).context("writing the usage file")
+    }
+}
+
+/// The usage file's totals; `None` when nothing was recorded yet.
+pub fn load(path: &Path) -> Result<Option<UsageStats>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let stats: UsageStats =
+        serde_json::from_str(&contents).map_err(|e| anyhow!("parsing {}: {e}", path.display()))?;
+    Ok(Some(stats))
+}
+
+/// The plain-text report of `harnas-monitor usage`: most used first in every list.
+pub fn write_report(out: &mut dyn Write, path: &Path, stats: &UsageStats) -> Result<()> {
+    // Whole minutes, unless there is less than one.
+    let minutes = |secs: u64| {
+        let rounded = if secs < 60 { secs } else { secs / 60 * 60 };
+        humantime::format_duration(Duration::from_secs(rounded))
+    };
+    writeln!(out, "usage statistics in {}", path.display())?;
+    if let (Some(first), Some(last)) = (&stats.first, &stats.last) {
+        writeln!(out, "first session {first}, latest {last}")?;
+    }
+    let average = stats.seconds / stats.sessions.max(1);
+    writeln!(
+        out,
+        "{} sessions, {} in total, {} on average, longest {}",
+        stats.sessions,
+        minutes(stats.seconds),
+        minutes(average),
+        minutes(stats.longest)
+    )?;
+    let lengths: Vec<String> = LENGTHS
+        .iter()
+        .map(|(_, label)| format!("{label} {}", stats.lengths.get(*label).unwrap_or(&0)))
+        .collect();
+    writeln!(out, "session lengths: {}", lengths.join(", "))?;
+    let total: u64 = stats.profiles.values().sum();
+    writeln!(out, "\nprofiles (time active):")?;
+    for (name, secs) in by_count(&stats.profiles) {
+        let share = (secs * 100).checked_div(total).unwrap_or(0);
+        writeln!(
+            out,
+            "  {name:<20} {:>10} {share:>3}%",
+            minutes(secs).to_string()
+        )?;
+    }
+    writeln!(out, "\npanels (times opened):")?;
+    for (name, n) in by_count(&stats.views) {
+        writeln!(out, "  {name:<20} {n:>6}")?;
+    }
+    writeln!(
+        out,
+        "\nkeys (outside panels and inputs, after profile remapping):"
+    )?;
+    for (name, n) in by_count(&stats.keys) {
+        writeln!(out, "  {name:<20} {n:>6}")?;
+    }
+    out.flush().context("writing output")
+}
+
+/// Entries by descending count, ties by name.
+fn by_count(map: &BTreeMap<String, u64>) -> Vec<(&str, u64)> {
+    let mut entries: Vec<(&str, u64)> = map.iter().map(|(k, n)| (k.as_str(), *n)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    entries
+}
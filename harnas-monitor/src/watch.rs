@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+#[cfg(feature = "tui")]
+use std::collections::BTreeSet;
+#[cfg(feature = "tui")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "tui")]
+use std::sync::mpsc;
+#[cfg(feature = "tui")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tui")]
+use anyhow::{Context, Result};
+#[cfg(feature = "tui")]
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// How often file metadata is compared as a fallback for filesystems (overlays, bind mounts,
+/// network shares) that drop or never deliver change events.
+#[cfg(feature = "tui")]
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[cfg(feature = "tui")]
+pub type WatchEvent = notify::Result<notify::Event>;
+
+/// Entries kept by the watch log unless `[retention] watch_log` says otherwise; older ones are
+/// dropped.
+pub const WATCH_LOG_LEN: usize = 500;
+
+/// Recent watcher activity for the debug view (`F12`, `--debug-watch`): raw events, polls,
+/// debounce windows and load attempts.
+#[derive(Debug)]
+pub struct WatchLog {
+    entries: VecDeque<WatchLogEntry>,
+    limit: usize,
+}
+
+impl Default for WatchLog {
+    fn default() -> Self {
+        WatchLog {
+            entries: VecDeque::new(),
+            limit: WATCH_LOG_LEN,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchLogEntry {
+    pub at: SystemTime,
+    /// Short category: `event`, `ignored`, `error`, `poll`, `debounce`, `load`, `symlink`.
+    pub kind: &'static str,
+    pub detail: String,
+}
+
+impl WatchLog {
+    pub fn push(&mut self, kind: &'static str, detail: impl Into<String>) {
+        if self.entries.len() >= self.limit {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(WatchLogEntry {
+            at: SystemTime::now(),
+            kind,
+            detail: detail.into(),
+        });
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit.max(1);
+        let excess = self.entries.len().saturating_sub(self.limit);
+        self.entries.drain(..excess);
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Rough size of the entries, in bytes.
+    pub fn retained_bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|e| std::mem::size_of::<WatchLogEntry>() + e.detail.capacity())
+            .sum()
+    }
+
+    /// Oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &WatchLogEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    /// One line for a raw notify event: its kind and paths.
+    #[cfg(feature = "tui")]
+    pub fn describe_event(event: &notify::Event) -> String {
+        let paths: Vec<String> = event
+            .paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        format!("{:?}  {}", event.kind, paths.join(", "))
+    }
+}
+
+/// Watches the tasks file robustly: parent directories instead of the file itself (editor
+/// atomic saves replace the inode), both the symlink and its current target when the path is a
+/// symlink, plus a metadata poll for filesystems without reliable events.
+#[cfg(feature = "tui")]
+pub struct FileWatch {
+    watcher: RecommendedWatcher,
+    path: PathBuf,
+    /// Resolved target when `path` is a symlink.
+    target: Option<PathBuf>,
+    /// Other files whose changes should trigger a reload (e.g. the annotations file).
+    extra: Vec<PathBuf>,
+    watched_dirs: BTreeSet<PathBuf>,
+    fingerprint: Option<(Option<SystemTime>, u64, Option<PathBuf>)>,
+    last_poll: Instant,
+}
+
+#[cfg(feature = "tui")]
+impl FileWatch {
+    pub fn new(path: &Path, extra: Vec<PathBuf>, tx: mpsc::Sender<WatchEvent>) -> Result<Self> {
+        let watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        })
+        .context("create file watcher")?;
+        let mut watch = FileWatch {
+            watcher,
+            path: path.to_path_buf(),
+            target: None,
+            extra,
+            watched_dirs: BTreeSet::new(),
+            fingerprint: None,
+            last_poll: Instant::now(),
+        };
+        watch.revalidate()?;
+        watch.fingerprint = watch.current_fingerprint();
+        Ok(watch)
+    }
+
+    /// Re-resolves the symlink target and adjusts the watched directories. Cheap enough to call
+    /// on every debounce expiry. Returns true when the symlink now points somewhere else.
+    pub fn revalidate(&mut self) -> Result<bool> {
+        let target = resolve_symlink(&self.path);
+        let retargeted = target != self.target;
+        self.target = target;
+
+        let mut wanted: BTreeSet<PathBuf> = BTreeSet::new();
+        for p in std::iter::once(&self.path).chain(self.target.iter()) {
+            if let Some(dir) = p.parent() {
+                wanted.insert(dir.to_path_buf());
+            }
+        }
+        // Extra files may live elsewhere (other workspace projects). A missing directory is
+        // skipped rather than failing the watch; the periodic refresh still reads the file.
+        for p in &self.extra {
+            if let Some(dir) = p.parent().filter(|d| d.is_dir()) {
+                wanted.insert(dir.to_path_buf());
+            }
+        }
+        for stale in self
+            .watched_dirs
+            .difference(&wanted)
+            .cloned()
+            .collect::<Vec<_>>()
+        {
+            let _ = self.watcher.unwatch(&stale);
+            self.watched_dirs.remove(&stale);
+        }
+        for dir in wanted {
+            if self.watched_dirs.contains(&dir) {
+                continue;
+            }
+            self.watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("watch {}", dir.display()))?;
+            self.watched_dirs.insert(dir);
+        }
+        Ok(retargeted)
+    }
+
+    /// Also reloads on changes to `path` (e.g. the spec file, once the plan names it). Takes
+    /// effect at the next `revalidate`.
+    pub fn watch_also(&mut self, path: PathBuf) {
+        if !self.extra.contains(&path) {
+            self.extra.push(path);
+        }
+    }
+
+    /// Whether a raw event concerns one of our files (directory watches also report siblings).
+    pub fn is_relevant(&self, event: &notify::Event) -> bool {
+        event.paths.iter().any(|p| {
+            p == &self.path || Some(p) == self.target.as_ref() || self.extra.iter().any(|e| e == p)
+        })
+    }
+
+    /// Rate-limited metadata comparison; true when mtime, size or symlink target changed since
+    /// the previous poll.
+    pub fn poll_changed(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+        let current = self.current_fingerprint();
+        let changed = current != self.fingerprint;
+        self.fingerprint = current;
+        changed
+    }
+
+    /// Short description of what is being watched, for the header.
+    pub fn describe(&self) -> String {
+        let dirs: Vec<String> = self
+            .watched_dirs
+            .iter()
+            .map(|d| d.display().to_string())
+            .collect();
+        match &self.target {
+            Some(t) => format!("symlink → {} (dirs: {})", t.display(), dirs.join(", ")),
+            None => format!("dir: {}", dirs.join(", ")),
+        }
+    }
+
+    fn current_fingerprint(&self) -> Option<(Option<SystemTime>, u64, Option<PathBuf>)> {
+        let meta = std::fs::metadata(&self.path).ok()?;
+        Some((meta.modified().ok(), meta.len(), self.target.clone()))
+    }
+}
+
+#[cfg(feature = "tui")]
+fn resolve_symlink(path: &Path) -> Option<PathBuf> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    if !meta.file_type().is_symlink() {
+        return None;
+    }
+    std::fs::canonicalize(path).ok()
+}
@@ -0,0 +1,256 @@
+//! The dashboard in a browser terminal: `web/index.html` fetches the plan from `--serve`
+//! (`/tasks.toml`), hands it to a `WebView` and writes its ANSI frames into xterm.js, passing
+//! key presses back. The view runs like `--demo --tasks`: annotations stay in the page and
+//! nothing is written. Built with `--no-default-features` for `wasm32-unknown-unknown`, where
+//! the functions in `exports` are the module's interface to the page.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::app::App;
+use crate::keys::{KeyCode, KeyEvent, KeyModifiers};
+use crate::ui::{render_ansi, UiTheme};
+
+pub struct WebView {
+    app: App,
+    theme: UiTheme,
+    plan: String,
+    quit: bool,
+}
+
+impl WebView {
+    pub fn new(plan: String) -> Self {
+        WebView {
+            app: App::demo(Some(plan.clone())),
+            theme: UiTheme::default(),
+            plan,
+            quit: false,
+        }
+    }
+
+    /// Takes the latest copy of the served file; reloads only when it changed.
+    pub fn set_plan(&mut self, plan: String) {
+        if plan != self.plan {
+            self.plan = plan.clone();
+            self.app.replace_demo_plan(plan);
+        }
+    }
+
+    /// Passes a key press to the dashboard, as the terminal's event loop does. A failed action
+    /// is reported in the status line rather than ending the view.
+    pub fn key(&mut self, key: KeyEvent) {
+        match self.app.handle_key(key) {
+            Ok(quit) => self.quit |= quit,
+            Err(e) => self.app.status_msg = Some(format!("{e:#}")),
+        }
+    }
+
+    /// The dashboard quit (`q`); the page shows that instead of further frames.
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Runs a pending reload, then draws one frame of `width` x `height`.
+    pub fn frame(&mut self, width: u16, height: u16) -> Result<String> {
+        self.app.maybe_reload(Duration::ZERO, Duration::MAX);
+        render_ansi(&self.theme, &self.app, width, height)
+    }
+}
+
+/// The key for a DOM `KeyboardEvent.key` (`"a"`, `"Enter"`, `"ArrowUp"`, `"F5"`); `None` for
+/// keys the dashboard has no use for (`"Shift"` on its own, `"Dead"`).
+pub fn dom_key(name: &str, ctrl: bool, alt: bool, shift: bool) -> Option<KeyEvent> {
+    let code = match name {
+        "Backspace" => KeyCode::Backspace,
+        "Enter" => KeyCode::Enter,
+        "ArrowLeft" => KeyCode::Left,
+        "ArrowRight" => KeyCode::Right,
+        "ArrowUp" => KeyCode::Up,
+        "ArrowDown" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Tab" if shift => KeyCode::BackTab,
+        "Tab" => KeyCode::Tab,
+        "Delete" => KeyCode::Delete,
+        "Insert" => KeyCode::Insert,
+        "Escape" => KeyCode::Esc,
+        _ => match name.strip_prefix('F').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=24) => KeyCode::F(n),
+            _ => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        },
+    };
+    let mut modifiers = KeyModifiers::NONE;
+    for (held, modifier) in [
+        (ctrl, KeyModifiers::CONTROL),
+        (alt, KeyModifiers::ALT),
+        // A typed character already says whether Shift was held, as crossterm reports it.
+        (
+            shift && !matches!(code, KeyCode::Char(_)),
+            KeyModifiers::SHIFT,
+        ),
+    ] {
+        if held {
+            modifiers = modifiers | modifier;
+        }
+    }
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// The module's interface to `web/index.html`. Strings cross as (pointer, length) pairs in the
+/// module's memory: the page writes into buffers from `harnas_alloc`, and reads the frame from
+/// `harnas_frame` and `harnas_frame_len`.
+#[cfg(target_arch = "wasm32")]
+mod exports {
+    use std::cell::RefCell;
+
+    use super::{dom_key, WebView};
+
+    thread_local! {
+        static VIEW: RefCell<Option<WebView>> = const { RefCell::new(None) };
+        static FRAME: RefCell<String> = const { RefCell::new(String::new()) };
+    }
+
+    /// Text the page wrote at `ptr`; invalid UTF-8 is replaced, not trusted.
+    fn text(ptr: *mut u8, len: usize) -> String {
+        // SAFETY: `ptr` came from `harnas_alloc(len)` and the page filled all `len` bytes.
+        let bytes = unsafe { Vec::from_raw_parts(ptr, len, len) };
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// A buffer of `len` bytes for the page to fill; the call it is passed to takes it back.
+    #[no_mangle]
+    pub extern "C" fn harnas_alloc(len: usize) -> *mut u8 {
+        let mut buffer = vec![0u8; len];
+        let ptr = buffer.as_mut_ptr();
+        std::mem::forget(buffer);
+        ptr
+    }
+
+    /// The served plan, fetched again by the page every few seconds.
+    #[no_mangle]
+    pub extern "C" fn harnas_set_plan(ptr: *mut u8, len: usize) {
+        let plan = text(ptr, len);
+        VIEW.with_borrow_mut(|view| match view {
+            Some(view) => view.set_plan(plan),
+            None => *view = Some(WebView::new(plan)),
+        });
+    }
+
+    /// A key press; `modifiers` has bit 0 for Ctrl, 1 for Alt and 2 for Shift. Returns 1 once
+    /// the dashboard has quit.
+    #[no_mangle]
+    pub extern "C" fn harnas_key(ptr: *mut u8, len: usize, modifiers: u32) -> u32 {
+        let name = text(ptr, len);
+        VIEW.with_borrow_mut(|view| {
+            let Some(view) = view else {
+                return 0;
+            };
+            if let Some(key) = dom_key(
+                &name,
+                modifiers & 1 != 0,
+                modifiers & 2 != 0,
+                modifiers & 4 != 0,
+            ) {
+                view.key(key);
+            }
+            u32::from(view.quit())
+        })
+    }
+
+    /// Draws a frame; read it with `harnas_frame_len`. Before the first plan, or when drawing
+    /// fails, the frame is a line saying so.
+    #[no_mangle]
+    pub extern "C" fn harnas_frame(width: u16, height: u16) -> *const u8 {
+        let frame = VIEW.with_borrow_mut(|view| match view {
+            Some(view) => view
+                .frame(width, height)
+                .unwrap_or_else(|e| format!("\x1b[2J\x1b[Hcannot draw the dashboard: {e:#}")),
+            None => "\x1b[2J\x1b[Hwaiting for the plan…".to_string(),
+        });
+        FRAME.with_borrow_mut(|out| {
+            *out = frame;
+            out.as_ptr()
+        })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn harnas_frame_len() -> usize {
+        FRAME.with_borrow(String::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"
+[[tasks]]
+id = "T-1"
+title = "first"
+status = "todo"
+"#;
+
+    #[test]
+    fn dom_keys_map_to_dashboard_keys() {
+        let key = |name, ctrl, shift| dom_key(name, ctrl, false, shift);
+        assert_eq!(
+            key("j", false, false),
+            Some(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            key("J", false, true),
+            Some(KeyEvent::new(KeyCode::Char('J'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            key("r", true, false),
+            Some(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            key("Tab", false, true),
+            Some(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT))
+        );
+        assert_eq!(
+            key("F12", false, false),
+            Some(KeyEvent::new(KeyCode::F(12), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            key("ArrowDown", false, false),
+            Some(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE))
+        );
+        assert_eq!(key("Shift", false, true), None);
+        assert_eq!(
+            key("F", false, true),
+            Some(KeyEvent::new(KeyCode::Char('F'), KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn frames_follow_the_served_plan() {
+        let mut view = WebView::new(PLAN.to_string());
+        let frame = view.frame(100, 30).unwrap();
+        assert!(frame.starts_with("\x1b[H"));
+        assert!(frame.contains("first"));
+        assert_eq!(frame.matches("\r\n").count(), 29);
+
+        view.set_plan(PLAN.replace("first", "renamed"));
+        let frame = view.frame(100, 30).unwrap();
+        assert!(frame.contains("renamed") && !frame.contains("first"));
+    }
+
+    #[test]
+    fn q_quits_the_view() {
+        let mut view = WebView::new(PLAN.to_string());
+        view.frame(100, 30).unwrap();
+        view.key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE));
+        assert!(view.quit());
+    }
+}
@@ -0,0 +1,96 @@
+//! Workspace mode: other projects' task files, named in the config's `[projects]` table, so
+//! `depends_on` can reference their tasks as `project:ID`. The other files are re-read with every
+//! load of the watched one, which also keeps the per-project summary behind the overview (`0`).
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::tasks::{load_tasks, task_status, LoadedTasks};
+
+/// Other projects' tasks as of the last load.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// Status of every task of every project that loaded, keyed `project:ID`.
+    pub statuses: HashMap<String, String>,
+    /// Configured projects: a summary, or why the project's file did not load.
+    pub projects: BTreeMap<String, Result<ProjectSummary, String>>,
+}
+
+/// One project's row in the workspace overview.
+#[derive(Debug, Clone)]
+pub struct ProjectSummary {
+    pub total: usize,
+    pub done: usize,
+    /// Blocked by status or by unmet dependencies.
+    pub blocked: usize,
+    pub problems: usize,
+    pub file_mtime: Option<SystemTime>,
+}
+
+impl ProjectSummary {
+    pub fn of(loaded: &LoadedTasks) -> Self {
+        ProjectSummary {
+            total: loaded.stats.total,
+            done: loaded.stats.done,
+            blocked: loaded.stats.segments.blocked,
+            problems: loaded.problems.len(),
+            file_mtime: loaded.file_mtime,
+        }
+    }
+
+    /// 0-100: starts at 100, loses up to 50 for the share of open tasks that are blocked and 5
+    /// per problem.
+    pub fn health(&self) -> u8 {
+        let open = self.total - self.done;
+        let blocked_share = if open == 0 {
+            0.0
+        } else {
+            self.blocked as f64 / open as f64
+        };
+        let score = 100.0 - 50.0 * blocked_share - 5.0 * self.problems as f64;
+        score.clamp(0.0, 100.0).round() as u8
+    }
+}
+
+impl Workspace {
+    pub fn load(projects: &BTreeMap<String, PathBuf>) -> Self {
+        let mut ws = Workspace::default();
+        for (name, path) in projects {
+            match load_tasks(path) {
+                Ok(loaded) => {
+                    for t in &loaded.tasks.tasks {
+                        ws.statuses
+                            .insert(format!("{name}:{}", t.id), task_status(t));
+                    }
+                    ws.projects
+                        .insert(name.clone(), Ok(ProjectSummary::of(&loaded)));
+                }
+                Err(e) => {
+                    ws.projects.insert(name.clone(), Err(format!("{e:#}")));
+                }
+            }
+        }
+        ws
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+
+    /// Why `dep` names no task: `None` for plain ids, which the caller reports as usual.
+    pub fn explain_missing(&self, dep: &str) -> Option<String> {
+        let (project, id) = split_ref(dep)?;
+        Some(match self.projects.get(project) {
+            None => format!("task {id} of unknown project {project}"),
+            Some(Err(e)) => format!("task {id} of project {project}, which did not load ({e})"),
+            Some(Ok(_)) => format!("unknown task {id} in project {project}"),
+        })
+    }
+}
+
+/// Splits a `project:ID` reference; plain ids give `None`.
+pub fn split_ref(dep: &str) -> Option<(&str, &str)> {
+    dep.split_once(':')
+        .filter(|(project, id)| !project.is_empty() && !id.is_empty())
+}
@@ -0,0 +1,314 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::analysis::Fix;
+use crate::backup;
+use crate::tasks::{fnv1a_64, EXTERNAL_KIND};
+
+/// Edits `TASKS.toml` in place, keeping comments and formatting, then replaces the file
+/// atomically. `expected_hash` is the content hash the edit was planned against; if the file
+/// changed on disk since (e.g. the generator ran), nothing is written. The previous contents go
+/// to a rotated backup first, `backups` of which are kept.
+pub fn edit_tasks_file(
+    path: &Path,
+    expected_hash: u64,
+    backups: usize,
+    edit: impl FnOnce(&mut DocumentMut) -> Result<()>,
+) -> Result<()> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    if fnv1a_64(contents.as_bytes()) != expected_hash {
+        bail!("{} changed on disk; reload before editing", path.display());
+    }
+    let mut doc: DocumentMut = contents.parse().map_err(|e| anyhow!("parsing TOML: {e}"))?;
+    edit(&mut doc)?;
+    backup::take(path, backups)?;
+    write_atomic(path, &doc.to_string())
+}
+
+/// Sets the `order` field of each listed task.
+pub fn set_task_orders(doc: &mut DocumentMut, orders: &[(String, i64)]) -> Result<()> {
+    let tasks = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .ok_or_else(|| anyhow!("no [[tasks]] array"))?;
+    for table in tasks.iter_mut() {
+        let Some(id) = table.get("id").and_then(|v| v.as_str()).map(str::to_string) else {
+            continue;
+        };
+        if let Some((_, order)) = orders.iter().find(|(oid, _)| *oid == id) {
+            table["order"] = value(*order);
+        }
+    }
+    Ok(())
+}
+
+/// Sets a string field of the first task with `id`, keeping the comments around an existing
+/// value.
+pub fn set_task_field(doc: &mut DocumentMut, id: &str, key: &str, to: &str) -> Result<()> {
+    set_task_value(doc, id, key, Value::from(to))
+}
+
+/// Sets a numeric field like `set_task_field`; whole numbers are written as integers.
+pub fn set_task_number(doc: &mut DocumentMut, id: &str, key: &str, to: f64) -> Result<()> {
+    let to = if to.fract() == 0.0 && to.abs() < 1e15 {
+        Value::from(to as i64)
+    } else {
+        Value::from(to)
+    };
+    set_task_value(doc, id, key, to)
+}
+
+/// Removes a field of the first task with `id`; a field that is not there is no error.
+pub fn remove_task_field(doc: &mut DocumentMut, id: &str, key: &str) -> Result<()> {
+    let table = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .and_then(|tasks| tasks.iter_mut().find(|t| has_id(t, id)))
+        .ok_or_else(|| anyhow!("{id} is no longer in the file"))?;
+    table.remove(key);
+    Ok(())
+}
+
+fn set_task_value(doc: &mut DocumentMut, id: &str, key: &str, to: Value) -> Result<()> {
+    let table = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .and_then(|tasks| tasks.iter_mut().find(|t| has_id(t, id)))
+        .ok_or_else(|| anyhow!("{id} is no longer in the file"))?;
+    match table.get_mut(key).and_then(Item::as_value_mut) {
+        Some(v) => replace_keeping_decor(v, to),
+        None => table[key] = value(to),
+    }
+    Ok(())
+}
+
+/// Applies a problem fix. Only the edited value changes; its comments stay in place.
+pub fn apply_fix(doc: &mut DocumentMut, fix: &Fix) -> Result<()> {
+    let tasks = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .ok_or_else(|| anyhow!("no [[tasks]] array"))?;
+    match fix {
+        Fix::RenameReference {
+            task,
+            field,
+            from,
+            to,
+        } => {
+            let deps = tasks
+                .iter_mut()
+                .filter(|t| has_id(t, task))
+                .find_map(|t| t.get_mut(field).and_then(Item::as_array_mut))
+                .ok_or_else(|| anyhow!("{task} has no {field}"))?;
+            let pos = deps
+                .iter()
+                .position(|d| listed_id(d) == Some(from.as_str()))
+                .ok_or_else(|| anyhow!("{task}'s {field} no longer names {from}"))?;
+            // Already listed under the right id: the typo is just dropped.
+            if deps.iter().any(|d| listed_id(d) == Some(to.as_str())) {
+                remove_entry(deps, pos);
+            } else if let Some(dep) = deps.get_mut(pos) {
+                match dep.as_inline_table_mut().and_then(|t| t.get_mut("id")) {
+                    Some(id) => replace_keeping_decor(id, to),
+                    None => replace_keeping_decor(dep, to),
+                }
+            }
+        }
+        Fix::RenameDuplicateId { id, occurrence, to } => {
+            let table = tasks
+                .iter_mut()
+                .filter(|t| has_id(t, id))
+                .nth(*occurrence)
+                .ok_or_else(|| anyhow!("{id} is no longer duplicated"))?;
+            if let Some(v) = table.get_mut("id").and_then(Item::as_value_mut) {
+                replace_keeping_decor(v, to);
+            }
+        }
+        Fix::NormalizeStatus { task, from, to } => {
+            let status = tasks
+                .iter_mut()
+                .filter(|t| has_id(t, task))
+                .find_map(|t| {
+                    t.get_mut("status")
+                        .and_then(Item::as_value_mut)
+                        .filter(|v| v.as_str() == Some(from.as_str()))
+                })
+                .ok_or_else(|| anyhow!("{task}'s status is no longer {from:?}"))?;
+            replace_keeping_decor(status, to);
+        }
+    }
+    Ok(())
+}
+
+/// Drops `dep` from the `depends_on` of the first task with id `task`.
+pub fn remove_dependency(doc: &mut DocumentMut, task: &str, dep: &str) -> Result<()> {
+    let deps = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .and_then(|tasks| tasks.iter_mut().find(|t| has_id(t, task)))
+        .and_then(|t| t.get_mut("depends_on").and_then(Item::as_array_mut))
+        .ok_or_else(|| anyhow!("{task} has no depends_on"))?;
+    let pos = deps
+        .iter()
+        .position(|d| listed_id(d) == Some(dep))
+        .ok_or_else(|| anyhow!("{task} no longer depends on {dep}"))?;
+    remove_entry(deps, pos);
+    Ok(())
+}
+
+/// Sets `kind = "external"` on a `depends_on` entry, turning a plain id into an annotated one.
+pub fn mark_dependency_external(doc: &mut DocumentMut, task: &str, dep: &str) -> Result<()> {
+    let deps = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .and_then(|tasks| tasks.iter_mut().find(|t| has_id(t, task)))
+        .and_then(|t| t.get_mut("depends_on").and_then(Item::as_array_mut))
+        .ok_or_else(|| anyhow!("{task} has no depends_on"))?;
+    let entry = deps
+        .iter_mut()
+        .find(|d| listed_id(d) == Some(dep))
+        .ok_or_else(|| anyhow!("{task} no longer depends on {dep}"))?;
+    match entry.as_inline_table_mut() {
+        Some(table) => {
+            table.insert("kind", Value::from(EXTERNAL_KIND));
+        }
+        None => {
+            let mut table = InlineTable::new();
+            table.insert("id", Value::from(dep));
+            table.insert("kind", Value::from(EXTERNAL_KIND));
+            replace_keeping_decor(entry, table);
+        }
+    }
+    Ok(())
+}
+
+/// Appends `dep` to the `depends_on` of the first task with id `task`, creating the list if
+/// there is none. A new entry copies the last one's leading whitespace, so multi-line arrays
+/// keep their layout.
+pub fn add_dependency(doc: &mut DocumentMut, task: &str, dep: &str) -> Result<()> {
+    let table = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .and_then(|tasks| tasks.iter_mut().find(|t| has_id(t, task)))
+        .ok_or_else(|| anyhow!("{task} is no longer in the file"))?;
+    let Some(deps) = table.get_mut("depends_on").and_then(Item::as_array_mut) else {
+        let mut deps = Array::new();
+        deps.push(dep);
+        table["depends_on"] = value(deps);
+        return Ok(());
+    };
+    if deps.iter().any(|d| listed_id(d) == Some(dep)) {
+        bail!("{task} already depends on {dep}");
+    }
+    let prefix = deps.iter().last().and_then(|d| d.decor().prefix().cloned());
+    deps.push(dep);
+    if let (Some(prefix), Some(added)) = (prefix, deps.iter_mut().last()) {
+        added.decor_mut().set_prefix(prefix);
+    }
+    Ok(())
+}
+
+/// Sets `component = to` on every task whose component is `from` (compared like
+/// `component_key`), and moves a `[meta.budgets]` entry for `from` to `to` unless `to` already
+/// has one. Returns the number of tasks changed.
+pub fn rename_component(doc: &mut DocumentMut, from: &str, to: &str) -> Result<usize> {
+    let key = from.trim().to_lowercase();
+    let matches = |v: &Value| v.as_str().is_some_and(|c| c.trim().to_lowercase() == key);
+    let tasks = doc
+        .get_mut("tasks")
+        .and_then(|t| t.as_array_of_tables_mut())
+        .ok_or_else(|| anyhow!("no [[tasks]] array"))?;
+    let mut renamed = 0;
+    for table in tasks.iter_mut() {
+        if let Some(c) = table.get_mut("component").and_then(Item::as_value_mut) {
+            if matches(c) {
+                replace_keeping_decor(c, to);
+                renamed += 1;
+            }
+        }
+    }
+    if renamed == 0 {
+        bail!("no task has component {from}");
+    }
+    let budgets = doc
+        .get_mut("meta")
+        .and_then(|m| m.get_mut("budgets"))
+        .and_then(Item::as_table_like_mut);
+    if let Some(budgets) = budgets {
+        let old = budgets
+            .iter()
+            .map(|(k, _)| k.to_string())
+            .find(|k| k.trim().to_lowercase() == key);
+        let taken = budgets
+            .iter()
+            .any(|(k, _)| k.trim().to_lowercase() == to.trim().to_lowercase());
+        if let (Some(old), false) = (old, taken) {
+            if let Some(days) = budgets.remove(&old) {
+                budgets.insert(to, days);
+            }
+        }
+    }
+    Ok(renamed)
+}
+
+/// Removes an array entry; a removed first entry hands its leading whitespace to the next, so
+/// multi-line arrays keep their indentation.
+fn remove_entry(list: &mut Array, pos: usize) {
+    let removed = list.remove(pos);
+    if let (0, Some(first)) = (pos, list.get_mut(0)) {
+        let prefix = removed.decor().prefix().cloned().unwrap_or_default();
+        first.decor_mut().set_prefix(prefix);
+    }
+}
+
+/// The lines a fix would change in `source`, as (line number, before, after).
+pub fn preview_fix(source: &str, fix: &Fix) -> Result<Vec<(usize, String, String)>> {
+    let mut doc: DocumentMut = source.parse().map_err(|e| anyhow!("parsing TOML: {e}"))?;
+    apply_fix(&mut doc, fix)?;
+    let after = doc.to_string();
+    // Fixes edit one value in place, so the line structure is unchanged.
+    Ok(source
+        .lines()
+        .zip(after.lines())
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(i, (a, b))| (i + 1, a.to_string(), b.to_string()))
+        .collect())
+}
+
+/// The id an entry of an id list names: the string itself, or the `id` of an annotated
+/// `depends_on` entry.
+pub fn listed_id(v: &Value) -> Option<&str> {
+    match v.as_inline_table() {
+        Some(table) => table.get("id").and_then(Value::as_str),
+        None => v.as_str(),
+    }
+}
+
+fn has_id(table: &Table, id: &str) -> bool {
+    table.get("id").and_then(Item::as_str) == Some(id)
+}
+
+fn replace_keeping_decor(v: &mut Value, to: impl Into<Value>) {
+    let decor = v.decor().clone();
+    *v = to.into();
+    *v.decor_mut() = decor;
+}
+
+/// Writes via a temporary sibling file and a rename, so readers never see a half-written file.
+/// Symlinks are followed, so the link itself is never replaced by a regular file.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = resolved.as_path();
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{file_name}.tmp"));
+    std::fs::write(&tmp, contents).with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("replacing {}", path.display()))?;
+    Ok(())
+}
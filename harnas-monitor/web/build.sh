@@ -0,0 +1,17 @@
+#!/bin/sh
+# Builds the browser view's module (`src/web.rs`) for wasm32 and puts it next to the
+# harnas-monitor binary in target/<profile>, where `--serve` finds it.
+#
+#   web/build.sh            # release, next to target/release/harnas-monitor
+#   web/build.sh debug      # next to target/debug/harnas-monitor (`cargo run`)
+set -eu
+cd "$(dirname "$0")/.."
+profile=${1:-release}
+if [ "$profile" = release ]; then
+    cargo rustc --lib --release --crate-type cdylib --no-default-features --target wasm32-unknown-unknown
+else
+    cargo rustc --lib --crate-type cdylib --no-default-features --target wasm32-unknown-unknown
+fi
+mkdir -p "target/$profile"
+cp "target/wasm32-unknown-unknown/$profile/harnas_monitor.wasm" "target/$profile/"
+echo "target/$profile/harnas_monitor.wasm"